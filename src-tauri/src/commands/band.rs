@@ -0,0 +1,71 @@
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tauri::{command, State};
+use serde::{Deserialize, Serialize};
+
+use crate::core::state::{AppState, AppStateDto};
+use crate::core::journal::{record_changes, ManagedSessionJournal};
+use crate::core::template::{BandConfig, BandType, ReportBands, SplitType};
+use crate::errors::Result;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateBandRequest {
+    pub band_type: BandType,
+    pub height: f64,
+    pub split_type: Option<SplitType>,
+}
+
+#[command]
+pub async fn get_report_bands(
+    state: State<'_, Arc<RwLock<AppState>>>,
+) -> Result<ReportBands> {
+    let app_state = state.read().await;
+    Ok(app_state.bands_with_members())
+}
+
+/// 新增或修改区段
+#[command]
+pub async fn update_report_band(
+    request: UpdateBandRequest,
+    state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
+) -> Result<()> {
+    let mut app_state = state.write().await;
+
+    let mut band = app_state.bands.get(request.band_type).cloned().unwrap_or_else(|| BandConfig::new(request.height));
+    band.height = request.height;
+    if let Some(split_type) = request.split_type {
+        band.split_type = split_type;
+    }
+    app_state.set_band(request.band_type, Some(band))?;
+
+    record_changes(&journal, &mut app_state).await;
+    
+    emit_state_change(&app_state).await;
+
+    Ok(())
+}
+
+/// 删除区段，其中的元素回到画布
+#[command]
+pub async fn remove_report_band(
+    band_type: BandType,
+    state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
+) -> Result<()> {
+    let mut app_state = state.write().await;
+
+    app_state.set_band(band_type, None)?;
+
+    record_changes(&journal, &mut app_state).await;
+    
+    emit_state_change(&app_state).await;
+
+    Ok(())
+}
+
+// Helper function to emit state changes
+async fn emit_state_change(app_state: &AppState) {
+    let dto = AppStateDto::from(app_state);
+    println!("Bands changed: {} elements", dto.elements.len());
+}
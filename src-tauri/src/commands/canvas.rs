@@ -4,6 +4,7 @@ use tauri::{command, State};
 use serde::{Deserialize, Serialize};
 
 use crate::core::state::{AppState, AppStateDto};
+use crate::core::journal::{record_changes, ManagedSessionJournal};
 use crate::core::canvas::CanvasConfig;
 use crate::errors::Result;
 
@@ -33,6 +34,7 @@ pub async fn get_canvas_config(
 pub async fn update_canvas_config(
     request: UpdateCanvasConfigRequest,
     state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<()> {
     let mut app_state = state.write().await;
     let mut config = app_state.canvas.clone();
@@ -85,6 +87,8 @@ pub async fn update_canvas_config(
     
     app_state.update_canvas_config(config);
     
+    record_changes(&journal, &mut app_state).await;
+    
     // Emit state change event
     emit_state_change(&app_state).await;
     
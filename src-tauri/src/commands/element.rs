@@ -4,7 +4,8 @@ use tauri::{command, State};
 use serde::{Deserialize, Serialize};
 
 use crate::core::state::{AppState, AppStateDto};
-use crate::core::element::{ElementId, ReportElement, ElementContent, Position, Size, TextStyle, TextAlign, BorderStyle, BorderStyleType, LineCapType, LineStyleType};
+use crate::core::journal::{record_changes, ManagedSessionJournal};
+use crate::core::element::{ElementId, ReportElement, ElementContent, Position, Size, TextStyle, TextAlign, BorderStyle, BorderStyleType, LineCapType, LineStyleType, SubreportConfig, TableConfig, CrosstabConfig, ChartConfig, BarcodeConfig};
 use crate::errors::Result;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -25,6 +26,7 @@ pub struct UpdateElementRequest {
 pub async fn create_element(
     request: CreateElementRequest,
     state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<String> {
     let mut app_state = state.write().await;
     
@@ -128,6 +130,15 @@ pub async fn create_element(
                 alt: img_data.get("alt")
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string()),
+                scale_mode: img_data.get("scale_mode")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default(),
+                horizontal_align: img_data.get("horizontal_align")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default(),
+                vertical_align: img_data.get("vertical_align")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default(),
             }
         }
         "line" => {
@@ -237,6 +248,42 @@ pub async fn create_element(
                 },
             }
         }
+        "subreport" => {
+            let config: SubreportConfig = serde_json::from_value(request.content_data)
+                .map_err(|e| crate::errors::AppError::ValidationError {
+                    message: format!("Invalid subreport configuration: {}", e),
+                })?;
+            ElementContent::Subreport(config)
+        }
+        "table" => {
+            let config: TableConfig = serde_json::from_value(request.content_data)
+                .map_err(|e| crate::errors::AppError::ValidationError {
+                    message: format!("Invalid table configuration: {}", e),
+                })?;
+            ElementContent::Table(config)
+        }
+        "crosstab" => {
+            let config: CrosstabConfig = serde_json::from_value(request.content_data)
+                .map_err(|e| crate::errors::AppError::ValidationError {
+                    message: format!("Invalid crosstab configuration: {}", e),
+                })?;
+            ElementContent::Crosstab(config)
+        }
+        "chart" => {
+            let config: ChartConfig = serde_json::from_value(request.content_data)
+                .map_err(|e| crate::errors::AppError::ValidationError {
+                    message: format!("Invalid chart configuration: {}", e),
+                })?;
+            ElementContent::Chart(config)
+        }
+        "barcode" => {
+            let config: BarcodeConfig = serde_json::from_value(request.content_data)
+                .map_err(|e| crate::errors::AppError::ValidationError {
+                    message: format!("Invalid barcode configuration: {}", e),
+                })?;
+            validate_barcode(&config)?;
+            ElementContent::Barcode(config)
+        }
         _ => {
             return Err(crate::errors::AppError::CanvasError {
                 message: format!("Unknown element type: {}", request.element_type),
@@ -249,6 +296,8 @@ pub async fn create_element(
     
     app_state.add_element(element)?;
     
+    record_changes(&journal, &mut app_state).await;
+    
     // Emit state change event
     emit_state_change(&app_state).await;
     
@@ -259,6 +308,7 @@ pub async fn create_element(
 pub async fn update_element(
     request: UpdateElementRequest,
     state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<()> {
     let mut app_state = state.write().await;
     
@@ -297,6 +347,21 @@ pub async fn update_element(
         element.z_index = z_index as i32;
     }
     
+    // 区段归属：null 表示移回画布
+    if let Some(band) = request.updates.get("band") {
+        element.band = serde_json::from_value(band.clone())
+            .map_err(|e| crate::errors::AppError::ValidationError {
+                message: format!("Invalid band type: {}", e),
+            })?;
+        if let Some(band_type) = element.band {
+            if app_state.bands.get(band_type).is_none() {
+                return Err(crate::errors::AppError::ValidationError {
+                    message: format!("Band {:?} is not defined", band_type),
+                });
+            }
+        }
+    }
+    
     // Handle content updates
     if let Some(content_updates) = request.updates.get("content") {
         element.content = update_element_content(element.content, content_updates)?;
@@ -304,6 +369,8 @@ pub async fn update_element(
     
     app_state.update_element(&element_id, element)?;
     
+    record_changes(&journal, &mut app_state).await;
+    
     // Emit state change event
     emit_state_change(&app_state).await;
     
@@ -314,12 +381,15 @@ pub async fn update_element(
 pub async fn delete_element(
     element_id: String,
     state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<()> {
     let mut app_state = state.write().await;
     
     let id = ElementId::from_string(&element_id)?;
     app_state.delete_element(&id)?;
     
+    record_changes(&journal, &mut app_state).await;
+    
     // Emit state change event
     emit_state_change(&app_state).await;
     
@@ -408,6 +478,7 @@ pub async fn paste_elements(
     offset_x: f64,
     offset_y: f64,
     state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<Vec<String>> {
     let mut app_state = state.write().await;
     
@@ -417,6 +488,8 @@ pub async fn paste_elements(
         .map(|id| id.to_string())
         .collect();
     
+    record_changes(&journal, &mut app_state).await;
+    
     // Emit state change event
     emit_state_change(&app_state).await;
     
@@ -470,6 +543,7 @@ pub struct ElementPositionUpdate {
 pub async fn batch_update_positions(
     request: BatchUpdatePositionRequest,
     state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<()> {
     let mut app_state = state.write().await;
     
@@ -482,6 +556,8 @@ pub async fn batch_update_positions(
         }
     }
     
+    record_changes(&journal, &mut app_state).await;
+    
     // Emit state change event
     emit_state_change(&app_state).await;
     
@@ -764,7 +840,7 @@ fn update_element_content(
             Ok(ElementContent::DataField { expression, format, style })
         },
         
-        ElementContent::Image { mut src, mut alt } => {
+        ElementContent::Image { mut src, mut alt, mut scale_mode, mut horizontal_align, mut vertical_align } => {
             if let Some(new_src) = updates.get("src").and_then(|v| v.as_str()) {
                 src = new_src.to_string();
             }
@@ -772,8 +848,81 @@ fn update_element_content(
             if let Some(new_alt) = updates.get("alt").and_then(|v| v.as_str()) {
                 alt = Some(new_alt.to_string());
             }
+
+            let invalid = |field: &str, e: serde_json::Error| crate::errors::AppError::ValidationError {
+                message: format!("Invalid image {}: {}", field, e),
+            };
+            if let Some(value) = updates.get("scale_mode") {
+                scale_mode = serde_json::from_value(value.clone()).map_err(|e| invalid("scale_mode", e))?;
+            }
+            if let Some(value) = updates.get("horizontal_align") {
+                horizontal_align = serde_json::from_value(value.clone()).map_err(|e| invalid("horizontal_align", e))?;
+            }
+            if let Some(value) = updates.get("vertical_align") {
+                vertical_align = serde_json::from_value(value.clone()).map_err(|e| invalid("vertical_align", e))?;
+            }
             
-            Ok(ElementContent::Image { src, alt })
+            Ok(ElementContent::Image { src, alt, scale_mode, horizontal_align, vertical_align })
         },
+
+        ElementContent::Subreport(mut config) => {
+            if let Some(template_path) = updates.get("template_path").and_then(|v| v.as_str()) {
+                config.template_path = template_path.to_string();
+            }
+
+            if let Some(parameters) = updates.get("parameters") {
+                config.parameters = serde_json::from_value(parameters.clone())
+                    .map_err(|e| crate::errors::AppError::ValidationError {
+                        message: format!("Invalid subreport parameters: {}", e),
+                    })?;
+            }
+
+            // 显式传入 null 时改为查询子报表自己的数据源
+            if let Some(data_expression) = updates.get("data_expression") {
+                config.data_expression = data_expression.as_str().map(|s| s.to_string());
+            }
+
+            Ok(ElementContent::Subreport(config))
+        },
+
+        ElementContent::Table(config) => Ok(ElementContent::Table(merge_config(&config, &updates, "table")?)),
+
+        ElementContent::Crosstab(config) => Ok(ElementContent::Crosstab(merge_config(&config, &updates, "crosstab")?)),
+
+        ElementContent::Chart(config) => Ok(ElementContent::Chart(merge_config(&config, &updates, "chart")?)),
+
+        ElementContent::Barcode(config) => {
+            let config = merge_config(&config, &updates, "barcode")?;
+            validate_barcode(&config)?;
+            Ok(ElementContent::Barcode(config))
+        },
+    }
+}
+
+/// 字面内容必须能按码制编码；新建时可以暂不填写内容，表达式内容在填充时才检查
+fn validate_barcode(config: &BarcodeConfig) -> Result<()> {
+    if config.value.is_empty() {
+        return Ok(());
+    }
+    crate::core::barcode::validate(config).map_err(|e| crate::errors::AppError::ValidationError {
+        message: e.to_string(),
+    })
+}
+
+/// 按顶层字段合并配置，columns、row_groups、palette 等数组整体替换
+fn merge_config<T>(config: &T, updates: &serde_json::Value, kind: &str) -> Result<T>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let mut merged = serde_json::to_value(config).map_err(|e| crate::errors::AppError::SerializationError {
+        message: e.to_string(),
+    })?;
+    if let (Some(merged), Some(updates)) = (merged.as_object_mut(), updates.as_object()) {
+        for (key, value) in updates {
+            merged.insert(key.clone(), value.clone());
+        }
     }
+    serde_json::from_value(merged).map_err(|e| crate::errors::AppError::ValidationError {
+        message: format!("Invalid {} configuration: {}", kind, e),
+    })
 }
\ No newline at end of file
@@ -3,7 +3,10 @@ use tokio::sync::RwLock;
 use tauri::{command, State};
 use serde::{Deserialize, Serialize};
 
+use crate::core::library::{ManagedTemplateLibrary, RecentTemplate};
+use crate::core::journal::{reset_journal, ManagedSessionJournal};
 use crate::core::state::{AppState, AppStateDto};
+use crate::core::template::JasperTemplate;
 use crate::errors::{AppError, Result};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,6 +35,7 @@ pub struct TemplateData {
 pub async fn save_template(
     request: SaveTemplateRequest,
     state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<String> {
     let mut app_state = state.write().await;
     
@@ -68,6 +72,8 @@ pub async fn save_template(
     app_state.template_name = Some(request.name);
     app_state.set_dirty(false);
     
+    reset_journal(&journal, &mut app_state).await;
+    
     // Emit state change event
     emit_state_change(&app_state).await;
     
@@ -78,6 +84,7 @@ pub async fn save_template(
 pub async fn load_template(
     request: LoadTemplateRequest,
     state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<()> {
     let mut app_state = state.write().await;
     
@@ -101,6 +108,8 @@ pub async fn load_template(
     
     app_state.set_dirty(false);
     
+    reset_journal(&journal, &mut app_state).await;
+    
     // Emit state change event
     emit_state_change(&app_state).await;
     
@@ -110,17 +119,54 @@ pub async fn load_template(
 #[command]
 pub async fn new_template(
     state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<()> {
     let mut app_state = state.write().await;
     
     app_state.clear();
     
+    reset_journal(&journal, &mut app_state).await;
+    
     // Emit state change event
     emit_state_change(&app_state).await;
     
     Ok(())
 }
 
+/// 把 load_jasper_template 读出的模板载入编辑状态，之后即可使用元素命令编辑
+#[command]
+pub async fn open_template_for_editing(
+    template: JasperTemplate,
+    state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
+) -> Result<AppStateDto> {
+    let mut app_state = state.write().await;
+    
+    app_state.load_template(&template);
+    
+    reset_journal(&journal, &mut app_state).await;
+    
+    // Emit state change event
+    emit_state_change(&app_state).await;
+    
+    Ok(AppStateDto::from(&*app_state))
+}
+
+/// 取回编辑后的模板，交给 save_jasper_template 保存
+#[command]
+pub async fn get_edited_template(
+    state: State<'_, Arc<RwLock<AppState>>>,
+) -> Result<JasperTemplate> {
+    let app_state = state.read().await;
+    
+    let mut template = app_state.to_template();
+    if app_state.is_dirty() {
+        template.metadata.last_modified = chrono::Utc::now();
+    }
+    
+    Ok(template)
+}
+
 #[command]
 pub async fn export_json(
     state: State<'_, Arc<RwLock<AppState>>>,
@@ -148,14 +194,17 @@ pub async fn export_json(
 }
 
 #[command]
-pub async fn get_recent_templates() -> Result<Vec<String>> {
-    // In a real implementation, we would read from a recent files list
-    // For now, return some sample templates
-    Ok(vec![
-        "标准银行回单.jdt".to_string(),
-        "详细交易明细.jdt".to_string(),
-        "简化回单模板.jdt".to_string(),
-    ])
+pub async fn get_recent_templates(
+    library: State<'_, ManagedTemplateLibrary>,
+) -> Result<Vec<RecentTemplate>> {
+    Ok(library.lock().await.recent())
+}
+
+#[command]
+pub async fn clear_recent_templates(
+    library: State<'_, ManagedTemplateLibrary>,
+) -> Result<()> {
+    library.lock().await.clear_recent()
 }
 
 // Helper function to create a sample template
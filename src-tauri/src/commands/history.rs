@@ -3,16 +3,20 @@ use tokio::sync::RwLock;
 use tauri::{command, State};
 
 use crate::core::state::{AppState, AppStateDto};
+use crate::core::journal::{record_changes, ManagedSessionJournal};
 use crate::errors::Result;
 
 #[command]
 pub async fn undo(
     state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<()> {
     let mut app_state = state.write().await;
     
     app_state.undo()?;
     
+    record_changes(&journal, &mut app_state).await;
+    
     // Emit state change event
     emit_state_change(&app_state).await;
     
@@ -22,11 +26,14 @@ pub async fn undo(
 #[command]
 pub async fn redo(
     state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<()> {
     let mut app_state = state.write().await;
     
     app_state.redo()?;
     
+    record_changes(&journal, &mut app_state).await;
+    
     // Emit state change event
     emit_state_change(&app_state).await;
     
@@ -68,10 +75,12 @@ pub async fn get_redo_description(
 #[command]
 pub async fn clear_history(
     state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<()> {
     let mut app_state = state.write().await;
-    app_state.history.clear();
-    app_state.set_dirty(true);
+    app_state.clear_history();
+    
+    record_changes(&journal, &mut app_state).await;
     
     // Emit state change event
     emit_state_change(&app_state).await;
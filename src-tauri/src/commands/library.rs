@@ -0,0 +1,39 @@
+// === Template Library Commands ===
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::core::library::{set_library_root, sync_library, LibraryChanges, LibraryEntry, LibraryQuery, LibrarySettings, ManagedTemplateLibrary};
+use crate::errors::Result;
+
+#[tauri::command]
+pub async fn get_template_library_settings(library: State<'_, ManagedTemplateLibrary>) -> Result<LibrarySettings> {
+    Ok(library.lock().await.settings().clone())
+}
+
+/// 设置模板库目录并重建索引，`None` 表示不使用模板库
+#[tauri::command]
+pub async fn set_template_library_root(
+    root: Option<String>,
+    library: State<'_, ManagedTemplateLibrary>,
+) -> Result<LibraryChanges> {
+    set_library_root(&library, root.map(PathBuf::from)).await
+}
+
+/// 立即同步磁盘上的变化，不等待文件监视
+#[tauri::command]
+pub async fn refresh_template_library(library: State<'_, ManagedTemplateLibrary>) -> Result<LibraryChanges> {
+    sync_library(&library).await
+}
+
+#[tauri::command]
+pub async fn search_template_library(
+    query: LibraryQuery,
+    library: State<'_, ManagedTemplateLibrary>,
+) -> Result<Vec<LibraryEntry>> {
+    Ok(library.lock().await.search(&query))
+}
+
+#[tauri::command]
+pub async fn list_template_library_tags(library: State<'_, ManagedTemplateLibrary>) -> Result<Vec<String>> {
+    Ok(library.lock().await.tags())
+}
@@ -3,6 +3,9 @@ pub mod canvas;
 pub mod history;
 pub mod file;
 pub mod template;
+pub mod band;
+pub mod library;
+pub mod recovery;
 
 // Debug commands
 #[tauri::command]
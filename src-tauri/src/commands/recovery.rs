@@ -0,0 +1,35 @@
+// === Session Recovery Commands ===
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tauri::{command, State};
+
+use crate::core::journal::{ManagedSessionJournal, RecoveryInfo};
+use crate::core::state::{AppState, AppStateDto};
+use crate::errors::Result;
+
+/// 启动时调用：上次异常退出留下未保存的编辑时返回其概况
+#[command]
+pub async fn get_recovery_info(
+    journal: State<'_, ManagedSessionJournal>,
+) -> Result<Option<RecoveryInfo>> {
+    journal.lock().await.recovery_info()
+}
+
+/// 恢复上次未保存的编辑，替换当前编辑状态
+#[command]
+pub async fn restore_session(
+    state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
+) -> Result<AppStateDto> {
+    let mut app_state = state.write().await;
+    let restored = journal.lock().await.restore()?;
+    *app_state = restored;
+    Ok(AppStateDto::from(&*app_state))
+}
+
+#[command]
+pub async fn discard_recovery(
+    journal: State<'_, ManagedSessionJournal>,
+) -> Result<()> {
+    journal.lock().await.discard_recovery()
+}
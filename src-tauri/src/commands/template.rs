@@ -1,33 +1,160 @@
 // === Template Management Commands ===
+use crate::core::binary::{self, BinarySignature, SigningKeyPair};
+use crate::core::diff::{self, TemplateDiff, TemplateMerge};
+use crate::core::jrxml::{self, JrxmlImport};
+use crate::core::journal::{reset_journal, ManagedSessionJournal};
+use crate::core::library::ManagedTemplateLibrary;
+use crate::core::lint::{self, LintOptions, LintReport};
+use crate::core::migration::AppliedMigration;
+use crate::core::package::{PackageManifest, PackageOptions, TemplatePackage};
+use crate::core::state::AppState;
 use crate::core::template::JasperTemplate;
-use crate::core::template_service::{TemplateLoader, TemplateSerializer, TemplateFormat};
-use crate::errors::Result;
+use crate::core::template_service::{LoadedTemplate, TemplateLoader, TemplateSerializer, TemplateFormat};
+use crate::errors::{AppError, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::RwLock;
 
 #[tauri::command]
-pub async fn load_jasper_template(file_path: String) -> Result<JasperTemplate> {
-    TemplateLoader::load(&file_path)
+pub async fn load_jasper_template(file_path: String, library: State<'_, ManagedTemplateLibrary>) -> Result<JasperTemplate> {
+    let template = TemplateLoader::load(&file_path)?;
+    record_recent(&library, &file_path).await;
+    Ok(template)
 }
 
+/// 加载模板并返回升级旧格式时执行的迁移
 #[tauri::command]
-pub async fn save_jasper_template(template: JasperTemplate, file_path: String) -> Result<()> {
+pub async fn load_template_with_migrations(
+    file_path: String,
+    library: State<'_, ManagedTemplateLibrary>,
+) -> Result<LoadedTemplate> {
+    let loaded = TemplateLoader::load_with_migrations(&file_path)?;
+    record_recent(&library, &file_path).await;
+    Ok(loaded)
+}
+
+/// 记入最近打开列表；失败不影响打开或保存本身
+async fn record_recent(library: &ManagedTemplateLibrary, file_path: &str) {
+    if let Err(e) = library.lock().await.record_opened(file_path) {
+        log::warn!("Failed to record recent template '{}': {}", file_path, e);
+    }
+}
+
+/// 导入 JRXML 模板，同时返回无法映射的结构列表
+#[tauri::command]
+pub async fn import_jrxml_template(file_path: String) -> Result<JrxmlImport> {
+    TemplateLoader::import_jrxml(&file_path)
+}
+
+/// 导出为 JRXML 文本，目标版本取自模板兼容性信息
+#[tauri::command]
+pub async fn export_template_jrxml(template: JasperTemplate) -> Result<String> {
+    jrxml::export_jrxml(&template)
+}
+
+#[tauri::command]
+pub async fn save_jasper_template(
+    template: JasperTemplate,
+    file_path: String,
+    library: State<'_, ManagedTemplateLibrary>,
+    state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
+) -> Result<()> {
     let serializer = TemplateSerializer::new();
-    serializer.save_to_file(&template, &file_path)
+    serializer.save_to_file(&template, &file_path)?;
+    record_recent(&library, &file_path).await;
+    mark_saved(&state, &journal).await;
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn save_template_as(
     template: JasperTemplate, 
     file_path: String, 
-    format: String
+    format: String,
+    library: State<'_, ManagedTemplateLibrary>,
+    state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
 ) -> Result<()> {
     let template_format = match format.as_str() {
         "json" => TemplateFormat::Json,
         "binary" => TemplateFormat::Binary,
         "jrxml" => TemplateFormat::Jrxml,
+        "package" => TemplateFormat::Package,
         _ => TemplateFormat::Json,
     };
     
-    TemplateLoader::save(&template, &file_path, template_format)
+    TemplateLoader::save(&template, &file_path, template_format)?;
+    record_recent(&library, &file_path).await;
+    mark_saved(&state, &journal).await;
+    Ok(())
+}
+
+/// 保存成功后清除修改标记和自动保存日志，崩溃后不再提示恢复已保存的内容
+async fn mark_saved(state: &RwLock<AppState>, journal: &ManagedSessionJournal) {
+    let mut app_state = state.write().await;
+    app_state.set_dirty(false);
+    reset_journal(journal, &mut app_state).await;
+}
+
+/// 保存为模板包，打包引用的图片、字体和样例数据快照
+#[tauri::command]
+pub async fn save_template_package(
+    request: SaveTemplatePackageReq,
+    library: State<'_, ManagedTemplateLibrary>,
+    state: State<'_, Arc<RwLock<AppState>>>,
+    journal: State<'_, ManagedSessionJournal>,
+) -> Result<PackageManifest> {
+    let options = PackageOptions {
+        base_dir: request
+            .base_dir
+            .map(PathBuf::from)
+            .or_else(|| Path::new(&request.file_path).parent().map(Path::to_path_buf)),
+        sample_data: request.sample_data,
+        font_dirs: Vec::new(),
+        system_fonts: true,
+    };
+    let package = TemplatePackage::pack(&request.template, &options)?;
+    TemplateLoader::save_package(&package, &request.file_path)?;
+    record_recent(&library, &request.file_path).await;
+    mark_saved(&state, &journal).await;
+    Ok(package.manifest)
+}
+
+/// 打开模板包，资源解包到缓存目录
+#[tauri::command]
+pub async fn open_template_package(file_path: String) -> Result<TemplatePackageContents> {
+    let package_data = std::fs::read(&file_path).map_err(|e| AppError::FileError {
+        message: format!("Failed to read template package '{}': {}", file_path, e),
+    })?;
+    let package = TemplatePackage::read(&package_data)?;
+    Ok(TemplatePackageContents {
+        template: package.extract_to_cache(&package_data)?,
+        sample_data: package.sample_data()?,
+        manifest: package.manifest,
+        migrations: package.migrations,
+    })
+}
+
+/// 生成模板签名密钥
+#[tauri::command]
+pub async fn generate_template_signing_key() -> Result<SigningKeyPair> {
+    binary::generate_signing_key()
+}
+
+/// 以二进制格式保存模板并生成分离签名文件
+#[tauri::command]
+pub async fn save_signed_template(template: JasperTemplate, file_path: String, secret_key: String) -> Result<BinarySignature> {
+    TemplateLoader::save_signed_binary(&template, &file_path, &secret_key)
+}
+
+/// 校验二进制模板的分离签名，可指定信任的公钥
+#[tauri::command]
+pub async fn verify_template_signature(file_path: String, trusted_key: Option<String>) -> Result<BinarySignature> {
+    TemplateLoader::verify_signed_binary(&file_path, trusted_key.as_deref())
 }
 
 #[tauri::command]
@@ -36,6 +163,16 @@ pub async fn validate_template(template: JasperTemplate) -> Result<bool> {
     Ok(true)
 }
 
+/// 检查模板并返回全部问题（问题面板），template_path 用于解析相对的图片路径
+#[tauri::command]
+pub async fn lint_template(template: JasperTemplate, template_path: Option<String>) -> Result<LintReport> {
+    let options = LintOptions {
+        base_dir: template_path.as_deref().and_then(|path| Path::new(path).parent()).map(Path::to_path_buf),
+        check_fonts: true,
+    };
+    Ok(lint::lint_template(&template, &options))
+}
+
 #[tauri::command]
 pub async fn create_empty_template() -> Result<JasperTemplate> {
     Ok(JasperTemplate::new())
@@ -64,6 +201,7 @@ pub async fn detect_template_format(file_path: String) -> Result<String> {
         TemplateFormat::Json => "json",
         TemplateFormat::Binary => "binary", 
         TemplateFormat::Jrxml => "jrxml",
+        TemplateFormat::Package => "package",
         TemplateFormat::Unknown => "unknown",
     };
     Ok(format_string.to_string())
@@ -101,6 +239,28 @@ pub async fn clone_template(template: JasperTemplate) -> Result<JasperTemplate>
 // === Template Information ===
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SaveTemplatePackageReq {
+    pub template: JasperTemplate,
+    pub file_path: String,
+    /// 相对图片路径的基准目录，默认为包文件所在目录
+    #[serde(default)]
+    pub base_dir: Option<String>,
+    /// 数据源ID -> 样例数据快照
+    #[serde(default)]
+    pub sample_data: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplatePackageContents {
+    pub template: JasperTemplate,
+    pub manifest: PackageManifest,
+    pub sample_data: BTreeMap<String, Value>,
+    pub migrations: Vec<AppliedMigration>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemplateInfo {
     pub version: String,
@@ -209,49 +369,20 @@ pub struct DataSourceSummary {
 }
 
 // === Template Utilities ===
+/// 比较两个模板，按元素、数据源、参数、变量、分组列出差异
+#[tauri::command]
+pub async fn diff_templates(base_template: JasperTemplate, other_template: JasperTemplate) -> Result<TemplateDiff> {
+    diff::diff_templates(&base_template, &other_template)
+}
+
+/// 以共同祖先 base 三方合并两份修改，冲突处保留 ours 并返回冲突列表
 #[tauri::command]
 pub async fn merge_templates(
-    base_template: JasperTemplate, 
-    overlay_template: JasperTemplate
-) -> Result<JasperTemplate> {
-    let mut merged = base_template.clone();
-    
-    // Merge elements with unique IDs
-    for mut element in overlay_template.elements {
-        element.id = uuid::Uuid::new_v4().to_string();
-        merged.elements.push(element);
-    }
-    
-    // Merge data sources
-    for data_source in overlay_template.data_sources {
-        if !merged.data_sources.iter().any(|ds| ds.id == data_source.id) {
-            merged.data_sources.push(data_source);
-        }
-    }
-    
-    // Merge parameters
-    for parameter in overlay_template.parameters {
-        if !merged.parameters.iter().any(|p| p.name == parameter.name) {
-            merged.parameters.push(parameter);
-        }
-    }
-    
-    // Merge variables
-    for variable in overlay_template.variables {
-        if !merged.variables.iter().any(|v| v.name == variable.name) {
-            merged.variables.push(variable);
-        }
-    }
-    
-    // Update metadata
-    merged.metadata.last_modified = chrono::Utc::now();
-    merged.metadata.description = Some(format!(
-        "Merged template (base: {}, overlay: {})",
-        base_template.metadata.description.unwrap_or_else(|| "Untitled".to_string()),
-        overlay_template.metadata.description.unwrap_or_else(|| "Untitled".to_string())
-    ));
-    
-    Ok(merged)
+    base_template: JasperTemplate,
+    our_template: JasperTemplate,
+    their_template: JasperTemplate,
+) -> Result<TemplateMerge> {
+    diff::merge_templates(&base_template, &our_template, &their_template)
 }
 
 #[tauri::command] 
@@ -316,4 +447,28 @@ mod tests {
         assert!(cloned.metadata.description.unwrap().contains("Copy"));
         assert_ne!(template.metadata.created_at, cloned.metadata.created_at);
     }
+
+    #[tokio::test]
+    async fn test_mark_saved_discards_recovery() {
+        use crate::core::element::{ElementContent, Position, ReportElement, Size};
+        use crate::core::journal::SessionJournal;
+
+        let data_dir = tempfile::TempDir::new().unwrap();
+        let journal: ManagedSessionJournal =
+            Arc::new(tokio::sync::Mutex::new(SessionJournal::start(data_dir.path()).unwrap()));
+        let state = RwLock::new(AppState::new());
+        {
+            let mut app_state = state.write().await;
+            let content = ElementContent::Rectangle { fill_color: None, border: None, corner_radius: None, opacity: None };
+            let element = ReportElement::new(content, Position::new(0.0, 0.0).unwrap(), Size::new(10.0, 10.0).unwrap()).unwrap();
+            app_state.add_element(element).unwrap();
+            journal.lock().await.record(&mut app_state).unwrap();
+        }
+
+        mark_saved(&state, &journal).await;
+        assert!(!state.read().await.is_dirty());
+        drop(journal);
+        let journal = SessionJournal::start(data_dir.path()).unwrap();
+        assert!(journal.recovery_info().unwrap().is_none());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,305 @@
+// === 条码和二维码 ===
+// 内容按码制编码为模块矩阵，输出为只含矩形子路径（M/H/V/Z）的 SVG path 和 text，
+// PNG、PDF 等各种导出都按矢量图形处理。
+use crate::core::element::{BarcodeConfig, BarcodeType, QrErrorCorrection};
+use rxing::datamatrix::encoder::SymbolShapeHint;
+use rxing::{BarcodeFormat, EncodeHints, MultiFormatWriter, Writer};
+use std::fmt::Write as _;
+use thiserror::Error;
+
+/// DataMatrix 最大符号（144×144）按字节计的安全容量，编码库在内容超出容量时会直接 panic
+const DATA_MATRIX_MAX_BYTES: usize = 1500;
+/// 一维码的条与下方文字的间距
+const TEXT_GAP: f64 = 2.0;
+/// 设计视图中内容为表达式的一维码显示的示例内容
+const LINEAR_SAMPLE: &str = "123456789012";
+const ERROR_COLOR: &str = "#d93025";
+
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum BarcodeError {
+    #[error("Barcode content is empty")]
+    Empty,
+
+    #[error("{symbology} cannot encode '{value}': {message}")]
+    InvalidContent { symbology: &'static str, value: String, message: String },
+}
+
+impl BarcodeType {
+    pub fn name(self) -> &'static str {
+        match self {
+            BarcodeType::QrCode => "QR Code",
+            BarcodeType::Code128 => "Code 128",
+            BarcodeType::Code39 => "Code 39",
+            BarcodeType::Ean13 => "EAN-13",
+            BarcodeType::Pdf417 => "PDF417",
+            BarcodeType::DataMatrix => "Data Matrix",
+        }
+    }
+
+    /// 一维码只有一行模块，下方可以显示内容文字
+    pub fn is_linear(self) -> bool {
+        matches!(self, BarcodeType::Code128 | BarcodeType::Code39 | BarcodeType::Ean13)
+    }
+
+    fn format(self) -> BarcodeFormat {
+        match self {
+            BarcodeType::QrCode => BarcodeFormat::QR_CODE,
+            BarcodeType::Code128 => BarcodeFormat::CODE_128,
+            BarcodeType::Code39 => BarcodeFormat::CODE_39,
+            BarcodeType::Ean13 => BarcodeFormat::EAN_13,
+            BarcodeType::Pdf417 => BarcodeFormat::PDF_417,
+            BarcodeType::DataMatrix => BarcodeFormat::DATA_MATRIX,
+        }
+    }
+}
+
+/// 编码后的模块矩阵，一维码只有一行
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub width: usize,
+    pub height: usize,
+    modules: Vec<bool>,
+}
+
+impl Symbol {
+    /// 是否为深色模块
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.width + x]
+    }
+
+    /// 每行连续深色模块的区间 `(行, 起始列, 结束列)`
+    fn runs(&self) -> Vec<(usize, usize, usize)> {
+        let mut runs = Vec::new();
+        for y in 0..self.height {
+            let mut x = 0;
+            while x < self.width {
+                if !self.is_dark(x, y) {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < self.width && self.is_dark(x, y) {
+                    x += 1;
+                }
+                runs.push((y, start, x));
+            }
+        }
+        runs
+    }
+}
+
+/// 按码制编码内容，字符集、长度、校验位或容量不符合码制规则时返回错误
+pub fn encode(barcode_type: BarcodeType, error_correction: QrErrorCorrection, value: &str) -> Result<Symbol, BarcodeError> {
+    if value.is_empty() {
+        return Err(BarcodeError::Empty);
+    }
+    let invalid = |message: String| BarcodeError::InvalidContent {
+        symbology: barcode_type.name(),
+        value: value.to_string(),
+        message,
+    };
+    if barcode_type == BarcodeType::DataMatrix && value.len() > DATA_MATRIX_MAX_BYTES {
+        return Err(invalid(format!("content exceeds {} bytes", DATA_MATRIX_MAX_BYTES)));
+    }
+
+    let hints = EncodeHints {
+        Margin: Some("0".to_string()),
+        ErrorCorrection: (barcode_type == BarcodeType::QrCode).then(|| format!("{:?}", error_correction)),
+        // 纯 ASCII 内容不写 ECI 标记，兼容只认默认字符集的扫码器
+        CharacterSet: (!value.is_ascii()).then(|| "UTF-8".to_string()),
+        DataMatrixShape: Some(SymbolShapeHint::FORCE_SQUARE),
+        DataMatrixCompact: Some(true),
+        ..EncodeHints::default()
+    };
+    let matrix = MultiFormatWriter
+        .encode_with_hints(value, &barcode_type.format(), 0, 0, &hints)
+        .map_err(|error| {
+            // 去掉 "IllegalArgumentException - " 之类的异常类型前缀
+            let message = error.to_string();
+            invalid(message.split_once(" - ").map_or(message.clone(), |(_, message)| message.to_string()))
+        })?;
+
+    let (width, height) = (matrix.getWidth() as usize, matrix.getHeight() as usize);
+    let modules = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| matrix.get(x as u32, y as u32))
+        .collect();
+    Ok(Symbol { width, height, modules })
+}
+
+/// 检查条码配置的字面内容能否按码制编码；内容为表达式时在填充后才能确定
+pub fn validate(config: &BarcodeConfig) -> Result<(), BarcodeError> {
+    if config.expression.as_deref().is_some_and(|expression| !expression.trim().is_empty()) {
+        return Ok(());
+    }
+    encode(config.barcode_type, config.error_correction, &config.value).map(|_| ())
+}
+
+/// 一维码下方显示的文字：12 位的 EAN-13 补上计算出的校验位
+pub fn display_text(barcode_type: BarcodeType, value: &str) -> String {
+    if barcode_type == BarcodeType::Ean13 && value.len() == 12 && value.bytes().all(|b| b.is_ascii_digit()) {
+        let sum: u32 = value
+            .bytes()
+            .enumerate()
+            .map(|(index, b)| u32::from(b - b'0') * if index % 2 == 0 { 1 } else { 3 })
+            .sum();
+        return format!("{}{}", value, (10 - sum % 10) % 10);
+    }
+    value.to_string()
+}
+
+/// 绘制条码，返回 SVG 片段：二维码保持正方形模块居中，一维码横向铺满元素宽度。
+/// 内容为未填充的表达式时显示示例，内容无法编码时显示错误提示
+pub fn render_svg(config: &BarcodeConfig, width: f64, height: f64) -> String {
+    let mut svg = String::new();
+    if let Some(background) = &config.background {
+        let _ = write!(svg, r#"<rect width="{}" height="{}" fill="{}"/>"#, width, height, escape(background));
+    }
+
+    let linear = config.barcode_type.is_linear();
+    let (value, caption) = match config.expression.as_deref().filter(|expression| !expression.trim().is_empty()) {
+        Some(expression) => {
+            let sample = if linear { LINEAR_SAMPLE.to_string() } else { expression.to_string() };
+            (sample, format!("[{}]", expression))
+        }
+        None => (config.value.clone(), display_text(config.barcode_type, &config.value)),
+    };
+    let symbol = match encode(config.barcode_type, config.error_correction, &value) {
+        Ok(symbol) => symbol,
+        Err(error) => {
+            let _ = write!(
+                svg,
+                r#"<rect width="{}" height="{}" fill="none" stroke="{}" stroke-width="1" stroke-dasharray="4,2"/><text x="{:.3}" y="{:.3}" font-family="{}" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="middle">{}</text>"#,
+                width,
+                height,
+                ERROR_COLOR,
+                width / 2.0,
+                height / 2.0,
+                escape(&config.font_family),
+                config.font_size,
+                ERROR_COLOR,
+                escape(&error.to_string())
+            );
+            return svg;
+        }
+    };
+
+    // 模块区域：一维码的条高扣除文字行，二维码按模块矩阵的宽高比缩放居中
+    let text_height = config.font_size + TEXT_GAP;
+    let show_text = linear && config.show_text && height > text_height * 2.0;
+    let (left, top, module_width, module_height) = if linear {
+        let bar_height = if show_text { height - text_height } else { height };
+        (0.0, 0.0, width / symbol.width as f64, bar_height)
+    } else {
+        let module = (width / symbol.width as f64).min(height / symbol.height as f64);
+        let (w, h) = (module * symbol.width as f64, module * symbol.height as f64);
+        ((width - w) / 2.0, (height - h) / 2.0, module, module)
+    };
+
+    let mut path = String::new();
+    for (row, start, end) in symbol.runs() {
+        let x = left + module_width * start as f64;
+        let y = top + module_height * row as f64;
+        let _ = write!(
+            path,
+            "M{:.3} {:.3}H{:.3}V{:.3}H{:.3}Z",
+            x,
+            y,
+            left + module_width * end as f64,
+            y + module_height,
+            x
+        );
+    }
+    let _ = write!(svg, r#"<path d="{}" fill="{}"/>"#, path, escape(&config.color));
+
+    if show_text {
+        let _ = write!(
+            svg,
+            r#"<text x="{:.3}" y="{:.3}" font-family="{}" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="hanging">{}</text>"#,
+            width / 2.0,
+            height - config.font_size,
+            escape(&config.font_family),
+            config.font_size,
+            escape(&config.color),
+            escape(&caption)
+        );
+    }
+    svg
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_validates_content_against_symbology() {
+        let qr = encode(BarcodeType::QrCode, QrErrorCorrection::H, "https://bank.example/verify?id=42").unwrap();
+        assert_eq!(qr.width, qr.height);
+        // 左上角定位图案
+        assert!(qr.is_dark(0, 0) && qr.is_dark(6, 6) && !qr.is_dark(1, 1));
+
+        let code128 = encode(BarcodeType::Code128, QrErrorCorrection::M, "SN-000123").unwrap();
+        assert_eq!(code128.height, 1);
+        // 起始符、字符集 B 的 S/N/-、切换到字符集 C、数字对 00/01/23、校验符各 11 个模块，终止符 13 个模块
+        assert_eq!(code128.width, 9 * 11 + 13);
+
+        assert_eq!(encode(BarcodeType::Ean13, QrErrorCorrection::M, "590123412345").unwrap().width, 95);
+        assert!(encode(BarcodeType::Ean13, QrErrorCorrection::M, "5901234123457").is_ok());
+        for (barcode_type, value) in [
+            (BarcodeType::Ean13, "5901234123458"),
+            (BarcodeType::Ean13, "12345"),
+            (BarcodeType::Code39, "编号"),
+            (BarcodeType::Code128, "编号"),
+        ] {
+            assert!(
+                matches!(encode(barcode_type, QrErrorCorrection::M, value), Err(BarcodeError::InvalidContent { .. })),
+                "{:?} accepted {}",
+                barcode_type,
+                value
+            );
+        }
+        assert_eq!(encode(BarcodeType::QrCode, QrErrorCorrection::M, ""), Err(BarcodeError::Empty));
+        let too_long = "x".repeat(DATA_MATRIX_MAX_BYTES + 1);
+        assert!(encode(BarcodeType::DataMatrix, QrErrorCorrection::M, &too_long).is_err());
+
+        let data_matrix = encode(BarcodeType::DataMatrix, QrErrorCorrection::M, "回单 2024-001").unwrap();
+        assert_eq!(data_matrix.width, data_matrix.height);
+        let pdf417 = encode(BarcodeType::Pdf417, QrErrorCorrection::M, "Hello").unwrap();
+        assert!(pdf417.width > pdf417.height);
+    }
+
+    #[test]
+    fn test_render_svg() {
+        assert_eq!(display_text(BarcodeType::Ean13, "590123412345"), "5901234123457");
+
+        let config = BarcodeConfig {
+            barcode_type: BarcodeType::Ean13,
+            value: "590123412345".to_string(),
+            ..BarcodeConfig::default()
+        };
+        let svg = format!("<svg>{}</svg>", render_svg(&config, 190.0, 60.0));
+        let document = roxmltree::Document::parse(&svg).unwrap();
+        let path = document.descendants().find(|node| node.has_tag_name("path")).unwrap();
+        // 每个模块 2pt，条高扣除文字行
+        assert!(path.attribute("d").unwrap().starts_with("M0.000 0.000H2.000V48.000H0.000Z"));
+        let text = document.descendants().find(|node| node.has_tag_name("text")).unwrap();
+        assert_eq!(text.text(), Some("5901234123457"));
+
+        // 表达式内容显示示例和表达式，无法编码的内容显示错误
+        let expression = BarcodeConfig { expression: Some("$F{serial}".to_string()), ..config.clone() };
+        assert!(render_svg(&expression, 190.0, 60.0).contains("[$F{serial}]"));
+        let invalid = BarcodeConfig { value: "ABC".to_string(), ..config };
+        assert!(render_svg(&invalid, 190.0, 60.0).contains("EAN-13 cannot encode"));
+
+        let qr = BarcodeConfig { value: "https://bank.example".to_string(), ..BarcodeConfig::default() };
+        let svg = render_svg(&qr, 100.0, 50.0);
+        assert!(svg.contains("<path") && !svg.contains("<text"));
+    }
+}
@@ -0,0 +1,309 @@
+// === Binary Template Format ===
+// v1: magic | version | u32 长度 | JSON
+// v2: magic | version | flags | u64 原始长度 | u64 数据长度 | CRC32(数据) | SHA-256(JSON) | 数据
+use std::io::{Read, Write};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::errors::{AppError, Result};
+
+pub const BINARY_MAGIC: &[u8; 8] = b"JASPER2\0";
+pub const BINARY_VERSION: u32 = 2;
+/// 分离签名文件的扩展名，追加在模板文件名之后（report.jbin.sig）
+pub const SIGNATURE_EXTENSION: &str = "sig";
+const SIGNATURE_ALGORITHM: &str = "ed25519";
+
+const V1_HEADER_LEN: usize = 16;
+const V2_HEADER_LEN: usize = 68;
+/// 数据经过 deflate 压缩
+const FLAG_DEFLATE: u32 = 1;
+
+/// 将模板 JSON 编码为 v2 二进制格式
+pub fn encode(json_data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(json_data).and_then(|_| encoder.flush()).map_err(|e| AppError::SerializationError {
+        message: format!("Failed to compress template: {}", e),
+    })?;
+    let payload = encoder.finish().map_err(|e| AppError::SerializationError {
+        message: format!("Failed to compress template: {}", e),
+    })?;
+
+    let mut binary_data = Vec::with_capacity(V2_HEADER_LEN + payload.len());
+    binary_data.extend_from_slice(BINARY_MAGIC);
+    binary_data.extend_from_slice(&BINARY_VERSION.to_le_bytes());
+    binary_data.extend_from_slice(&FLAG_DEFLATE.to_le_bytes());
+    binary_data.extend_from_slice(&(json_data.len() as u64).to_le_bytes());
+    binary_data.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    binary_data.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    binary_data.extend_from_slice(&Sha256::digest(json_data));
+    binary_data.extend_from_slice(&payload);
+    Ok(binary_data)
+}
+
+/// 解码 v1/v2 二进制格式，返回模板 JSON
+pub fn decode(binary_data: &[u8]) -> Result<Vec<u8>> {
+    if binary_data.len() < V1_HEADER_LEN {
+        return Err(invalid("too short"));
+    }
+    if &binary_data[0..8] != BINARY_MAGIC {
+        return Err(invalid("bad magic number"));
+    }
+
+    match read_u32(binary_data, 8) {
+        1 => decode_v1(binary_data),
+        2 => decode_v2(binary_data),
+        version => Err(AppError::SerializationError {
+            message: format!("Unsupported binary format version: {}", version),
+        }),
+    }
+}
+
+fn decode_v1(binary_data: &[u8]) -> Result<Vec<u8>> {
+    let data_size = read_u32(binary_data, 12) as usize;
+    binary_data
+        .get(V1_HEADER_LEN..V1_HEADER_LEN + data_size)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| invalid("incomplete data"))
+}
+
+fn decode_v2(binary_data: &[u8]) -> Result<Vec<u8>> {
+    if binary_data.len() < V2_HEADER_LEN {
+        return Err(invalid("too short"));
+    }
+    let flags = read_u32(binary_data, 12);
+    let json_len = read_u64(binary_data, 16);
+    let payload_len = read_u64(binary_data, 24);
+    let crc = read_u32(binary_data, 32);
+    let digest = &binary_data[36..68];
+
+    let payload = usize::try_from(payload_len)
+        .ok()
+        .and_then(|len| binary_data.get(V2_HEADER_LEN..V2_HEADER_LEN.checked_add(len)?))
+        .ok_or_else(|| invalid("incomplete data"))?;
+    if crc32fast::hash(payload) != crc {
+        return Err(invalid("checksum mismatch, the file is corrupted"));
+    }
+
+    let json_data = if flags & FLAG_DEFLATE != 0 {
+        let mut json_data = Vec::new();
+        DeflateDecoder::new(payload)
+            .take(json_len)
+            .read_to_end(&mut json_data)
+            .map_err(|e| invalid(&format!("failed to decompress: {}", e)))?;
+        json_data
+    } else {
+        payload.to_vec()
+    };
+
+    if json_data.len() as u64 != json_len || Sha256::digest(&json_data).as_slice() != digest {
+        return Err(invalid("content hash mismatch, the file is corrupted"));
+    }
+    Ok(json_data)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn invalid(reason: &str) -> AppError {
+    AppError::SerializationError {
+        message: format!("Invalid binary format: {}", reason),
+    }
+}
+
+// === Detached Signature ===
+
+/// 签名密钥对（十六进制）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningKeyPair {
+    pub public_key: String,
+    pub secret_key: String,
+}
+
+/// 分离签名，保存在模板文件旁的 `.sig` 文件中
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BinarySignature {
+    pub algorithm: String,
+    /// 签名者公钥（十六进制）
+    pub public_key: String,
+    /// 被签名文件的 SHA-256（十六进制）
+    pub sha256: String,
+    pub signature: String,
+    pub signed_at: DateTime<Utc>,
+}
+
+/// 生成新的 Ed25519 签名密钥
+pub fn generate_signing_key() -> Result<SigningKeyPair> {
+    let mut seed = [0u8; 32];
+    getrandom::getrandom(&mut seed).map_err(|e| AppError::ConfigError {
+        message: format!("Failed to generate signing key: {}", e),
+    })?;
+    let key = SigningKey::from_bytes(&seed);
+    Ok(SigningKeyPair {
+        public_key: to_hex(key.verifying_key().as_bytes()),
+        secret_key: to_hex(&seed),
+    })
+}
+
+/// 对文件内容的 SHA-256 签名
+pub fn sign(data: &[u8], secret_key: &str) -> Result<BinarySignature> {
+    let seed: [u8; 32] = from_hex(secret_key)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| signature_error("Invalid secret key"))?;
+    let key = SigningKey::from_bytes(&seed);
+    let digest = Sha256::digest(data);
+
+    Ok(BinarySignature {
+        algorithm: SIGNATURE_ALGORITHM.to_string(),
+        public_key: to_hex(key.verifying_key().as_bytes()),
+        sha256: to_hex(&digest),
+        signature: to_hex(&key.sign(&digest).to_bytes()),
+        signed_at: Utc::now(),
+    })
+}
+
+/// 校验分离签名；指定 `trusted_key` 时还要求签名者为该公钥
+pub fn verify(data: &[u8], signature: &BinarySignature, trusted_key: Option<&str>) -> Result<()> {
+    if signature.algorithm != SIGNATURE_ALGORITHM {
+        return Err(signature_error(&format!("Unsupported signature algorithm '{}'", signature.algorithm)));
+    }
+    if let Some(trusted_key) = trusted_key {
+        if !trusted_key.trim().eq_ignore_ascii_case(&signature.public_key) {
+            return Err(signature_error("Template was signed by an untrusted key"));
+        }
+    }
+
+    let digest = Sha256::digest(data);
+    if to_hex(&digest) != signature.sha256.to_ascii_lowercase() {
+        return Err(signature_error("Template content does not match its signature"));
+    }
+
+    let public_key: [u8; 32] = from_hex(&signature.public_key)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| signature_error("Invalid public key"))?;
+    let signature_bytes: [u8; 64] = from_hex(&signature.signature)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| signature_error("Invalid signature encoding"))?;
+    let key = VerifyingKey::from_bytes(&public_key).map_err(|_| signature_error("Invalid public key"))?;
+    key.verify(&digest, &Signature::from_bytes(&signature_bytes))
+        .map_err(|_| signature_error("Signature verification failed"))
+}
+
+/// 模板文件对应的签名文件路径
+pub fn signature_path(file_path: &str) -> String {
+    format!("{}.{}", file_path, SIGNATURE_EXTENSION)
+}
+
+fn signature_error(message: &str) -> AppError {
+    AppError::ValidationError {
+        message: message.to_string(),
+    }
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim();
+    if text.len() % 2 != 0 || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&text[index..index + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_json() -> Vec<u8> {
+        let elements: Vec<String> = (0..200)
+            .map(|index| format!(r#"{{"id":"element-{}","text":"Invoice line {}"}}"#, index, index))
+            .collect();
+        format!(r#"{{"elements":[{}]}}"#, elements.join(",")).into_bytes()
+    }
+
+    #[test]
+    fn test_v2_round_trip_is_compressed() {
+        let json_data = sample_json();
+        let binary_data = encode(&json_data).unwrap();
+        assert!(binary_data.starts_with(BINARY_MAGIC));
+        assert_eq!(read_u32(&binary_data, 8), BINARY_VERSION);
+        assert!(binary_data.len() < json_data.len() / 4);
+        assert_eq!(decode(&binary_data).unwrap(), json_data);
+    }
+
+    #[test]
+    fn test_reads_v1() {
+        let json_data = sample_json();
+        let mut binary_data = BINARY_MAGIC.to_vec();
+        binary_data.extend_from_slice(&1u32.to_le_bytes());
+        binary_data.extend_from_slice(&(json_data.len() as u32).to_le_bytes());
+        binary_data.extend_from_slice(&json_data);
+        assert_eq!(decode(&binary_data).unwrap(), json_data);
+
+        binary_data.truncate(100);
+        assert!(decode(&binary_data).is_err());
+    }
+
+    #[test]
+    fn test_detects_corruption() {
+        let binary_data = encode(&sample_json()).unwrap();
+
+        let mut corrupted = binary_data.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xff;
+        assert!(decode(&corrupted).unwrap_err().to_string().contains("checksum mismatch"));
+
+        // CRC 正确但内容哈希不符
+        let mut corrupted = binary_data.clone();
+        corrupted[40] ^= 0xff;
+        assert!(decode(&corrupted).unwrap_err().to_string().contains("content hash mismatch"));
+
+        assert!(decode(&binary_data[..binary_data.len() - 10]).is_err());
+
+        let mut future = binary_data;
+        future[8] = 9;
+        assert!(decode(&future).unwrap_err().to_string().contains("version: 9"));
+    }
+
+    #[test]
+    fn test_signature() {
+        let binary_data = encode(&sample_json()).unwrap();
+        let keys = generate_signing_key().unwrap();
+        let signature = sign(&binary_data, &keys.secret_key).unwrap();
+        assert_eq!(signature.public_key, keys.public_key);
+
+        verify(&binary_data, &signature, None).unwrap();
+        verify(&binary_data, &signature, Some(&keys.public_key.to_uppercase())).unwrap();
+
+        let other = generate_signing_key().unwrap();
+        assert!(verify(&binary_data, &signature, Some(&other.public_key)).is_err());
+
+        let mut tampered = binary_data.clone();
+        tampered[70] ^= 1;
+        assert!(verify(&tampered, &signature, None).is_err());
+
+        // 伪造摘要和公钥也无法通过
+        let mut forged = sign(&tampered, &other.secret_key).unwrap();
+        forged.public_key = keys.public_key.clone();
+        assert!(verify(&tampered, &forged, None).is_err());
+
+        assert!(sign(&binary_data, "not-a-key").is_err());
+        assert_eq!(signature_path("a/report.jbin"), "a/report.jbin.sig");
+    }
+}
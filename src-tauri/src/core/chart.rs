@@ -0,0 +1,610 @@
+// === 图表渲染 ===
+// 图表配置和数据绘制为 SVG 片段，坐标相对于元素左上角。只输出 rect、line、circle、text
+// 和仅含 M/L/C/Z 命令的 path，PDF 导出可以逐个转换为矢量绘图指令。
+use crate::core::element::{ChartConfig, ChartData, ChartSeries, ChartType};
+use crate::format::Formatter;
+use serde_json::Value;
+use std::f64::consts::{FRAC_PI_2, PI};
+use std::fmt::Write;
+
+/// 图表四周留白
+const PADDING: f64 = 8.0;
+const GRID_COLOR: &str = "#e0e0e0";
+const AXIS_COLOR: &str = "#666666";
+/// 环形图内径与外径之比
+const DONUT_RATIO: f64 = 0.55;
+
+impl ChartData {
+    /// 设计视图用的示例数据：四个分类，有系列表达式时两个系列
+    pub fn sample(config: &ChartConfig) -> Self {
+        let names: Vec<String> = if config.series_expression.is_some() {
+            vec!["Series 1".to_string(), "Series 2".to_string()]
+        } else {
+            vec![series_name(&config.value_expression)]
+        };
+        let values = [[40.0, 65.0, 50.0, 80.0], [25.0, 35.0, 45.0, 30.0]];
+        let series = names
+            .into_iter()
+            .zip(values)
+            .map(|(name, values)| ChartSeries {
+                name,
+                values: if config.chart_type == ChartType::Scatter { Vec::new() } else { values.iter().copied().map(Some).collect() },
+                points: if config.chart_type == ChartType::Scatter {
+                    values.iter().enumerate().map(|(index, value)| [10.0 + 20.0 * index as f64 + value / 5.0, *value]).collect()
+                } else {
+                    Vec::new()
+                },
+            })
+            .collect();
+        let categories = if config.chart_type == ChartType::Scatter {
+            Vec::new()
+        } else {
+            ["Q1", "Q2", "Q3", "Q4"].iter().map(|category| category.to_string()).collect()
+        };
+        Self { categories, series, locale: None }
+    }
+}
+
+/// 没有系列表达式时唯一系列的名称：`$F{amount}` 取字段名，其余取表达式本身
+pub fn series_name(value_expression: &str) -> String {
+    let expression = value_expression.trim();
+    let name = expression.strip_prefix("$F{").and_then(|rest| rest.strip_suffix('}')).unwrap_or(expression);
+    if name.is_empty() { "Value".to_string() } else { name.to_string() }
+}
+
+/// 绘制图表，返回 SVG 片段
+pub fn render_svg(config: &ChartConfig, data: &ChartData, width: f64, height: f64) -> String {
+    let mut chart = Chart {
+        config,
+        data,
+        formatter: data.locale.as_deref().map_or_else(Formatter::default, Formatter::new),
+        svg: String::new(),
+    };
+    let mut area = Area { left: PADDING, top: PADDING, right: width - PADDING, bottom: height - PADDING };
+    chart.title(&mut area, width);
+    chart.legend(&mut area);
+    if area.right - area.left <= 0.0 || area.bottom - area.top <= 0.0 {
+        return chart.svg;
+    }
+    match config.chart_type {
+        ChartType::Pie | ChartType::Donut => chart.pie(&area),
+        ChartType::Scatter => chart.scatter(area),
+        _ => chart.categorical(area),
+    }
+    chart.svg
+}
+
+/// 绘图区域
+#[derive(Debug, Clone, Copy)]
+struct Area {
+    left: f64,
+    top: f64,
+    right: f64,
+    bottom: f64,
+}
+
+impl Area {
+    fn width(&self) -> f64 {
+        self.right - self.left
+    }
+
+    fn height(&self) -> f64 {
+        self.bottom - self.top
+    }
+}
+
+/// 坐标轴刻度：从 `min` 到 `max`，间隔 `step`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scale {
+    min: f64,
+    max: f64,
+    step: f64,
+}
+
+impl Scale {
+    /// 包含 `low..high` 的整齐刻度，间隔取 1、2、5 乘以 10 的幂，刻度数约为 `count`
+    fn nice(low: f64, high: f64, count: usize) -> Self {
+        let (low, high) = if high > low { (low, high) } else { (low, low + 1.0) };
+        let raw = (high - low) / count.max(1) as f64;
+        let magnitude = 10f64.powf(raw.log10().floor());
+        let step = [1.0, 2.0, 5.0, 10.0]
+            .iter()
+            .map(|factor| factor * magnitude)
+            .find(|step| *step >= raw)
+            .unwrap_or(10.0 * magnitude);
+        Self { min: (low / step).floor() * step, max: (high / step).ceil() * step, step }
+    }
+
+    fn ticks(&self) -> Vec<f64> {
+        let count = ((self.max - self.min) / self.step).round() as usize;
+        (0..=count).map(|index| self.min + self.step * index as f64).collect()
+    }
+
+    /// 值在 `from..to` 区间上的位置
+    fn map(&self, value: f64, from: f64, to: f64) -> f64 {
+        from + (value - self.min) / (self.max - self.min) * (to - from)
+    }
+}
+
+/// 文字的对齐方式和样式
+struct Label<'s> {
+    anchor: &'static str,
+    baseline: &'static str,
+    color: &'s str,
+    size: f64,
+    bold: bool,
+}
+
+struct Chart<'a> {
+    config: &'a ChartConfig,
+    data: &'a ChartData,
+    formatter: Formatter,
+    svg: String,
+}
+
+impl<'a> Chart<'a> {
+    /// 图表字号和文字颜色的常规文字
+    fn label(&self, anchor: &'static str, baseline: &'static str) -> Label<'a> {
+        let config: &'a ChartConfig = self.config;
+        Label { anchor, baseline, color: &config.text_color, size: config.font_size, bold: false }
+    }
+
+    fn color(&self, index: usize) -> &'a str {
+        let config: &'a ChartConfig = self.config;
+        match config.palette.len() {
+            0 => "#4e79a7",
+            len => &config.palette[index % len],
+        }
+    }
+
+    fn format(&self, value: f64) -> String {
+        let value = if value.fract() == 0.0 && value.abs() < 1e15 {
+            Value::from(value as i64)
+        } else {
+            serde_json::Number::from_f64(value).map_or(Value::Null, Value::Number)
+        };
+        self.formatter.format(&value, self.config.value_format.as_deref())
+    }
+
+    fn text(&mut self, x: f64, y: f64, text: &str, label: Label) {
+        let _ = write!(
+            self.svg,
+            r#"<text x="{:.2}" y="{:.2}" font-family="{}" font-size="{}" font-weight="{}" fill="{}" text-anchor="{}" dominant-baseline="{}">{}</text>"#,
+            x,
+            y,
+            escape(&self.config.font_family),
+            label.size,
+            if label.bold { "bold" } else { "normal" },
+            escape(label.color),
+            label.anchor,
+            label.baseline,
+            escape(text)
+        );
+    }
+
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, color: &str) {
+        let _ = write!(
+            self.svg,
+            r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}" stroke-width="1"/>"#,
+            x1, y1, x2, y2, color
+        );
+    }
+
+    fn title(&mut self, area: &mut Area, width: f64) {
+        let Some(title) = self.config.title.clone().filter(|title| !title.trim().is_empty()) else {
+            return;
+        };
+        let label = Label { size: self.config.font_size * 1.2, bold: true, ..self.label("middle", "hanging") };
+        let top = area.top;
+        area.top += label.size * 1.5;
+        self.text(width / 2.0, top, &title, label);
+    }
+
+    /// 图例放在底部，一行放不下时换行；饼图按分类，其余按系列
+    fn legend(&mut self, area: &mut Area) {
+        if !self.config.show_legend {
+            return;
+        }
+        let data: &'a ChartData = self.data;
+        let pie = matches!(self.config.chart_type, ChartType::Pie | ChartType::Donut);
+        let names: Vec<&str> = if pie {
+            data.categories.iter().map(String::as_str).collect()
+        } else {
+            data.series.iter().map(|series| series.name.as_str()).collect()
+        };
+        if names.is_empty() {
+            return;
+        }
+
+        let size = self.config.font_size;
+        let swatch = size * 0.8;
+        let line_height = size * 1.5;
+        let mut lines: Vec<Vec<(usize, f64)>> = vec![Vec::new()];
+        let mut x = area.left;
+        for (index, name) in names.iter().enumerate() {
+            let entry = swatch + 4.0 + text_width(name, size) + 12.0;
+            if x + entry > area.right && !lines.last().is_some_and(Vec::is_empty) {
+                lines.push(Vec::new());
+                x = area.left;
+            }
+            lines.last_mut().unwrap().push((index, x));
+            x += entry;
+        }
+
+        let top = area.bottom - line_height * lines.len() as f64;
+        for (line, entries) in lines.iter().enumerate() {
+            let y = top + line_height * (line as f64 + 0.5);
+            for &(index, x) in entries {
+                let fill = self.color(index);
+                let _ = write!(
+                    self.svg,
+                    r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}"/>"#,
+                    x,
+                    y - swatch / 2.0,
+                    swatch,
+                    swatch,
+                    escape(fill)
+                );
+                self.text(x + swatch + 4.0, y, names[index], self.label("start", "middle"));
+            }
+        }
+        area.bottom = top - 4.0;
+    }
+
+    /// 饼图/环形图：取第一个系列，扇区从 12 点钟方向顺时针排列，负值和空值忽略
+    fn pie(&mut self, area: &Area) {
+        let Some(series) = self.data.series.first() else {
+            return;
+        };
+        let values: Vec<f64> = series.values.iter().map(|value| value.unwrap_or(0.0).max(0.0)).collect();
+        let total: f64 = values.iter().sum();
+        if total <= 0.0 {
+            return;
+        }
+
+        let (cx, cy) = ((area.left + area.right) / 2.0, (area.top + area.bottom) / 2.0);
+        let radius = area.width().min(area.height()) / 2.0;
+        let inner = if self.config.chart_type == ChartType::Donut { radius * DONUT_RATIO } else { 0.0 };
+        let mut start = -FRAC_PI_2;
+        let mut labels = Vec::new();
+        for (index, value) in values.iter().enumerate() {
+            if *value <= 0.0 {
+                continue;
+            }
+            let end = start + value / total * 2.0 * PI;
+            let mut path = String::new();
+            if inner > 0.0 {
+                let (x, y) = polar(cx, cy, radius, start);
+                let _ = write!(path, "M{:.2} {:.2}", x, y);
+                arc(&mut path, cx, cy, radius, start, end);
+                let (x, y) = polar(cx, cy, inner, end);
+                let _ = write!(path, "L{:.2} {:.2}", x, y);
+                arc(&mut path, cx, cy, inner, end, start);
+            } else {
+                let (x, y) = polar(cx, cy, radius, start);
+                let _ = write!(path, "M{:.2} {:.2}L{:.2} {:.2}", cx, cy, x, y);
+                arc(&mut path, cx, cy, radius, start, end);
+            }
+            path.push('Z');
+            let fill = escape(self.color(index));
+            let _ = write!(self.svg, r##"<path d="{}" fill="{}" stroke="#ffffff" stroke-width="1"/>"##, path, fill);
+            let middle = (start + end) / 2.0;
+            labels.push((polar(cx, cy, if inner > 0.0 { (radius + inner) / 2.0 } else { radius * 0.65 }, middle), *value));
+            start = end;
+        }
+
+        if self.config.show_labels {
+            for ((x, y), value) in labels {
+                let text = self.format(value);
+                let label = Label { color: "#ffffff", bold: true, ..self.label("middle", "middle") };
+                self.text(x, y, &text, label);
+            }
+        }
+    }
+
+    /// 数值轴：网格线和刻度标签，返回扣除刻度标签宽度后的绘图区域
+    fn value_axis(&mut self, mut area: Area, scale: &Scale) -> Area {
+        if !self.config.show_axes {
+            return area;
+        }
+        let size = self.config.font_size;
+        let labels: Vec<(f64, String)> = scale.ticks().into_iter().map(|tick| (tick, self.format(tick))).collect();
+        let label_width = labels.iter().map(|(_, label)| text_width(label, size)).fold(0.0, f64::max);
+        area.left += label_width + 6.0;
+        for (tick, label) in labels {
+            let y = scale.map(tick, area.bottom, area.top);
+            self.line(area.left, y, area.right, y, GRID_COLOR);
+            self.text(area.left - 4.0, y, &label, self.label("end", "middle"));
+        }
+        self.line(area.left, area.top, area.left, area.bottom, AXIS_COLOR);
+        area
+    }
+
+    /// 柱状图、堆叠柱状图、折线图和面积图：分类均匀分布在 X 轴上
+    fn categorical(&mut self, mut area: Area) {
+        let data: &'a ChartData = self.data;
+        let count = data.categories.len();
+        let stacked = self.config.chart_type == ChartType::StackedBar;
+        let value = |series: &ChartSeries, index: usize| series.values.get(index).copied().flatten();
+        let (mut low, mut high) = (0.0f64, 0.0f64);
+        for index in 0..count {
+            let values = data.series.iter().filter_map(|series| value(series, index));
+            if stacked {
+                let (positive, negative) = values.fold((0.0, 0.0), |(p, n), v| if v >= 0.0 { (p + v, n) } else { (p, n + v) });
+                high = high.max(positive);
+                low = low.min(negative);
+            } else {
+                for v in values {
+                    high = high.max(v);
+                    low = low.min(v);
+                }
+            }
+        }
+
+        let size = self.config.font_size;
+        if self.config.show_axes {
+            area.bottom -= size * 1.6;
+        }
+        if self.config.show_labels {
+            area.top += size * 1.2;
+        }
+        let ticks = ((area.height() / (size * 3.0)) as usize).clamp(2, 10);
+        let scale = Scale::nice(low, high, ticks);
+        let area = self.value_axis(area, &scale);
+        if count == 0 || area.width() <= 0.0 {
+            return;
+        }
+
+        let band = area.width() / count as f64;
+        let y = |value: f64| scale.map(value, area.bottom, area.top);
+        let center = |index: usize| area.left + band * (index as f64 + 0.5);
+        if self.config.show_axes {
+            // 分类标签过密时隔几个显示一个
+            let widest = data.categories.iter().map(|category| text_width(category, size)).fold(0.0, f64::max);
+            let stride = ((widest + 4.0) / band).ceil().max(1.0) as usize;
+            for index in (0..count).step_by(stride) {
+                self.text(center(index), area.bottom + 4.0, &data.categories[index], self.label("middle", "hanging"));
+            }
+        }
+
+        let mut labels: Vec<(f64, f64, f64)> = Vec::new();
+        match self.config.chart_type {
+            ChartType::Bar | ChartType::StackedBar => {
+                let series_count = data.series.len().max(1);
+                let bar_width = if stacked { band * 0.6 } else { band * 0.8 / series_count as f64 };
+                let mut stacks = vec![(0.0, 0.0); count];
+                for (series_index, series) in data.series.iter().enumerate() {
+                    let fill = escape(self.color(series_index));
+                    for (index, stack) in stacks.iter_mut().enumerate() {
+                        let Some(v) = value(series, index) else {
+                            continue;
+                        };
+                        let (from, to, x) = if stacked {
+                            let base = if v >= 0.0 { &mut stack.0 } else { &mut stack.1 };
+                            let from = *base;
+                            *base += v;
+                            (from, *base, center(index) - bar_width / 2.0)
+                        } else {
+                            (0.0, v, area.left + band * index as f64 + band * 0.1 + bar_width * series_index as f64)
+                        };
+                        let (top, bottom) = (y(from.max(to)), y(from.min(to)));
+                        let _ = write!(
+                            self.svg,
+                            r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}"/>"#,
+                            x,
+                            top,
+                            bar_width,
+                            (bottom - top).max(0.0),
+                            fill
+                        );
+                        labels.push((x + bar_width / 2.0, if stacked { (top + bottom) / 2.0 } else { y(to) }, v));
+                    }
+                }
+            }
+            ChartType::Line | ChartType::Area => {
+                for (series_index, series) in data.series.iter().enumerate() {
+                    let stroke = escape(self.color(series_index));
+                    // 空值处断开，每段连续的点单独成线
+                    let mut runs: Vec<Vec<(f64, f64, f64)>> = vec![Vec::new()];
+                    for index in 0..count {
+                        match value(series, index) {
+                            Some(v) => runs.last_mut().unwrap().push((center(index), y(v), v)),
+                            None if !runs.last().unwrap().is_empty() => runs.push(Vec::new()),
+                            None => {}
+                        }
+                    }
+                    for run in runs.iter().filter(|run| !run.is_empty()) {
+                        let line: String = run
+                            .iter()
+                            .enumerate()
+                            .map(|(index, (x, y, _))| format!("{}{:.2} {:.2}", if index == 0 { 'M' } else { 'L' }, x, y))
+                            .collect();
+                        if self.config.chart_type == ChartType::Area {
+                            let base = y(0.0);
+                            let _ = write!(
+                                self.svg,
+                                r#"<path d="{}L{:.2} {:.2}L{:.2} {:.2}Z" fill="{}" fill-opacity="0.35"/>"#,
+                                line,
+                                run[run.len() - 1].0,
+                                base,
+                                run[0].0,
+                                base,
+                                stroke
+                            );
+                        }
+                        let _ = write!(self.svg, r#"<path d="{}" fill="none" stroke="{}" stroke-width="2"/>"#, line, stroke);
+                        for (x, y, v) in run {
+                            let _ = write!(self.svg, r#"<circle cx="{:.2}" cy="{:.2}" r="2.5" fill="{}"/>"#, x, y, stroke);
+                            labels.push((*x, *y, *v));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.line(area.left, y(0.0), area.right, y(0.0), AXIS_COLOR);
+
+        if self.config.show_labels {
+            for (x, label_y, v) in labels {
+                let text = self.format(v);
+                let (label_y, label) = if stacked {
+                    (label_y, Label { color: "#ffffff", ..self.label("middle", "middle") })
+                } else if v >= 0.0 {
+                    (label_y - 3.0, self.label("middle", "auto"))
+                } else {
+                    (label_y + 3.0, self.label("middle", "hanging"))
+                };
+                self.text(x, label_y, &text, Label { size: size * 0.9, ..label });
+            }
+        }
+    }
+
+    /// 散点图：X、Y 都是数值轴
+    fn scatter(&mut self, mut area: Area) {
+        let data: &'a ChartData = self.data;
+        let points = data.series.iter().flat_map(|series| series.points.iter());
+        let (x_range, y_range) = points.fold(((f64::MAX, f64::MIN), (f64::MAX, f64::MIN)), |(x, y), [px, py]| {
+            ((x.0.min(*px), x.1.max(*px)), (y.0.min(*py), y.1.max(*py)))
+        });
+        if x_range.0 > x_range.1 {
+            return;
+        }
+
+        let size = self.config.font_size;
+        if self.config.show_axes {
+            area.bottom -= size * 1.6;
+        }
+        if self.config.show_labels {
+            area.top += size * 1.2;
+        }
+        let y_scale = Scale::nice(y_range.0.min(0.0), y_range.1, ((area.height() / (size * 3.0)) as usize).clamp(2, 10));
+        let area = self.value_axis(area, &y_scale);
+        if area.width() <= 0.0 {
+            return;
+        }
+        let x_scale = Scale::nice(x_range.0, x_range.1, ((area.width() / (size * 6.0)) as usize).clamp(2, 10));
+        if self.config.show_axes {
+            for tick in x_scale.ticks() {
+                let x = x_scale.map(tick, area.left, area.right);
+                let label = self.format(tick);
+                self.line(x, area.top, x, area.bottom, GRID_COLOR);
+                self.text(x, area.bottom + 4.0, &label, self.label("middle", "hanging"));
+            }
+        }
+        self.line(area.left, area.bottom, area.right, area.bottom, AXIS_COLOR);
+
+        for (series_index, series) in data.series.iter().enumerate() {
+            let fill = escape(self.color(series_index));
+            for [px, py] in &series.points {
+                let (x, y) = (x_scale.map(*px, area.left, area.right), y_scale.map(*py, area.bottom, area.top));
+                let _ = write!(self.svg, r#"<circle cx="{:.2}" cy="{:.2}" r="3" fill="{}" fill-opacity="0.8"/>"#, x, y, fill);
+                if self.config.show_labels {
+                    let text = self.format(*py);
+                    self.text(x, y - 5.0, &text, Label { size: size * 0.9, ..self.label("middle", "auto") });
+                }
+            }
+        }
+    }
+}
+
+fn polar(cx: f64, cy: f64, radius: f64, angle: f64) -> (f64, f64) {
+    (cx + radius * angle.cos(), cy + radius * angle.sin())
+}
+
+/// 圆弧拆成不超过 90° 的三次贝塞尔曲线段，起点为当前点
+fn arc(path: &mut String, cx: f64, cy: f64, radius: f64, start: f64, end: f64) {
+    let segments = ((end - start).abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+    let delta = (end - start) / segments as f64;
+    let k = 4.0 / 3.0 * (delta / 4.0).tan() * radius;
+    for index in 0..segments {
+        let a0 = start + delta * index as f64;
+        let a1 = a0 + delta;
+        let (x0, y0) = polar(cx, cy, radius, a0);
+        let (x1, y1) = polar(cx, cy, radius, a1);
+        let _ = write!(
+            path,
+            "C{:.2} {:.2} {:.2} {:.2} {:.2} {:.2}",
+            x0 - k * a0.sin(),
+            y0 + k * a0.cos(),
+            x1 + k * a1.sin(),
+            y1 - k * a1.cos(),
+            x1,
+            y1
+        );
+    }
+}
+
+/// 估算文本宽度：中日韩字符按一个字号，其余按 0.55 个字号
+fn text_width(text: &str, size: f64) -> f64 {
+    text.chars().map(|c| if c >= '\u{2e80}' { size } else { size * 0.55 }).sum()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nice_scale() {
+        assert_eq!(Scale::nice(0.0, 83.0, 5), Scale { min: 0.0, max: 100.0, step: 20.0 });
+        assert_eq!(Scale::nice(-3.0, 7.0, 5), Scale { min: -4.0, max: 8.0, step: 2.0 });
+        assert_eq!(Scale::nice(0.0, 0.0, 4).ticks(), vec![0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_render_all_chart_types() {
+        for chart_type in [
+            ChartType::Bar,
+            ChartType::StackedBar,
+            ChartType::Line,
+            ChartType::Area,
+            ChartType::Pie,
+            ChartType::Donut,
+            ChartType::Scatter,
+        ] {
+            let config = ChartConfig {
+                chart_type,
+                series_expression: Some("$F{region}".to_string()),
+                title: Some("Sales & Returns".to_string()),
+                show_labels: true,
+                ..ChartConfig::default()
+            };
+            let svg = format!("<svg>{}</svg>", render_svg(&config, &ChartData::sample(&config), 300.0, 200.0));
+            let document = roxmltree::Document::parse(&svg).unwrap();
+            let shapes = document
+                .descendants()
+                .filter(|node| matches!(node.tag_name().name(), "rect" | "path" | "circle"))
+                .count();
+            assert!(shapes > 2, "{:?} drew no shapes", chart_type);
+            assert!(svg.contains("Sales &amp; Returns"));
+            // 只使用 PDF 导出支持的路径命令
+            for node in document.descendants().filter(|node| node.has_tag_name("path")) {
+                assert!(node.attribute("d").unwrap().chars().filter(char::is_ascii_alphabetic).all(|c| "MLCZ".contains(c)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bar_heights_follow_values() {
+        let config = ChartConfig { show_axes: false, show_legend: false, ..ChartConfig::default() };
+        let data = ChartData {
+            categories: vec!["A".to_string(), "B".to_string()],
+            series: vec![ChartSeries { name: "v".to_string(), values: vec![Some(50.0), Some(100.0)], points: Vec::new() }],
+            locale: None,
+        };
+        let svg = format!("<svg>{}</svg>", render_svg(&config, &data, 116.0, 116.0));
+        let document = roxmltree::Document::parse(&svg).unwrap();
+        let heights: Vec<f64> = document
+            .descendants()
+            .filter(|node| node.has_tag_name("rect"))
+            .map(|node| node.attribute("height").unwrap().parse().unwrap())
+            .collect();
+        assert_eq!(heights, vec![50.0, 100.0]);
+    }
+}
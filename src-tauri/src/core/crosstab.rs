@@ -0,0 +1,263 @@
+// === 交叉表网格 ===
+// 汇总结果到单元格的布局，填充引擎和设计视图共用
+use crate::core::element::{CrosstabConfig, CrosstabGroup, GridCell, GridStyle, ReportElement, TextAlign};
+use serde_json::Value;
+
+/// 交叉表汇总结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Crosstab {
+    /// 行表头条目（组值前缀），按显示顺序排列；前缀短于行组数的是小计，空前缀是总计
+    pub rows: Vec<Vec<Value>>,
+    /// 列表头条目，规则同行表头
+    pub columns: Vec<Vec<Value>>,
+    /// 单元格值，按 `[行][列][度量]` 排列
+    pub values: Vec<Vec<Vec<Value>>>,
+}
+
+/// 交叉表展开后的单元格，坐标相对于交叉表左上角
+pub struct CrosstabGrid {
+    pub elements: Vec<ReportElement>,
+    /// 列表头（含左上角标题区）单元格，跨页时重复
+    pub header: Vec<ReportElement>,
+    pub header_height: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// 按分组顺序排好的完整组值插入小计和总计条目：每组结束后输出该组的小计，
+/// 没有分组时只有一个总计条目
+pub fn with_totals(groups: &[CrosstabGroup], keys: Vec<Vec<Value>>) -> Vec<Vec<Value>> {
+    if groups.is_empty() {
+        return vec![Vec::new()];
+    }
+    let mut entries = Vec::new();
+    for (index, key) in keys.iter().enumerate() {
+        entries.push(key.clone());
+        let next = keys.get(index + 1);
+        for level in (0..groups.len()).rev() {
+            let closes = next.is_none_or(|next| next[..level] != key[..level]);
+            if closes && groups[level].show_total {
+                entries.push(key[..level].to_vec());
+            }
+        }
+    }
+    if keys.is_empty() && groups[0].show_total {
+        entries.push(Vec::new());
+    }
+    entries
+}
+
+impl Crosstab {
+    /// 设计视图用的示例：每个分组一个占位值，单元格显示度量标题
+    pub fn sample(config: &CrosstabConfig) -> Self {
+        let placeholder = |groups: &[CrosstabGroup]| {
+            let key = groups.iter().map(|group| Value::String(format!("[{}]", group.field))).collect();
+            with_totals(groups, vec![key])
+        };
+        let rows = placeholder(&config.row_groups);
+        let columns = placeholder(&config.column_groups);
+        let cell: Vec<Value> = config.measures.iter().map(|measure| Value::String(measure.caption())).collect();
+        let values = vec![vec![cell; columns.len()]; rows.len()];
+        Self { rows, columns, values }
+    }
+
+    /// 布局为单元格：左上角为行组标题，上方为列组表头（外层组跨越其下所有列），
+    /// 多个度量时列表头下再加一行度量标题；`format` 把值按格式转为文字
+    pub fn grid(&self, config: &CrosstabConfig, format: &dyn Fn(&Value, Option<&str>) -> String) -> CrosstabGrid {
+        let style = GridStyle {
+            font_family: &config.font_family,
+            font_size: config.font_size,
+            color: &config.text_color,
+            border: config.border.as_ref(),
+        };
+        let row_levels = config.row_groups.len().max(1);
+        let column_levels = config.column_groups.len();
+        let measures = config.measures.len().max(1);
+        let measure_row = config.measures.len() > 1 || column_levels == 0;
+        let header_rows = column_levels + usize::from(measure_row);
+        let h = config.cell_height;
+        let header_width = config.row_header_width * row_levels as f64;
+        let header_height = h * header_rows as f64;
+        let width = header_width + config.cell_width * (self.columns.len() * measures) as f64;
+        let height = header_height + h * self.rows.len() as f64;
+        let header_background = config.header_background.as_deref();
+        let total_background = config.total_background.as_deref().or(header_background);
+        let label = |group: &CrosstabGroup, value: &Value| format(value, group.format.as_deref());
+
+        let mut header = Vec::new();
+        // 左上角：行组标题放在最后一行表头，上方留空
+        if header_rows > 1 {
+            let cell = GridCell { text: "", align: TextAlign::Left, background: header_background, font_weight: "bold" };
+            style.push(&mut header, cell, 0.0, 0.0, header_width, header_height - h);
+        }
+        for (level, group) in config.row_groups.iter().enumerate() {
+            let x = config.row_header_width * level as f64;
+            let cell = GridCell { text: group.caption(), align: TextAlign::Left, background: header_background, font_weight: "bold" };
+            style.push(&mut header, cell, x, header_height - h, config.row_header_width, h);
+        }
+        if config.row_groups.is_empty() {
+            let cell = GridCell { text: "", align: TextAlign::Left, background: header_background, font_weight: "bold" };
+            style.push(&mut header, cell, 0.0, header_height - h, header_width, h);
+        }
+
+        // 列表头：组值跨越同一前缀下的所有列，小计标题向下跨越剩余的表头行
+        let column_width = config.cell_width * measures as f64;
+        for (level, group) in config.column_groups.iter().enumerate() {
+            let y = h * level as f64;
+            for (start, span) in spans(&self.columns, level) {
+                let x = header_width + column_width * start as f64;
+                let entry = &self.columns[start];
+                if entry.len() > level {
+                    let text = label(group, &entry[level]);
+                    let cell = GridCell { text: &text, align: TextAlign::Center, background: header_background, font_weight: "bold" };
+                    style.push(&mut header, cell, x, y, column_width * span as f64, h);
+                } else {
+                    let cell = GridCell { text: &config.total_label, align: TextAlign::Center, background: total_background, font_weight: "bold" };
+                    style.push(&mut header, cell, x, y, column_width, h * (column_levels - level) as f64);
+                }
+            }
+        }
+        if measure_row {
+            let y = h * column_levels as f64;
+            for column in 0..self.columns.len() {
+                for (index, measure) in config.measures.iter().enumerate() {
+                    let x = header_width + column_width * column as f64 + config.cell_width * index as f64;
+                    let text = measure.caption();
+                    let cell = GridCell { text: &text, align: TextAlign::Center, background: header_background, font_weight: "bold" };
+                    style.push(&mut header, cell, x, y, config.cell_width, h);
+                }
+            }
+        }
+
+        let mut elements = header.clone();
+        // 行表头：规则同列表头，方向转置
+        for level in 0..row_levels {
+            let x = config.row_header_width * level as f64;
+            for (start, span) in spans(&self.rows, level) {
+                let y = header_height + h * start as f64;
+                let entry = &self.rows[start];
+                match config.row_groups.get(level) {
+                    Some(group) if entry.len() > level => {
+                        let text = label(group, &entry[level]);
+                        let cell = GridCell { text: &text, align: TextAlign::Left, background: header_background, font_weight: "bold" };
+                        style.push(&mut elements, cell, x, y, config.row_header_width, h * span as f64);
+                    }
+                    _ => {
+                        let cell = GridCell { text: &config.total_label, align: TextAlign::Left, background: total_background, font_weight: "bold" };
+                        style.push(&mut elements, cell, x, y, config.row_header_width * (row_levels - level) as f64, h);
+                    }
+                }
+            }
+        }
+
+        // 数据单元格：小计行列使用汇总背景
+        for (row, entry) in self.rows.iter().enumerate() {
+            let y = header_height + h * row as f64;
+            let row_total = entry.len() < config.row_groups.len();
+            for (column, column_entry) in self.columns.iter().enumerate() {
+                let total = row_total || column_entry.len() < column_levels;
+                let background = if total { total_background } else { None };
+                let font_weight = if total { "bold" } else { "normal" };
+                for (index, measure) in config.measures.iter().enumerate() {
+                    let value = self.values.get(row).and_then(|r| r.get(column)).and_then(|c| c.get(index)).unwrap_or(&Value::Null);
+                    let text = format(value, measure.format.as_deref());
+                    let x = header_width + column_width * column as f64 + config.cell_width * index as f64;
+                    let cell = GridCell { text: &text, align: TextAlign::Right, background, font_weight };
+                    style.push(&mut elements, cell, x, y, config.cell_width, h);
+                }
+            }
+        }
+
+        CrosstabGrid { elements, header, header_height, width, height }
+    }
+}
+
+/// 某一层级的表头跨度 `(起始条目, 条目数)`：相同前缀的相邻完整条目合并，
+/// 该层级的小计条目单独占一格，更外层的小计条目已由外层单元格覆盖
+fn spans(entries: &[Vec<Value>], level: usize) -> Vec<(usize, usize)> {
+    let mut spans: Vec<(usize, usize)> = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.len() < level {
+            continue;
+        }
+        if entry.len() > level {
+            if let Some((start, span)) = spans.last_mut() {
+                let previous = &entries[*start];
+                if *start + *span == index && previous.len() > level && previous[..=level] == entry[..=level] {
+                    *span += 1;
+                    continue;
+                }
+            }
+        }
+        spans.push((index, 1));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{CrosstabMeasure, ElementContent};
+    use crate::data::types::AggregateFunction;
+    use serde_json::json;
+
+    fn group(field: &str) -> CrosstabGroup {
+        CrosstabGroup { field: field.to_string(), label: None, format: None, show_total: true }
+    }
+
+    #[test]
+    fn test_with_totals_inserts_subtotals_after_each_group() {
+        let keys = vec![
+            vec![json!("A"), json!(1)],
+            vec![json!("A"), json!(2)],
+            vec![json!("B"), json!(1)],
+        ];
+        let entries = with_totals(&[group("region"), group("year")], keys);
+        assert_eq!(
+            entries,
+            vec![
+                vec![json!("A"), json!(1)],
+                vec![json!("A"), json!(2)],
+                vec![json!("A")],
+                vec![json!("B"), json!(1)],
+                vec![json!("B")],
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sample_grid_has_headers_and_totals() {
+        let config = CrosstabConfig {
+            row_groups: vec![group("region")],
+            column_groups: vec![group("year")],
+            measures: vec![CrosstabMeasure {
+                field: "amount".to_string(),
+                function: AggregateFunction::Sum,
+                label: None,
+                format: None,
+            }],
+            ..CrosstabConfig::default()
+        };
+        let format = |value: &Value, _: Option<&str>| value.as_str().unwrap_or_default().to_string();
+        let grid = Crosstab::sample(&config).grid(&config, &format);
+
+        // 一个列组、一个度量：一行列表头，两行数据（组值 + 总计）
+        assert_eq!(grid.header_height, 20.0);
+        assert_eq!(grid.height, 60.0);
+        assert_eq!(grid.width, 80.0 + 60.0 * 2.0);
+        let texts: Vec<&str> = grid
+            .elements
+            .iter()
+            .filter_map(|element| match &element.content {
+                ElementContent::Text { content, .. } => Some(content.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            texts,
+            vec!["region", "[year]", "Total", "[region]", "Total", "Sum(amount)", "Sum(amount)", "Sum(amount)", "Sum(amount)"]
+        );
+        assert_eq!(grid.header.len(), 3);
+    }
+}
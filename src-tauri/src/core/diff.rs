@@ -0,0 +1,450 @@
+// === Template Diff & Three-way Merge ===
+// 在 JSON 结构上比较模板：元素、数据源按 id，参数、变量、分组按名称对应
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::core::template::JasperTemplate;
+use crate::errors::{AppError, Result};
+
+/// 按键对应的集合：(字段名, 键字段)
+const KEYED_COLLECTIONS: &[(&str, &str)] = &[
+    ("elements", "id"),
+    ("data_sources", "id"),
+    ("parameters", "name"),
+    ("variables", "name"),
+    ("groups", "name"),
+];
+/// 每次保存都会变化的元数据，不参与比较
+const IGNORED_METADATA: &[&str] = &["last_modified"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// 属性级变化，`path` 如 `position.x`、`content.font.size`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyChange {
+    pub path: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// 集合项（元素、参数等）的变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemChange {
+    /// 元素/数据源 id，参数/变量/分组名称
+    pub key: String,
+    pub kind: ChangeKind,
+    /// 修改时的属性变化，新增和删除时为空
+    pub changes: Vec<PropertyChange>,
+}
+
+/// 两个模板之间的结构差异
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateDiff {
+    pub elements: Vec<ItemChange>,
+    pub data_sources: Vec<ItemChange>,
+    pub parameters: Vec<ItemChange>,
+    pub variables: Vec<ItemChange>,
+    pub groups: Vec<ItemChange>,
+    /// 元数据、画布、区段和格式设置的属性变化，如 `canvas.margins.top`
+    pub properties: Vec<PropertyChange>,
+}
+
+impl TemplateDiff {
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+            && self.data_sources.is_empty()
+            && self.parameters.is_empty()
+            && self.variables.is_empty()
+            && self.groups.is_empty()
+            && self.properties.is_empty()
+    }
+
+    fn collection_mut(&mut self, name: &str) -> &mut Vec<ItemChange> {
+        match name {
+            "elements" => &mut self.elements,
+            "data_sources" => &mut self.data_sources,
+            "parameters" => &mut self.parameters,
+            "variables" => &mut self.variables,
+            _ => &mut self.groups,
+        }
+    }
+}
+
+/// 双方修改了同一属性且结果不同
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergeConflict {
+    /// 如 `elements[<id>].position.x`、`canvas.width`
+    pub path: String,
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+/// 三方合并结果，冲突处保留 ours 的值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateMerge {
+    pub merged: JasperTemplate,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// 比较两个模板
+pub fn diff_templates(before: &JasperTemplate, after: &JasperTemplate) -> Result<TemplateDiff> {
+    let before = normalize(before)?;
+    let after = normalize(after)?;
+    let mut diff = TemplateDiff::default();
+
+    for key in union_keys(&before, &after) {
+        let (old, new) = (before.get(&key), after.get(&key));
+        if KEYED_COLLECTIONS.iter().any(|(name, _)| *name == key) {
+            let (empty_old, empty_new) = (Map::new(), Map::new());
+            let old = old.and_then(Value::as_object).unwrap_or(&empty_old);
+            let new = new.and_then(Value::as_object).unwrap_or(&empty_new);
+            let changes = diff_collection(old, new);
+            *diff.collection_mut(&key) = changes;
+        } else {
+            diff_values(&key, old, new, &mut diff.properties);
+        }
+    }
+
+    Ok(diff)
+}
+
+/// 三方合并：以 base 为共同祖先合并 ours 和 theirs
+///
+/// 只有一方修改的属性直接采用；双方都修改且不同的属性记为冲突并保留 ours。
+/// 字符串/数字列表（如区段中的元素 id）按集合合并双方的增删。
+pub fn merge_templates(base: &JasperTemplate, ours: &JasperTemplate, theirs: &JasperTemplate) -> Result<TemplateMerge> {
+    let base_value = normalize(base)?;
+    let ours_value = normalize(ours)?;
+    let theirs_value = normalize(theirs)?;
+
+    let mut conflicts = Vec::new();
+    let mut merged = merge_objects("", &base_value, &ours_value, &theirs_value, &mut conflicts);
+
+    // 还原为数组：ours 的顺序在前，theirs 新增的项追加在后
+    for (name, _) in KEYED_COLLECTIONS {
+        let Some(Value::Object(mut items)) = merged.remove(*name) else { continue };
+        let mut array = Vec::new();
+        for template in [ours, theirs, base] {
+            for key in item_keys(template, name) {
+                if let Some(item) = items.remove(&key) {
+                    array.push(item);
+                }
+            }
+        }
+        merged.insert(name.to_string(), Value::Array(array));
+    }
+    if let Some(Value::Object(metadata)) = merged.get_mut("metadata") {
+        metadata.insert("last_modified".to_string(), serde_json::json!(chrono::Utc::now()));
+    }
+
+    let mut merged: JasperTemplate = serde_json::from_value(Value::Object(merged)).map_err(|e| AppError::SerializationError {
+        message: format!("Merged template is not valid: {}", e),
+    })?;
+
+    // 删除一方移除元素后残留的区段引用
+    let element_ids: Vec<String> = merged.elements.iter().map(|element| element.id.clone()).collect();
+    let bands = &mut merged.bands;
+    let report_bands = [
+        &mut bands.title,
+        &mut bands.page_header,
+        &mut bands.column_header,
+        &mut bands.detail,
+        &mut bands.column_footer,
+        &mut bands.page_footer,
+        &mut bands.summary,
+    ];
+    let group_bands = merged.groups.iter_mut().flat_map(|group| [&mut group.header, &mut group.footer]);
+    for band in report_bands.into_iter().chain(group_bands).flatten() {
+        band.elements.retain(|id| element_ids.contains(id));
+    }
+    merged.validate()?;
+
+    Ok(TemplateMerge { merged, conflicts })
+}
+
+// === Helpers ===
+
+/// 模板转为 JSON，按键对应的集合转为以键为索引的对象
+fn normalize(template: &JasperTemplate) -> Result<Map<String, Value>> {
+    let value = serde_json::to_value(template).map_err(|e| AppError::SerializationError {
+        message: format!("Failed to serialize template: {}", e),
+    })?;
+    let Value::Object(mut root) = value else {
+        return Err(AppError::SerializationError {
+            message: "Template must serialize to an object".to_string(),
+        });
+    };
+
+    for (name, key_field) in KEYED_COLLECTIONS {
+        let items = match root.remove(*name) {
+            Some(Value::Array(items)) => items,
+            _ => Vec::new(),
+        };
+        let mut keyed = Map::new();
+        for item in items {
+            let key = item.get(*key_field).and_then(Value::as_str).unwrap_or_default().to_string();
+            keyed.insert(key, item);
+        }
+        root.insert(name.to_string(), Value::Object(keyed));
+    }
+
+    if let Some(Value::Object(metadata)) = root.get_mut("metadata") {
+        for key in IGNORED_METADATA {
+            metadata.remove(*key);
+        }
+    }
+    Ok(root)
+}
+
+/// 集合项的键，保持模板中的顺序
+fn item_keys(template: &JasperTemplate, collection: &str) -> Vec<String> {
+    match collection {
+        "elements" => template.elements.iter().map(|element| element.id.clone()).collect(),
+        "data_sources" => template.data_sources.iter().map(|source| source.id.clone()).collect(),
+        "parameters" => template.parameters.iter().map(|parameter| parameter.name.clone()).collect(),
+        "variables" => template.variables.iter().map(|variable| variable.name.clone()).collect(),
+        _ => template.groups.iter().map(|group| group.name.clone()).collect(),
+    }
+}
+
+fn union_keys(a: &Map<String, Value>, b: &Map<String, Value>) -> Vec<String> {
+    let mut keys: Vec<String> = a.keys().cloned().collect();
+    keys.extend(b.keys().filter(|key| !a.contains_key(*key)).cloned());
+    keys
+}
+
+fn diff_collection(before: &Map<String, Value>, after: &Map<String, Value>) -> Vec<ItemChange> {
+    let mut changes = Vec::new();
+    for key in union_keys(before, after) {
+        match (before.get(&key), after.get(&key)) {
+            (Some(_), None) => changes.push(ItemChange { key, kind: ChangeKind::Removed, changes: Vec::new() }),
+            (None, Some(_)) => changes.push(ItemChange { key, kind: ChangeKind::Added, changes: Vec::new() }),
+            (Some(old), Some(new)) if old != new => {
+                let mut properties = Vec::new();
+                diff_values("", Some(old), Some(new), &mut properties);
+                changes.push(ItemChange { key, kind: ChangeKind::Modified, changes: properties });
+            }
+            _ => {}
+        }
+    }
+    changes
+}
+
+/// 对象逐属性比较，其余值整体比较
+fn diff_values(path: &str, before: Option<&Value>, after: Option<&Value>, changes: &mut Vec<PropertyChange>) {
+    if before == after {
+        return;
+    }
+    if let (Some(Value::Object(old)), Some(Value::Object(new))) = (before, after) {
+        for key in union_keys(old, new) {
+            diff_values(&join(path, &key), old.get(&key), new.get(&key), changes);
+        }
+        return;
+    }
+    changes.push(PropertyChange {
+        path: path.to_string(),
+        before: before.cloned(),
+        after: after.cloned(),
+    });
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn merge_objects(
+    path: &str,
+    base: &Map<String, Value>,
+    ours: &Map<String, Value>,
+    theirs: &Map<String, Value>,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Map<String, Value> {
+    let keyed = KEYED_COLLECTIONS.iter().any(|(name, _)| *name == path);
+    let mut keys = union_keys(ours, theirs);
+    let removed: Vec<String> = base.keys().filter(|key| !keys.contains(key)).cloned().collect();
+    keys.extend(removed);
+
+    let mut merged = Map::new();
+    for key in keys {
+        let child_path = if keyed {
+            format!("{}[{}]", path, key)
+        } else {
+            join(path, &key)
+        };
+        if let Some(value) = merge_value(&child_path, base.get(&key), ours.get(&key), theirs.get(&key), conflicts) {
+            merged.insert(key, value);
+        }
+    }
+    merged
+}
+
+fn merge_value(
+    path: &str,
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Option<Value> {
+    if ours == theirs || base == theirs {
+        return ours.cloned();
+    }
+    if base == ours {
+        return theirs.cloned();
+    }
+
+    // 双方都修改：对象逐属性合并，标量列表按集合合并
+    let empty = Map::new();
+    match (base, ours, theirs) {
+        (None | Some(Value::Object(_)), Some(Value::Object(ours)), Some(Value::Object(theirs))) => {
+            let base = base.and_then(Value::as_object).unwrap_or(&empty);
+            Some(Value::Object(merge_objects(path, base, ours, theirs, conflicts)))
+        }
+        (Some(Value::Array(base)), Some(Value::Array(ours)), Some(Value::Array(theirs)))
+            if [base, ours, theirs].iter().all(|items| items.iter().all(is_scalar)) =>
+        {
+            let mut merged: Vec<Value> = ours.iter().filter(|item| theirs.contains(item) || !base.contains(item)).cloned().collect();
+            let added: Vec<Value> = theirs.iter().filter(|item| !base.contains(item) && !merged.contains(item)).cloned().collect();
+            merged.extend(added);
+            Some(Value::Array(merged))
+        }
+        _ => {
+            conflicts.push(MergeConflict {
+                path: path.to_string(),
+                base: base.cloned(),
+                ours: ours.cloned(),
+                theirs: theirs.cloned(),
+            });
+            ours.cloned()
+        }
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    matches!(value, Value::String(_) | Value::Number(_) | Value::Bool(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{Position, Size};
+    use crate::core::template::{BandConfig, ElementType, Parameter, DataType, TemplateElement};
+    use serde_json::json;
+
+    fn element(id: &str, x: f64) -> TemplateElement {
+        let mut element = TemplateElement {
+            id: id.to_string(),
+            ..TemplateElement::new(ElementType::Text, Position { x, y: 10.0 }, Size { width: 100.0, height: 20.0 })
+        };
+        element.content.text = Some(format!("text {}", id));
+        element
+    }
+
+    fn base() -> JasperTemplate {
+        let mut template = JasperTemplate::new();
+        template.elements = vec![element("a", 10.0), element("b", 20.0), element("c", 30.0)];
+        let mut detail = BandConfig::new(40.0);
+        detail.elements = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        template.bands.detail = Some(detail);
+        template.parameters.push(Parameter {
+            name: "from".to_string(),
+            param_type: DataType::Date,
+            default: None,
+            description: None,
+            required: false,
+        });
+        template
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = base();
+        let mut after = before.clone();
+        after.metadata.last_modified = chrono::Utc::now() + chrono::Duration::seconds(5);
+        after.elements[0].position.x = 15.0;
+        after.elements[0].content.text = Some("changed".to_string());
+        after.elements.remove(1);
+        after.elements.push(element("d", 40.0));
+        after.parameters[0].required = true;
+        after.canvas.margins.top = 30.0;
+
+        let diff = diff_templates(&before, &after).unwrap();
+        assert_eq!(
+            diff.elements.iter().map(|change| (change.key.as_str(), change.kind)).collect::<Vec<_>>(),
+            vec![("a", ChangeKind::Modified), ("b", ChangeKind::Removed), ("d", ChangeKind::Added)]
+        );
+        let changes = &diff.elements[0].changes;
+        assert_eq!(changes.len(), 2);
+        let moved = changes.iter().find(|change| change.path == "position.x").unwrap();
+        assert_eq!((moved.before.clone(), moved.after.clone()), (Some(json!(10.0)), Some(json!(15.0))));
+        assert!(changes.iter().any(|change| change.path == "content.text"));
+        assert_eq!(diff.parameters[0].changes[0].path, "required");
+        assert!(diff.properties.iter().any(|change| change.path == "canvas.margins.top"));
+        assert!(!diff.properties.iter().any(|change| change.path.starts_with("metadata")));
+
+        assert!(diff_templates(&before, &before).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_merge_without_conflicts() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.elements[0].position.x = 50.0;
+        ours.elements.push(element("d", 40.0));
+        ours.bands.detail.as_mut().unwrap().elements.push("d".to_string());
+
+        let mut theirs = base.clone();
+        theirs.elements[0].content.text = Some("theirs".to_string());
+        theirs.elements.retain(|element| element.id != "b");
+        theirs.bands.detail.as_mut().unwrap().elements.retain(|id| id != "b");
+        theirs.elements.push(element("e", 60.0));
+        theirs.bands.detail.as_mut().unwrap().elements.push("e".to_string());
+        theirs.canvas.margins.left = 40.0;
+
+        let result = merge_templates(&base, &ours, &theirs).unwrap();
+        assert!(result.conflicts.is_empty(), "{:?}", result.conflicts);
+        let merged = result.merged;
+        let ids: Vec<&str> = merged.elements.iter().map(|element| element.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "d", "e"]);
+        assert_eq!(merged.elements[0].position.x, 50.0);
+        assert_eq!(merged.elements[0].content.text.as_deref(), Some("theirs"));
+        assert_eq!(merged.bands.detail.as_ref().unwrap().elements, vec!["a", "c", "d", "e"]);
+        assert_eq!(merged.canvas.margins.left, 40.0);
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.elements[0].position.x = 50.0;
+        ours.elements[1].content.text = Some("ours".to_string());
+
+        let mut theirs = base.clone();
+        theirs.elements[0].position.x = 70.0;
+        theirs.elements.retain(|element| element.id != "b");
+        theirs.bands.detail.as_mut().unwrap().elements.retain(|id| id != "b");
+
+        let result = merge_templates(&base, &ours, &theirs).unwrap();
+        let paths: Vec<&str> = result.conflicts.iter().map(|conflict| conflict.path.as_str()).collect();
+        assert_eq!(paths, vec!["elements[a].position.x", "elements[b]"]);
+        assert_eq!(result.conflicts[0].theirs, Some(json!(70.0)));
+        assert_eq!(result.conflicts[1].theirs, None);
+
+        // 冲突处保留 ours
+        assert_eq!(result.merged.elements[0].position.x, 50.0);
+        assert!(result.merged.elements.iter().any(|element| element.id == "b"));
+        // ours 仍引用 b，但 theirs 已从区段移除；区段列表按集合合并
+        assert!(!result.merged.bands.detail.as_ref().unwrap().elements.contains(&"b".to_string()));
+    }
+}
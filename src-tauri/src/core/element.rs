@@ -1,25 +1,29 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use crate::errors::{AppError, Result};
+use crate::core::template::{BandType, Calculation, TemplateElement};
+use crate::data::types::AggregateFunction;
 
+/// 元素 ID：设计器新建的元素使用 UUID，从模板载入的元素保留原 ID
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub struct ElementId(Uuid);
+pub struct ElementId(String);
 
 impl ElementId {
     pub fn new() -> Self {
-        Self(Uuid::new_v4())
+        Self(Uuid::new_v4().to_string())
     }
     
     pub fn from_string(s: &str) -> Result<Self> {
-        let uuid = Uuid::parse_str(s)
-            .map_err(|_| AppError::SerializationError { 
-                message: format!("Invalid UUID: {}", s) 
-            })?;
-        Ok(Self(uuid))
+        if s.trim().is_empty() {
+            return Err(AppError::SerializationError {
+                message: "Element id must not be empty".to_string(),
+            });
+        }
+        Ok(Self(s.to_string()))
     }
     
     pub fn to_string(&self) -> String {
-        self.0.to_string()
+        self.0.clone()
     }
 }
 
@@ -29,7 +33,7 @@ impl Default for ElementId {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Position {
     pub x: f64,
     pub y: f64,
@@ -51,7 +55,7 @@ impl Position {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Size {
     pub width: f64,
     pub height: f64,
@@ -76,7 +80,7 @@ impl Size {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextStyle {
     pub font_family: String,
     pub font_size: f64,
@@ -90,7 +94,7 @@ pub struct TextStyle {
     pub background: Option<TextBackgroundStyle>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TextAlign {
     Left,
     Center,
@@ -98,7 +102,7 @@ pub enum TextAlign {
 }
 
 // Phase 1新增: 文字专用边框样式
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextBorderStyle {
     pub color: String,
     pub width: f64,
@@ -108,7 +112,7 @@ pub struct TextBorderStyle {
 }
 
 // Phase 1新增: 文字专用背景样式
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextBackgroundStyle {
     pub color: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -117,21 +121,21 @@ pub struct TextBackgroundStyle {
     pub padding: Option<f64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BorderStyle {
     pub color: String,
     pub width: f64,
     pub style: BorderStyleType,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BorderStyleType {
     Solid,
     Dashed,
     Dotted,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LineCapType {
     None,
     Arrow,
@@ -139,7 +143,7 @@ pub enum LineCapType {
     Square,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LineStyleType {
     Solid,
     Dashed,
@@ -147,7 +151,28 @@ pub enum LineStyleType {
     DashDot,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 图片缩放方式，与 JasperReports 的 scaleImage 对应
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ImageScaleMode {
+    /// 按原始尺寸绘制，超出元素框的部分裁掉
+    Clip,
+    /// 拉伸填满元素框
+    FillFrame,
+    /// 等比缩放到元素框内
+    #[default]
+    RetainShape,
+}
+
+/// 图片在元素框内的对齐：水平方向为左/中/右，垂直方向为上/中/下
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ImageAlign {
+    #[default]
+    Start,
+    Center,
+    End,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ElementContent {
     Text {
@@ -157,6 +182,12 @@ pub enum ElementContent {
     Image {
         src: String,
         alt: Option<String>,
+        #[serde(default)]
+        scale_mode: ImageScaleMode,
+        #[serde(default)]
+        horizontal_align: ImageAlign,
+        #[serde(default)]
+        vertical_align: ImageAlign,
     },
     Rectangle {
         fill_color: Option<String>,
@@ -177,6 +208,565 @@ pub enum ElementContent {
         format: Option<String>,
         style: TextStyle,
     },
+    Subreport(SubreportConfig),
+    Table(TableConfig),
+    Crosstab(CrosstabConfig),
+    Chart(ChartConfig),
+    Barcode(BarcodeConfig),
+}
+
+/// 子报表参数：父报表中的表达式，求值结果作为子报表参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubreportParameter {
+    pub name: String,
+    pub expression: String,
+}
+
+/// 子报表：嵌入另一个模板，按父报表的每条记录填充一次
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubreportConfig {
+    /// 子报表模板文件，相对路径按父模板所在目录解析
+    pub template_path: String,
+    /// 传给子报表的参数，表达式可引用父记录字段和父报表参数
+    #[serde(default)]
+    pub parameters: Vec<SubreportParameter>,
+    /// 子报表数据取自父记录中的数组（如 `$F{transactions}`），为空时按参数执行子模板自己的数据查询
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_expression: Option<String>,
+}
+
+/// 表格列
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableColumn {
+    pub header: String,
+    /// 单元格表达式，按表格数据的每条记录求值
+    pub expression: String,
+    pub width: f64,
+    #[serde(default = "default_table_align")]
+    pub align: TextAlign,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// 表尾汇总方式（对本列全部单元格的值），为空时表尾显示 `footer_text`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub footer_calculation: Option<Calculation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub footer_text: Option<String>,
+}
+
+/// 表格：按列定义逐行输出绑定数据源的记录，跨页时重复表头
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableConfig {
+    /// 绑定的模板数据源 ID
+    pub data_source_id: String,
+    /// 表格数据取自父记录中的数组（如 `$F{lines}`），设置后不再查询数据源
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_expression: Option<String>,
+    #[serde(default)]
+    pub columns: Vec<TableColumn>,
+    #[serde(default = "default_table_row_height")]
+    pub header_height: f64,
+    #[serde(default = "default_table_row_height")]
+    pub row_height: f64,
+    #[serde(default = "default_true")]
+    pub show_header: bool,
+    #[serde(default)]
+    pub show_footer: bool,
+    #[serde(default = "default_table_font_family")]
+    pub font_family: String,
+    #[serde(default = "default_table_font_size")]
+    pub font_size: f64,
+    #[serde(default = "default_table_text_color")]
+    pub text_color: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header_background: Option<String>,
+    /// 斑马纹：偶数数据行的背景色
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zebra_color: Option<String>,
+    /// 单元格边框，为空时不画边框
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<BorderStyle>,
+}
+
+fn default_table_align() -> TextAlign {
+    TextAlign::Left
+}
+
+fn default_table_row_height() -> f64 {
+    20.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_table_font_family() -> String {
+    "Arial".to_string()
+}
+
+fn default_table_font_size() -> f64 {
+    10.0
+}
+
+fn default_table_text_color() -> String {
+    "#000000".to_string()
+}
+
+impl Default for TableConfig {
+    fn default() -> Self {
+        Self {
+            data_source_id: String::new(),
+            data_expression: None,
+            columns: Vec::new(),
+            header_height: default_table_row_height(),
+            row_height: default_table_row_height(),
+            show_header: true,
+            show_footer: false,
+            font_family: default_table_font_family(),
+            font_size: default_table_font_size(),
+            text_color: default_table_text_color(),
+            header_background: None,
+            zebra_color: None,
+            border: None,
+        }
+    }
+}
+
+/// 单元格文字与边框的水平间距
+const TABLE_CELL_PADDING: f64 = 2.0;
+
+impl TableConfig {
+    /// 表头高度（不显示表头时为 0）
+    pub fn header_extent(&self) -> f64 {
+        if self.show_header { self.header_height } else { 0.0 }
+    }
+
+    /// 表格总高度
+    pub fn height(&self, rows: usize) -> f64 {
+        let footer = if self.show_footer { self.row_height } else { 0.0 };
+        self.header_extent() + self.row_height * rows as f64 + footer
+    }
+
+    /// 表头单元格，坐标相对于表格左上角
+    pub fn header_cells(&self) -> Vec<ReportElement> {
+        if !self.show_header {
+            return Vec::new();
+        }
+        let texts: Vec<&str> = self.columns.iter().map(|column| column.header.as_str()).collect();
+        self.row(0.0, self.header_height, &texts, self.header_background.as_deref(), "bold")
+    }
+
+    /// 第 `index` 条数据行的单元格
+    pub fn body_cells(&self, index: usize, texts: &[&str]) -> Vec<ReportElement> {
+        let y = self.header_extent() + self.row_height * index as f64;
+        let background = if index % 2 == 1 { self.zebra_color.as_deref() } else { None };
+        self.row(y, self.row_height, texts, background, "normal")
+    }
+
+    /// 表尾单元格，位于 `rows` 条数据行之后
+    pub fn footer_cells(&self, rows: usize, texts: &[&str]) -> Vec<ReportElement> {
+        if !self.show_footer {
+            return Vec::new();
+        }
+        let y = self.header_extent() + self.row_height * rows as f64;
+        self.row(y, self.row_height, texts, self.header_background.as_deref(), "bold")
+    }
+
+    /// 一行单元格：每列一个矩形（背景与边框）和一段文字，宽度不为正的列跳过
+    fn row(&self, y: f64, height: f64, texts: &[&str], background: Option<&str>, font_weight: &str) -> Vec<ReportElement> {
+        let style = GridStyle {
+            font_family: &self.font_family,
+            font_size: self.font_size,
+            color: &self.text_color,
+            border: self.border.as_ref(),
+        };
+        let mut cells = Vec::new();
+        let mut x = 0.0;
+        for (index, column) in self.columns.iter().enumerate() {
+            let cell = GridCell {
+                text: texts.get(index).copied().unwrap_or_default(),
+                align: column.align.clone(),
+                background,
+                font_weight,
+            };
+            style.push(&mut cells, cell, x, y, column.width, height);
+            x += column.width;
+        }
+        cells
+    }
+}
+
+/// 表格类元素（表格、交叉表）的单元格样式
+pub(crate) struct GridStyle<'a> {
+    pub font_family: &'a str,
+    pub font_size: f64,
+    pub color: &'a str,
+    pub border: Option<&'a BorderStyle>,
+}
+
+/// 单元格内容
+pub(crate) struct GridCell<'a> {
+    pub text: &'a str,
+    pub align: TextAlign,
+    pub background: Option<&'a str>,
+    pub font_weight: &'a str,
+}
+
+impl GridStyle<'_> {
+    /// 追加一个单元格：有背景或边框时先画矩形，再放内缩的文字；尺寸不为正时跳过
+    pub fn push(&self, cells: &mut Vec<ReportElement>, cell: GridCell, x: f64, y: f64, width: f64, height: f64) {
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+        if cell.background.is_some() || self.border.is_some() {
+            cells.push(Self::element(
+                ElementContent::Rectangle {
+                    fill_color: cell.background.map(str::to_string),
+                    border: self.border.cloned(),
+                    corner_radius: None,
+                    opacity: None,
+                },
+                x,
+                y,
+                width,
+                height,
+            ));
+        }
+        let inset = TABLE_CELL_PADDING.min(width / 4.0);
+        cells.push(Self::element(
+            ElementContent::Text {
+                content: cell.text.to_string(),
+                style: TextStyle {
+                    font_family: self.font_family.to_string(),
+                    font_size: self.font_size,
+                    font_weight: cell.font_weight.to_string(),
+                    color: self.color.to_string(),
+                    align: cell.align,
+                    border: None,
+                    background: None,
+                },
+            },
+            x + inset,
+            y,
+            width - 2.0 * inset,
+            height,
+        ));
+    }
+
+    fn element(content: ElementContent, x: f64, y: f64, width: f64, height: f64) -> ReportElement {
+        ReportElement {
+            id: ElementId::new(),
+            position: Position { x, y },
+            size: Size { width, height },
+            content,
+            z_index: 0,
+            visible: true,
+            locked: false,
+            name: None,
+            band: None,
+            origin: None,
+        }
+    }
+}
+
+/// 交叉表分组（行组或列组），外层在前
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrosstabGroup {
+    /// 分组字段，支持 `customer.region` 形式的嵌套字段
+    pub field: String,
+    /// 分组标题，为空时显示字段名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// 组值的显示格式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// 每组之后输出小计；最外层分组的小计即总计
+    #[serde(default = "default_true")]
+    pub show_total: bool,
+}
+
+impl CrosstabGroup {
+    pub fn caption(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.field)
+    }
+}
+
+/// 交叉表度量：对落在单元格内的记录汇总某个字段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrosstabMeasure {
+    pub field: String,
+    pub function: AggregateFunction,
+    /// 度量标题，为空时显示 `Sum(amount)` 形式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+impl CrosstabMeasure {
+    pub fn caption(&self) -> String {
+        self.label.clone().unwrap_or_else(|| format!("{:?}({})", self.function, self.field))
+    }
+}
+
+/// 交叉表：按行组和列组对绑定数据源的记录做透视汇总，带小计和总计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrosstabConfig {
+    /// 绑定的模板数据源 ID
+    pub data_source_id: String,
+    /// 交叉表数据取自父记录中的数组（如 `$F{lines}`），设置后不再查询数据源
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_expression: Option<String>,
+    #[serde(default)]
+    pub row_groups: Vec<CrosstabGroup>,
+    #[serde(default)]
+    pub column_groups: Vec<CrosstabGroup>,
+    #[serde(default)]
+    pub measures: Vec<CrosstabMeasure>,
+    /// 每个行组标题列的宽度
+    #[serde(default = "default_crosstab_header_width")]
+    pub row_header_width: f64,
+    /// 每个度量单元格的宽度
+    #[serde(default = "default_crosstab_cell_width")]
+    pub cell_width: f64,
+    /// 每行（含列标题行）的高度
+    #[serde(default = "default_table_row_height")]
+    pub cell_height: f64,
+    /// 小计与总计的标题文字
+    #[serde(default = "default_crosstab_total_label")]
+    pub total_label: String,
+    #[serde(default = "default_table_font_family")]
+    pub font_family: String,
+    #[serde(default = "default_table_font_size")]
+    pub font_size: f64,
+    #[serde(default = "default_table_text_color")]
+    pub text_color: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header_background: Option<String>,
+    /// 小计与总计单元格的背景色
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_background: Option<String>,
+    /// 单元格边框，为空时不画边框
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<BorderStyle>,
+}
+
+fn default_crosstab_header_width() -> f64 {
+    80.0
+}
+
+fn default_crosstab_cell_width() -> f64 {
+    60.0
+}
+
+fn default_crosstab_total_label() -> String {
+    "Total".to_string()
+}
+
+impl Default for CrosstabConfig {
+    fn default() -> Self {
+        Self {
+            data_source_id: String::new(),
+            data_expression: None,
+            row_groups: Vec::new(),
+            column_groups: Vec::new(),
+            measures: Vec::new(),
+            row_header_width: default_crosstab_header_width(),
+            cell_width: default_crosstab_cell_width(),
+            cell_height: default_table_row_height(),
+            total_label: default_crosstab_total_label(),
+            font_family: default_table_font_family(),
+            font_size: default_table_font_size(),
+            text_color: default_table_text_color(),
+            header_background: None,
+            total_background: None,
+            border: None,
+        }
+    }
+}
+
+/// 图表类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ChartType {
+    #[default]
+    Bar,
+    StackedBar,
+    Line,
+    Area,
+    Pie,
+    Donut,
+    Scatter,
+}
+
+/// 图表：按分类、系列和数值表达式汇总绑定数据源的记录，渲染为矢量 SVG
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartConfig {
+    #[serde(default)]
+    pub chart_type: ChartType,
+    /// 绑定的模板数据源 ID
+    pub data_source_id: String,
+    /// 图表数据取自父记录中的数组（如 `$F{lines}`），设置后不再查询数据源
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_expression: Option<String>,
+    /// 分类表达式；散点图为 X 值
+    #[serde(default)]
+    pub category_expression: String,
+    /// 系列表达式，为空时只有一个系列
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series_expression: Option<String>,
+    #[serde(default)]
+    pub value_expression: String,
+    /// 同一分类和系列有多条记录时的汇总方式
+    #[serde(default = "default_chart_aggregate")]
+    pub aggregate: AggregateFunction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default = "default_true")]
+    pub show_legend: bool,
+    /// 在柱、点和扇区上显示数值
+    #[serde(default)]
+    pub show_labels: bool,
+    /// 显示坐标轴、刻度和网格线（饼图忽略）
+    #[serde(default = "default_true")]
+    pub show_axes: bool,
+    /// 数值标签和刻度的显示格式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_format: Option<String>,
+    /// 系列（饼图为分类）依次使用的颜色，用完后循环
+    #[serde(default = "default_chart_palette")]
+    pub palette: Vec<String>,
+    #[serde(default = "default_table_font_family")]
+    pub font_family: String,
+    #[serde(default = "default_table_font_size")]
+    pub font_size: f64,
+    #[serde(default = "default_table_text_color")]
+    pub text_color: String,
+    /// 填充后的数据，设计时为空（显示示例数据）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<ChartData>,
+}
+
+/// 图表数据：由填充引擎按数据源汇总
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChartData {
+    /// 分类，按首次出现的顺序排列（散点图为空）
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub series: Vec<ChartSeries>,
+    /// 格式化数值使用的区域设置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChartSeries {
+    pub name: String,
+    /// 各分类的值，与 `ChartData::categories` 对齐，没有数据的分类为空
+    #[serde(default)]
+    pub values: Vec<Option<f64>>,
+    /// 散点图的点 `[x, y]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub points: Vec<[f64; 2]>,
+}
+
+fn default_chart_aggregate() -> AggregateFunction {
+    AggregateFunction::Sum
+}
+
+fn default_chart_palette() -> Vec<String> {
+    ["#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7", "#9c755f", "#bab0ac"]
+        .iter()
+        .map(|color| color.to_string())
+        .collect()
+}
+
+impl Default for ChartConfig {
+    fn default() -> Self {
+        Self {
+            chart_type: ChartType::default(),
+            data_source_id: String::new(),
+            data_expression: None,
+            category_expression: String::new(),
+            series_expression: None,
+            value_expression: String::new(),
+            aggregate: default_chart_aggregate(),
+            title: None,
+            show_legend: true,
+            show_labels: false,
+            show_axes: true,
+            value_format: None,
+            palette: default_chart_palette(),
+            font_family: default_table_font_family(),
+            font_size: default_table_font_size(),
+            text_color: default_table_text_color(),
+            data: None,
+        }
+    }
+}
+
+/// 条码码制
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum BarcodeType {
+    #[default]
+    QrCode,
+    Code128,
+    Code39,
+    Ean13,
+    Pdf417,
+    DataMatrix,
+}
+
+/// 二维码纠错等级，可恢复约 7%/15%/25%/30% 的损坏
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum QrErrorCorrection {
+    L,
+    #[default]
+    M,
+    Q,
+    H,
+}
+
+/// 条码：内容为字面值或数据表达式，按码制编码后渲染为矢量 SVG
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BarcodeConfig {
+    #[serde(default)]
+    pub barcode_type: BarcodeType,
+    /// 字面内容，没有表达式时使用
+    #[serde(default)]
+    pub value: String,
+    /// 内容表达式（如 `$F{serial_no}`），填充时求值后写入 `value`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+    /// 仅二维码使用
+    #[serde(default)]
+    pub error_correction: QrErrorCorrection,
+    /// 在一维码下方显示内容文字
+    #[serde(default = "default_true")]
+    pub show_text: bool,
+    #[serde(default = "default_table_text_color")]
+    pub color: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(default = "default_table_font_family")]
+    pub font_family: String,
+    #[serde(default = "default_table_font_size")]
+    pub font_size: f64,
+}
+
+impl Default for BarcodeConfig {
+    fn default() -> Self {
+        Self {
+            barcode_type: BarcodeType::default(),
+            value: String::new(),
+            expression: None,
+            error_correction: QrErrorCorrection::default(),
+            show_text: true,
+            color: default_table_text_color(),
+            background: None,
+            font_family: default_table_font_family(),
+            font_size: default_table_font_size(),
+        }
+    }
 }
 
 impl ElementContent {
@@ -187,11 +777,16 @@ impl ElementContent {
             ElementContent::Rectangle { .. } => "rectangle",
             ElementContent::Line { .. } => "line",
             ElementContent::DataField { .. } => "data_field",
+            ElementContent::Subreport(_) => "subreport",
+            ElementContent::Table(_) => "table",
+            ElementContent::Crosstab(_) => "crosstab",
+            ElementContent::Chart(_) => "chart",
+            ElementContent::Barcode(_) => "barcode",
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ReportElement {
     pub id: ElementId,
     pub position: Position,
@@ -201,6 +796,12 @@ pub struct ReportElement {
     pub visible: bool,
     pub locked: bool,
     pub name: Option<String>,
+    /// 所属报表区段（为空时按画布绝对坐标处理）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub band: Option<BandType>,
+    /// 从模板载入时的原始元素，保存时据此保留设计器无法表达的模板属性
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<Box<TemplateElement>>,
 }
 
 impl ReportElement {
@@ -217,6 +818,8 @@ impl ReportElement {
             visible: true,
             locked: false,
             name: None,
+            band: None,
+            origin: None,
         })
     }
     
@@ -0,0 +1,314 @@
+// === 图片来源与摆放 ===
+// 图片元素的 src 可以是数据 URI、本地路径（相对路径按模板所在目录解析）或数据字段的值
+// （BLOB 经数据层转成的 base64、字节数组、数据 URI 或路径）。统一解码为 PNG/JPEG/GIF 字节，
+// 以数据 URI 嵌入 SVG，PDF 和 Excel 导出再从中取出位图。
+use crate::core::element::{ImageAlign, ImageScaleMode};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use image::{DynamicImage, ImageFormat};
+use serde_json::Value;
+use std::io::Cursor;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum ImageSourceError {
+    #[error("Image source is empty")]
+    Empty,
+
+    #[error("Failed to read image '{path}': {message}")]
+    Read { path: String, message: String },
+
+    #[error("Invalid image data: {message}")]
+    InvalidData { message: String },
+}
+
+pub type ImageSourceResult<T> = Result<T, ImageSourceError>;
+
+/// 解码后的图片：原始字节（PNG/JPEG/GIF）和像素尺寸
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageData {
+    pub format: ImageFormat,
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImageData {
+    /// 识别图片格式并读取尺寸；SVG 渲染库和 Excel 不支持的格式（WebP、TIFF 等）转成 PNG
+    pub fn from_bytes(bytes: Vec<u8>) -> ImageSourceResult<Self> {
+        let format = image::guess_format(&bytes).map_err(invalid_data)?;
+        if matches!(format, ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif) {
+            let (width, height) = image::io::Reader::with_format(Cursor::new(&bytes), format)
+                .into_dimensions()
+                .map_err(invalid_data)?;
+            return Ok(Self { format, bytes, width, height });
+        }
+
+        let decoded = image::load_from_memory_with_format(&bytes, format).map_err(invalid_data)?;
+        Self::from_image(&decoded)
+    }
+
+    /// 编码为 PNG
+    pub fn from_image(image: &DynamicImage) -> ImageSourceResult<Self> {
+        let mut bytes = Vec::new();
+        image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png).map_err(invalid_data)?;
+        Ok(Self { format: ImageFormat::Png, bytes, width: image.width(), height: image.height() })
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self.format {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Gif => "image/gif",
+            _ => "image/png",
+        }
+    }
+
+    pub fn to_data_uri(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type(), STANDARD.encode(&self.bytes))
+    }
+
+    pub fn decode(&self) -> ImageSourceResult<DynamicImage> {
+        image::load_from_memory_with_format(&self.bytes, self.format).map_err(invalid_data)
+    }
+}
+
+/// src 含字段、参数或变量引用（包括字段简写 `${name}`）时按表达式处理，填充时才能得到图片
+pub fn is_expression(src: &str) -> bool {
+    ["$F{", "$P{", "$V{", "${"].iter().any(|prefix| src.contains(prefix))
+}
+
+/// 字面量本地路径：不是表达式、数据 URI 或网络地址
+pub fn is_local_path(src: &str) -> bool {
+    let src = src.trim();
+    !src.is_empty()
+        && !is_expression(src)
+        && !["data:", "http://", "https://"].iter().any(|scheme| src.starts_with(scheme))
+}
+
+/// 数据 URI 或绝对路径；相对路径只有填充时才能按模板所在目录解析，预览和导出不按进程工作目录读取
+pub fn is_resolved(src: &str) -> bool {
+    let src = src.trim();
+    src.starts_with("data:") || Path::new(src).is_absolute()
+}
+
+/// 加载数据 URI 或本地文件，相对路径按 `base_dir` 解析
+pub fn load(src: &str, base_dir: Option<&Path>) -> ImageSourceResult<ImageData> {
+    let src = src.trim();
+    if src.is_empty() {
+        return Err(ImageSourceError::Empty);
+    }
+    if src.starts_with("data:") {
+        return ImageData::from_bytes(decode_data_uri(src)?);
+    }
+
+    let path = Path::new(src);
+    let resolved = match base_dir {
+        Some(base_dir) if path.is_relative() => base_dir.join(path),
+        _ => path.to_path_buf(),
+    };
+    let bytes = std::fs::read(&resolved).map_err(|e| ImageSourceError::Read {
+        path: resolved.display().to_string(),
+        message: e.to_string(),
+    })?;
+    ImageData::from_bytes(bytes)
+}
+
+/// 由数据字段的值加载图片：base64 文本、字节数组、数据 URI 或文件路径
+pub fn from_value(value: &Value, base_dir: Option<&Path>) -> ImageSourceResult<ImageData> {
+    match value {
+        Value::Null => Err(ImageSourceError::Empty),
+        Value::String(text) => {
+            let text = text.trim();
+            match decode_base64(text) {
+                Some(bytes) if !text.starts_with("data:") => ImageData::from_bytes(bytes),
+                _ => load(text, base_dir),
+            }
+        }
+        Value::Array(items) => {
+            let bytes = items
+                .iter()
+                .map(|item| item.as_u64().filter(|byte| *byte <= u8::MAX as u64).map(|byte| byte as u8))
+                .collect::<Option<Vec<u8>>>()
+                .ok_or_else(|| ImageSourceError::InvalidData { message: "Byte array expected".to_string() })?;
+            ImageData::from_bytes(bytes)
+        }
+        other => Err(ImageSourceError::InvalidData { message: format!("Unsupported image value: {}", other) }),
+    }
+}
+
+fn decode_data_uri(uri: &str) -> ImageSourceResult<Vec<u8>> {
+    let (header, data) = uri
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(','))
+        .ok_or_else(|| ImageSourceError::InvalidData { message: "Malformed data URI".to_string() })?;
+    if !header.ends_with(";base64") {
+        return Err(ImageSourceError::InvalidData { message: "Only base64 data URIs are supported".to_string() });
+    }
+    decode_base64(data).ok_or_else(|| ImageSourceError::InvalidData { message: "Invalid base64 data".to_string() })
+}
+
+/// 只有能识别出图片格式的 base64 才算图片内容，避免把文件名误当作 base64
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let compact: String = text.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    let bytes = STANDARD.decode(compact).ok()?;
+    image::guess_format(&bytes).is_ok().then_some(bytes)
+}
+
+fn invalid_data(error: impl ToString) -> ImageSourceError {
+    ImageSourceError::InvalidData { message: error.to_string() }
+}
+
+/// 图片在元素框内的位置和尺寸（相对元素左上角）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// 按缩放方式和对齐计算图片位置；Clip 按 1 像素对应 1 个单位绘制，结果可能超出元素框
+pub fn place(
+    scale_mode: ImageScaleMode,
+    horizontal: ImageAlign,
+    vertical: ImageAlign,
+    (box_width, box_height): (f64, f64),
+    (image_width, image_height): (f64, f64),
+) -> Placement {
+    let (width, height) = match scale_mode {
+        ImageScaleMode::FillFrame => (box_width, box_height),
+        ImageScaleMode::Clip => (image_width, image_height),
+        ImageScaleMode::RetainShape => {
+            let scale = (box_width / image_width).min(box_height / image_height);
+            (image_width * scale, image_height * scale)
+        }
+    };
+    Placement {
+        x: align_offset(horizontal, box_width - width),
+        y: align_offset(vertical, box_height - height),
+        width,
+        height,
+    }
+}
+
+fn align_offset(align: ImageAlign, free: f64) -> f64 {
+    match align {
+        ImageAlign::Start => 0.0,
+        ImageAlign::Center => free / 2.0,
+        ImageAlign::End => free,
+    }
+}
+
+/// 计算图片在元素框内的位置；Clip 超出元素框时裁掉框外部分，返回裁剪后的图片
+pub fn fit(
+    image: &ImageData,
+    scale_mode: ImageScaleMode,
+    horizontal: ImageAlign,
+    vertical: ImageAlign,
+    (box_width, box_height): (f64, f64),
+) -> ImageSourceResult<(Placement, ImageData)> {
+    let placement = place(
+        scale_mode,
+        horizontal,
+        vertical,
+        (box_width, box_height),
+        (image.width as f64, image.height as f64),
+    );
+    let inside = placement.x >= 0.0
+        && placement.y >= 0.0
+        && placement.x + placement.width <= box_width
+        && placement.y + placement.height <= box_height;
+    if scale_mode != ImageScaleMode::Clip || inside {
+        return Ok((placement, image.clone()));
+    }
+
+    let left = placement.x.max(0.0);
+    let top = placement.y.max(0.0);
+    let right = (placement.x + placement.width).min(box_width);
+    let bottom = (placement.y + placement.height).min(box_height);
+    let crop_x = ((left - placement.x).round() as u32).min(image.width - 1);
+    let crop_y = ((top - placement.y).round() as u32).min(image.height - 1);
+    let crop_width = ((right - left).round() as u32).clamp(1, image.width - crop_x);
+    let crop_height = ((bottom - top).round() as u32).clamp(1, image.height - crop_y);
+
+    let cropped = image.decode()?.crop_imm(crop_x, crop_y, crop_width, crop_height);
+    let placement = Placement { x: left, y: top, width: crop_width as f64, height: crop_height as f64 };
+    Ok((placement, ImageData::from_image(&cropped)?))
+}
+
+/// 输出 SVG image 节点，坐标相对元素左上角
+pub fn render_svg(
+    image: &ImageData,
+    scale_mode: ImageScaleMode,
+    horizontal: ImageAlign,
+    vertical: ImageAlign,
+    size: (f64, f64),
+) -> ImageSourceResult<String> {
+    let (placement, image) = fit(image, scale_mode, horizontal, vertical, size)?;
+    Ok(format!(
+        r#"<image x="{:.3}" y="{:.3}" width="{:.3}" height="{:.3}" preserveAspectRatio="none" href="{}"/>"#,
+        placement.x,
+        placement.y,
+        placement.width,
+        placement.height,
+        image.to_data_uri()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, Rgba, RgbaImage};
+
+    fn sample(width: u32, height: u32) -> ImageData {
+        let image = RgbaImage::from_fn(width, height, |x, _| Rgba([(x * 10) as u8, 0, 0, 255]));
+        ImageData::from_image(&DynamicImage::ImageRgba8(image)).unwrap()
+    }
+
+    #[test]
+    fn test_place_scale_modes() {
+        let (start, center, end) = (ImageAlign::Start, ImageAlign::Center, ImageAlign::End);
+
+        let fill = place(ImageScaleMode::FillFrame, end, end, (100.0, 50.0), (20.0, 20.0));
+        assert_eq!(fill, Placement { x: 0.0, y: 0.0, width: 100.0, height: 50.0 });
+
+        let retain = place(ImageScaleMode::RetainShape, center, start, (100.0, 50.0), (20.0, 20.0));
+        assert_eq!(retain, Placement { x: 25.0, y: 0.0, width: 50.0, height: 50.0 });
+
+        let clip = place(ImageScaleMode::Clip, end, center, (100.0, 50.0), (20.0, 10.0));
+        assert_eq!(clip, Placement { x: 80.0, y: 20.0, width: 20.0, height: 10.0 });
+    }
+
+    #[test]
+    fn test_fit_clip_crops_to_box() {
+        let image = sample(20, 10);
+        let (placement, cropped) =
+            fit(&image, ImageScaleMode::Clip, ImageAlign::Center, ImageAlign::Start, (10.0, 10.0)).unwrap();
+        assert_eq!(placement, Placement { x: 0.0, y: 0.0, width: 10.0, height: 10.0 });
+        assert_eq!((cropped.width, cropped.height), (10, 10));
+        // 居中裁剪，保留的是原图第 5~14 列
+        let pixel = cropped.decode().unwrap().get_pixel(0, 0);
+        assert_eq!(pixel[0], 50);
+    }
+
+    #[test]
+    fn test_load_sources() {
+        let image = sample(4, 3);
+        let uri = image.to_data_uri();
+        assert_eq!(load(&uri, None).unwrap(), image);
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("logo.png"), &image.bytes).unwrap();
+        assert_eq!(load("logo.png", Some(dir.path())).unwrap().width, 4);
+        assert!(matches!(load("missing.png", Some(dir.path())), Err(ImageSourceError::Read { .. })));
+
+        // 数据字段：base64 文本、字节数组和相对路径
+        let base64 = Value::String(STANDARD.encode(&image.bytes));
+        assert_eq!(from_value(&base64, None).unwrap(), image);
+        let bytes = Value::Array(image.bytes.iter().map(|b| Value::from(*b)).collect());
+        assert_eq!(from_value(&bytes, None).unwrap(), image);
+        assert_eq!(from_value(&Value::from("logo.png"), Some(dir.path())).unwrap().height, 3);
+        assert_eq!(from_value(&Value::Null, None), Err(ImageSourceError::Empty));
+    }
+}
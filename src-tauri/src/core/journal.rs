@@ -0,0 +1,445 @@
+// === Autosave Journal ===
+// 编辑会话的预写日志：快照 + 快照之后的修改，异常退出后可恢复未保存的编辑
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, RwLock};
+use crate::core::canvas::CanvasConfig;
+use crate::core::history::Operation;
+use crate::core::state::AppState;
+use crate::core::template::{BandConfig, BandType};
+use crate::errors::{AppError, Result};
+
+const AUTOSAVE_DIR: &str = "autosave";
+const RECOVERY_DIR: &str = "recovery";
+/// 会话运行期间存在，正常退出时删除
+const LOCK_FILE: &str = "session.lock";
+const SNAPSHOT_FILE: &str = "snapshot.json";
+const JOURNAL_FILE: &str = "journal.jsonl";
+/// 日志累计到此条数时写入新快照
+const SNAPSHOT_EVERY: usize = 100;
+/// 定时快照的间隔
+pub const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(30);
+
+pub type ManagedSessionJournal = Arc<Mutex<SessionJournal>>;
+
+/// 一条会话修改，重放时按顺序作用于快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JournalEntry {
+    /// 记入撤销历史的元素操作
+    Operation { operation: Operation, description: String },
+    Undo,
+    Redo,
+    ClearHistory,
+    Canvas { config: CanvasConfig },
+    Band { band_type: BandType, config: Option<BandConfig> },
+}
+
+/// 日志文件中的一行
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalRecord {
+    seq: u64,
+    timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    entry: JournalEntry,
+}
+
+/// 完整的编辑状态，`seq` 为已包含的最后一条日志
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionSnapshot {
+    seq: u64,
+    saved_at: DateTime<Utc>,
+    state: AppState,
+}
+
+/// 上次异常退出留下的未保存编辑
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryInfo {
+    pub template_name: Option<String>,
+    pub snapshot_at: DateTime<Utc>,
+    /// 最后一次修改的时间
+    pub last_change_at: DateTime<Utc>,
+    /// 快照之后的修改条数
+    pub pending_changes: usize,
+    pub element_count: usize,
+}
+
+pub struct SessionJournal {
+    dir: PathBuf,
+    /// 最后写入的日志序号
+    seq: u64,
+    /// 当前快照之后写入的条数
+    since_snapshot: usize,
+    has_snapshot: bool,
+}
+
+impl SessionJournal {
+    /// 开始新会话；上次会话未正常退出时，把它的快照和日志移入恢复目录
+    pub fn start(data_dir: &Path) -> Result<Self> {
+        let dir = data_dir.join(AUTOSAVE_DIR);
+        create_dir(&dir)?;
+
+        let unclean = dir.join(LOCK_FILE).exists();
+        if unclean && dir.join(SNAPSHOT_FILE).exists() {
+            let recovery = dir.join(RECOVERY_DIR);
+            remove_dir(&recovery)?;
+            create_dir(&recovery)?;
+            for name in [SNAPSHOT_FILE, JOURNAL_FILE] {
+                if dir.join(name).exists() {
+                    fs::rename(dir.join(name), recovery.join(name)).map_err(|e| AppError::FileError {
+                        message: format!("Failed to move '{}' to recovery: {}", name, e),
+                    })?;
+                }
+            }
+            log::warn!("Previous session did not shut down cleanly, unsaved changes kept for recovery");
+        }
+
+        let journal = Self { dir, seq: 0, since_snapshot: 0, has_snapshot: false };
+        journal.discard_files()?;
+        write_atomic(&journal.dir.join(LOCK_FILE), Utc::now().to_rfc3339().as_bytes())?;
+        Ok(journal)
+    }
+
+    /// 写入状态中尚未记录的修改；还没有快照时直接以当前状态作为快照
+    pub fn record(&mut self, state: &mut AppState) -> Result<()> {
+        let entries = state.take_journal();
+        if entries.is_empty() {
+            return Ok(());
+        }
+        if !self.has_snapshot {
+            return self.snapshot(state);
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(JOURNAL_FILE))
+            .map_err(|e| AppError::FileError {
+                message: format!("Failed to open autosave journal: {}", e),
+            })?;
+        let mut lines = String::new();
+        for entry in entries {
+            self.seq += 1;
+            let record = JournalRecord { seq: self.seq, timestamp: Utc::now(), entry };
+            lines.push_str(&to_json(&record)?);
+            lines.push('\n');
+            self.since_snapshot += 1;
+        }
+        file.write_all(lines.as_bytes())
+            .and_then(|_| file.sync_data())
+            .map_err(|e| AppError::FileError {
+                message: format!("Failed to write autosave journal: {}", e),
+            })?;
+
+        if self.since_snapshot >= SNAPSHOT_EVERY {
+            self.snapshot(state)?;
+        }
+        Ok(())
+    }
+
+    /// 写入完整快照并清空日志
+    pub fn snapshot(&mut self, state: &AppState) -> Result<()> {
+        let snapshot = SessionSnapshot { seq: self.seq, saved_at: Utc::now(), state: state.clone() };
+        write_atomic(&self.dir.join(SNAPSHOT_FILE), to_json(&snapshot)?.as_bytes())?;
+        remove_file(&self.dir.join(JOURNAL_FILE))?;
+        self.has_snapshot = true;
+        self.since_snapshot = 0;
+        Ok(())
+    }
+
+    /// 有未写入快照的日志时才需要定时快照
+    pub fn needs_snapshot(&self) -> bool {
+        self.since_snapshot > 0
+    }
+
+    /// 模板已保存或新建/打开了模板：之前的修改无需恢复
+    pub fn reset(&mut self, state: &mut AppState) -> Result<()> {
+        state.take_journal();
+        self.clear();
+        self.discard_files()
+    }
+
+    /// 正常退出：删除会话文件和锁
+    pub fn close(&mut self) -> Result<()> {
+        self.discard_files()?;
+        remove_file(&self.dir.join(LOCK_FILE))
+    }
+
+    fn discard_files(&self) -> Result<()> {
+        remove_file(&self.dir.join(SNAPSHOT_FILE))?;
+        remove_file(&self.dir.join(JOURNAL_FILE))
+    }
+
+    fn clear(&mut self) {
+        self.seq = 0;
+        self.since_snapshot = 0;
+        self.has_snapshot = false;
+    }
+
+    // === Recovery ===
+
+    /// 上次异常退出留下的编辑，没有时返回 `None`
+    pub fn recovery_info(&self) -> Result<Option<RecoveryInfo>> {
+        let Some((snapshot, records)) = self.read_recovery()? else { return Ok(None) };
+        Ok(Some(RecoveryInfo {
+            template_name: snapshot.state.template_name.clone(),
+            snapshot_at: snapshot.saved_at,
+            last_change_at: records.last().map_or(snapshot.saved_at, |record| record.timestamp),
+            pending_changes: records.len(),
+            element_count: snapshot.state.elements.len(),
+        }))
+    }
+
+    /// 恢复上次的编辑：快照加日志重放，恢复后以该状态开始新的日志
+    pub fn restore(&mut self) -> Result<AppState> {
+        let (snapshot, records) = self.read_recovery()?.ok_or_else(|| AppError::FileError {
+            message: "No unsaved session to recover".to_string(),
+        })?;
+
+        let mut state = snapshot.state;
+        let entries: Vec<JournalEntry> = records.into_iter().map(|record| record.entry).collect();
+        state.replay(&entries)?;
+        state.set_dirty(true);
+
+        self.clear();
+        self.snapshot(&state)?;
+        remove_dir(&self.dir.join(RECOVERY_DIR))?;
+        Ok(state)
+    }
+
+    /// 放弃恢复
+    pub fn discard_recovery(&mut self) -> Result<()> {
+        remove_dir(&self.dir.join(RECOVERY_DIR))
+    }
+
+    /// 读取恢复目录；日志末尾因崩溃写坏的行被忽略
+    fn read_recovery(&self) -> Result<Option<(SessionSnapshot, Vec<JournalRecord>)>> {
+        let dir = self.dir.join(RECOVERY_DIR);
+        let snapshot_path = dir.join(SNAPSHOT_FILE);
+        if !snapshot_path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&snapshot_path).map_err(|e| AppError::FileError {
+            message: format!("Failed to read autosave snapshot: {}", e),
+        })?;
+        let snapshot: SessionSnapshot = serde_json::from_str(&content).map_err(|e| AppError::SerializationError {
+            message: format!("Failed to parse autosave snapshot: {}", e),
+        })?;
+
+        let mut records = Vec::new();
+        if let Ok(file) = File::open(dir.join(JOURNAL_FILE)) {
+            for line in BufReader::new(file).lines() {
+                let Ok(line) = line else { break };
+                match serde_json::from_str::<JournalRecord>(&line) {
+                    // 快照已包含的条目
+                    Ok(record) if record.seq <= snapshot.seq => {}
+                    Ok(record) => records.push(record),
+                    Err(e) => {
+                        log::warn!("Ignoring damaged autosave journal tail: {}", e);
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(Some((snapshot, records)))
+    }
+}
+
+/// 定时为有修改的会话写入快照，缩短恢复时需要重放的日志
+pub fn spawn_autosave(
+    state: Arc<RwLock<AppState>>,
+    journal: ManagedSessionJournal,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let mut app_state = state.write().await;
+            let mut journal = journal.lock().await;
+            let result = journal.record(&mut app_state).and_then(|_| {
+                if journal.needs_snapshot() {
+                    journal.snapshot(&app_state)
+                } else {
+                    Ok(())
+                }
+            });
+            if let Err(e) = result {
+                log::warn!("Autosave failed: {}", e);
+            }
+        }
+    })
+}
+
+/// 命令修改状态后调用，失败只记录日志，不影响编辑本身
+pub async fn record_changes(journal: &ManagedSessionJournal, state: &mut AppState) {
+    if let Err(e) = journal.lock().await.record(state) {
+        log::warn!("Failed to write autosave journal: {}", e);
+    }
+}
+
+/// 保存、新建或打开模板后调用，之前的修改不再需要恢复
+pub async fn reset_journal(journal: &ManagedSessionJournal, state: &mut AppState) {
+    if let Err(e) = journal.lock().await.reset(state) {
+        log::warn!("Failed to reset autosave journal: {}", e);
+    }
+}
+
+// === Helpers ===
+
+fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|e| AppError::SerializationError {
+        message: format!("Failed to serialize autosave data: {}", e),
+    })
+}
+
+/// 先写临时文件再改名，崩溃时不会留下半个快照
+fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let temp = path.with_extension("tmp");
+    let result = File::create(&temp)
+        .and_then(|mut file| file.write_all(data).and_then(|_| file.sync_all()))
+        .and_then(|_| fs::rename(&temp, path));
+    result.map_err(|e| AppError::FileError {
+        message: format!("Failed to write '{}': {}", path.display(), e),
+    })
+}
+
+fn create_dir(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir).map_err(|e| AppError::FileError {
+        message: format!("Failed to create directory '{}': {}", dir.display(), e),
+    })
+}
+
+fn remove_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(AppError::FileError {
+            message: format!("Failed to remove '{}': {}", path.display(), e),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn remove_dir(dir: &Path) -> Result<()> {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(AppError::FileError {
+            message: format!("Failed to remove '{}': {}", dir.display(), e),
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{ElementContent, Position, ReportElement, Size};
+    use crate::core::template::SplitType;
+    use tempfile::TempDir;
+
+    fn rectangle(x: f64) -> ReportElement {
+        let content = ElementContent::Rectangle {
+            fill_color: Some("#ffffff".to_string()),
+            border: None,
+            corner_radius: None,
+            opacity: None,
+        };
+        ReportElement::new(content, Position::new(x, 10.0).unwrap(), Size::new(50.0, 20.0).unwrap()).unwrap()
+    }
+
+    /// 模拟命令：修改状态后写日志
+    fn edit(journal: &mut SessionJournal, state: &mut AppState, change: impl FnOnce(&mut AppState)) {
+        change(state);
+        journal.record(state).unwrap();
+    }
+
+    #[test]
+    fn test_recover_after_crash() {
+        let data_dir = TempDir::new().unwrap();
+        let mut journal = SessionJournal::start(data_dir.path()).unwrap();
+        let mut state = AppState::new();
+        state.template_name = Some("回单".to_string());
+
+        let (first, second) = (rectangle(10.0), rectangle(80.0));
+        let first_id = first.id.clone();
+        edit(&mut journal, &mut state, |state| state.add_element(first).unwrap());
+        edit(&mut journal, &mut state, |state| state.add_element(second).unwrap());
+        edit(&mut journal, &mut state, |state| {
+            let mut moved = state.get_element(&first_id).unwrap().clone();
+            moved.position = Position::new(30.0, 40.0).unwrap();
+            state.update_element(&first_id, moved).unwrap();
+        });
+        edit(&mut journal, &mut state, |state| state.undo().unwrap());
+        edit(&mut journal, &mut state, |state| {
+            state.set_band(BandType::Detail, Some(BandConfig::new(60.0))).unwrap()
+        });
+        // 崩溃时最后一行只写了一半
+        let mut file = OpenOptions::new().append(true).open(journal.dir.join(JOURNAL_FILE)).unwrap();
+        file.write_all(b"{\"seq\":99,\"timest").unwrap();
+        drop(journal);
+
+        let mut journal = SessionJournal::start(data_dir.path()).unwrap();
+        let info = journal.recovery_info().unwrap().unwrap();
+        assert_eq!(info.template_name.as_deref(), Some("回单"));
+        assert_eq!(info.pending_changes, 4);
+        assert_eq!(info.element_count, 1);
+
+        let restored = journal.restore().unwrap();
+        assert_eq!(restored.elements.len(), 2);
+        assert_eq!(restored.get_element(&first_id).unwrap().position.x, 10.0);
+        assert_eq!(restored.bands.detail.as_ref().unwrap().split_type, SplitType::Stretch);
+        assert!(restored.can_undo() && restored.can_redo());
+        assert!(restored.is_dirty());
+        assert!(journal.recovery_info().unwrap().is_none());
+
+        // 恢复后的状态成为新会话的快照
+        journal.close().unwrap();
+        assert!(!data_dir.path().join(AUTOSAVE_DIR).join(LOCK_FILE).exists());
+    }
+
+    #[test]
+    fn test_snapshots_and_clean_shutdown() {
+        let data_dir = TempDir::new().unwrap();
+        let mut journal = SessionJournal::start(data_dir.path()).unwrap();
+        let mut state = AppState::new();
+        for index in 0..=SNAPSHOT_EVERY {
+            edit(&mut journal, &mut state, |state| state.add_element(rectangle(index as f64)).unwrap());
+        }
+        // 达到条数后写入快照，日志重新开始
+        assert!(!journal.needs_snapshot());
+        edit(&mut journal, &mut state, |state| state.clear_history());
+        assert!(journal.needs_snapshot());
+
+        journal.close().unwrap();
+        let journal = SessionJournal::start(data_dir.path()).unwrap();
+        assert!(journal.recovery_info().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_saved_template_is_not_recovered() {
+        let data_dir = TempDir::new().unwrap();
+        let mut journal = SessionJournal::start(data_dir.path()).unwrap();
+        let mut state = AppState::new();
+        edit(&mut journal, &mut state, |state| state.add_element(rectangle(10.0)).unwrap());
+        journal.reset(&mut state).unwrap();
+        drop(journal);
+
+        let mut journal = SessionJournal::start(data_dir.path()).unwrap();
+        assert!(journal.recovery_info().unwrap().is_none());
+        assert!(journal.restore().is_err());
+
+        // 保存之后的新修改重新从快照开始记录
+        let mut state = AppState::new();
+        edit(&mut journal, &mut state, |state| state.add_element(rectangle(10.0)).unwrap());
+        drop(journal);
+        let mut journal = SessionJournal::start(data_dir.path()).unwrap();
+        assert_eq!(journal.recovery_info().unwrap().unwrap().element_count, 1);
+        journal.discard_recovery().unwrap();
+        assert!(journal.recovery_info().unwrap().is_none());
+    }
+}
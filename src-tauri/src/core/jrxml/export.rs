@@ -0,0 +1,933 @@
+// === JRXML 导出 ===
+use crate::core::element::ImageScaleMode;
+use crate::core::jrxml::{PROPERTY_LOCALE, PROPERTY_NULL_TEXT, PROPERTY_REQUIRED};
+use crate::core::template::{
+    BandConfig, BandType, BorderStyle, BorderStyleType, Calculation, DataSourceType, DataType, ElementType,
+    FontStyle, FontWeight, HorizontalAlignment, IncrementType, JasperTemplate, PageOrientation, PageUnit, ResetType,
+    TemplateElement, VerticalAlignment,
+};
+use crate::errors::{AppError, Result};
+use crate::expression::{Expr, Expression};
+use serde_json::Value;
+use std::collections::HashSet;
+
+const JRXML_NAMESPACE: &str = "http://jasperreports.sourceforge.net/jasperreports";
+const JRXML_SCHEMA_LOCATION: &str =
+    "http://jasperreports.sourceforge.net/jasperreports http://jasperreports.sourceforge.net/xsd/jasperreport.xsd";
+/// 未声明目标版本时按此版本导出
+const DEFAULT_JASPERREPORTS_VERSION: &str = "6.20.0";
+
+/// 将模板导出为 JRXML，目标版本取自 `CompatibilityInfo.jasperreports_version`（支持 3.5 - 6.x）
+///
+/// 未使用区段的模板（单据模式）导出为一个占满页面的 detail 区段。
+pub fn export_jrxml(template: &JasperTemplate) -> Result<String> {
+    template.validate()?;
+
+    let version = template
+        .metadata
+        .compatibility
+        .jasperreports_version
+        .as_deref()
+        .unwrap_or(DEFAULT_JASPERREPORTS_VERSION);
+    let target = JrVersion::parse(version)?;
+
+    let mut exporter = Exporter {
+        template,
+        target,
+        scale: unit_scale(&template.canvas.unit),
+        xml: XmlWriter::new(),
+    };
+    exporter.write_report(version);
+    Ok(exporter.xml.out)
+}
+
+/// 目标 JasperReports 版本
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct JrVersion {
+    major: u32,
+    minor: u32,
+}
+
+impl JrVersion {
+    fn parse(version: &str) -> Result<Self> {
+        let mut parts = version.trim().split('.').map(|part| part.parse::<u32>());
+        let parsed = match (parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor))) => Self { major, minor },
+            (Some(Ok(major)), None) => Self { major, minor: 0 },
+            _ => {
+                return Err(AppError::SerializationError {
+                    message: format!("Invalid JasperReports version '{}'", version),
+                })
+            }
+        };
+        if parsed < (Self { major: 3, minor: 5 }) || parsed.major >= 7 {
+            return Err(AppError::SerializationError {
+                message: format!("JRXML export supports JasperReports 3.5 to 6.x, got {}", version),
+            });
+        }
+        Ok(parsed)
+    }
+
+    /// reportElement 的 uuid 属性从 JasperReports 4.6 开始支持
+    fn supports_uuid(self) -> bool {
+        self >= Self { major: 4, minor: 6 }
+    }
+}
+
+/// 模板单位换算为 JRXML 使用的点（1/72 英寸）
+fn unit_scale(unit: &PageUnit) -> f64 {
+    match unit {
+        PageUnit::Point => 1.0,
+        PageUnit::Millimeter => 72.0 / 25.4,
+        PageUnit::Inch => 72.0,
+        PageUnit::Pixel => 0.75,
+    }
+}
+
+struct Exporter<'a> {
+    template: &'a JasperTemplate,
+    target: JrVersion,
+    scale: f64,
+    xml: XmlWriter,
+}
+
+type Attributes = Vec<(&'static str, String)>;
+
+impl<'a> Exporter<'a> {
+    /// 坐标和尺寸换算为整数点
+    fn points(&self, value: f64) -> String {
+        ((value * self.scale).round() as i64).to_string()
+    }
+
+    fn write_report(&mut self, version: &str) {
+        let template = self.template;
+        let canvas = &template.canvas;
+        let margins = &canvas.margins;
+        let column_width = canvas.width - margins.left - margins.right;
+
+        self.xml.comment(&format!("Created with jasper-designer-v2 for JasperReports {}", version));
+        self.xml.start(
+            "jasperReport",
+            vec![
+                ("xmlns", JRXML_NAMESPACE.to_string()),
+                ("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance".to_string()),
+                ("xsi:schemaLocation", JRXML_SCHEMA_LOCATION.to_string()),
+                ("name", template.metadata.description.clone().unwrap_or_else(|| "report".to_string())),
+                ("pageWidth", self.points(canvas.width)),
+                ("pageHeight", self.points(canvas.height)),
+                (
+                    "orientation",
+                    match canvas.orientation {
+                        PageOrientation::Landscape => "Landscape",
+                        PageOrientation::Portrait => "Portrait",
+                    }
+                    .to_string(),
+                ),
+                ("columnWidth", self.points(column_width)),
+                ("leftMargin", self.points(margins.left)),
+                ("rightMargin", self.points(margins.right)),
+                ("topMargin", self.points(margins.top)),
+                ("bottomMargin", self.points(margins.bottom)),
+            ],
+        );
+
+        // 子元素顺序遵循 jasperreport.xsd
+        self.write_properties();
+        self.write_parameters();
+        self.write_data_source();
+        self.write_variables();
+        self.write_groups();
+        self.write_bands();
+
+        self.xml.end("jasperReport");
+    }
+
+    fn write_properties(&mut self) {
+        let formatting = &self.template.formatting;
+        self.xml.empty("property", vec![("name", PROPERTY_LOCALE.to_string()), ("value", formatting.locale.clone())]);
+        if !formatting.null_text.is_empty() {
+            self.xml
+                .empty("property", vec![("name", PROPERTY_NULL_TEXT.to_string()), ("value", formatting.null_text.clone())]);
+        }
+    }
+
+    fn write_parameters(&mut self) {
+        for parameter in &self.template.parameters {
+            self.xml.start(
+                "parameter",
+                vec![("name", parameter.name.clone()), ("class", java_class(&parameter.param_type).to_string())],
+            );
+            if parameter.required {
+                self.xml.empty("property", vec![("name", PROPERTY_REQUIRED.to_string()), ("value", "true".to_string())]);
+            }
+            if let Some(description) = &parameter.description {
+                self.xml.cdata("parameterDescription", description);
+            }
+            if let Some(literal) = parameter.default.as_ref().and_then(|value| java_literal(value, &parameter.param_type)) {
+                self.xml.cdata("defaultValueExpression", &literal);
+            }
+            self.xml.end("parameter");
+        }
+    }
+
+    /// 第一个数据源导出为 queryString、field 和 sortField
+    fn write_data_source(&mut self) {
+        let Some(source) = self.template.data_sources.first() else {
+            return;
+        };
+        if self.template.data_sources.len() > 1 {
+            log::warn!(
+                "JRXML export only includes the first data source, {} ignored",
+                self.template.data_sources.len() - 1
+            );
+        }
+
+        if let Some(query) = &source.query {
+            let (language, text) = match source.source_type {
+                DataSourceType::Json => ("json", query.path.as_ref()),
+                DataSourceType::Xml => ("xPath", query.path.as_ref()),
+                _ => ("SQL", query.sql.as_ref().or(query.path.as_ref())),
+            };
+            if let Some(text) = text {
+                self.xml.cdata_with("queryString", vec![("language", language.to_string())], text);
+            }
+        }
+
+        for column in &source.schema.columns {
+            let attributes = vec![("name", column.name.clone()), ("class", java_class(&column.data_type).to_string())];
+            match &column.description {
+                Some(description) => {
+                    self.xml.start("field", attributes);
+                    self.xml.cdata("fieldDescription", description);
+                    self.xml.end("field");
+                }
+                None => self.xml.empty("field", attributes),
+            }
+        }
+
+        let sort = source.query.as_ref().and_then(|query| query.sort.as_deref()).unwrap_or_default();
+        for item in sort.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let mut parts = item.split_whitespace();
+            let name = parts.next().unwrap_or_default().to_string();
+            let mut attributes = vec![("name", name)];
+            if parts.next().map_or(false, |direction| direction.eq_ignore_ascii_case("desc")) {
+                attributes.push(("order", "Descending".to_string()));
+            }
+            self.xml.empty("sortField", attributes);
+        }
+    }
+
+    fn write_variables(&mut self) {
+        for variable in &self.template.variables {
+            let mut attributes = vec![("name", variable.name.clone()), ("class", java_class(&variable.var_type).to_string())];
+            if variable.reset_type != ResetType::Report {
+                attributes.push(("resetType", format!("{:?}", variable.reset_type)));
+            }
+            if let Some(group) = variable.reset_group.as_ref().filter(|_| variable.reset_type == ResetType::Group) {
+                attributes.push(("resetGroup", group.clone()));
+            }
+            if variable.increment_type != IncrementType::None {
+                attributes.push(("incrementType", format!("{:?}", variable.increment_type)));
+            }
+            if let Some(group) = variable.increment_group.as_ref().filter(|_| variable.increment_type == IncrementType::Group) {
+                attributes.push(("incrementGroup", group.clone()));
+            }
+            if variable.calculation != Calculation::Nothing {
+                let calculation = match variable.calculation {
+                    Calculation::Min => "Lowest".to_string(),
+                    Calculation::Max => "Highest".to_string(),
+                    other => format!("{:?}", other),
+                };
+                attributes.push(("calculation", calculation));
+            }
+
+            self.xml.start("variable", attributes);
+            if !variable.expression.trim().is_empty() {
+                self.xml.cdata("variableExpression", &java_expression(&variable.expression));
+            }
+            if let Some(literal) = variable.initial_value.as_ref().and_then(|value| java_literal(value, &variable.var_type)) {
+                self.xml.cdata("initialValueExpression", &literal);
+            }
+            self.xml.end("variable");
+        }
+    }
+
+    fn write_groups(&mut self) {
+        for group in &self.template.groups {
+            let mut attributes = vec![("name", group.name.clone())];
+            if group.start_on_new_page {
+                attributes.push(("isStartNewPage", "true".to_string()));
+            }
+            if group.keep_together {
+                attributes.push(("keepTogether", "true".to_string()));
+            }
+
+            self.xml.start("group", attributes);
+            self.xml.cdata("groupExpression", &java_expression(&group.expression));
+            for (section, band) in [("groupHeader", &group.header), ("groupFooter", &group.footer)] {
+                if let Some(band) = band {
+                    self.xml.start(section, Vec::new());
+                    self.write_band(band);
+                    self.xml.end(section);
+                }
+            }
+            self.xml.end("group");
+        }
+    }
+
+    fn write_bands(&mut self) {
+        let template = self.template;
+        let canvas = &template.canvas;
+        let content_height = canvas.height - canvas.margins.top - canvas.margins.bottom;
+
+        // 不属于任何区段的元素使用页面坐标
+        let band_members: HashSet<&str> = template
+            .bands
+            .iter()
+            .map(|(_, band)| band)
+            .chain(template.groups.iter().flat_map(|group| group.bands().map(|(_, band)| band)))
+            .flat_map(|band| band.elements.iter().map(String::as_str))
+            .collect();
+        let mut free: Vec<&TemplateElement> =
+            template.elements.iter().filter(|element| !band_members.contains(element.id.as_str())).collect();
+        free.sort_by_key(|element| element.z_index);
+
+        if template.bands.is_empty() {
+            self.xml.start("detail", Vec::new());
+            self.write_page_band(&free, content_height);
+            self.xml.end("detail");
+            return;
+        }
+
+        if !free.is_empty() {
+            self.xml.start("background", Vec::new());
+            self.write_page_band(&free, content_height);
+            self.xml.end("background");
+        }
+        for (band_type, band) in template.bands.iter() {
+            let section = section_name(band_type);
+            self.xml.start(section, Vec::new());
+            self.write_band(band);
+            self.xml.end(section);
+        }
+    }
+
+    /// 页面坐标的元素写入占满内容区的区段
+    fn write_page_band(&mut self, elements: &[&TemplateElement], height: f64) {
+        let margins = &self.template.canvas.margins;
+        let origin = (margins.left, margins.top);
+        self.xml.start("band", vec![("height", self.points(height)), ("splitType", "Stretch".to_string())]);
+        for element in elements {
+            self.write_element(element, origin);
+        }
+        self.xml.end("band");
+    }
+
+    fn write_band(&mut self, band: &BandConfig) {
+        let attributes = vec![
+            ("height", self.points(band.height)),
+            ("splitType", format!("{:?}", band.split_type)),
+        ];
+        let mut elements: Vec<&TemplateElement> =
+            band.elements.iter().filter_map(|id| self.template.get_element_by_id(id)).collect();
+        if elements.is_empty() {
+            self.xml.empty("band", attributes);
+            return;
+        }
+
+        elements.sort_by_key(|element| element.z_index);
+        self.xml.start("band", attributes);
+        for element in elements {
+            self.write_element(element, (0.0, 0.0));
+        }
+        self.xml.end("band");
+    }
+
+    // === 元素 ===
+    fn write_element(&mut self, element: &TemplateElement, origin: (f64, f64)) {
+        let tag = match element.element_type {
+            ElementType::Text => "staticText",
+            ElementType::DataField => "textField",
+            ElementType::Rectangle => "rectangle",
+            ElementType::Line => "line",
+            ElementType::Image => "image",
+            ElementType::Barcode | ElementType::Chart | ElementType::Subreport | ElementType::Table
+            | ElementType::Crosstab => {
+                log::warn!("JRXML export skipped unsupported element '{}' ({:?})", element.id, element.element_type);
+                self.xml.comment(&format!("Unsupported element {:?} '{}' was not exported", element.element_type, element.id));
+                return;
+            }
+        };
+
+        let mut attributes = Vec::new();
+        if tag == "textField" {
+            if let Some(pattern) = element.content.format.as_deref().and_then(jasper_pattern) {
+                attributes.push(("pattern", pattern));
+            }
+        }
+        if tag == "image" {
+            let scale_image = match element.content.scale_mode.unwrap_or_default() {
+                ImageScaleMode::Clip => "Clip",
+                ImageScaleMode::FillFrame => "FillFrame",
+                ImageScaleMode::RetainShape => "RetainShape",
+            };
+            attributes.push(("scaleImage", scale_image.to_string()));
+            if let Some(alignment) = &element.content.alignment {
+                let horizontal = match alignment.horizontal {
+                    HorizontalAlignment::Center => "Center",
+                    HorizontalAlignment::Right => "Right",
+                    HorizontalAlignment::Left | HorizontalAlignment::Justify => "Left",
+                };
+                let vertical = match alignment.vertical {
+                    VerticalAlignment::Top => "Top",
+                    VerticalAlignment::Middle => "Middle",
+                    VerticalAlignment::Bottom => "Bottom",
+                };
+                attributes.push(("hAlign", horizontal.to_string()));
+                attributes.push(("vAlign", vertical.to_string()));
+            }
+        }
+        self.xml.start(tag, attributes);
+        self.write_report_element(element, origin);
+
+        match element.element_type {
+            ElementType::Text => {
+                self.write_box(element);
+                self.write_text_element(element);
+                self.xml.cdata("text", element.content.text.as_deref().unwrap_or_default());
+            }
+            ElementType::DataField => {
+                self.write_box(element);
+                self.write_text_element(element);
+                let expression = element
+                    .content
+                    .expression
+                    .clone()
+                    .filter(|expression| !expression.trim().is_empty())
+                    .or_else(|| element.data_binding.as_ref().map(|binding| format!("$F{{{}}}", binding.field_name)))
+                    .unwrap_or_default();
+                self.xml.cdata("textFieldExpression", &java_expression(&expression));
+            }
+            ElementType::Rectangle => {
+                let pen = element.style.border.clone();
+                self.write_graphic_pen(pen.as_ref(), None);
+            }
+            ElementType::Line => {
+                let pen = element.style.border.clone().unwrap_or(BorderStyle {
+                    width: 1.0,
+                    color: String::new(),
+                    style: BorderStyleType::Solid,
+                });
+                self.write_graphic_pen(Some(&pen), element.content.color.as_deref());
+            }
+            _ => {
+                self.write_box(element);
+                let source = element.content.expression.clone().unwrap_or_default();
+                let expression = if ["$F{", "$P{", "$V{"].iter().any(|prefix| source.contains(prefix)) {
+                    source
+                } else {
+                    java_string(&source)
+                };
+                self.xml.cdata("imageExpression", &expression);
+            }
+        }
+
+        self.xml.end(tag);
+    }
+
+    fn write_report_element(&mut self, element: &TemplateElement, origin: (f64, f64)) {
+        let mut attributes = Vec::new();
+        if self.target.supports_uuid() && uuid::Uuid::parse_str(&element.id).is_ok() {
+            attributes.push(("uuid", element.id.clone()));
+        }
+
+        // 矩形的填充色也可能记录在 content.color 中
+        let background = element.style.background.as_ref().and_then(|background| background.color.clone()).or_else(|| {
+            matches!(element.element_type, ElementType::Rectangle).then(|| element.content.color.clone()).flatten()
+        });
+        match &background {
+            Some(_) => attributes.push(("mode", "Opaque".to_string())),
+            None => attributes.push(("mode", "Transparent".to_string())),
+        }
+        attributes.extend([
+            ("x", self.points((element.position.x - origin.0).max(0.0))),
+            ("y", self.points((element.position.y - origin.1).max(0.0))),
+            ("width", self.points(element.size.width)),
+            ("height", self.points(element.size.height)),
+        ]);
+        if matches!(element.element_type, ElementType::Text | ElementType::DataField) {
+            if let Some(color) = &element.content.color {
+                attributes.push(("forecolor", color.clone()));
+            }
+        }
+        if let Some(color) = background {
+            attributes.push(("backcolor", color));
+        }
+
+        if element.visible {
+            self.xml.empty("reportElement", attributes);
+        } else {
+            self.xml.start("reportElement", attributes);
+            self.xml.cdata("printWhenExpression", "Boolean.FALSE");
+            self.xml.end("reportElement");
+        }
+    }
+
+    fn write_box(&mut self, element: &TemplateElement) {
+        let style = &element.style;
+        if style.border.is_none() && style.padding.is_none() {
+            return;
+        }
+
+        let mut attributes = Vec::new();
+        if let Some(padding) = &style.padding {
+            attributes.extend([
+                ("topPadding", number(padding.top)),
+                ("leftPadding", number(padding.left)),
+                ("bottomPadding", number(padding.bottom)),
+                ("rightPadding", number(padding.right)),
+            ]);
+        }
+        match &style.border {
+            Some(border) => {
+                self.xml.start("box", attributes);
+                self.xml.empty("pen", pen_attributes(border, None));
+                self.xml.end("box");
+            }
+            None => self.xml.empty("box", attributes),
+        }
+    }
+
+    fn write_graphic_pen(&mut self, pen: Option<&BorderStyle>, color: Option<&str>) {
+        let attributes = match pen {
+            Some(pen) => pen_attributes(pen, color),
+            None => vec![("lineWidth", "0.0".to_string())],
+        };
+        self.xml.start("graphicElement", Vec::new());
+        self.xml.empty("pen", attributes);
+        self.xml.end("graphicElement");
+    }
+
+    fn write_text_element(&mut self, element: &TemplateElement) {
+        let content = &element.content;
+        let mut attributes = Vec::new();
+        if let Some(alignment) = &content.alignment {
+            let horizontal = match alignment.horizontal {
+                HorizontalAlignment::Left => "Left",
+                HorizontalAlignment::Center => "Center",
+                HorizontalAlignment::Right => "Right",
+                HorizontalAlignment::Justify => "Justified",
+            };
+            let vertical = match alignment.vertical {
+                VerticalAlignment::Top => "Top",
+                VerticalAlignment::Middle => "Middle",
+                VerticalAlignment::Bottom => "Bottom",
+            };
+            attributes.push(("textAlignment", horizontal.to_string()));
+            attributes.push(("verticalAlignment", vertical.to_string()));
+        }
+
+        let Some(font) = &content.font else {
+            self.xml.empty("textElement", attributes);
+            return;
+        };
+        let mut font_attributes = vec![("fontName", font.family.clone()), ("size", number(font.size))];
+        if matches!(font.weight, FontWeight::Bold) {
+            font_attributes.push(("isBold", "true".to_string()));
+        }
+        if matches!(font.style, FontStyle::Italic | FontStyle::Oblique) {
+            font_attributes.push(("isItalic", "true".to_string()));
+        }
+        self.xml.start("textElement", attributes);
+        self.xml.empty("font", font_attributes);
+        self.xml.end("textElement");
+    }
+}
+
+fn section_name(band_type: BandType) -> &'static str {
+    match band_type {
+        BandType::Title => "title",
+        BandType::PageHeader => "pageHeader",
+        BandType::ColumnHeader => "columnHeader",
+        BandType::Detail => "detail",
+        BandType::ColumnFooter => "columnFooter",
+        BandType::PageFooter => "pageFooter",
+        BandType::Summary => "summary",
+    }
+}
+
+fn pen_attributes(pen: &BorderStyle, color: Option<&str>) -> Attributes {
+    let mut attributes = vec![("lineWidth", number(pen.width))];
+    let style = match pen.style {
+        BorderStyleType::Solid => "Solid",
+        BorderStyleType::Dashed => "Dashed",
+        BorderStyleType::Dotted => "Dotted",
+    };
+    attributes.push(("lineStyle", style.to_string()));
+    if let Some(color) = color.or(Some(pen.color.as_str())).filter(|color| !color.is_empty()) {
+        attributes.push(("lineColor", color.to_string()));
+    }
+    attributes
+}
+
+/// 小数去掉多余的零（1.0 -> 1.0，0.50 -> 0.5）
+fn number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.1}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// 模板数据类型对应的 Java 类
+fn java_class(data_type: &DataType) -> &'static str {
+    match data_type {
+        DataType::String => "java.lang.String",
+        DataType::Number => "java.math.BigDecimal",
+        DataType::Boolean => "java.lang.Boolean",
+        DataType::Date => "java.sql.Date",
+        DataType::DateTime => "java.sql.Timestamp",
+        DataType::Array => "java.util.List",
+        DataType::Object | DataType::Null => "java.lang.Object",
+    }
+}
+
+fn java_string(text: &str) -> String {
+    let mut literal = String::with_capacity(text.len() + 2);
+    literal.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\t' => literal.push_str("\\t"),
+            other => literal.push(other),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// 参数默认值、变量初始值转为 Java 字面量；数组和对象无法表示时返回 None
+fn java_literal(value: &Value, data_type: &DataType) -> Option<String> {
+    let literal = match (value, data_type) {
+        (Value::Null, _) => "null".to_string(),
+        (Value::Array(_) | Value::Object(_), _) => return None,
+        (Value::Bool(flag), DataType::Boolean | DataType::Object) => {
+            if *flag { "Boolean.TRUE" } else { "Boolean.FALSE" }.to_string()
+        }
+        (Value::Number(number), DataType::Number | DataType::Object) => {
+            format!("new java.math.BigDecimal(\"{}\")", number)
+        }
+        (Value::String(text), DataType::Number) if text.trim().parse::<f64>().is_ok() => {
+            format!("new java.math.BigDecimal(\"{}\")", text.trim())
+        }
+        (Value::String(text), DataType::Date) => format!("java.sql.Date.valueOf({})", java_string(text)),
+        (Value::String(text), DataType::DateTime) => format!("java.sql.Timestamp.valueOf({})", java_string(text)),
+        (Value::String(text), _) => java_string(text),
+        (other, _) => java_string(&other.to_string()),
+    };
+    Some(literal)
+}
+
+/// 旧写法的裸字段名（customerName / ${customerName}）转为 $F{...}，其余表达式原样输出
+fn java_expression(expression: &str) -> String {
+    let trimmed = expression.trim();
+    match Expression::parse(trimmed) {
+        Ok(parsed) if !trimmed.starts_with("$F{") => match &parsed.root().expr {
+            Expr::Field(path) => format!("$F{{{}}}", path),
+            _ => trimmed.to_string(),
+        },
+        _ => trimmed.to_string(),
+    }
+}
+
+/// 数字/日期模式原样导出；设计器扩展（`|空值文本`、中文大写金额）JasperReports 不支持，不导出
+fn jasper_pattern(format: &str) -> Option<String> {
+    let pattern = format.rsplit_once('|').map_or(format, |(pattern, _)| pattern).trim();
+    match crate::format::FormatPattern::parse(pattern) {
+        Ok(crate::format::FormatPattern::ChineseUpper) | Err(_) => None,
+        Ok(_) => Some(pattern.to_string()),
+    }
+}
+
+// === XML 输出 ===
+struct XmlWriter {
+    out: String,
+    depth: usize,
+}
+
+impl XmlWriter {
+    fn new() -> Self {
+        Self {
+            out: "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n".to_string(),
+            depth: 0,
+        }
+    }
+
+    fn open_tag(&mut self, tag: &str, attributes: &Attributes) {
+        self.out.push_str(&"\t".repeat(self.depth));
+        self.out.push('<');
+        self.out.push_str(tag);
+        for (name, value) in attributes {
+            self.out.push_str(&format!(" {}=\"{}\"", name, escape(value)));
+        }
+    }
+
+    fn start(&mut self, tag: &str, attributes: Attributes) {
+        self.open_tag(tag, &attributes);
+        self.out.push_str(">\n");
+        self.depth += 1;
+    }
+
+    fn end(&mut self, tag: &str) {
+        self.depth -= 1;
+        self.out.push_str(&format!("{}</{}>\n", "\t".repeat(self.depth), tag));
+    }
+
+    fn empty(&mut self, tag: &str, attributes: Attributes) {
+        self.open_tag(tag, &attributes);
+        self.out.push_str("/>\n");
+    }
+
+    fn cdata(&mut self, tag: &str, text: &str) {
+        self.cdata_with(tag, Vec::new(), text);
+    }
+
+    /// 文本内容放在 CDATA 中，`]]>` 拆分到两个 CDATA 段
+    fn cdata_with(&mut self, tag: &str, attributes: Attributes, text: &str) {
+        self.open_tag(tag, &attributes);
+        self.out.push_str(&format!("><![CDATA[{}]]></{}>\n", text.replace("]]>", "]]]]><![CDATA[>"), tag));
+    }
+
+    fn comment(&mut self, text: &str) {
+        self.out.push_str(&format!("{}<!-- {} -->\n", "\t".repeat(self.depth), text.replace("--", "- -")));
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{Position, Size};
+    use crate::core::jrxml::import_jrxml;
+
+    /// 只包含支持的结构，导入 -> 导出 -> 导入应完全一致
+    const SUPPORTED: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<jasperReport xmlns="http://jasperreports.sourceforge.net/jasperreports" name="Statement" pageWidth="595" pageHeight="842" columnWidth="535" leftMargin="30" rightMargin="30" topMargin="20" bottomMargin="20">
+    <property name="jasper.designer.format.locale" value="en-US"/>
+    <property name="jasper.designer.format.null.text" value="--"/>
+    <parameter name="accountId" class="java.lang.Integer">
+        <property name="jasper.designer.required" value="true"/>
+        <parameterDescription><![CDATA[Account]]></parameterDescription>
+    </parameter>
+    <parameter name="since" class="java.sql.Date">
+        <defaultValueExpression><![CDATA[java.sql.Date.valueOf("2024-01-01")]]></defaultValueExpression>
+    </parameter>
+    <parameter name="minAmount" class="java.math.BigDecimal">
+        <defaultValueExpression><![CDATA[new java.math.BigDecimal("0.5")]]></defaultValueExpression>
+    </parameter>
+    <queryString><![CDATA[SELECT day, amount, category FROM ledger WHERE account_id = $P{accountId} AND note <> ']]]]><![CDATA[>']]></queryString>
+    <field name="day" class="java.sql.Timestamp"/>
+    <field name="amount" class="java.math.BigDecimal">
+        <fieldDescription><![CDATA[金额 & "余额"]]></fieldDescription>
+    </field>
+    <field name="category" class="java.lang.String"/>
+    <sortField name="category"/>
+    <sortField name="day" order="Descending"/>
+    <variable name="categoryTotal" class="java.math.BigDecimal" resetType="Group" resetGroup="categoryGroup" calculation="Sum">
+        <variableExpression><![CDATA[$F{amount}]]></variableExpression>
+        <initialValueExpression><![CDATA[new java.math.BigDecimal("0")]]></initialValueExpression>
+    </variable>
+    <variable name="largest" class="java.math.BigDecimal" calculation="Highest">
+        <variableExpression><![CDATA[$F{amount}]]></variableExpression>
+    </variable>
+    <group name="categoryGroup" keepTogether="true">
+        <groupExpression><![CDATA[$F{category}]]></groupExpression>
+        <groupHeader>
+            <band height="24" splitType="Prevent">
+                <textField>
+                    <reportElement mode="Opaque" x="0" y="2" width="200" height="20" forecolor="#FFFFFF" backcolor="#336699"/>
+                    <box topPadding="2" leftPadding="4" bottomPadding="2" rightPadding="4"/>
+                    <textElement verticalAlignment="Middle"><font fontName="SansSerif" size="11" isBold="true"/></textElement>
+                    <textFieldExpression><![CDATA[$F{category}]]></textFieldExpression>
+                </textField>
+            </band>
+        </groupHeader>
+        <groupFooter>
+            <band height="20">
+                <textField pattern="#,##0.00;(#,##0.00)">
+                    <reportElement x="435" y="0" width="100" height="20"/>
+                    <textElement textAlignment="Right"/>
+                    <textFieldExpression><![CDATA[$V{categoryTotal}]]></textFieldExpression>
+                </textField>
+            </band>
+        </groupFooter>
+    </group>
+    <background>
+        <band height="802">
+            <image scaleImage="Clip" hAlign="Right" vAlign="Bottom">
+                <reportElement x="200" y="300" width="135" height="135"/>
+                <imageExpression><![CDATA["images/watermark.png"]]></imageExpression>
+            </image>
+        </band>
+    </background>
+    <title>
+        <band height="40">
+            <staticText>
+                <reportElement x="0" y="0" width="535" height="30"/>
+                <box><pen lineWidth="1.5" lineStyle="Dotted" lineColor="#333333"/></box>
+                <textElement textAlignment="Center"><font fontName="Serif" size="16.5" isItalic="true"/></textElement>
+                <text><![CDATA[Statement <"2024">]]></text>
+            </staticText>
+            <staticText>
+                <reportElement x="0" y="30" width="100" height="10">
+                    <printWhenExpression><![CDATA[Boolean.FALSE]]></printWhenExpression>
+                </reportElement>
+                <text><![CDATA[draft]]></text>
+            </staticText>
+        </band>
+    </title>
+    <detail>
+        <band height="20">
+            <textField pattern="yyyy-MM-dd">
+                <reportElement x="0" y="0" width="120" height="20"/>
+                <textFieldExpression><![CDATA[$F{day}]]></textFieldExpression>
+            </textField>
+            <line>
+                <reportElement x="0" y="19" width="535" height="1"/>
+                <graphicElement><pen lineWidth="0.5" lineColor="#CCCCCC"/></graphicElement>
+            </line>
+        </band>
+    </detail>
+    <pageFooter>
+        <band height="30" splitType="Immediate">
+            <rectangle>
+                <reportElement mode="Opaque" x="0" y="5" width="535" height="20" backcolor="#EEEEEE"/>
+                <graphicElement><pen lineWidth="0.0"/></graphicElement>
+            </rectangle>
+            <textField>
+                <reportElement x="400" y="5" width="135" height="20"/>
+                <textFieldExpression><![CDATA["Page " + $V{PAGE_NUMBER}]]></textFieldExpression>
+            </textField>
+        </band>
+    </pageFooter>
+    <summary>
+        <band height="0"/>
+    </summary>
+</jasperReport>"##;
+
+    /// 比较用的模板视图：元素ID替换为序号，忽略时间戳
+    fn normalized(template: &JasperTemplate) -> Value {
+        let ids: Vec<String> = template.elements.iter().map(|element| element.id.clone()).collect();
+        let text = serde_json::to_string(template).unwrap();
+        let text = ids
+            .iter()
+            .enumerate()
+            .fold(text, |text, (index, id)| text.replace(id, &format!("element-{}", index)));
+        let mut value: Value = serde_json::from_str(&text).unwrap();
+        value["metadata"]["created_at"] = Value::Null;
+        value["metadata"]["last_modified"] = Value::Null;
+        value
+    }
+
+    #[test]
+    fn test_round_trip_preserves_supported_constructs() {
+        let first = import_jrxml(SUPPORTED).unwrap();
+        assert!(first.unsupported.is_empty(), "{:?}", first.unsupported);
+
+        let exported = export_jrxml(&first.template).unwrap();
+        let second = import_jrxml(&exported).unwrap();
+        assert!(second.unsupported.is_empty(), "{:?}", second.unsupported);
+        assert_eq!(normalized(&first.template), normalized(&second.template));
+
+        // 除元素 uuid 外，再次导出的结果应完全相同
+        let without_uuid = |xml: &str| regex::Regex::new(r#" uuid="[^"]*""#).unwrap().replace_all(xml, "").into_owned();
+        assert_eq!(without_uuid(&export_jrxml(&second.template).unwrap()), without_uuid(&exported));
+    }
+
+    #[test]
+    fn test_round_trip_details() {
+        let template = import_jrxml(SUPPORTED).unwrap().template;
+        let template = import_jrxml(&export_jrxml(&template).unwrap()).unwrap().template;
+
+        assert_eq!(template.formatting.locale, "en-US");
+        assert_eq!(template.formatting.null_text, "--");
+        assert!(template.parameters[0].required);
+        assert_eq!(template.parameters[1].default, Some(Value::from("2024-01-01")));
+        assert_eq!(template.parameters[2].default, Some(Value::from(0.5)));
+        assert_eq!(template.variables[1].calculation, Calculation::Max);
+        assert!(template.elements.iter().any(|element| !element.visible));
+
+        let image = template.elements.iter().find(|element| matches!(element.element_type, ElementType::Image)).unwrap();
+        assert_eq!(image.content.scale_mode, Some(ImageScaleMode::Clip));
+        let alignment = image.content.alignment.as_ref().unwrap();
+        assert_eq!((&alignment.horizontal, &alignment.vertical), (&HorizontalAlignment::Right, &VerticalAlignment::Bottom));
+
+        let query = template.data_sources[0].query.as_ref().unwrap();
+        assert!(query.sql.as_deref().unwrap().ends_with("note <> ']]>'"));
+        assert_eq!(query.sort.as_deref(), Some("category ASC, day DESC"));
+        assert_eq!(
+            template.data_sources[0].schema.columns[1].description.as_deref(),
+            Some("金额 & \"余额\"")
+        );
+    }
+
+    fn element(element_type: ElementType, x: f64, y: f64) -> TemplateElement {
+        let mut element = TemplateElement::new(element_type, Position { x, y }, Size { width: 50.0, height: 10.0 });
+        element.content.text = Some("label".to_string());
+        element.content.expression = Some("customerName".to_string());
+        element.content.format = Some("#,##0.00|--".to_string());
+        element
+    }
+
+    #[test]
+    fn test_export_document_mode_template() {
+        let mut template = JasperTemplate::new();
+        template.canvas.unit = PageUnit::Millimeter;
+        template.canvas.width = 210.0;
+        template.canvas.height = 297.0;
+        template.canvas.margins.left = 10.0;
+        template.canvas.margins.top = 10.0;
+        template.elements.push(element(ElementType::DataField, 30.0, 40.0));
+        template.elements.push(element(ElementType::Chart, 0.0, 0.0));
+
+        let xml = export_jrxml(&template).unwrap();
+        let document = roxmltree::Document::parse(&xml).unwrap();
+        let root = document.root_element();
+        assert_eq!(root.attribute("pageWidth"), Some("595"));
+        assert_eq!(root.attribute("leftMargin"), Some("28"));
+
+        // 未使用区段时所有元素放入 detail，坐标减去页边距
+        let text_field = root.descendants().find(|node| node.has_tag_name("textField")).unwrap();
+        assert_eq!(text_field.attribute("pattern"), Some("#,##0.00"));
+        let report_element = text_field.first_element_child().unwrap();
+        assert!(report_element.attribute("uuid").is_some());
+        assert_eq!(report_element.attribute("x"), Some("57"));
+        assert_eq!(report_element.attribute("y"), Some("85"));
+        assert!(xml.contains("<![CDATA[$F{customerName}]]>"));
+        assert!(xml.contains("Unsupported element Chart"));
+        assert_eq!(root.children().filter(|node| node.has_tag_name("detail")).count(), 1);
+    }
+
+    #[test]
+    fn test_export_target_versions() {
+        let mut template = JasperTemplate::new();
+        template.elements.push(element(ElementType::Text, 30.0, 40.0));
+
+        template.metadata.compatibility.jasperreports_version = Some("4.5.1".to_string());
+        assert!(!export_jrxml(&template).unwrap().contains("uuid="));
+
+        template.metadata.compatibility.jasperreports_version = Some("7.0.0".to_string());
+        assert!(export_jrxml(&template).is_err());
+        template.metadata.compatibility.jasperreports_version = Some("latest".to_string());
+        assert!(export_jrxml(&template).is_err());
+    }
+}
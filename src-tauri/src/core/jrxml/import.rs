@@ -0,0 +1,1125 @@
+// === JRXML 导入 ===
+use crate::core::element::{ImageScaleMode, Position, Size};
+use crate::core::jrxml::{JrxmlImport, UnsupportedConstruct, PROPERTY_LOCALE, PROPERTY_NULL_TEXT, PROPERTY_REQUIRED};
+use crate::core::template::{
+    AlignmentConfig, BackgroundStyle, BandConfig, BandType, BorderStyle, BorderStyleType, Calculation, ColumnInfo,
+    DataQuery, DataSchema, DataSource, DataSourceConfig, DataSourceType, DataType, ElementContent, ElementStyle,
+    ElementType, FontConfig, FontStyle, FontWeight, Group, HorizontalAlignment, IncrementType, JasperTemplate,
+    PaddingConfig, PageMargins, PageOrientation, PageUnit, Parameter, ResetType, SplitType,
+    TemplateElement, Variable, VerticalAlignment,
+};
+use crate::errors::{AppError, Result};
+use crate::expression::Expression;
+use roxmltree::{Document, Node, ParsingOptions};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// JasperReports 未指定字体时的默认值
+const DEFAULT_FONT_FAMILY: &str = "SansSerif";
+const DEFAULT_FONT_SIZE: f64 = 10.0;
+const DEFAULT_COLOR: &str = "#000000";
+const DEFAULT_BACKCOLOR: &str = "#FFFFFF";
+
+/// 导入的主数据源ID
+pub const MAIN_DATA_SOURCE_ID: &str = "main";
+
+/// 解析 JRXML 并映射为模板；无法映射的结构记录在 `unsupported` 中而不是报错
+pub fn import_jrxml(xml: &str) -> Result<JrxmlImport> {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let document = Document::parse_with_options(xml, options).map_err(|e| AppError::SerializationError {
+        message: format!("Failed to parse JRXML: {}", e),
+    })?;
+    let root = document.root_element();
+    if root.tag_name().name() != "jasperReport" {
+        return Err(AppError::SerializationError {
+            message: format!("Not a JRXML document: unexpected root element <{}>", root.tag_name().name()),
+        });
+    }
+
+    let mut importer = Importer {
+        document: &document,
+        template: JasperTemplate::new(),
+        unsupported: Vec::new(),
+        next_z_index: 0,
+    };
+    importer.import_report(root);
+
+    let Importer { template, unsupported, .. } = importer;
+    template.validate()?;
+    Ok(JrxmlImport { template, unsupported })
+}
+
+struct Importer<'a, 'input> {
+    document: &'a Document<'input>,
+    template: JasperTemplate,
+    unsupported: Vec<UnsupportedConstruct>,
+    next_z_index: i32,
+}
+
+/// reportElement 中的通用属性
+struct ElementFrame {
+    position: Position,
+    size: Size,
+    forecolor: Option<String>,
+    backcolor: Option<String>,
+    /// mode 属性：Opaque / Transparent，未指定时为 None
+    opaque: Option<bool>,
+    /// printWhenExpression 为 Boolean.FALSE 时隐藏
+    visible: bool,
+}
+
+impl<'a, 'input> Importer<'a, 'input> {
+    fn import_report(&mut self, root: Node) {
+        self.import_page_format(root);
+
+        let mut fields = Vec::new();
+        let mut sort_fields = Vec::new();
+        let mut query = None;
+        for child in root.children().filter(Node::is_element) {
+            let name = child.tag_name().name();
+            match name {
+                "property" => self.import_property(child),
+                "import" => {}
+                "parameter" => self.import_parameter(child),
+                "queryString" => query = Some(child),
+                "field" => fields.push(self.import_field(child)),
+                "sortField" => sort_fields.extend(self.import_sort_field(child)),
+                "variable" => self.import_variable(child),
+                "group" => self.import_group(child),
+                "background" => self.import_background(child),
+                _ => match band_type(name) {
+                    Some(band_type) => {
+                        let band = self.import_section(child, name);
+                        *self.template.bands.get_mut(band_type) = band;
+                    }
+                    None => self.unsupported(child, name, name, unsupported_message(name)),
+                },
+            }
+        }
+
+        if query.is_some() || !fields.is_empty() {
+            self.import_data_source(root, query, fields, sort_fields);
+        }
+    }
+
+    // === 页面设置 ===
+    fn import_page_format(&mut self, root: Node) {
+        let canvas = &mut self.template.canvas;
+        canvas.unit = PageUnit::Point;
+        canvas.width = attr_f64(root, "pageWidth").unwrap_or(canvas.width);
+        canvas.height = attr_f64(root, "pageHeight").unwrap_or(canvas.height);
+        canvas.orientation = match root.attribute("orientation") {
+            Some("Landscape") => PageOrientation::Landscape,
+            _ => PageOrientation::Portrait,
+        };
+        let defaults = PageMargins::default();
+        canvas.margins = PageMargins {
+            top: attr_f64(root, "topMargin").unwrap_or(defaults.top),
+            bottom: attr_f64(root, "bottomMargin").unwrap_or(defaults.bottom),
+            left: attr_f64(root, "leftMargin").unwrap_or(defaults.left),
+            right: attr_f64(root, "rightMargin").unwrap_or(defaults.right),
+        };
+        self.template.metadata.description = root.attribute("name").map(str::to_string);
+
+        if attr_f64(root, "columnCount").map_or(false, |count| count > 1.0) {
+            self.unsupported(root, "columnCount", "jasperReport", "Multi-column layout is imported as a single column");
+        }
+        for flag in ["isTitleNewPage", "isSummaryNewPage", "isFloatColumnFooter"] {
+            if attr_bool(root, flag) {
+                self.unsupported(root, flag, "jasperReport", format!("{} is ignored", flag));
+            }
+        }
+        if root.attribute("scriptletClass").is_some() {
+            self.unsupported(root, "scriptletClass", "jasperReport", "Scriptlets are not supported");
+        }
+    }
+
+    /// 设计器写入的扩展属性，其他属性忽略
+    fn import_property(&mut self, node: Node) {
+        let value = node.attribute("value").unwrap_or_default().to_string();
+        match node.attribute("name") {
+            Some(PROPERTY_LOCALE) => self.template.formatting.locale = value,
+            Some(PROPERTY_NULL_TEXT) => self.template.formatting.null_text = value,
+            _ => {}
+        }
+    }
+
+    // === 参数、字段、变量 ===
+    fn import_parameter(&mut self, node: Node) {
+        let name = node.attribute("name").unwrap_or_default().to_string();
+        let path = format!("parameter[{}]", name);
+        let default = child(node, "defaultValueExpression").map(text_of).and_then(|expression| {
+            let literal = java_literal(&expression);
+            if literal.is_none() {
+                self.unsupported(
+                    node,
+                    "defaultValueExpression",
+                    &path,
+                    format!("Default value expression '{}' is not a literal and was dropped", expression.trim()),
+                );
+            }
+            literal
+        });
+
+        self.template.parameters.push(Parameter {
+            name,
+            param_type: java_type(node.attribute("class")),
+            default,
+            description: child(node, "parameterDescription").map(text_of).filter(|text| !text.is_empty()),
+            required: children(node, "property").any(|property| {
+                property.attribute("name") == Some(PROPERTY_REQUIRED) && property.attribute("value") == Some("true")
+            }),
+        });
+    }
+
+    fn import_field(&mut self, node: Node) -> ColumnInfo {
+        ColumnInfo {
+            name: node.attribute("name").unwrap_or_default().to_string(),
+            data_type: java_type(node.attribute("class")),
+            nullable: true,
+            default_value: None,
+            constraints: Vec::new(),
+            description: child(node, "fieldDescription").map(text_of).filter(|text| !text.is_empty()),
+            format_hint: None,
+        }
+    }
+
+    fn import_sort_field(&mut self, node: Node) -> Option<String> {
+        let name = node.attribute("name").unwrap_or_default();
+        if node.attribute("type") == Some("Variable") {
+            self.unsupported(node, "sortField", "sortField", format!("Sorting by variable '{}' is not supported", name));
+            return None;
+        }
+        let direction = if node.attribute("order") == Some("Descending") { "DESC" } else { "ASC" };
+        Some(format!("{} {}", name, direction))
+    }
+
+    fn import_variable(&mut self, node: Node) {
+        let name = node.attribute("name").unwrap_or_default().to_string();
+        let path = format!("variable[{}]", name);
+
+        let calculation = match node.attribute("calculation").unwrap_or("Nothing") {
+            "Count" => Calculation::Count,
+            "DistinctCount" => Calculation::DistinctCount,
+            "Sum" => Calculation::Sum,
+            "Average" => Calculation::Average,
+            "Lowest" => Calculation::Min,
+            "Highest" => Calculation::Max,
+            "First" => Calculation::First,
+            "Nothing" => Calculation::Nothing,
+            other => {
+                self.unsupported(node, "calculation", &path, format!("Calculation '{}' is imported as Nothing", other));
+                Calculation::Nothing
+            }
+        };
+        let reset_type = match node.attribute("resetType").unwrap_or("Report") {
+            "Page" => ResetType::Page,
+            "Column" => ResetType::Column,
+            "Group" => ResetType::Group,
+            "None" => ResetType::None,
+            _ => ResetType::Report,
+        };
+        let increment_type = match node.attribute("incrementType").unwrap_or("None") {
+            "Report" => IncrementType::Report,
+            "Page" => IncrementType::Page,
+            "Column" => IncrementType::Column,
+            "Group" => IncrementType::Group,
+            _ => IncrementType::None,
+        };
+
+        let expression = child(node, "variableExpression").map(text_of).unwrap_or_default();
+        self.check_expression(node, &expression, &path);
+        let initial_value = child(node, "initialValueExpression").map(text_of).and_then(|expression| {
+            let literal = java_literal(&expression);
+            if literal.is_none() {
+                self.unsupported(
+                    node,
+                    "initialValueExpression",
+                    &path,
+                    format!("Initial value expression '{}' is not a literal and was dropped", expression.trim()),
+                );
+            }
+            literal
+        });
+
+        self.template.variables.push(Variable {
+            var_type: java_type(node.attribute("class")),
+            initial_value,
+            reset_type,
+            reset_group: node.attribute("resetGroup").map(str::to_string),
+            increment_type,
+            increment_group: node.attribute("incrementGroup").map(str::to_string),
+            ..Variable::new(&name, expression.trim(), calculation)
+        });
+    }
+
+    // === 分组与区段 ===
+    fn import_group(&mut self, node: Node) {
+        let name = node.attribute("name").unwrap_or_default();
+        let path = format!("group[{}]", name);
+        let expression = child(node, "groupExpression").map(text_of).unwrap_or_default();
+        self.check_expression(node, &expression, &path);
+        for flag in ["isReprintHeaderOnEachPage", "isResetPageNumber"] {
+            if attr_bool(node, flag) {
+                self.unsupported(node, flag, &path, format!("{} is ignored", flag));
+            }
+        }
+        if attr_f64(node, "minHeightToStartNewPage").map_or(false, |height| height > 0.0) {
+            self.unsupported(node, "minHeightToStartNewPage", &path, "minHeightToStartNewPage is ignored");
+        }
+
+        let mut group = Group::new(name, expression.trim());
+        group.start_on_new_page = attr_bool(node, "isStartNewPage");
+        group.keep_together = attr_bool(node, "keepTogether");
+        group.header = child(node, "groupHeader").and_then(|header| self.import_section(header, &format!("{}/groupHeader", path)));
+        group.footer = child(node, "groupFooter").and_then(|footer| self.import_section(footer, &format!("{}/groupFooter", path)));
+        self.template.groups.push(group);
+    }
+
+    /// 导入区段；一个区段中有多个 band 时上下拼接为一个区段
+    fn import_section(&mut self, section: Node, path: &str) -> Option<BandConfig> {
+        let bands: Vec<Node> = children(section, "band").collect();
+        let mut config = BandConfig::new(0.0);
+        config.split_type = match bands.first()?.attribute("splitType") {
+            Some("Prevent") => SplitType::Prevent,
+            Some("Immediate") => SplitType::Immediate,
+            _ => SplitType::Stretch,
+        };
+
+        for (index, band) in bands.iter().enumerate() {
+            let band_path = if bands.len() > 1 {
+                format!("{}/band[{}]", path, index + 1)
+            } else {
+                format!("{}/band", path)
+            };
+            if child(*band, "printWhenExpression").is_some() {
+                self.unsupported(*band, "printWhenExpression", &band_path, "Band print conditions are ignored");
+            }
+            let origin = Position { x: 0.0, y: config.height };
+            let ids = self.import_elements(*band, &origin, &band_path);
+            config.elements.extend(ids);
+            config.height += attr_f64(*band, "height").unwrap_or(0.0);
+        }
+
+        let bottom = config
+            .elements
+            .iter()
+            .filter_map(|id| self.template.get_element_by_id(id))
+            .map(|element| element.position.y + element.size.height)
+            .fold(0.0, f64::max);
+        if bottom > config.height {
+            self.unsupported(
+                section,
+                "band",
+                path,
+                format!("Elements extend below the band; height increased from {} to {}", config.height, bottom),
+            );
+            config.height = bottom;
+        }
+        Some(config)
+    }
+
+    /// 背景区段的元素不属于任何区段，坐标换算为页面坐标
+    fn import_background(&mut self, node: Node) {
+        let margins = &self.template.canvas.margins;
+        let origin = Position { x: margins.left, y: margins.top };
+        for band in children(node, "band") {
+            self.import_elements(band, &origin, "background/band");
+        }
+    }
+
+    // === 元素 ===
+    /// 导入容器中的元素，返回新元素的ID；frame 和 elementGroup 展开为普通元素
+    fn import_elements(&mut self, container: Node, origin: &Position, path: &str) -> Vec<String> {
+        let mut ids = Vec::new();
+        for node in container.children().filter(Node::is_element) {
+            let name = node.tag_name().name();
+            let node_path = format!("{}/{}", path, name);
+            match name {
+                "reportElement" | "box" | "printWhenExpression" | "property" => {}
+                "staticText" | "textField" | "rectangle" | "line" | "image" => {
+                    let element = self.import_element(node, name, origin, &node_path);
+                    ids.push(element.id.clone());
+                    self.template.elements.push(element);
+                }
+                "frame" => {
+                    let frame = self.element_frame(node, origin, &node_path);
+                    let style = self.box_style(node, &frame, false);
+                    if style.background.is_some() || style.border.is_some() {
+                        let element = self.new_element(ElementType::Rectangle, &frame, empty_content(), style);
+                        ids.push(element.id.clone());
+                        self.template.elements.push(element);
+                    }
+                    ids.extend(self.import_elements(node, &frame.position, &node_path));
+                }
+                "elementGroup" => ids.extend(self.import_elements(node, origin, &node_path)),
+                other => self.unsupported(node, other, &node_path, unsupported_message(other)),
+            }
+        }
+        ids
+    }
+
+    fn import_element(&mut self, node: Node, kind: &str, origin: &Position, path: &str) -> TemplateElement {
+        let frame = self.element_frame(node, origin, path);
+        match kind {
+            "staticText" => {
+                let mut content = self.text_content(node, &frame, path);
+                content.text = Some(child(node, "text").map(text_of).unwrap_or_default());
+                let style = self.box_style(node, &frame, false);
+                self.new_element(ElementType::Text, &frame, content, style)
+            }
+            "textField" => {
+                let expression = child(node, "textFieldExpression").map(text_of).unwrap_or_default();
+                self.check_expression(node, &expression, path);
+                if let Some(time) = node.attribute("evaluationTime").filter(|time| *time != "Now") {
+                    self.unsupported(node, "evaluationTime", path, format!("Evaluation time '{}' is evaluated immediately", time));
+                }
+                if child(node, "patternExpression").is_some() {
+                    self.unsupported(node, "patternExpression", path, "Dynamic patterns are not supported");
+                }
+                let mut content = self.text_content(node, &frame, path);
+                content.expression = Some(expression.trim().to_string());
+                content.format = node.attribute("pattern").map(str::to_string);
+                let style = self.box_style(node, &frame, false);
+                self.new_element(ElementType::DataField, &frame, content, style)
+            }
+            "rectangle" => {
+                if attr_f64(node, "radius").map_or(false, |radius| radius > 0.0) {
+                    self.unsupported(node, "radius", path, "Rounded corners are imported as square corners");
+                }
+                let mut style = self.box_style(node, &frame, true);
+                style.border = self.graphic_pen(node, &frame);
+                self.new_element(ElementType::Rectangle, &frame, empty_content(), style)
+            }
+            "line" => {
+                if node.attribute("direction") == Some("BottomUp") {
+                    self.unsupported(node, "direction", path, "Bottom-up lines are imported as top-down lines");
+                }
+                let border = self.graphic_pen(node, &frame);
+                let mut content = empty_content();
+                content.color = Some(
+                    border
+                        .as_ref()
+                        .map(|pen| pen.color.clone())
+                        .or_else(|| frame.forecolor.clone())
+                        .unwrap_or_else(|| DEFAULT_COLOR.to_string()),
+                );
+                let style = ElementStyle { background: None, border, padding: None };
+                self.new_element(ElementType::Line, &frame, content, style)
+            }
+            _ => {
+                let expression = child(node, "imageExpression").map(text_of).unwrap_or_default();
+                let source = match java_literal(&expression) {
+                    Some(Value::String(path)) => path,
+                    _ => {
+                        self.unsupported(
+                            node,
+                            "imageExpression",
+                            path,
+                            format!("Image expression '{}' is not a literal path", expression.trim()),
+                        );
+                        expression.trim().to_string()
+                    }
+                };
+                let mut content = empty_content();
+                content.expression = Some(source);
+                // 缺省为 RetainShape、左上对齐，与设计器默认值一致时不写入模板
+                content.scale_mode = match node.attribute("scaleImage") {
+                    Some("Clip") => Some(ImageScaleMode::Clip),
+                    Some("FillFrame") => Some(ImageScaleMode::FillFrame),
+                    _ => None,
+                };
+                let horizontal = match node.attribute("hAlign").or_else(|| node.attribute("horizontalAlignment")) {
+                    Some("Center") => HorizontalAlignment::Center,
+                    Some("Right") => HorizontalAlignment::Right,
+                    _ => HorizontalAlignment::Left,
+                };
+                let vertical = match node.attribute("vAlign").or_else(|| node.attribute("verticalAlignment")) {
+                    Some("Middle") => VerticalAlignment::Middle,
+                    Some("Bottom") => VerticalAlignment::Bottom,
+                    _ => VerticalAlignment::Top,
+                };
+                if horizontal != HorizontalAlignment::Left || vertical != VerticalAlignment::Top {
+                    content.alignment = Some(AlignmentConfig { horizontal, vertical });
+                }
+                let style = self.box_style(node, &frame, false);
+                self.new_element(ElementType::Image, &frame, content, style)
+            }
+        }
+    }
+
+    fn new_element(&mut self, element_type: ElementType, frame: &ElementFrame, content: ElementContent, style: ElementStyle) -> TemplateElement {
+        self.next_z_index += 1;
+        TemplateElement {
+            id: uuid::Uuid::new_v4().to_string(),
+            element_type,
+            position: frame.position.clone(),
+            size: frame.size.clone(),
+            z_index: self.next_z_index,
+            visible: frame.visible,
+            content,
+            style,
+            data_binding: None,
+            designer: None,
+        }
+    }
+
+    fn element_frame(&mut self, node: Node, origin: &Position, path: &str) -> ElementFrame {
+        let Some(report_element) = child(node, "reportElement") else {
+            return ElementFrame {
+                position: origin.clone(),
+                size: Size { width: 0.0, height: 0.0 },
+                forecolor: None,
+                backcolor: None,
+                opaque: None,
+                visible: true,
+            };
+        };
+        let print_when = child(report_element, "printWhenExpression").map(text_of);
+        let hidden = print_when.as_deref().and_then(java_literal) == Some(Value::Bool(false));
+        if print_when.is_some() && !hidden {
+            self.unsupported(report_element, "printWhenExpression", path, "Element print conditions are ignored");
+        }
+        if report_element.attribute("style").is_some() {
+            self.unsupported(report_element, "style", path, "Style references are not resolved");
+        }
+
+        ElementFrame {
+            position: Position {
+                x: origin.x + attr_f64(report_element, "x").unwrap_or(0.0),
+                y: origin.y + attr_f64(report_element, "y").unwrap_or(0.0),
+            },
+            size: Size {
+                width: attr_f64(report_element, "width").unwrap_or(0.0),
+                height: attr_f64(report_element, "height").unwrap_or(0.0),
+            },
+            forecolor: report_element.attribute("forecolor").map(str::to_string),
+            backcolor: report_element.attribute("backcolor").map(str::to_string),
+            opaque: report_element.attribute("mode").map(|mode| mode == "Opaque"),
+            visible: !hidden,
+        }
+    }
+
+    /// 文本元素的字体、对齐和颜色
+    fn text_content(&mut self, node: Node, frame: &ElementFrame, path: &str) -> ElementContent {
+        let text_element = child(node, "textElement");
+        let font = text_element.and_then(|text| child(text, "font"));
+
+        if let Some(text_element) = text_element {
+            if text_element.attribute("rotation").map_or(false, |rotation| rotation != "None") {
+                self.unsupported(text_element, "rotation", path, "Rotated text is imported unrotated");
+            }
+            if let Some(markup) = text_element.attribute("markup").filter(|markup| *markup != "none") {
+                self.unsupported(text_element, "markup", path, format!("Markup '{}' is imported as plain text", markup));
+            }
+        }
+        if let Some(font) = font {
+            if attr_bool(font, "isUnderline") || attr_bool(font, "isStrikeThrough") {
+                self.unsupported(font, "font", path, "Underline and strike-through are not supported");
+            }
+        }
+
+        ElementContent {
+            text: None,
+            expression: None,
+            font: Some(FontConfig {
+                family: font.and_then(|f| f.attribute("fontName")).unwrap_or(DEFAULT_FONT_FAMILY).to_string(),
+                size: font.and_then(|f| attr_f64(f, "size")).unwrap_or(DEFAULT_FONT_SIZE),
+                weight: if font.map_or(false, |f| attr_bool(f, "isBold")) { FontWeight::Bold } else { FontWeight::Normal },
+                style: if font.map_or(false, |f| attr_bool(f, "isItalic")) { FontStyle::Italic } else { FontStyle::Normal },
+            }),
+            alignment: Some(AlignmentConfig {
+                horizontal: match text_element.and_then(|t| t.attribute("textAlignment")) {
+                    Some("Center") => HorizontalAlignment::Center,
+                    Some("Right") => HorizontalAlignment::Right,
+                    Some("Justified") => HorizontalAlignment::Justify,
+                    _ => HorizontalAlignment::Left,
+                },
+                vertical: match text_element.and_then(|t| t.attribute("verticalAlignment")) {
+                    Some("Middle") => VerticalAlignment::Middle,
+                    Some("Bottom") => VerticalAlignment::Bottom,
+                    _ => VerticalAlignment::Top,
+                },
+            }),
+            color: Some(frame.forecolor.clone().unwrap_or_else(|| DEFAULT_COLOR.to_string())),
+            format: None,
+            subreport: None,
+            table: None,
+            crosstab: None,
+            chart: None,
+            barcode: None,
+            scale_mode: None,
+        }
+    }
+
+    /// 背景、边框（box/pen）和内边距；`opaque_default` 为图形元素默认不透明
+    fn box_style(&mut self, node: Node, frame: &ElementFrame, opaque_default: bool) -> ElementStyle {
+        let background = frame.opaque.unwrap_or(opaque_default).then(|| BackgroundStyle {
+            color: Some(frame.backcolor.clone().unwrap_or_else(|| DEFAULT_BACKCOLOR.to_string())),
+            image: None,
+        });
+
+        let Some(border_box) = child(node, "box") else {
+            return ElementStyle { background, border: None, padding: None };
+        };
+
+        let border = child(border_box, "pen").and_then(|pen| parse_pen(pen, frame, 0.0));
+        if border.is_none() {
+            let sides = ["topPen", "leftPen", "bottomPen", "rightPen"];
+            if let Some(side) = sides.iter().find_map(|side| child(border_box, side).and_then(|pen| parse_pen(pen, frame, 0.0).map(|_| pen))) {
+                self.unsupported(side, side.tag_name().name(), "box", "Borders on individual sides are not supported");
+            }
+        }
+
+        let padding = attr_f64(border_box, "padding").unwrap_or(0.0);
+        let side_padding = |name: &str| attr_f64(border_box, name).unwrap_or(padding);
+        let padding = PaddingConfig {
+            top: side_padding("topPadding"),
+            bottom: side_padding("bottomPadding"),
+            left: side_padding("leftPadding"),
+            right: side_padding("rightPadding"),
+        };
+        let has_padding = padding.top > 0.0 || padding.bottom > 0.0 || padding.left > 0.0 || padding.right > 0.0;
+
+        ElementStyle {
+            background,
+            border,
+            padding: has_padding.then_some(padding),
+        }
+    }
+
+    /// 图形元素（矩形、线条）的画笔，默认宽度为1
+    fn graphic_pen(&mut self, node: Node, frame: &ElementFrame) -> Option<BorderStyle> {
+        match child(node, "graphicElement").and_then(|graphic| child(graphic, "pen")) {
+            Some(pen) => parse_pen(pen, frame, 1.0),
+            None => Some(BorderStyle {
+                width: 1.0,
+                color: frame.forecolor.clone().unwrap_or_else(|| DEFAULT_COLOR.to_string()),
+                style: BorderStyleType::Solid,
+            }),
+        }
+    }
+
+    // === 数据源 ===
+    fn import_data_source(&mut self, root: Node, query: Option<Node>, fields: Vec<ColumnInfo>, sort_fields: Vec<String>) {
+        let mut source_type = DataSourceType::Sql;
+        let mut provider_type = "database";
+        let query = query.map(|node| {
+            let text = text_of(node).trim().to_string();
+            let language = node.attribute("language").unwrap_or("SQL").to_lowercase();
+            let (sql, path) = match language.as_str() {
+                "json" | "jsonql" => {
+                    source_type = DataSourceType::Json;
+                    provider_type = "json";
+                    (None, Some(text))
+                }
+                "xpath" | "xpath2" => {
+                    source_type = DataSourceType::Xml;
+                    provider_type = "xml";
+                    (None, Some(text))
+                }
+                "sql" | "plsql" => (Some(text), None),
+                other => {
+                    self.unsupported(node, "language", "queryString", format!("Query language '{}' is imported as SQL", other));
+                    (Some(text), None)
+                }
+            };
+            if sql.as_deref().map_or(false, |sql| sql.contains("$P!{")) {
+                self.unsupported(
+                    node,
+                    "queryString",
+                    "queryString",
+                    "Raw parameter substitution $P!{...} is not supported, use $P{...} instead",
+                );
+            }
+
+            DataQuery {
+                sql,
+                path,
+                filter: None,
+                limit: None,
+                offset: None,
+                sort: (!sort_fields.is_empty()).then(|| sort_fields.join(", ")),
+                parameters: Vec::new(),
+            }
+        });
+
+        self.template.data_sources.push(DataSource {
+            id: MAIN_DATA_SOURCE_ID.to_string(),
+            name: root.attribute("name").unwrap_or("Main dataset").to_string(),
+            source_type,
+            provider_type: provider_type.to_string(),
+            config: DataSourceConfig {
+                host: None,
+                port: None,
+                database: None,
+                username: None,
+                password: None,
+                ssl_mode: None,
+                file_path: None,
+                sheet_name: None,
+                delimiter: None,
+                url: None,
+                headers: None,
+                auth_method: None,
+                timeout: None,
+                retry_count: None,
+            },
+            schema: DataSchema {
+                columns: fields,
+                primary_key: None,
+                indexes: Vec::new(),
+                relationships: HashMap::new(),
+                metadata: HashMap::new(),
+            },
+            query,
+        });
+    }
+
+    // === 辅助 ===
+    /// 表达式无法被设计器解析时（如 Java 方法调用）原样保留并记录
+    fn check_expression(&mut self, node: Node, expression: &str, path: &str) {
+        if expression.trim().is_empty() {
+            return;
+        }
+        if let Err(error) = Expression::parse(expression) {
+            self.unsupported(
+                node,
+                "expression",
+                path,
+                format!("Expression '{}' is kept as-is but cannot be evaluated: {}", expression.trim(), error.message()),
+            );
+        }
+    }
+
+    fn unsupported(&mut self, node: Node, construct: &str, path: &str, message: impl Into<String>) {
+        self.unsupported.push(UnsupportedConstruct {
+            construct: construct.to_string(),
+            path: path.to_string(),
+            line: self.document.text_pos_at(node.range().start).row,
+            message: message.into(),
+        });
+    }
+}
+
+fn band_type(section: &str) -> Option<BandType> {
+    match section {
+        "title" => Some(BandType::Title),
+        "pageHeader" => Some(BandType::PageHeader),
+        "columnHeader" => Some(BandType::ColumnHeader),
+        "detail" => Some(BandType::Detail),
+        "columnFooter" => Some(BandType::ColumnFooter),
+        "pageFooter" => Some(BandType::PageFooter),
+        "summary" => Some(BandType::Summary),
+        _ => None,
+    }
+}
+
+fn unsupported_message(construct: &str) -> String {
+    match construct {
+        "style" | "template" => "Report styles are not imported".to_string(),
+        "subDataset" => "Sub datasets are not supported".to_string(),
+        "lastPageFooter" => "The last page footer is not supported, the page footer is used instead".to_string(),
+        "noData" => "The no-data band is not supported".to_string(),
+        "filterExpression" => "Filter expressions are not supported".to_string(),
+        other if other.ends_with("Chart") => format!("Chart <{}> is not supported", other),
+        other => format!("<{}> is not supported", other),
+    }
+}
+
+fn empty_content() -> ElementContent {
+    ElementContent {
+        text: None,
+        expression: None,
+        font: None,
+        alignment: None,
+        color: None,
+        format: None,
+        subreport: None,
+        table: None,
+        crosstab: None,
+        chart: None,
+        barcode: None,
+        scale_mode: None,
+    }
+}
+
+fn parse_pen(pen: Node, frame: &ElementFrame, default_width: f64) -> Option<BorderStyle> {
+    let width = attr_f64(pen, "lineWidth").unwrap_or(default_width);
+    (width > 0.0).then(|| BorderStyle {
+        width,
+        color: pen
+            .attribute("lineColor")
+            .map(str::to_string)
+            .or_else(|| frame.forecolor.clone())
+            .unwrap_or_else(|| DEFAULT_COLOR.to_string()),
+        style: match pen.attribute("lineStyle") {
+            Some("Dashed") => BorderStyleType::Dashed,
+            Some("Dotted") => BorderStyleType::Dotted,
+            _ => BorderStyleType::Solid,
+        },
+    })
+}
+
+/// Java 类名映射为模板数据类型
+fn java_type(class: Option<&str>) -> DataType {
+    let class = class.unwrap_or("java.lang.String");
+    let simple = class.rsplit('.').next().unwrap_or(class);
+    match simple {
+        "String" | "Character" => DataType::String,
+        "Integer" | "Long" | "Short" | "Byte" | "Double" | "Float" | "BigDecimal" | "BigInteger" | "Number" => {
+            DataType::Number
+        }
+        "Boolean" => DataType::Boolean,
+        "LocalDate" => DataType::Date,
+        "Date" if class == "java.sql.Date" => DataType::Date,
+        "Date" | "Timestamp" | "Time" | "LocalDateTime" | "ZonedDateTime" | "Instant" => DataType::DateTime,
+        "List" | "Collection" | "ArrayList" | "Set" => DataType::Array,
+        _ => DataType::Object,
+    }
+}
+
+/// 解析 Java 字面量表达式（"text"、12、new Integer(0)、Boolean.TRUE 等）
+fn java_literal(expression: &str) -> Option<Value> {
+    let mut expression = expression.trim();
+    let mut numeric_wrapper = false;
+
+    // 去掉 new Integer(...) / Integer.valueOf(...) 包装
+    if let Some(inner) = expression.strip_suffix(')') {
+        if let Some(open) = inner.find('(') {
+            let wrapper = &inner[..open];
+            let is_constructor = wrapper.starts_with("new ");
+            if is_constructor || wrapper.ends_with(".valueOf") {
+                let class = wrapper.trim_start_matches("new ").trim_end_matches(".valueOf");
+                numeric_wrapper = matches!(java_type(Some(class)), DataType::Number);
+                expression = inner[open + 1..].trim();
+            }
+        }
+    }
+
+    let simple = expression.rsplit('.').next().unwrap_or(expression);
+    if expression.contains("BigDecimal.") || expression.contains("BigInteger.") {
+        return match simple {
+            "ZERO" => Some(Value::from(0)),
+            "ONE" => Some(Value::from(1)),
+            "TEN" => Some(Value::from(10)),
+            _ => None,
+        };
+    }
+    match expression {
+        "null" => return Some(Value::Null),
+        "true" | "Boolean.TRUE" | "java.lang.Boolean.TRUE" => return Some(Value::Bool(true)),
+        "false" | "Boolean.FALSE" | "java.lang.Boolean.FALSE" => return Some(Value::Bool(false)),
+        _ => {}
+    }
+
+    if let Some(body) = expression.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+        let mut text = String::with_capacity(body.len());
+        let mut chars = body.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                // 未转义的引号说明是拼接表达式
+                if c == '"' {
+                    return None;
+                }
+                text.push(c);
+                continue;
+            }
+            match chars.next()? {
+                'n' => text.push('\n'),
+                't' => text.push('\t'),
+                other => text.push(other),
+            }
+        }
+        // new BigDecimal("12.50") 按数字处理
+        if numeric_wrapper {
+            if let Some(number) = number_literal(&text) {
+                return Some(number);
+            }
+        }
+        return Some(Value::String(text));
+    }
+
+    number_literal(expression)
+}
+
+/// 数字字面量，允许 Java 的 L/D/F 后缀
+fn number_literal(text: &str) -> Option<Value> {
+    let number = text.trim().trim_end_matches(['L', 'l', 'D', 'd', 'F', 'f']);
+    if let Ok(integer) = number.parse::<i64>() {
+        return Some(Value::from(integer));
+    }
+    number.parse::<f64>().ok().and_then(serde_json::Number::from_f64).map(Value::Number)
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.is_element() && n.tag_name().name() == name)
+}
+
+fn children<'a, 'input: 'a>(node: Node<'a, 'input>, name: &'a str) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |n| n.is_element() && n.tag_name().name() == name)
+}
+
+/// 元素的文本内容（含 CDATA）
+fn text_of(node: Node) -> String {
+    node.children().filter(Node::is_text).filter_map(|n| n.text()).collect()
+}
+
+fn attr_f64(node: Node, name: &str) -> Option<f64> {
+    node.attribute(name).and_then(|value| value.trim().parse().ok())
+}
+
+fn attr_bool(node: Node, name: &str) -> bool {
+    node.attribute(name) == Some("true")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVOICE: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE jasperReport PUBLIC "-//JasperReports//DTD Report Design//EN" "http://jasperreports.sourceforge.net/dtds/jasperreport.dtd">
+<jasperReport xmlns="http://jasperreports.sourceforge.net/jasperreports" name="Invoice" pageWidth="842" pageHeight="595" orientation="Landscape" columnWidth="802" leftMargin="20" rightMargin="20" topMargin="30" bottomMargin="30">
+    <property name="ireport.zoom" value="1.0"/>
+    <style name="Base" fontName="SansSerif"/>
+    <parameter name="customerId" class="java.lang.Integer">
+        <parameterDescription><![CDATA[客户编号]]></parameterDescription>
+        <defaultValueExpression><![CDATA[new Integer(42)]]></defaultValueExpression>
+    </parameter>
+    <parameter name="title" class="java.lang.String">
+        <defaultValueExpression><![CDATA["月度账单"]]></defaultValueExpression>
+    </parameter>
+    <queryString language="SQL">
+        <![CDATA[SELECT name, amount, region FROM orders WHERE customer_id = $P{customerId}]]>
+    </queryString>
+    <field name="name" class="java.lang.String"/>
+    <field name="amount" class="java.math.BigDecimal"/>
+    <field name="region" class="java.lang.String"/>
+    <sortField name="region"/>
+    <sortField name="amount" order="Descending"/>
+    <variable name="regionTotal" class="java.math.BigDecimal" resetType="Group" resetGroup="regionGroup" calculation="Sum">
+        <variableExpression><![CDATA[$F{amount}]]></variableExpression>
+        <initialValueExpression><![CDATA[java.math.BigDecimal.ZERO]]></initialValueExpression>
+    </variable>
+    <group name="regionGroup" isStartNewPage="true">
+        <groupExpression><![CDATA[$F{region}]]></groupExpression>
+        <groupHeader>
+            <band height="20">
+                <textField>
+                    <reportElement x="0" y="0" width="200" height="20"/>
+                    <textFieldExpression><![CDATA["Region: " + $F{region}]]></textFieldExpression>
+                </textField>
+            </band>
+        </groupHeader>
+        <groupFooter>
+            <band height="20">
+                <textField pattern="#,##0.00">
+                    <reportElement x="600" y="0" width="100" height="20"/>
+                    <textElement textAlignment="Right"/>
+                    <textFieldExpression><![CDATA[$V{regionTotal}]]></textFieldExpression>
+                </textField>
+            </band>
+        </groupFooter>
+    </group>
+    <title>
+        <band height="50" splitType="Prevent">
+            <staticText>
+                <reportElement x="0" y="10" width="300" height="30" forecolor="#333333" backcolor="#EEEEEE" mode="Opaque"/>
+                <box padding="4"><pen lineWidth="0.5" lineColor="#999999"/></box>
+                <textElement textAlignment="Center" verticalAlignment="Middle">
+                    <font fontName="SimHei" size="18" isBold="true"/>
+                </textElement>
+                <text><![CDATA[发票]]></text>
+            </staticText>
+            <image scaleImage="FillFrame" hAlign="Center" vAlign="Middle">
+                <reportElement x="700" y="0" width="100" height="50"/>
+                <imageExpression><![CDATA["images/logo.png"]]></imageExpression>
+            </image>
+        </band>
+    </title>
+    <detail>
+        <band height="20">
+            <textField>
+                <reportElement x="0" y="0" width="200" height="20"/>
+                <textFieldExpression><![CDATA[$F{name}]]></textFieldExpression>
+            </textField>
+            <ellipse>
+                <reportElement x="210" y="0" width="20" height="20"/>
+            </ellipse>
+        </band>
+        <band height="15">
+            <frame>
+                <reportElement x="10" y="2" width="400" height="12" mode="Opaque" backcolor="#F0F0F0"/>
+                <line>
+                    <reportElement x="0" y="5" width="300" height="1" forecolor="#FF0000"/>
+                    <graphicElement><pen lineWidth="2.0" lineStyle="Dashed"/></graphicElement>
+                </line>
+            </frame>
+            <subreport>
+                <reportElement x="0" y="0" width="100" height="10"/>
+            </subreport>
+        </band>
+    </detail>
+    <pageFooter>
+        <band height="20">
+            <rectangle radius="5">
+                <reportElement x="0" y="0" width="802" height="20"/>
+            </rectangle>
+            <textField evaluationTime="Report">
+                <reportElement x="700" y="0" width="100" height="20"/>
+                <textFieldExpression><![CDATA[$V{PAGE_NUMBER}.toString()]]></textFieldExpression>
+            </textField>
+        </band>
+    </pageFooter>
+    <lastPageFooter>
+        <band height="20"/>
+    </lastPageFooter>
+</jasperReport>"##;
+
+    fn find<'a>(template: &'a JasperTemplate, element_type: &str) -> Vec<&'a TemplateElement> {
+        template
+            .elements
+            .iter()
+            .filter(|element| format!("{:?}", element.element_type) == element_type)
+            .collect()
+    }
+
+    #[test]
+    fn test_import_page_format_parameters_and_query() {
+        let import = import_jrxml(INVOICE).unwrap();
+        let template = &import.template;
+
+        assert_eq!(template.metadata.description.as_deref(), Some("Invoice"));
+        assert_eq!((template.canvas.width, template.canvas.height), (842.0, 595.0));
+        assert!(matches!(template.canvas.orientation, PageOrientation::Landscape));
+        assert_eq!(template.canvas.margins.top, 30.0);
+        assert_eq!(template.canvas.margins.left, 20.0);
+
+        assert_eq!(template.parameters.len(), 2);
+        assert!(matches!(template.parameters[0].param_type, DataType::Number));
+        assert_eq!(template.parameters[0].default, Some(Value::from(42)));
+        assert_eq!(template.parameters[0].description.as_deref(), Some("客户编号"));
+        assert_eq!(template.parameters[1].default, Some(Value::from("月度账单")));
+
+        let source = &template.data_sources[0];
+        assert_eq!(source.id, MAIN_DATA_SOURCE_ID);
+        assert_eq!(source.schema.columns.len(), 3);
+        assert!(matches!(source.schema.columns[1].data_type, DataType::Number));
+        let query = source.query.as_ref().unwrap();
+        assert_eq!(
+            query.sql.as_deref(),
+            Some("SELECT name, amount, region FROM orders WHERE customer_id = $P{customerId}")
+        );
+        assert_eq!(query.sort.as_deref(), Some("region ASC, amount DESC"));
+    }
+
+    #[test]
+    fn test_import_variables_groups_and_bands() {
+        let template = import_jrxml(INVOICE).unwrap().template;
+
+        let variable = &template.variables[0];
+        assert_eq!(variable.calculation, Calculation::Sum);
+        assert_eq!(variable.reset_type, ResetType::Group);
+        assert_eq!(variable.reset_group.as_deref(), Some("regionGroup"));
+        assert_eq!(variable.initial_value, Some(Value::from(0)));
+
+        let group = &template.groups[0];
+        assert_eq!(group.expression, "$F{region}");
+        assert!(group.start_on_new_page);
+        assert_eq!(group.header.as_ref().unwrap().elements.len(), 1);
+        assert_eq!(group.footer.as_ref().unwrap().height, 20.0);
+
+        let title = template.bands.title.as_ref().unwrap();
+        assert_eq!(title.split_type, SplitType::Prevent);
+        assert_eq!(title.elements.len(), 2);
+
+        // 两个 detail band 拼接：第二个 band 的元素整体下移 20
+        let detail = template.bands.detail.as_ref().unwrap();
+        assert_eq!(detail.height, 35.0);
+        assert_eq!(detail.elements.len(), 3);
+        let line = find(&template, "Line")[0];
+        assert_eq!((line.position.x, line.position.y), (10.0, 27.0));
+        assert_eq!(line.content.color.as_deref(), Some("#FF0000"));
+        let pen = line.style.border.as_ref().unwrap();
+        assert_eq!(pen.width, 2.0);
+        assert!(matches!(pen.style, BorderStyleType::Dashed));
+    }
+
+    #[test]
+    fn test_import_elements() {
+        let template = import_jrxml(INVOICE).unwrap().template;
+
+        let text = find(&template, "Text")[0];
+        assert_eq!(text.content.text.as_deref(), Some("发票"));
+        let font = text.content.font.as_ref().unwrap();
+        assert_eq!((font.family.as_str(), font.size), ("SimHei", 18.0));
+        assert!(matches!(font.weight, FontWeight::Bold));
+        assert!(matches!(text.content.alignment.as_ref().unwrap().horizontal, HorizontalAlignment::Center));
+        assert_eq!(text.style.background.as_ref().unwrap().color.as_deref(), Some("#EEEEEE"));
+        assert_eq!(text.style.border.as_ref().unwrap().color, "#999999");
+        assert_eq!(text.style.padding.as_ref().unwrap().left, 4.0);
+
+        let total = find(&template, "DataField")
+            .into_iter()
+            .find(|element| element.content.expression.as_deref() == Some("$V{regionTotal}"))
+            .unwrap();
+        assert_eq!(total.content.format.as_deref(), Some("#,##0.00"));
+
+        let image = find(&template, "Image")[0];
+        assert_eq!(image.content.expression.as_deref(), Some("images/logo.png"));
+        assert_eq!(image.content.scale_mode, Some(ImageScaleMode::FillFrame));
+        assert!(matches!(image.content.alignment.as_ref().unwrap().vertical, VerticalAlignment::Middle));
+
+        // frame 的背景导入为矩形，页脚矩形默认不透明白色
+        let rectangles = find(&template, "Rectangle");
+        assert_eq!(rectangles.len(), 2);
+        assert_eq!(rectangles[0].style.background.as_ref().unwrap().color.as_deref(), Some("#F0F0F0"));
+        assert_eq!(rectangles[1].style.background.as_ref().unwrap().color.as_deref(), Some("#FFFFFF"));
+    }
+
+    #[test]
+    fn test_unsupported_constructs_are_reported() {
+        let import = import_jrxml(INVOICE).unwrap();
+        let constructs: Vec<(&str, &str)> = import
+            .unsupported
+            .iter()
+            .map(|item| (item.construct.as_str(), item.path.as_str()))
+            .collect();
+
+        assert!(constructs.contains(&("style", "style")));
+        assert!(constructs.contains(&("ellipse", "detail/band[1]/ellipse")));
+        assert!(constructs.contains(&("subreport", "detail/band[2]/subreport")));
+        assert!(constructs.contains(&("radius", "pageFooter/band/rectangle")));
+        assert!(constructs.contains(&("evaluationTime", "pageFooter/band/textField")));
+        assert!(constructs.contains(&("expression", "pageFooter/band/textField")));
+        assert!(constructs.contains(&("lastPageFooter", "lastPageFooter")));
+
+        let ellipse = import.unsupported.iter().find(|item| item.construct == "ellipse").unwrap();
+        assert_eq!(ellipse.line, 67);
+    }
+
+    #[test]
+    fn test_import_errors() {
+        assert!(import_jrxml("<jasperReport").is_err());
+        assert!(import_jrxml("<report/>").is_err());
+
+        let minimal = import_jrxml(r#"<jasperReport name="Empty"/>"#).unwrap();
+        assert!(minimal.template.data_sources.is_empty());
+        assert!(minimal.unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_java_literals() {
+        assert_eq!(java_literal("\"a\\\"b\""), Some(Value::from("a\"b")));
+        assert_eq!(java_literal("Integer.valueOf(7)"), Some(Value::from(7)));
+        assert_eq!(java_literal("100L"), Some(Value::from(100)));
+        assert_eq!(java_literal("new java.math.BigDecimal(\"12.5\")"), Some(Value::from(12.5)));
+        assert_eq!(java_literal("java.sql.Date.valueOf(\"2024-01-31\")"), Some(Value::from("2024-01-31")));
+        assert_eq!(java_literal("Boolean.TRUE"), Some(Value::Bool(true)));
+        assert_eq!(java_literal("\"a\" + \"b\""), None);
+        assert_eq!(java_literal("new java.util.Date()"), None);
+    }
+}
@@ -0,0 +1,36 @@
+// === JasperReports JRXML 转换 ===
+pub mod export;
+pub mod import;
+
+pub use export::export_jrxml;
+pub use import::import_jrxml;
+
+use crate::core::template::JasperTemplate;
+use serde::{Deserialize, Serialize};
+
+/// 保存设计器格式设置的报表属性（JasperReports 会忽略未知属性）
+pub const PROPERTY_LOCALE: &str = "jasper.designer.format.locale";
+pub const PROPERTY_NULL_TEXT: &str = "jasper.designer.format.null.text";
+/// 参数属性：设计器中的必填标记
+pub const PROPERTY_REQUIRED: &str = "jasper.designer.required";
+
+/// 无法映射到模板模型的 JRXML 结构，导入时跳过或降级处理
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsupportedConstruct {
+    /// 标签名或属性名，如 `subreport`、`columnCount`
+    pub construct: String,
+    /// 在文档中的位置，如 `detail/band[2]/frame`
+    pub path: String,
+    /// 行号（从1开始）
+    pub line: u32,
+    pub message: String,
+}
+
+/// JRXML 导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JrxmlImport {
+    pub template: JasperTemplate,
+    pub unsupported: Vec<UnsupportedConstruct>,
+}
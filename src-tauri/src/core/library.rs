@@ -0,0 +1,829 @@
+// === Template Library ===
+// 模板库：索引配置目录下的模板文件，支持全文搜索、最近打开列表和缩略图
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+use resvg::usvg::{self, TreeParsing};
+use crate::core::binary::to_hex;
+use crate::core::package::{TemplatePackage, PACKAGE_EXTENSION};
+use crate::core::template::{ElementType, JasperTemplate, TemplateElement};
+use crate::core::template_service::{TemplateFormat, TemplateLoader};
+use crate::errors::{AppError, Result};
+
+/// 保存在应用数据目录（与 data_sources.json 同级）
+const LIBRARY_FILE: &str = "template_library.json";
+const THUMBNAIL_DIR: &str = "thumbnails";
+const TEMPLATE_EXTENSIONS: &[&str] = &["jasper", "jbin", "jrxml", PACKAGE_EXTENSION];
+const DEFAULT_MAX_RECENT: usize = 10;
+/// 缩略图宽度（像素），高度按页面比例
+pub const THUMBNAIL_WIDTH: u32 = 240;
+/// 文件监视的轮询间隔
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(3);
+
+pub type ManagedTemplateLibrary = Arc<Mutex<TemplateLibrary>>;
+
+/// 模板库设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibrarySettings {
+    /// 模板库目录，未设置时只记录最近打开的文件
+    pub root: Option<PathBuf>,
+    #[serde(default = "default_max_recent")]
+    pub max_recent: usize,
+}
+
+impl Default for LibrarySettings {
+    fn default() -> Self {
+        Self { root: None, max_recent: DEFAULT_MAX_RECENT }
+    }
+}
+
+fn default_max_recent() -> usize {
+    DEFAULT_MAX_RECENT
+}
+
+/// 模板库中的一个文件，元数据取自 `TemplateMetadata`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryEntry {
+    pub path: String,
+    /// 不含扩展名的文件名
+    pub name: String,
+    /// json / binary / jrxml / package
+    pub format: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub last_modified: DateTime<Utc>,
+    pub element_count: usize,
+    pub file_size: u64,
+    /// 文件系统的修改时间，用于判断是否需要重新索引
+    pub file_modified: DateTime<Utc>,
+    /// PNG 缩略图路径
+    pub thumbnail: Option<String>,
+}
+
+/// 索引记录：条目加上用于搜索的小写文本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexRecord {
+    #[serde(flatten)]
+    entry: LibraryEntry,
+    search_text: String,
+}
+
+/// 最近打开的模板
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentTemplate {
+    pub path: String,
+    pub name: String,
+    pub opened_at: DateTime<Utc>,
+    /// 列出时文件是否仍然存在
+    #[serde(default)]
+    pub exists: bool,
+}
+
+/// 搜索条件，各条件同时满足
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct LibraryQuery {
+    /// 空格分隔的关键字，匹配文件名、描述、标签、作者以及元素文本和表达式
+    pub text: Option<String>,
+    /// 必须包含的全部标签
+    pub tags: Vec<String>,
+    pub created_by: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// 一次同步中变化的文件
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryChanges {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl LibraryChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// 持久化内容
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibraryFile {
+    #[serde(default)]
+    settings: LibrarySettings,
+    #[serde(default)]
+    recent: Vec<RecentTemplate>,
+    #[serde(default)]
+    index: Vec<IndexRecord>,
+}
+
+pub struct TemplateLibrary {
+    data_dir: PathBuf,
+    settings: LibrarySettings,
+    recent: Vec<RecentTemplate>,
+    index: BTreeMap<String, IndexRecord>,
+}
+
+impl TemplateLibrary {
+    /// 空模板库，`data_dir` 为应用数据目录
+    pub fn new(data_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+            settings: LibrarySettings::default(),
+            recent: Vec::new(),
+            index: BTreeMap::new(),
+        }
+    }
+
+    /// 从应用数据目录读取设置、最近列表和索引
+    pub fn open(data_dir: impl Into<PathBuf>) -> Result<Self> {
+        let mut library = Self::new(data_dir);
+        let path = library.library_file();
+        if !path.exists() {
+            return Ok(library);
+        }
+
+        let content = fs::read_to_string(&path).map_err(|e| AppError::FileError {
+            message: format!("Failed to read template library '{}': {}", path.display(), e),
+        })?;
+        let file: LibraryFile = serde_json::from_str(&content).map_err(|e| AppError::SerializationError {
+            message: format!("Failed to parse template library '{}': {}", path.display(), e),
+        })?;
+        library.settings = file.settings;
+        library.recent = file.recent;
+        library.index = file.index.into_iter().map(|record| (record.entry.path.clone(), record)).collect();
+        Ok(library)
+    }
+
+    pub fn settings(&self) -> &LibrarySettings {
+        &self.settings
+    }
+
+    /// 更换模板库目录，索引由下一次同步重建（见 `set_library_root`）
+    pub fn set_root(&mut self, root: Option<PathBuf>) -> Result<()> {
+        if let Some(root) = &root {
+            if !root.is_dir() {
+                return Err(AppError::ConfigError {
+                    message: format!("Template library directory '{}' does not exist", root.display()),
+                });
+            }
+        }
+        self.settings.root = root;
+        self.save()
+    }
+
+    pub fn set_max_recent(&mut self, max_recent: usize) -> Result<()> {
+        self.settings.max_recent = max_recent;
+        self.recent.truncate(max_recent);
+        self.save()
+    }
+
+    /// 对比磁盘与索引：新增、修改的文件重新索引，消失的文件移出索引
+    ///
+    /// 大小和修改时间都没变的文件不重新读取。
+    pub fn sync(&mut self) -> Result<LibraryChanges> {
+        let scanned = self.scan().run();
+        self.apply_scan(scanned)
+    }
+
+    /// 取出当前索引的快照，扫描可以在不持有模板库锁的情况下进行
+    pub fn scan(&self) -> LibraryScan {
+        LibraryScan {
+            root: self.settings.root.clone(),
+            data_dir: self.data_dir.clone(),
+            index: self.index.clone(),
+        }
+    }
+
+    /// 换入扫描得到的索引；扫描期间更换了模板库目录时丢弃结果
+    pub fn apply_scan(&mut self, scanned: ScannedIndex) -> Result<LibraryChanges> {
+        if scanned.root != self.settings.root {
+            return Ok(LibraryChanges::default());
+        }
+        self.index = scanned.index;
+        if !scanned.changes.is_empty() {
+            self.save()?;
+        }
+        Ok(scanned.changes)
+    }
+
+    /// 索引单个文件并生成缩略图
+    fn index_file(&mut self, path: &Path) -> Result<()> {
+        let record = index_template(&self.data_dir, path)?;
+        self.index.insert(record.entry.path.clone(), record);
+        Ok(())
+    }
+
+    /// 搜索模板库，按模板修改时间倒序
+    pub fn search(&self, query: &LibraryQuery) -> Vec<LibraryEntry> {
+        let terms: Vec<String> = query
+            .text
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+
+        let mut entries: Vec<&LibraryEntry> = self
+            .index
+            .values()
+            .filter(|record| terms.iter().all(|term| record.search_text.contains(term.as_str())))
+            .map(|record| &record.entry)
+            .filter(|entry| {
+                query.tags.iter().all(|tag| entry.tags.iter().any(|entry_tag| entry_tag.eq_ignore_ascii_case(tag)))
+            })
+            .filter(|entry| {
+                query.created_by.as_deref().map_or(true, |author| entry.created_by.eq_ignore_ascii_case(author))
+            })
+            .collect();
+        entries.sort_by(|a, b| b.last_modified.cmp(&a.last_modified).then_with(|| a.path.cmp(&b.path)));
+
+        entries
+            .into_iter()
+            .take(query.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    /// 模板库中出现过的全部标签
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self.index.values().flat_map(|record| record.entry.tags.iter().cloned()).collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// 记录打开或保存的文件，移到最近列表最前面
+    ///
+    /// 位于模板库目录中的文件同时重新索引。
+    pub fn record_opened(&mut self, file_path: &str) -> Result<()> {
+        let path = fs::canonicalize(file_path).unwrap_or_else(|_| PathBuf::from(file_path));
+        let key = path.to_string_lossy().to_string();
+
+        self.recent.retain(|recent| recent.path != key);
+        self.recent.insert(0, RecentTemplate {
+            path: key.clone(),
+            name: file_name(&path),
+            opened_at: Utc::now(),
+            exists: true,
+        });
+        self.recent.truncate(self.settings.max_recent);
+
+        if self.contains(&path) {
+            if let Err(e) = self.index_file(&path) {
+                log::warn!("Failed to index template '{}': {}", key, e);
+            }
+        }
+        self.save()
+    }
+
+    /// 最近打开的文件，`exists` 标记文件是否仍在
+    pub fn recent(&self) -> Vec<RecentTemplate> {
+        self.recent
+            .iter()
+            .map(|recent| RecentTemplate { exists: Path::new(&recent.path).is_file(), ..recent.clone() })
+            .collect()
+    }
+
+    pub fn clear_recent(&mut self) -> Result<()> {
+        self.recent.clear();
+        self.save()
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        let Some(root) = &self.settings.root else { return false };
+        let root = fs::canonicalize(root).unwrap_or_else(|_| root.clone());
+        path.starts_with(root) && is_template_file(path)
+    }
+
+    fn library_file(&self) -> PathBuf {
+        self.data_dir.join(LIBRARY_FILE)
+    }
+
+    fn save(&self) -> Result<()> {
+        let file = LibraryFile {
+            settings: self.settings.clone(),
+            recent: self.recent.clone(),
+            index: self.index.values().cloned().collect(),
+        };
+        let json = serde_json::to_string_pretty(&file).map_err(|e| AppError::SerializationError {
+            message: format!("Failed to serialize template library: {}", e),
+        })?;
+        write_file(&self.library_file(), json.as_bytes())
+    }
+}
+
+/// 一次同步的扫描任务，持有索引快照
+///
+/// 目录扫描、读取模板和生成缩略图都是阻塞操作，在 `run` 中完成，结果由 `TemplateLibrary::apply_scan` 换入。
+pub struct LibraryScan {
+    root: Option<PathBuf>,
+    data_dir: PathBuf,
+    index: BTreeMap<String, IndexRecord>,
+}
+
+/// 扫描后的索引和相对快照的变化
+pub struct ScannedIndex {
+    root: Option<PathBuf>,
+    index: BTreeMap<String, IndexRecord>,
+    changes: LibraryChanges,
+}
+
+impl LibraryScan {
+    pub fn run(mut self) -> ScannedIndex {
+        let files = match &self.root {
+            Some(root) => scan_templates(root),
+            None => Vec::new(),
+        };
+
+        let mut changes = LibraryChanges::default();
+        let on_disk: Vec<String> = files.iter().map(|path| path.to_string_lossy().to_string()).collect();
+        let removed: Vec<String> = self.index.keys().filter(|path| !on_disk.contains(path)).cloned().collect();
+        for path in removed {
+            if let Some(record) = self.index.remove(&path) {
+                if let Some(thumbnail) = record.entry.thumbnail {
+                    let _ = fs::remove_file(thumbnail);
+                }
+            }
+            changes.removed.push(path);
+        }
+
+        for path in files {
+            let key = path.to_string_lossy().to_string();
+            let Ok(metadata) = fs::metadata(&path) else { continue };
+            let modified = file_modified(&metadata);
+            let unchanged = self.index.get(&key).map_or(false, |record| {
+                record.entry.file_size == metadata.len() && record.entry.file_modified == modified
+            });
+            if unchanged {
+                continue;
+            }
+
+            let existed = self.index.contains_key(&key);
+            match index_template(&self.data_dir, &path) {
+                Ok(record) => {
+                    self.index.insert(key.clone(), record);
+                    if existed {
+                        changes.updated.push(key);
+                    } else {
+                        changes.added.push(key);
+                    }
+                }
+                Err(e) => {
+                    // 无法读取的文件不进入索引，下次同步时再试
+                    log::warn!("Failed to index template '{}': {}", key, e);
+                    if self.index.remove(&key).is_some() {
+                        changes.removed.push(key);
+                    }
+                }
+            }
+        }
+
+        ScannedIndex { root: self.root, index: self.index, changes }
+    }
+}
+
+/// 在阻塞线程池中扫描磁盘，只在取快照和换入结果时持有模板库锁
+pub async fn sync_library(library: &ManagedTemplateLibrary) -> Result<LibraryChanges> {
+    let scan = library.lock().await.scan();
+    let scanned = tokio::task::spawn_blocking(move || scan.run()).await.map_err(|e| AppError::FileError {
+        message: format!("Template library scan failed: {}", e),
+    })?;
+    library.lock().await.apply_scan(scanned)
+}
+
+/// 更换模板库目录并在阻塞线程池中重建索引
+pub async fn set_library_root(library: &ManagedTemplateLibrary, root: Option<PathBuf>) -> Result<LibraryChanges> {
+    library.lock().await.set_root(root)?;
+    sync_library(library).await
+}
+
+/// 按固定间隔同步模板库，使索引跟随磁盘上的增删改
+///
+/// 采用轮询：只比较文件大小和修改时间，未变化时不读取文件内容。第一次同步立即执行，启动时的索引也在这里完成。
+pub fn spawn_watcher(library: ManagedTemplateLibrary, interval: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match sync_library(&library).await {
+                Ok(changes) if !changes.is_empty() => log::info!(
+                    "Template library updated: {} added, {} updated, {} removed",
+                    changes.added.len(),
+                    changes.updated.len(),
+                    changes.removed.len()
+                ),
+                Ok(_) => {}
+                Err(e) => log::warn!("Failed to sync template library: {}", e),
+            }
+        }
+    })
+}
+
+// === Thumbnails ===
+
+/// 绘制页面布局草图：区段分隔线、元素边框，文本以灰条表示
+pub fn render_thumbnail(template: &JasperTemplate, width: u32) -> Result<Vec<u8>> {
+    let svg = thumbnail_svg(template);
+    let tree = usvg::Tree::from_str(&svg, &usvg::Options::default()).map_err(|e| AppError::SerializationError {
+        message: format!("Failed to build thumbnail: {}", e),
+    })?;
+
+    let scale = width as f32 / tree.size.width();
+    let height = (tree.size.height() * scale).ceil().max(1.0) as u32;
+    let mut pixmap = tiny_skia::Pixmap::new(width.max(1), height).ok_or_else(|| AppError::SerializationError {
+        message: "Failed to create thumbnail pixmap".to_string(),
+    })?;
+    resvg::Tree::from_usvg(&tree).render(tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+    pixmap.encode_png().map_err(|e| AppError::SerializationError {
+        message: format!("Failed to encode thumbnail: {}", e),
+    })
+}
+
+fn thumbnail_svg(template: &JasperTemplate) -> String {
+    let canvas = &template.canvas;
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><rect width="{w}" height="{h}" fill="#ffffff"/>"##,
+        w = canvas.width.max(1.0),
+        h = canvas.height.max(1.0)
+    );
+
+    // 区段自上而下排列，区段内元素坐标相对区段顶部
+    let mut offsets: BTreeMap<&str, f64> = BTreeMap::new();
+    let mut top = canvas.margins.top;
+    for (_, band) in template.bands.iter() {
+        for id in &band.elements {
+            offsets.insert(id.as_str(), top);
+        }
+        top += band.height;
+        svg.push_str(&format!(
+            r##"<line x1="0" y1="{y}" x2="{w}" y2="{y}" stroke="#c8d0e0" stroke-width="0.5" stroke-dasharray="4 2"/>"##,
+            y = top,
+            w = canvas.width
+        ));
+    }
+
+    let mut elements: Vec<&TemplateElement> = template.elements.iter().filter(|element| element.visible).collect();
+    elements.sort_by_key(|element| element.z_index);
+    for element in elements {
+        let offset = offsets.get(element.id.as_str()).copied().unwrap_or(0.0);
+        svg.push_str(&element_sketch(element, offset));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+fn element_sketch(element: &TemplateElement, offset: f64) -> String {
+    let (x, y) = (element.position.x, element.position.y + offset);
+    let (w, h) = (element.size.width.max(0.5), element.size.height.max(0.5));
+    match element.element_type {
+        ElementType::Text | ElementType::DataField => {
+            let text = element.content.text.as_deref().or(element.content.expression.as_deref()).unwrap_or_default();
+            let font_size = element.content.font.as_ref().map_or(12.0, |font| font.size);
+            let bar = (text.chars().count() as f64 * font_size * 0.55).clamp(font_size, w);
+            let color = if matches!(element.element_type, ElementType::DataField) { "#7aa2d6" } else { "#9aa0a6" };
+            format!(
+                r#"<rect x="{}" y="{}" width="{}" height="{}" rx="1" fill="{}"/>"#,
+                x,
+                y + (h - font_size.min(h) * 0.6) / 2.0,
+                bar,
+                font_size.min(h) * 0.6,
+                color
+            )
+        }
+        ElementType::Line => format!(
+            r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#5f6368" stroke-width="1"/>"##,
+            x,
+            y,
+            x + element.size.width,
+            y + element.size.height
+        ),
+        ElementType::Rectangle => format!(
+            r##"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="#5f6368" stroke-width="1"/>"##,
+            x, y, w, h
+        ),
+        ElementType::Image | ElementType::Barcode | ElementType::Chart | ElementType::Subreport | ElementType::Table
+        | ElementType::Crosstab => format!(
+            r##"<rect x="{x}" y="{y}" width="{w}" height="{h}" fill="#eef1f6" stroke="#b0b8c4" stroke-width="0.5"/><path d="M{x} {y}L{x2} {y2}M{x2} {y}L{x} {y2}" stroke="#b0b8c4" stroke-width="0.5"/>"##,
+            x = x,
+            y = y,
+            w = w,
+            h = h,
+            x2 = x + w,
+            y2 = y + h
+        ),
+    }
+}
+
+// === Helpers ===
+
+/// 读取模板文件生成索引记录，缩略图写入应用数据目录
+fn index_template(data_dir: &Path, path: &Path) -> Result<IndexRecord> {
+    let key = path.to_string_lossy().to_string();
+    let metadata = fs::metadata(path).map_err(|e| AppError::FileError {
+        message: format!("Failed to read '{}': {}", key, e),
+    })?;
+    // 模板包只读取清单和模板，不解包到缓存目录，也不注册其中的字体
+    let template = match TemplateFormat::detect_from_file(&key) {
+        TemplateFormat::Package => {
+            let data = fs::read(path).map_err(|e| AppError::FileError {
+                message: format!("Failed to read '{}': {}", key, e),
+            })?;
+            TemplatePackage::read(&data)?.template
+        }
+        _ => TemplateLoader::load(&key)?,
+    };
+
+    let thumbnail = thumbnail_path(data_dir, &key);
+    let thumbnail = match render_thumbnail(&template, THUMBNAIL_WIDTH).and_then(|png| write_file(&thumbnail, &png)) {
+        Ok(()) => Some(thumbnail.to_string_lossy().to_string()),
+        Err(e) => {
+            log::warn!("Failed to render thumbnail for '{}': {}", key, e);
+            None
+        }
+    };
+
+    let entry = LibraryEntry {
+        path: key.clone(),
+        name: file_name(path),
+        format: format_name(&TemplateFormat::detect_from_file(&key)).to_string(),
+        description: template.metadata.description.clone(),
+        tags: template.metadata.tags.clone(),
+        created_by: template.metadata.created_by.clone(),
+        created_at: template.metadata.created_at,
+        last_modified: template.metadata.last_modified,
+        element_count: template.elements.len(),
+        file_size: metadata.len(),
+        file_modified: file_modified(&metadata),
+        thumbnail,
+    };
+    let search_text = search_text(&entry, &template);
+    Ok(IndexRecord { entry, search_text })
+}
+
+fn thumbnail_path(data_dir: &Path, key: &str) -> PathBuf {
+    let hash = to_hex(&Sha256::digest(key.as_bytes()));
+    data_dir.join(THUMBNAIL_DIR).join(format!("{}.png", &hash[..16]))
+}
+
+/// 递归查找模板文件，跳过隐藏目录
+fn scan_templates(root: &Path) -> Vec<PathBuf> {
+    let root = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    let mut files = Vec::new();
+    let mut pending = vec![root];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() && !hidden => pending.push(path),
+                Ok(file_type) if file_type.is_file() && is_template_file(&path) => files.push(path),
+                _ => {}
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn is_template_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map_or(false, |extension| TEMPLATE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+fn search_text(entry: &LibraryEntry, template: &JasperTemplate) -> String {
+    let mut parts: Vec<&str> = vec![&entry.name, &entry.created_by];
+    parts.extend(entry.description.as_deref());
+    parts.extend(entry.tags.iter().map(String::as_str));
+    for element in &template.elements {
+        parts.extend(element.content.text.as_deref());
+        parts.extend(element.content.expression.as_deref());
+        if let Some(binding) = &element.data_binding {
+            parts.push(&binding.field_name);
+        }
+    }
+    parts.join("\n").to_lowercase()
+}
+
+fn format_name(format: &TemplateFormat) -> &'static str {
+    match format {
+        TemplateFormat::Json => "json",
+        TemplateFormat::Binary => "binary",
+        TemplateFormat::Jrxml => "jrxml",
+        TemplateFormat::Package => "package",
+        TemplateFormat::Unknown => "unknown",
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+/// 精确到秒，避免持久化后精度变化导致重复索引
+fn file_modified(metadata: &fs::Metadata) -> DateTime<Utc> {
+    let modified: DateTime<Utc> = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH).into();
+    DateTime::from_timestamp(modified.timestamp(), 0).unwrap_or(modified)
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::FileError {
+            message: format!("Failed to create directory '{}': {}", parent.display(), e),
+        })?;
+    }
+    fs::write(path, data).map_err(|e| AppError::FileError {
+        message: format!("Failed to write '{}': {}", path.display(), e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{Position, Size};
+    use crate::core::package::PackageOptions;
+    use crate::core::template::BandConfig;
+    use crate::core::template_service::TemplateSerializer;
+    use tempfile::TempDir;
+
+    fn element(element_type: ElementType, text: Option<&str>, expression: Option<&str>) -> TemplateElement {
+        let mut element = TemplateElement::new(element_type, Position { x: 20.0, y: 10.0 }, Size { width: 200.0, height: 20.0 });
+        element.content.text = text.map(str::to_string);
+        element.content.expression = expression.map(str::to_string);
+        element
+    }
+
+    fn save(path: &Path, description: &str, tags: &[&str], text: &str, expression: &str) {
+        let mut template = JasperTemplate::new();
+        template.metadata.description = Some(description.to_string());
+        template.metadata.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        template.metadata.created_by = "finance".to_string();
+        template.elements.push(element(ElementType::Text, Some(text), None));
+        template.elements.push(element(ElementType::DataField, None, Some(expression)));
+        let mut detail = BandConfig::new(40.0);
+        detail.elements = template.elements.iter().map(|element| element.id.clone()).collect();
+        template.bands.detail = Some(detail);
+        TemplateSerializer::new().save_to_file(&template, path.to_str().unwrap()).unwrap();
+    }
+
+    fn setup() -> (TempDir, TempDir, TemplateLibrary) {
+        let data_dir = TempDir::new().unwrap();
+        let root = TempDir::new().unwrap();
+        fs::create_dir(root.path().join("bank")).unwrap();
+        save(&root.path().join("invoice.jasper"), "月度发票", &["finance", "invoice"], "增值税发票", "{amount} * 1.13");
+        save(&root.path().join("bank/receipt.jasper"), "银行回单", &["bank"], "电子回单", "{account_no}");
+        fs::write(root.path().join("notes.txt"), "not a template").unwrap();
+
+        let mut library = TemplateLibrary::new(data_dir.path());
+        library.set_root(Some(root.path().to_path_buf())).unwrap();
+        let changes = library.sync().unwrap();
+        assert_eq!(changes.added.len(), 2);
+        (data_dir, root, library)
+    }
+
+    #[test]
+    fn test_index_and_search() {
+        let (_data_dir, _root, library) = setup();
+
+        let all = library.search(&LibraryQuery::default());
+        assert_eq!(all.len(), 2);
+        let invoice = all.iter().find(|entry| entry.name == "invoice").unwrap();
+        assert_eq!(invoice.format, "json");
+        assert_eq!(invoice.element_count, 2);
+        assert_eq!(invoice.created_by, "finance");
+        assert!(Path::new(invoice.thumbnail.as_ref().unwrap()).is_file());
+
+        let search = |text: &str| -> Vec<String> {
+            let query = LibraryQuery { text: Some(text.to_string()), ..LibraryQuery::default() };
+            library.search(&query).into_iter().map(|entry| entry.name).collect()
+        };
+        assert_eq!(search("增值税"), vec!["invoice"]);
+        assert_eq!(search("ACCOUNT_NO"), vec!["receipt"]);
+        assert_eq!(search("回单 bank"), vec!["receipt"]);
+        assert_eq!(search("finance").len(), 2);
+        assert!(search("payroll").is_empty());
+
+        let query = LibraryQuery { tags: vec!["Invoice".to_string()], ..LibraryQuery::default() };
+        assert_eq!(library.search(&query).len(), 1);
+        assert_eq!(library.tags(), vec!["bank", "finance", "invoice"]);
+    }
+
+    #[test]
+    fn test_sync_tracks_disk_changes() {
+        let (data_dir, root, mut library) = setup();
+        assert!(library.sync().unwrap().is_empty());
+
+        let invoice = root.path().join("invoice.jasper");
+        save(&invoice, "月度发票", &["finance", "invoice"], "普通发票和增值税发票明细表", "{amount} * 1.13");
+        fs::remove_file(root.path().join("bank/receipt.jasper")).unwrap();
+        save(&root.path().join("payroll.jasper"), "工资条", &["hr"], "工资", "{salary}");
+
+        let changes = library.sync().unwrap();
+        assert_eq!(changes.updated, vec![invoice.canonicalize().unwrap().to_string_lossy().to_string()]);
+        assert_eq!(changes.removed.len(), 1);
+        assert_eq!(changes.added.len(), 1);
+
+        // 索引随设置一起持久化
+        let reopened = TemplateLibrary::open(data_dir.path()).unwrap();
+        assert_eq!(reopened.settings().root.as_deref(), Some(root.path()));
+        let query = LibraryQuery { text: Some("明细表".to_string()), ..LibraryQuery::default() };
+        assert_eq!(reopened.search(&query).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sync_library_scans_outside_lock() {
+        let (_data_dir, root, library) = setup();
+        let library: ManagedTemplateLibrary = Arc::new(Mutex::new(library));
+        save(&root.path().join("payroll.jasper"), "工资条", &["hr"], "工资", "{salary}");
+
+        // 扫描只持有快照，模板库在此期间仍可使用
+        let scan = library.lock().await.scan();
+        assert!(library.try_lock().is_ok());
+        let scanned = scan.run();
+        assert_eq!(scanned.changes.added.len(), 1);
+
+        // 扫描期间更换了目录，结果被丢弃
+        let other = TempDir::new().unwrap();
+        let changes = set_library_root(&library, Some(other.path().to_path_buf())).await.unwrap();
+        assert_eq!(changes.removed.len(), 2);
+        assert!(library.lock().await.apply_scan(scanned).unwrap().is_empty());
+        assert!(library.lock().await.search(&LibraryQuery::default()).is_empty());
+
+        save(&other.path().join("contract.jasper"), "合同", &[], "合同", "{party}");
+        let changes = sync_library(&library).await.unwrap();
+        assert_eq!(changes.added.len(), 1);
+        assert_eq!(library.lock().await.search(&LibraryQuery::default()).len(), 1);
+    }
+
+    #[test]
+    fn test_index_package_without_extracting() {
+        let (_data_dir, root, mut library) = setup();
+        let mut template = JasperTemplate::new();
+        template.metadata.description = Some("打包的报价单".to_string());
+        let data = TemplatePackage::pack(&template, &PackageOptions::default()).unwrap().write().unwrap();
+        fs::write(root.path().join("quote.jpkg"), &data).unwrap();
+
+        let changes = library.sync().unwrap();
+        assert_eq!(changes.added.len(), 1);
+        let query = LibraryQuery { text: Some("报价单".to_string()), ..LibraryQuery::default() };
+        assert_eq!(library.search(&query)[0].format, "package");
+
+        // 索引不会把模板包解到缓存目录
+        let cache = std::env::temp_dir().join("jasper-designer").join("packages").join(&to_hex(&Sha256::digest(&data))[..16]);
+        assert!(!cache.exists());
+    }
+
+    #[test]
+    fn test_recent_templates() {
+        let (data_dir, root, mut library) = setup();
+        library.set_max_recent(2).unwrap();
+        let outside = TempDir::new().unwrap();
+        let other = outside.path().join("other.jasper");
+        save(&other, "其他", &[], "其他", "{x}");
+
+        for name in ["invoice.jasper", "bank/receipt.jasper", "invoice.jasper"] {
+            library.record_opened(root.path().join(name).to_str().unwrap()).unwrap();
+        }
+        library.record_opened(other.to_str().unwrap()).unwrap();
+        fs::remove_file(&other).unwrap();
+
+        let reopened = TemplateLibrary::open(data_dir.path()).unwrap();
+        let recent = reopened.recent();
+        assert_eq!(recent.iter().map(|recent| recent.name.as_str()).collect::<Vec<_>>(), vec!["other", "invoice"]);
+        assert!(!recent[0].exists);
+        assert!(recent[1].exists);
+
+        // 模板库外的文件不进入索引
+        assert_eq!(reopened.search(&LibraryQuery::default()).len(), 2);
+
+        library.clear_recent().unwrap();
+        assert!(TemplateLibrary::open(data_dir.path()).unwrap().recent().is_empty());
+    }
+
+    #[test]
+    fn test_thumbnail() {
+        let mut template = JasperTemplate::new();
+        template.elements.push(element(ElementType::Text, Some("标题"), None));
+        template.elements.push(element(ElementType::Image, None, None));
+        let png = render_thumbnail(&template, THUMBNAIL_WIDTH).unwrap();
+        let image = image::load_from_memory(&png).unwrap();
+        assert_eq!(image.width(), THUMBNAIL_WIDTH);
+        assert_eq!(image.height(), (842.0_f32 * THUMBNAIL_WIDTH as f32 / 595.0).ceil() as u32);
+    }
+}
@@ -0,0 +1,716 @@
+// === Template Lint Engine ===
+// 与 `JasperTemplate::validate` 不同，检查不会在第一个问题处停止，而是返回全部诊断，
+// 供设计器的问题面板展示。
+use crate::core::{barcode, image_source};
+use crate::core::element::{ChartConfig, CrosstabConfig, Position, Size, SubreportConfig, TableConfig};
+use crate::core::template::{BandConfig, ElementType, JasperTemplate, TemplateElement};
+use crate::expression::Expression;
+use crate::preview::formats::pdf::FontManager;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// 布局引擎内置的变量
+const BUILTIN_VARIABLES: &[&str] = &["PAGE_NUMBER", "REPORT_COUNT"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// 诊断代码，供前端按类型过滤和跳转
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintCode {
+    /// 元素超出页边距以内的可打印区域
+    OutsidePrintableArea,
+    /// 元素超出所在区段的高度
+    ExceedsBand,
+    /// 同一区段内的元素互相重叠
+    OverlappingElements,
+    /// 表达式无法解析
+    InvalidExpression,
+    /// 分组表达式无法解析
+    InvalidGroupExpression,
+    /// 引用了数据结构中没有的字段
+    UnknownField,
+    UnknownParameter,
+    UnknownVariable,
+    /// 数据绑定引用了不存在的数据源
+    UnknownDataSource,
+    /// 图片文件不存在
+    MissingImage,
+    /// 子报表模板文件不存在
+    MissingSubreport,
+    /// 条码内容不符合码制规则
+    InvalidBarcode,
+    /// 字体既不是内置字体也没有安装
+    UnavailableFont,
+}
+
+impl LintCode {
+    pub fn severity(self) -> Severity {
+        match self {
+            LintCode::InvalidExpression
+            | LintCode::InvalidGroupExpression
+            | LintCode::UnknownField
+            | LintCode::UnknownParameter
+            | LintCode::UnknownVariable
+            | LintCode::UnknownDataSource
+            | LintCode::MissingSubreport
+            | LintCode::InvalidBarcode
+            | LintCode::ExceedsBand => Severity::Error,
+            LintCode::OutsidePrintableArea
+            | LintCode::OverlappingElements
+            | LintCode::MissingImage
+            | LintCode::UnavailableFont => Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub code: LintCode,
+    pub severity: Severity,
+    pub message: String,
+    /// 相关元素（非元素问题时为空）
+    pub element_id: Option<String>,
+    /// 出问题的属性，如 `groups[customer].expression`
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintReport {
+    pub diagnostics: Vec<Diagnostic>,
+    pub error_count: usize,
+    pub warning_count: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LintOptions {
+    /// 模板文件所在目录，用于解析相对的图片路径
+    pub base_dir: Option<PathBuf>,
+    /// 检查字体是否可用（需要扫描系统字体）
+    pub check_fonts: bool,
+}
+
+/// 检查模板并返回全部问题，按严重程度排序
+pub fn lint_template(template: &JasperTemplate, options: &LintOptions) -> LintReport {
+    let mut linter = Linter {
+        template,
+        options,
+        fonts: options.check_fonts.then(FontManager::new),
+        diagnostics: Vec::new(),
+    };
+    linter.check_layout();
+    for element in &template.elements {
+        linter.check_element(element);
+    }
+    linter.check_groups();
+    linter.check_variables();
+
+    let mut diagnostics = linter.diagnostics;
+    diagnostics.sort_by_key(|diagnostic| diagnostic.severity);
+    LintReport {
+        error_count: diagnostics.iter().filter(|d| d.severity == Severity::Error).count(),
+        warning_count: diagnostics.iter().filter(|d| d.severity == Severity::Warning).count(),
+        diagnostics,
+    }
+}
+
+struct Linter<'a> {
+    template: &'a JasperTemplate,
+    options: &'a LintOptions,
+    fonts: Option<FontManager>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Linter<'a> {
+    fn report(&mut self, code: LintCode, message: String, element_id: Option<&str>, path: Option<String>) {
+        self.diagnostics.push(Diagnostic {
+            code,
+            severity: code.severity(),
+            message,
+            element_id: element_id.map(str::to_string),
+            path,
+        });
+    }
+
+    // === 布局 ===
+    fn check_layout(&mut self) {
+        let canvas = &self.template.canvas;
+        let margins = &canvas.margins;
+        let content_width = canvas.width - margins.left - margins.right;
+
+        let report_bands = self.template.bands.iter().map(|(band_type, band)| (format!("{:?}", band_type), band));
+        let group_bands = self.template.groups.iter().flat_map(|group| {
+            group.bands().map(move |(kind, band)| (format!("group '{}' {}", group.name, kind), band))
+        });
+        let bands: Vec<(String, &BandConfig)> = report_bands.chain(group_bands).collect();
+
+        let mut band_members = HashSet::new();
+        for (band_name, band) in &bands {
+            let members: Vec<&TemplateElement> = band
+                .elements
+                .iter()
+                .filter_map(|id| self.template.get_element_by_id(id))
+                .collect();
+
+            for element in &members {
+                band_members.insert(element.id.as_str());
+                // 区段内坐标相对于页边距以内的区段左上角
+                if element.position.x < 0.0 || element.position.x + element.size.width > content_width {
+                    self.report(
+                        LintCode::OutsidePrintableArea,
+                        format!("Element '{}' extends beyond the page margins", element.id),
+                        Some(&element.id),
+                        Some(format!("elements[{}].position", element.id)),
+                    );
+                }
+                if element.position.y < 0.0 || element.position.y + element.size.height > band.height {
+                    self.report(
+                        LintCode::ExceedsBand,
+                        format!("Element '{}' exceeds the height of band {}", element.id, band_name),
+                        Some(&element.id),
+                        Some(format!("elements[{}].position", element.id)),
+                    );
+                }
+            }
+
+            // 矩形和线条常用作边框、分隔线，与其他元素重叠是正常的
+            let content: Vec<&TemplateElement> = members
+                .into_iter()
+                .filter(|element| element.visible && !matches!(element.element_type, ElementType::Rectangle | ElementType::Line))
+                .collect();
+            for (index, first) in content.iter().enumerate() {
+                for second in &content[index + 1..] {
+                    if overlaps(&first.position, &first.size, &second.position, &second.size) {
+                        self.report(
+                            LintCode::OverlappingElements,
+                            format!("Elements '{}' and '{}' overlap in band {}", first.id, second.id, band_name),
+                            Some(&first.id),
+                            Some(format!("elements[{}].position", second.id)),
+                        );
+                    }
+                }
+            }
+        }
+
+        // 不属于任何区段的元素按页面绝对坐标放置
+        let page_elements: Vec<&TemplateElement> = self
+            .template
+            .elements
+            .iter()
+            .filter(|element| !band_members.contains(element.id.as_str()))
+            .collect();
+        for element in page_elements {
+            let position = &element.position;
+            if position.x < margins.left
+                || position.y < margins.top
+                || position.x + element.size.width > canvas.width - margins.right
+                || position.y + element.size.height > canvas.height - margins.bottom
+            {
+                self.report(
+                    LintCode::OutsidePrintableArea,
+                    format!("Element '{}' extends beyond the page margins", element.id),
+                    Some(&element.id),
+                    Some(format!("elements[{}].position", element.id)),
+                );
+            }
+        }
+    }
+
+    // === 元素 ===
+    fn check_element(&mut self, element: &TemplateElement) {
+        let template = self.template;
+        let source = match &element.data_binding {
+            Some(binding) => {
+                let source = template.get_data_source_by_id(&binding.source_id);
+                if source.is_none() {
+                    self.report(
+                        LintCode::UnknownDataSource,
+                        format!("Data source '{}' not found for element '{}'", binding.source_id, element.id),
+                        Some(&element.id),
+                        Some(format!("elements[{}].data_binding.source_id", element.id)),
+                    );
+                }
+                source
+            }
+            // 未绑定时使用模板唯一的数据源
+            None if template.data_sources.len() == 1 => template.data_sources.first(),
+            None => None,
+        };
+        // 没有列信息时无法判断字段是否存在
+        let columns: Option<HashSet<&str>> = source
+            .filter(|source| !source.schema.columns.is_empty())
+            .map(|source| source.schema.columns.iter().map(|column| column.name.as_str()).collect());
+
+        match element.element_type {
+            ElementType::Barcode if element.content.barcode.is_some() => {
+                self.check_barcode(element, columns.as_ref());
+            }
+            // 没有条码配置的旧模板按 expression 生成条码
+            ElementType::DataField | ElementType::Barcode => {
+                if let Some(expression) = element.content.expression.as_deref().filter(|e| !e.trim().is_empty()) {
+                    let path = format!("elements[{}].content.expression", element.id);
+                    self.check_expression(expression, columns.as_ref(), Some(&element.id), path, LintCode::InvalidExpression);
+                }
+            }
+            ElementType::Image => {
+                if let Some(src) = element.content.expression.as_deref() {
+                    self.check_image(element, src);
+                }
+            }
+            ElementType::Subreport => {
+                if let Some(config) = &element.content.subreport {
+                    self.check_subreport(element, config, columns.as_ref());
+                }
+            }
+            ElementType::Table => {
+                if let Some(config) = &element.content.table {
+                    self.check_table(element, config, columns.as_ref());
+                }
+            }
+            ElementType::Crosstab => {
+                if let Some(config) = &element.content.crosstab {
+                    self.check_crosstab(element, config, columns.as_ref());
+                }
+            }
+            ElementType::Chart => {
+                if let Some(config) = &element.content.chart {
+                    self.check_chart(element, config, columns.as_ref());
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(font) = &element.content.font {
+            if matches!(element.element_type, ElementType::Text | ElementType::DataField) {
+                let available = self.fonts.as_mut().map_or(true, |fonts| fonts.has_font(&font.family));
+                if !available {
+                    self.report(
+                        LintCode::UnavailableFont,
+                        format!("Font '{}' used by element '{}' is not available", font.family, element.id),
+                        Some(&element.id),
+                        Some(format!("elements[{}].content.font.family", element.id)),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_image(&mut self, element: &TemplateElement, src: &str) {
+        let src = src.trim();
+        // 数据 URI、网络地址和动态表达式在填充时才能确定
+        if !image_source::is_local_path(src) {
+            return;
+        }
+
+        let path = Path::new(src);
+        let resolved = match &self.options.base_dir {
+            Some(base_dir) if path.is_relative() => base_dir.join(path),
+            _ => path.to_path_buf(),
+        };
+        if !resolved.is_file() {
+            self.report(
+                LintCode::MissingImage,
+                format!("Image file '{}' used by element '{}' does not exist", src, element.id),
+                Some(&element.id),
+                Some(format!("elements[{}].content.expression", element.id)),
+            );
+        }
+    }
+
+    fn check_barcode(&mut self, element: &TemplateElement, columns: Option<&HashSet<&str>>) {
+        let Some(config) = &element.content.barcode else {
+            return;
+        };
+        if let Some(expression) = config.expression.as_deref().filter(|e| !e.trim().is_empty()) {
+            let path = format!("elements[{}].content.barcode.expression", element.id);
+            self.check_expression(expression, columns, Some(&element.id), path, LintCode::InvalidExpression);
+        } else if let Err(error) = barcode::validate(config) {
+            self.report(
+                LintCode::InvalidBarcode,
+                format!("Barcode '{}' is invalid: {}", element.id, error),
+                Some(&element.id),
+                Some(format!("elements[{}].content.barcode.value", element.id)),
+            );
+        }
+    }
+
+    fn check_subreport(&mut self, element: &TemplateElement, config: &SubreportConfig, columns: Option<&HashSet<&str>>) {
+        for parameter in &config.parameters {
+            let path = format!("elements[{}].content.subreport.parameters[{}]", element.id, parameter.name);
+            self.check_expression(&parameter.expression, columns, Some(&element.id), path, LintCode::InvalidExpression);
+        }
+        if let Some(expression) = config.data_expression.as_deref().filter(|e| !e.trim().is_empty()) {
+            let path = format!("elements[{}].content.subreport.data_expression", element.id);
+            self.check_expression(expression, columns, Some(&element.id), path, LintCode::InvalidExpression);
+        }
+
+        let path = Path::new(&config.template_path);
+        let resolved = match &self.options.base_dir {
+            Some(base_dir) if path.is_relative() => base_dir.join(path),
+            _ => path.to_path_buf(),
+        };
+        if config.template_path.trim().is_empty() || !resolved.is_file() {
+            self.report(
+                LintCode::MissingSubreport,
+                format!("Subreport template '{}' used by element '{}' does not exist", config.template_path, element.id),
+                Some(&element.id),
+                Some(format!("elements[{}].content.subreport.template_path", element.id)),
+            );
+        }
+    }
+
+    /// 表格单元格按表格自己的数据源检查字段；数据取自父记录数组时无法得知其结构
+    fn check_table(&mut self, element: &TemplateElement, config: &TableConfig, columns: Option<&HashSet<&str>>) {
+        let table_columns =
+            self.bound_columns(element, "table", &config.data_source_id, config.data_expression.as_deref(), columns);
+        for (index, column) in config.columns.iter().enumerate() {
+            let path = format!("elements[{}].content.table.columns[{}].expression", element.id, index);
+            self.check_expression(&column.expression, table_columns.as_ref(), Some(&element.id), path, LintCode::InvalidExpression);
+        }
+    }
+
+    /// 交叉表的分组和度量字段按交叉表自己的数据源检查
+    fn check_crosstab(&mut self, element: &TemplateElement, config: &CrosstabConfig, columns: Option<&HashSet<&str>>) {
+        let source_columns =
+            self.bound_columns(element, "crosstab", &config.data_source_id, config.data_expression.as_deref(), columns);
+        let Some(source_columns) = source_columns else {
+            return;
+        };
+        let fields = config
+            .row_groups
+            .iter()
+            .enumerate()
+            .map(|(index, group)| (format!("row_groups[{}]", index), &group.field))
+            .chain(config.column_groups.iter().enumerate().map(|(index, group)| (format!("column_groups[{}]", index), &group.field)))
+            .chain(config.measures.iter().enumerate().map(|(index, measure)| (format!("measures[{}]", index), &measure.field)));
+        for (path, field) in fields {
+            // 嵌套字段只检查第一段
+            let name = field.split('.').next().unwrap_or_default();
+            if !source_columns.contains(name) {
+                self.report(
+                    LintCode::UnknownField,
+                    format!("Field '{}' used by crosstab '{}' is not in its data source", field, element.id),
+                    Some(&element.id),
+                    Some(format!("elements[{}].content.crosstab.{}.field", element.id, path)),
+                );
+            }
+        }
+    }
+
+    /// 图表的分类、系列和数值表达式按图表自己的数据源检查
+    fn check_chart(&mut self, element: &TemplateElement, config: &ChartConfig, columns: Option<&HashSet<&str>>) {
+        let source_columns =
+            self.bound_columns(element, "chart", &config.data_source_id, config.data_expression.as_deref(), columns);
+        let expressions = [
+            ("category_expression", Some(&config.category_expression)),
+            ("series_expression", config.series_expression.as_ref()),
+            ("value_expression", Some(&config.value_expression)),
+        ];
+        for (name, expression) in expressions {
+            let Some(expression) = expression else {
+                continue;
+            };
+            let path = format!("elements[{}].content.chart.{}", element.id, name);
+            self.check_expression(expression, source_columns.as_ref(), Some(&element.id), path, LintCode::InvalidExpression);
+        }
+    }
+
+    /// 表格类元素绑定数据的列：有数据表达式时检查表达式（父记录数组的结构未知），否则取绑定数据源的列
+    fn bound_columns(
+        &mut self,
+        element: &TemplateElement,
+        kind: &str,
+        data_source_id: &str,
+        data_expression: Option<&str>,
+        columns: Option<&HashSet<&str>>,
+    ) -> Option<HashSet<&'a str>> {
+        let template: &'a JasperTemplate = self.template;
+        match data_expression {
+            Some(expression) => {
+                let path = format!("elements[{}].content.{}.data_expression", element.id, kind);
+                self.check_expression(expression, columns, Some(&element.id), path, LintCode::InvalidExpression);
+                None
+            }
+            None => match template.get_data_source_by_id(data_source_id) {
+                Some(source) if !source.schema.columns.is_empty() => {
+                    Some(source.schema.columns.iter().map(|column| column.name.as_str()).collect())
+                }
+                Some(_) => None,
+                None => {
+                    self.report(
+                        LintCode::UnknownDataSource,
+                        format!("Data source '{}' not found for {} '{}'", data_source_id, kind, element.id),
+                        Some(&element.id),
+                        Some(format!("elements[{}].content.{}.data_source_id", element.id, kind)),
+                    );
+                    None
+                }
+            },
+        }
+    }
+
+    // === 分组与变量 ===
+    fn check_groups(&mut self) {
+        let columns = self.default_columns();
+        let template = self.template;
+        for group in &template.groups {
+            let path = format!("groups[{}].expression", group.name);
+            self.check_expression(&group.expression, columns.as_ref(), None, path, LintCode::InvalidGroupExpression);
+        }
+    }
+
+    fn check_variables(&mut self) {
+        let columns = self.default_columns();
+        let template = self.template;
+        for variable in &template.variables {
+            if variable.expression.trim().is_empty() {
+                continue;
+            }
+            let path = format!("variables[{}].expression", variable.name);
+            self.check_expression(&variable.expression, columns.as_ref(), None, path, LintCode::InvalidExpression);
+        }
+    }
+
+    /// 模板只有一个数据源时，分组和变量表达式按它的列检查
+    fn default_columns(&self) -> Option<HashSet<&'a str>> {
+        let template: &'a JasperTemplate = self.template;
+        match template.data_sources.as_slice() {
+            [source] if !source.schema.columns.is_empty() => {
+                Some(source.schema.columns.iter().map(|column| column.name.as_str()).collect())
+            }
+            _ => None,
+        }
+    }
+
+    fn check_expression(
+        &mut self,
+        source: &str,
+        columns: Option<&HashSet<&str>>,
+        element_id: Option<&str>,
+        path: String,
+        invalid_code: LintCode,
+    ) {
+        let expression = match Expression::parse(source) {
+            Ok(expression) => expression,
+            Err(error) => {
+                self.report(invalid_code, format!("Cannot parse '{}': {}", source, error), element_id, Some(path));
+                return;
+            }
+        };
+
+        let references = expression.references();
+        if let Some(columns) = columns {
+            for field in &references.fields {
+                if !columns.contains(field_root(field)) {
+                    self.report(
+                        LintCode::UnknownField,
+                        format!("Field '{}' is not defined in the data source schema", field),
+                        element_id,
+                        Some(path.clone()),
+                    );
+                }
+            }
+        }
+        for parameter in &references.parameters {
+            if !self.template.parameters.iter().any(|p| &p.name == parameter) {
+                self.report(
+                    LintCode::UnknownParameter,
+                    format!("Parameter '{}' is not defined", parameter),
+                    element_id,
+                    Some(path.clone()),
+                );
+            }
+        }
+        for variable in &references.variables {
+            let defined = BUILTIN_VARIABLES.contains(&variable.as_str())
+                || self.template.variables.iter().any(|v| &v.name == variable);
+            if !defined {
+                self.report(
+                    LintCode::UnknownVariable,
+                    format!("Variable '{}' is not defined", variable),
+                    element_id,
+                    Some(path.clone()),
+                );
+            }
+        }
+    }
+}
+
+/// 字段路径的第一段（`account.cards[1]` → `account`）
+fn field_root(path: &str) -> &str {
+    path.split(|c| c == '.' || c == '[').next().unwrap_or(path)
+}
+
+fn overlaps(a: &Position, a_size: &Size, b: &Position, b_size: &Size) -> bool {
+    a.x < b.x + b_size.width
+        && b.x < a.x + a_size.width
+        && a.y < b.y + b_size.height
+        && b.y < a.y + a_size.height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{BarcodeConfig, BarcodeType};
+    use crate::core::template::{
+        BandConfig, ColumnInfo, DataSchema, DataSource, DataSourceType, DataType,
+        FontConfig, Group, Parameter,
+    };
+
+    fn element(id: &str, element_type: ElementType, x: f64, y: f64) -> TemplateElement {
+        TemplateElement {
+            id: id.to_string(),
+            ..TemplateElement::new(element_type, Position { x, y }, Size { width: 100.0, height: 20.0 })
+        }
+    }
+
+    fn data_source(columns: &[&str]) -> DataSource {
+        DataSource {
+            id: "orders".to_string(),
+            name: "Orders".to_string(),
+            source_type: DataSourceType::Json,
+            provider_type: "json".to_string(),
+            config: serde_json::from_value(serde_json::json!({})).unwrap(),
+            schema: DataSchema {
+                columns: columns
+                    .iter()
+                    .map(|name| ColumnInfo {
+                        name: name.to_string(),
+                        data_type: DataType::String,
+                        nullable: true,
+                        default_value: None,
+                        constraints: Vec::new(),
+                        description: None,
+                        format_hint: None,
+                    })
+                    .collect(),
+                primary_key: None,
+                indexes: Vec::new(),
+                relationships: Default::default(),
+                metadata: Default::default(),
+            },
+            query: None,
+        }
+    }
+
+    fn codes(report: &LintReport) -> Vec<(LintCode, Option<&str>)> {
+        report.diagnostics.iter().map(|d| (d.code, d.element_id.as_deref())).collect()
+    }
+
+    #[test]
+    fn test_lint_reports_all_problems() {
+        let mut template = JasperTemplate::new();
+        template.data_sources.push(data_source(&["customer", "amount"]));
+        template.parameters.push(Parameter {
+            name: "bank".to_string(),
+            param_type: DataType::String,
+            default: None,
+            description: None,
+            required: false,
+        });
+
+        let mut amount = element("amount", ElementType::DataField, 0.0, 0.0);
+        amount.content.expression = Some("$F{amount} + $P{fee} + $V{PAGE_NUMBER}".to_string());
+        let mut account = element("account", ElementType::DataField, 50.0, 0.0);
+        account.content.expression = Some("account.owner + $P{bank}".to_string());
+        let mut wide = element("wide", ElementType::Text, 500.0, 0.0);
+        wide.content.font = Some(FontConfig { family: "No Such Font 9f3c".to_string(), ..FontConfig::default() });
+        let mut logo = element("logo", ElementType::Image, 30.0, 30.0);
+        logo.content.expression = Some("images/missing-logo.png".to_string());
+        let frame = element("frame", ElementType::Rectangle, 0.0, 0.0);
+        template.elements = vec![amount, account, wide, logo, frame];
+
+        let mut detail = BandConfig::new(20.0);
+        detail.elements = vec!["amount".to_string(), "account".to_string(), "wide".to_string(), "frame".to_string()];
+        template.bands.detail = Some(detail);
+        template.groups.push(Group::new("customer", "$F{customer} +"));
+
+        let report = lint_template(&template, &LintOptions { base_dir: None, check_fonts: true });
+        let found = codes(&report);
+        assert!(found.contains(&(LintCode::UnknownParameter, Some("amount"))));
+        assert!(found.contains(&(LintCode::UnknownField, Some("account"))));
+        assert!(found.contains(&(LintCode::OverlappingElements, Some("amount"))));
+        assert!(found.contains(&(LintCode::OutsidePrintableArea, Some("wide"))));
+        assert!(found.contains(&(LintCode::UnavailableFont, Some("wide"))));
+        assert!(found.contains(&(LintCode::MissingImage, Some("logo"))));
+        assert!(found.contains(&(LintCode::InvalidGroupExpression, None)));
+        // 内置变量、已定义的参数和矩形边框不报告
+        assert!(!found.iter().any(|(code, _)| *code == LintCode::UnknownVariable));
+        assert!(!report.diagnostics.iter().any(|d| d.message.contains("'frame'")));
+
+        assert_eq!(report.error_count, 3);
+        assert_eq!(report.warning_count, report.diagnostics.len() - 3);
+        assert_eq!(report.diagnostics[0].severity, Severity::Error);
+        let group = report.diagnostics.iter().find(|d| d.code == LintCode::InvalidGroupExpression).unwrap();
+        assert_eq!(group.path.as_deref(), Some("groups[customer].expression"));
+    }
+
+    #[test]
+    fn test_lint_image_expressions() {
+        let mut template = JasperTemplate::new();
+        template.data_sources.push(data_source(&["photo"]));
+        for (id, src) in [("field", "$F{photo}"), ("shorthand", "${photo}"), ("uri", "data:image/png;base64,AA==")] {
+            let mut image = element(id, ElementType::Image, 0.0, 0.0);
+            image.content.expression = Some(src.to_string());
+            template.elements.push(image);
+        }
+
+        let report = lint_template(&template, &LintOptions { base_dir: None, check_fonts: false });
+        assert!(!codes(&report).iter().any(|(code, _)| *code == LintCode::MissingImage));
+    }
+
+    #[test]
+    fn test_lint_barcode_content() {
+        let mut template = JasperTemplate::new();
+        template.data_sources.push(data_source(&["serial"]));
+        let barcode = |id: &str, x: f64, config: BarcodeConfig| {
+            let mut element = element(id, ElementType::Barcode, x, 0.0);
+            element.content.barcode = Some(config);
+            element
+        };
+        let ean = BarcodeConfig { barcode_type: BarcodeType::Ean13, ..BarcodeConfig::default() };
+        template.elements = vec![
+            barcode("bad_digits", 0.0, BarcodeConfig { value: "12345".to_string(), ..ean.clone() }),
+            barcode("good", 60.0, BarcodeConfig { value: "5901234123457".to_string(), ..ean.clone() }),
+            barcode("bound", 120.0, BarcodeConfig { expression: Some("$F{serial_no}".to_string()), ..ean }),
+        ];
+
+        let report = lint_template(&template, &LintOptions { base_dir: None, check_fonts: false });
+        let found = codes(&report);
+        assert!(found.contains(&(LintCode::InvalidBarcode, Some("bad_digits"))));
+        assert!(found.contains(&(LintCode::UnknownField, Some("bound"))));
+        assert!(!found.contains(&(LintCode::InvalidBarcode, Some("good"))));
+    }
+
+    #[test]
+    fn test_lint_clean_template() {
+        let dir = std::env::temp_dir().join(format!("jasper-lint-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("logo.png"), b"png").unwrap();
+
+        let mut template = JasperTemplate::new();
+        template.data_sources.push(data_source(&["account"]));
+        let mut field = element("field", ElementType::DataField, 0.0, 0.0);
+        field.content.expression = Some("$F{account.cards[0]}".to_string());
+        field.content.font = Some(FontConfig::default());
+        let mut logo = element("logo", ElementType::Image, 120.0, 0.0);
+        logo.content.expression = Some("logo.png".to_string());
+        template.elements = vec![field, logo];
+        let mut detail = BandConfig::new(20.0);
+        detail.elements = vec!["field".to_string(), "logo".to_string()];
+        template.bands.detail = Some(detail);
+
+        let report = lint_template(&template, &LintOptions { base_dir: Some(dir.clone()), check_fonts: true });
+        assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
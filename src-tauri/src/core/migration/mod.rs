@@ -0,0 +1,340 @@
+// === 模板格式版本迁移 ===
+use crate::errors::{AppError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// 当前模板格式版本，新建和保存的模板都使用此版本
+pub const CURRENT_FORMAT_VERSION: &str = "1.9.0";
+/// 未记录 format_version 的文件按最早的格式处理
+const INITIAL_FORMAT_VERSION: &str = "1.0.0";
+
+/// 一个格式版本的升级步骤，直接作用于原始 JSON
+///
+/// 升级函数只能依赖当时的 JSON 结构，不要引用当前的模板类型。
+struct Migration {
+    from: &'static str,
+    to: &'static str,
+    description: &'static str,
+    upgrade: fn(&mut Map<String, Value>),
+}
+
+/// 按版本顺序排列，修改 `JasperTemplate` 结构时在末尾追加一步并更新 `CURRENT_FORMAT_VERSION`
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: "1.0.0",
+        to: "1.1.0",
+        description: "Add report bands, variable calculations and group paging options",
+        upgrade: add_bands_and_calculations,
+    },
+    Migration {
+        from: "1.1.0",
+        to: "1.2.0",
+        description: "Add locale and null text format settings",
+        upgrade: add_format_settings,
+    },
+    Migration {
+        from: "1.2.0",
+        to: "1.3.0",
+        description: "Add designer-only element properties",
+        upgrade: keep_optional_fields,
+    },
+    Migration {
+        from: "1.3.0",
+        to: "1.4.0",
+        description: "Add subreport elements",
+        upgrade: keep_optional_fields,
+    },
+    Migration {
+        from: "1.4.0",
+        to: "1.5.0",
+        description: "Add table elements",
+        upgrade: keep_optional_fields,
+    },
+    Migration {
+        from: "1.5.0",
+        to: "1.6.0",
+        description: "Add crosstab elements",
+        upgrade: keep_optional_fields,
+    },
+    Migration {
+        from: "1.6.0",
+        to: "1.7.0",
+        description: "Add chart elements",
+        upgrade: keep_optional_fields,
+    },
+    Migration {
+        from: "1.7.0",
+        to: "1.8.0",
+        description: "Add barcode elements and move legacy barcode content into the barcode settings",
+        upgrade: add_barcode_config,
+    },
+    Migration {
+        from: "1.8.0",
+        to: "1.9.0",
+        description: "Add image scale mode and alignment",
+        upgrade: keep_optional_fields,
+    },
+];
+
+/// 加载时执行过的迁移步骤
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedMigration {
+    pub from: String,
+    pub to: String,
+    pub description: String,
+}
+
+/// 将模板 JSON 升级到当前格式版本，返回依次执行的迁移
+///
+/// 比当前版本新的文件无法安全读取，直接报错。
+pub fn migrate(value: &mut Value) -> Result<Vec<AppliedMigration>> {
+    let root = value.as_object_mut().ok_or_else(|| AppError::SerializationError {
+        message: "Template must be a JSON object".to_string(),
+    })?;
+
+    let current = parse_version(CURRENT_FORMAT_VERSION)?;
+    let mut version = root
+        .get("metadata")
+        .and_then(|metadata| metadata.get("format_version"))
+        .and_then(Value::as_str)
+        .unwrap_or(INITIAL_FORMAT_VERSION)
+        .to_string();
+    if parse_version(&version)? > current {
+        return Err(AppError::SerializationError {
+            message: format!(
+                "Template format version {} is newer than the supported version {}, please upgrade the designer",
+                version, CURRENT_FORMAT_VERSION
+            ),
+        });
+    }
+
+    let mut applied = Vec::new();
+    while parse_version(&version)? < current {
+        let parsed = parse_version(&version)?;
+        let step = MIGRATIONS
+            .iter()
+            .find(|step| parse_version(step.from).map_or(false, |from| from == parsed))
+            .ok_or_else(|| AppError::SerializationError {
+                message: format!("No migration from template format version {}", version),
+            })?;
+
+        (step.upgrade)(root);
+        if let Some(Value::Object(metadata)) = root.get_mut("metadata") {
+            metadata.insert("format_version".to_string(), json!(step.to));
+        }
+        applied.push(AppliedMigration {
+            from: step.from.to_string(),
+            to: step.to.to_string(),
+            description: step.description.to_string(),
+        });
+        version = step.to.to_string();
+    }
+
+    Ok(applied)
+}
+
+/// 解析 `主.次.修订` 版本号，省略的部分按 0 处理
+fn parse_version(version: &str) -> Result<(u32, u32, u32)> {
+    let invalid = || AppError::SerializationError {
+        message: format!("Invalid template format version '{}'", version),
+    };
+    let parts = version
+        .trim()
+        .split('.')
+        .map(|part| part.parse::<u32>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>>>()?;
+    match parts.as_slice() {
+        [major] => Ok((*major, 0, 0)),
+        [major, minor] => Ok((*major, *minor, 0)),
+        [major, minor, patch] => Ok((*major, *minor, *patch)),
+        _ => Err(invalid()),
+    }
+}
+
+fn objects_mut<'a>(root: &'a mut Map<String, Value>, key: &str) -> impl Iterator<Item = &'a mut Map<String, Value>> {
+    root.get_mut(key)
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object_mut)
+}
+
+// === 升级步骤 ===
+
+/// 1.0.0 -> 1.1.0：报表区段、变量聚合与重置方式、分组分页选项、区段拆分方式
+fn add_bands_and_calculations(root: &mut Map<String, Value>) {
+    root.entry("bands").or_insert_with(|| json!({}));
+
+    for variable in objects_mut(root, "variables") {
+        variable.entry("calculation").or_insert_with(|| json!("nothing"));
+        variable.entry("reset_type").or_insert_with(|| json!("report"));
+        variable.entry("increment_type").or_insert_with(|| json!("none"));
+    }
+
+    for group in objects_mut(root, "groups") {
+        group.entry("start_on_new_page").or_insert(json!(false));
+        group.entry("keep_together").or_insert(json!(false));
+        for key in ["header", "footer"] {
+            if let Some(Value::Object(band)) = group.get_mut(key) {
+                band.entry("split_type").or_insert_with(|| json!("stretch"));
+            }
+        }
+    }
+}
+
+/// 1.1.0 -> 1.2.0：数据字段格式化的区域和空值文本
+fn add_format_settings(root: &mut Map<String, Value>) {
+    root.entry("formatting").or_insert_with(|| json!({ "locale": "zh-CN", "null_text": "" }));
+}
+
+/// 新增的字段都可以缺省，旧文件原样读取；提升版本只是为了让旧版设计器拒绝打开新文件
+fn keep_optional_fields(_root: &mut Map<String, Value>) {}
+
+/// 1.7.0 -> 1.8.0：条码元素的配置写入 content.barcode，旧文件的文本和表达式按二维码编码
+fn add_barcode_config(root: &mut Map<String, Value>) {
+    for element in objects_mut(root, "elements") {
+        let is_barcode = element
+            .get("element_type")
+            .and_then(|element_type| element_type.get("type"))
+            .and_then(Value::as_str)
+            == Some("Barcode");
+        let Some(Value::Object(content)) = element.get_mut("content") else {
+            continue;
+        };
+        if !is_barcode || content.get("barcode").map_or(false, |barcode| !barcode.is_null()) {
+            continue;
+        }
+
+        let mut barcode = Map::new();
+        barcode.insert("barcode_type".to_string(), json!("QrCode"));
+        barcode.insert("value".to_string(), content.get("text").filter(|text| text.is_string()).cloned().unwrap_or(json!("")));
+        if let Some(expression) = content.get("expression").filter(|expression| expression.is_string()) {
+            barcode.insert("expression".to_string(), expression.clone());
+        }
+        content.insert("barcode".to_string(), Value::Object(barcode));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::BarcodeType;
+    use crate::core::template::{Calculation, JasperTemplate, SplitType};
+
+    /// 每个历史格式版本保存的模板
+    const FIXTURES: &[(&str, &str)] = &[
+        ("1.0.0", include_str!("fixtures/format-1.0.0.jasper")),
+        ("1.1.0", include_str!("fixtures/format-1.1.0.jasper")),
+        ("1.2.0", include_str!("fixtures/format-1.2.0.jasper")),
+        ("1.3.0", include_str!("fixtures/format-1.3.0.jasper")),
+        ("1.4.0", include_str!("fixtures/format-1.4.0.jasper")),
+        ("1.5.0", include_str!("fixtures/format-1.5.0.jasper")),
+        ("1.6.0", include_str!("fixtures/format-1.6.0.jasper")),
+        ("1.7.0", include_str!("fixtures/format-1.7.0.jasper")),
+        ("1.8.0", include_str!("fixtures/format-1.8.0.jasper")),
+        ("1.9.0", include_str!("fixtures/format-1.9.0.jasper")),
+    ];
+
+    fn load(json: &str) -> (JasperTemplate, Vec<AppliedMigration>) {
+        let mut value: Value = serde_json::from_str(json).unwrap();
+        let applied = migrate(&mut value).unwrap();
+        (serde_json::from_value(value).unwrap(), applied)
+    }
+
+    #[test]
+    fn test_every_version_has_fixture() {
+        let versions: Vec<&str> = MIGRATIONS
+            .iter()
+            .map(|step| step.from)
+            .chain(std::iter::once(CURRENT_FORMAT_VERSION))
+            .collect();
+        let fixtures: Vec<&str> = FIXTURES.iter().map(|(version, _)| *version).collect();
+        assert_eq!(versions, fixtures);
+
+        // 迁移链首尾相接
+        for pair in MIGRATIONS.windows(2) {
+            assert_eq!(pair[0].to, pair[1].from);
+        }
+        assert_eq!(MIGRATIONS.last().unwrap().to, CURRENT_FORMAT_VERSION);
+    }
+
+    #[test]
+    fn test_fixtures_upgrade_to_current_version() {
+        for (index, (version, json)) in FIXTURES.iter().enumerate() {
+            let (template, applied) = load(json);
+            assert_eq!(template.metadata.format_version, CURRENT_FORMAT_VERSION, "fixture {}", version);
+            assert_eq!(applied.len(), MIGRATIONS.len() - index, "fixture {}", version);
+            if let Some(first) = applied.first() {
+                assert_eq!(first.from, *version);
+            }
+            template.validate().unwrap();
+
+            assert_eq!(template.metadata.description.as_deref(), Some("Invoice"));
+            assert_eq!(template.elements.len(), 2);
+            assert_eq!(template.variables[0].name, "total");
+            assert_eq!(template.groups[0].header.as_ref().unwrap().split_type, SplitType::Stretch);
+            assert_eq!(template.formatting.locale, "zh-CN");
+        }
+    }
+
+    #[test]
+    fn test_migrated_values() {
+        let (template, applied) = load(FIXTURES[0].1);
+        assert_eq!(applied[0].description, MIGRATIONS[0].description);
+        assert!(template.bands.is_empty());
+        assert_eq!(template.variables[0].calculation, Calculation::Nothing);
+        assert!(!template.groups[0].keep_together);
+        assert_eq!(template.formatting.null_text, "");
+
+        // 迁移不会覆盖已有的值
+        let (template, applied) = load(FIXTURES[1].1);
+        assert_eq!(applied.len(), MIGRATIONS.len() - 1);
+        assert!(template.bands.detail.is_some());
+        assert_eq!(template.variables[0].calculation, Calculation::Sum);
+        assert!(template.groups[0].keep_together);
+
+        let (template, _) = load(FIXTURES[3].1);
+        assert!(template.elements[0].designer.as_ref().unwrap().locked);
+    }
+
+    #[test]
+    fn test_legacy_barcode_config() {
+        let mut value: Value = serde_json::from_str(FIXTURES[7].1).unwrap();
+        value["metadata"]["format_version"] = json!("1.7.0");
+        value["elements"][1]["element_type"] = json!({ "type": "Barcode" });
+        migrate(&mut value).unwrap();
+        let template: JasperTemplate = serde_json::from_value(value).unwrap();
+
+        let barcode = template.elements[1].content.barcode.as_ref().unwrap();
+        assert_eq!(barcode.barcode_type, BarcodeType::QrCode);
+        assert_eq!(barcode.expression.as_deref(), Some("customerName"));
+        assert!(template.elements[0].content.barcode.is_none());
+    }
+
+    #[test]
+    fn test_missing_format_version_is_initial_version() {
+        let mut value: Value = serde_json::from_str(FIXTURES[0].1).unwrap();
+        value["metadata"].as_object_mut().unwrap().remove("format_version");
+        let applied = migrate(&mut value).unwrap();
+        assert_eq!(applied.len(), MIGRATIONS.len());
+        assert_eq!(value["metadata"]["format_version"], json!(CURRENT_FORMAT_VERSION));
+    }
+
+    #[test]
+    fn test_unsupported_versions() {
+        let mut newer: Value = serde_json::from_str(FIXTURES[2].1).unwrap();
+        newer["metadata"]["format_version"] = json!("2.0.0");
+        let error = migrate(&mut newer).unwrap_err().to_string();
+        assert!(error.contains("newer than the supported version"), "{}", error);
+
+        let mut unknown: Value = serde_json::from_str(FIXTURES[0].1).unwrap();
+        unknown["metadata"]["format_version"] = json!("0.9.0");
+        assert!(migrate(&mut unknown).unwrap_err().to_string().contains("No migration"));
+
+        let mut invalid: Value = serde_json::from_str(FIXTURES[0].1).unwrap();
+        invalid["metadata"]["format_version"] = json!("v1");
+        assert!(migrate(&mut invalid).is_err());
+        assert!(migrate(&mut json!([])).is_err());
+    }
+}
@@ -1,6 +1,18 @@
 pub mod element;
+pub mod crosstab;
+pub mod chart;
+pub mod barcode;
+pub mod image_source;
 pub mod canvas;
 pub mod state;
 pub mod history;
 pub mod template;
-pub mod template_service;
\ No newline at end of file
+pub mod template_service;
+pub mod binary;
+pub mod jrxml;
+pub mod migration;
+pub mod package;
+pub mod diff;
+pub mod library;
+pub mod journal;
+pub mod lint;
@@ -0,0 +1,685 @@
+// === Template Package ===
+// zip 容器：manifest.json、template.json、images/、subreports/、fonts/、data/
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use usvg::fontdb;
+use crate::core::binary::to_hex;
+use crate::core::image_source;
+use crate::core::migration::AppliedMigration;
+use crate::core::template::{ElementType, JasperTemplate};
+use crate::core::template_service::{TemplateLoader, TemplateSerializer};
+use crate::errors::{AppError, Result};
+use crate::preview::formats::pdf::FontManager;
+
+pub const PACKAGE_EXTENSION: &str = "jpkg";
+const MANIFEST_PATH: &str = "manifest.json";
+const TEMPLATE_PATH: &str = "template.json";
+/// 2：加入子报表模板
+const PACKAGE_VERSION: u32 = 2;
+/// 子报表嵌套层数上限，防止循环引用
+const MAX_SUBREPORT_DEPTH: usize = 8;
+/// zip 本地文件头，包内第一个文件固定为 manifest.json
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// 包内文件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Template,
+    Image,
+    /// 子报表模板（JSON 格式，资源引用已改写为包内路径）
+    Subreport,
+    Font,
+    SampleData,
+}
+
+/// 清单中的单个文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// 包内路径，如 `images/3f2a9c41d0e7-logo.png`
+    pub path: String,
+    pub kind: AssetKind,
+    pub size: u64,
+    /// 内容的 SHA-256（小写十六进制）
+    pub sha256: String,
+    /// 来源：图片或子报表的原路径、字体族名或数据源ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackageManifest {
+    pub package_version: u32,
+    pub format_version: String,
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// 打包选项
+#[derive(Debug, Clone, Default)]
+pub struct PackageOptions {
+    /// 相对图片和子报表路径的基准目录（通常是模板文件所在目录）
+    pub base_dir: Option<PathBuf>,
+    /// 数据源ID -> 样例数据快照
+    pub sample_data: BTreeMap<String, Value>,
+    /// 查找字体文件的额外目录
+    pub font_dirs: Vec<PathBuf>,
+    /// 是否从系统字体中查找模板使用的字体
+    pub system_fonts: bool,
+}
+
+/// 模板包：模板及其引用的图片、子报表、字体和样例数据
+///
+/// 包内模板的资源引用使用包内路径，`extract_to` 解包时改写为解包目录下的绝对路径。
+#[derive(Debug, Clone)]
+pub struct TemplatePackage {
+    pub template: JasperTemplate,
+    pub manifest: PackageManifest,
+    /// 包内路径 -> 文件内容（不含 manifest.json 和 template.json）
+    pub files: BTreeMap<String, Vec<u8>>,
+    /// 读取时模板执行过的格式迁移
+    pub migrations: Vec<AppliedMigration>,
+}
+
+impl TemplatePackage {
+    /// 收集模板引用的资源并生成模板包
+    pub fn pack(template: &JasperTemplate, options: &PackageOptions) -> Result<Self> {
+        let mut template = template.clone();
+        let mut files = BTreeMap::new();
+        let mut entries = Vec::new();
+
+        // 图片和子报表：读取本地文件并改写为包内路径
+        let mut families = BTreeSet::new();
+        pack_template_assets(&mut template, options.base_dir.as_deref(), 0, &mut files, &mut entries, &mut families)?;
+
+        // 字体：按模板及子报表使用的字体族查找字体文件
+        for (family, font_path) in find_font_files(&families, options) {
+            let data = fs::read(&font_path).map_err(|e| AppError::FileError {
+                message: format!("Failed to read font file '{}': {}", font_path.display(), e),
+            })?;
+            let hash = sha256_hex(&data);
+            let path = format!("fonts/{}-{}", &hash[..12], file_name(&font_path));
+            if files.contains_key(&path) {
+                continue;
+            }
+            entries.push(ManifestEntry {
+                path: path.clone(),
+                kind: AssetKind::Font,
+                size: data.len() as u64,
+                sha256: hash,
+                source: Some(family),
+            });
+            files.insert(path, data);
+        }
+
+        // 样例数据快照
+        for (source_id, data) in &options.sample_data {
+            if !template.data_sources.iter().any(|source| &source.id == source_id) {
+                return Err(AppError::ValidationError {
+                    message: format!("Sample data references unknown data source '{}'", source_id),
+                });
+            }
+            let data = serde_json::to_vec_pretty(data).map_err(|e| AppError::SerializationError {
+                message: format!("Failed to serialize sample data: {}", e),
+            })?;
+            let path = format!("data/{}.json", sanitize_file_name(source_id));
+            entries.push(ManifestEntry {
+                path: path.clone(),
+                kind: AssetKind::SampleData,
+                size: data.len() as u64,
+                sha256: sha256_hex(&data),
+                source: Some(source_id.clone()),
+            });
+            files.insert(path, data);
+        }
+
+        let template_json = TemplateSerializer::new().serialize(&template)?;
+        entries.insert(0, ManifestEntry {
+            path: TEMPLATE_PATH.to_string(),
+            kind: AssetKind::Template,
+            size: template_json.len() as u64,
+            sha256: sha256_hex(template_json.as_bytes()),
+            source: None,
+        });
+
+        let manifest = PackageManifest {
+            package_version: PACKAGE_VERSION,
+            format_version: template.metadata.format_version.clone(),
+            created_at: Utc::now(),
+            created_by: template.metadata.created_by.clone(),
+            entries,
+        };
+
+        Ok(Self { template, manifest, files, migrations: Vec::new() })
+    }
+
+    /// 写出 zip 数据，manifest.json 固定为第一个文件
+    pub fn write(&self) -> Result<Vec<u8>> {
+        // 模板可能在打包后被修改，写出时重新计算其清单项
+        let template_json = TemplateSerializer::new().serialize(&self.template)?;
+        let mut manifest = self.manifest.clone();
+        for entry in manifest.entries.iter_mut().filter(|entry| entry.kind == AssetKind::Template) {
+            entry.size = template_json.len() as u64;
+            entry.sha256 = sha256_hex(template_json.as_bytes());
+        }
+        let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| AppError::SerializationError {
+            message: format!("Failed to serialize package manifest: {}", e),
+        })?;
+
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let entries = [(MANIFEST_PATH, manifest_json.as_slice()), (TEMPLATE_PATH, template_json.as_bytes())]
+            .into_iter()
+            .chain(self.files.iter().map(|(path, data)| (path.as_str(), data.as_slice())));
+        for (path, data) in entries {
+            writer.start_file(path, options).map_err(zip_error)?;
+            writer.write_all(data).map_err(|e| AppError::SerializationError {
+                message: format!("Failed to write package entry '{}': {}", path, e),
+            })?;
+        }
+
+        let cursor = writer.finish().map_err(zip_error)?;
+        Ok(cursor.into_inner())
+    }
+
+    /// 读取 zip 数据，校验清单中每个文件的大小和哈希
+    pub fn read(data: &[u8]) -> Result<Self> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(data)).map_err(zip_error)?;
+
+        let manifest: PackageManifest = serde_json::from_slice(&read_entry(&mut archive, MANIFEST_PATH)?)
+            .map_err(|e| AppError::SerializationError {
+                message: format!("Invalid package manifest: {}", e),
+            })?;
+        if manifest.package_version > PACKAGE_VERSION {
+            return Err(AppError::SerializationError {
+                message: format!(
+                    "Package version {} is newer than the supported version {}",
+                    manifest.package_version, PACKAGE_VERSION
+                ),
+            });
+        }
+
+        let mut template_json = None;
+        let mut files = BTreeMap::new();
+        for entry in &manifest.entries {
+            if !is_safe_path(&entry.path) {
+                return Err(AppError::SerializationError {
+                    message: format!("Invalid package entry path '{}'", entry.path),
+                });
+            }
+            let content = read_entry(&mut archive, &entry.path)?;
+            if content.len() as u64 != entry.size || sha256_hex(&content) != entry.sha256 {
+                return Err(AppError::SerializationError {
+                    message: format!("Package entry '{}' is corrupted: hash mismatch", entry.path),
+                });
+            }
+            match entry.kind {
+                AssetKind::Template => template_json = Some(content),
+                _ => {
+                    files.insert(entry.path.clone(), content);
+                }
+            }
+        }
+
+        let listed: BTreeSet<&str> = manifest.entries.iter().map(|entry| entry.path.as_str()).collect();
+        for name in archive.file_names() {
+            if name != MANIFEST_PATH && !listed.contains(name) {
+                log::warn!("Ignoring package entry '{}' not listed in the manifest", name);
+            }
+        }
+
+        let template_json = template_json.ok_or_else(|| AppError::SerializationError {
+            message: format!("Package does not contain {}", TEMPLATE_PATH),
+        })?;
+        let template_json = String::from_utf8(template_json).map_err(|e| AppError::SerializationError {
+            message: format!("Invalid template encoding in package: {}", e),
+        })?;
+        let loaded = TemplateSerializer::deserialize_migrated(&template_json)?;
+
+        Ok(Self {
+            template: loaded.template,
+            manifest,
+            files,
+            migrations: loaded.migrations,
+        })
+    }
+
+    /// 样例数据快照：数据源ID -> 数据
+    pub fn sample_data(&self) -> Result<BTreeMap<String, Value>> {
+        let mut data = BTreeMap::new();
+        for entry in self.manifest.entries.iter().filter(|entry| entry.kind == AssetKind::SampleData) {
+            let Some(content) = self.files.get(&entry.path) else { continue };
+            let value = serde_json::from_slice(content).map_err(|e| AppError::SerializationError {
+                message: format!("Invalid sample data '{}': {}", entry.path, e),
+            })?;
+            data.insert(entry.source.clone().unwrap_or_else(|| entry.path.clone()), value);
+        }
+        Ok(data)
+    }
+
+    /// 解包到目录，返回资源引用改写为绝对路径的模板
+    ///
+    /// 子报表模板中的引用同样改写，包内字体注册到字体管理器供导出使用。
+    pub fn extract_to(&self, dir: &Path) -> Result<JasperTemplate> {
+        let to_absolute = |template: &mut JasperTemplate| {
+            for_each_asset_ref(template, |_, reference| {
+                if self.files.contains_key(reference.as_str()) {
+                    *reference = dir.join(reference.as_str()).to_string_lossy().into_owned();
+                }
+            });
+        };
+
+        for entry in self.manifest.entries.iter().filter(|entry| entry.kind != AssetKind::Template) {
+            let Some(data) = self.files.get(&entry.path) else { continue };
+            let target = dir.join(&entry.path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| AppError::FileError {
+                    message: format!("Failed to create directory: {}", e),
+                })?;
+            }
+
+            let data = match entry.kind {
+                AssetKind::Subreport => {
+                    let json = String::from_utf8_lossy(data);
+                    let mut subreport = TemplateSerializer::deserialize_migrated(&json)?.template;
+                    to_absolute(&mut subreport);
+                    TemplateSerializer::new().serialize(&subreport)?.into_bytes()
+                }
+                _ => data.clone(),
+            };
+            fs::write(&target, data).map_err(|e| AppError::FileError {
+                message: format!("Failed to extract package entry '{}': {}", entry.path, e),
+            })?;
+
+            if entry.kind == AssetKind::Font {
+                FontManager::register_font_file(&target);
+            }
+        }
+
+        let mut template = self.template.clone();
+        to_absolute(&mut template);
+        Ok(template)
+    }
+
+    /// 解包到临时目录下按内容哈希区分的缓存目录
+    pub fn extract_to_cache(&self, data: &[u8]) -> Result<JasperTemplate> {
+        let hash = sha256_hex(data);
+        let dir = std::env::temp_dir().join("jasper-designer").join("packages").join(&hash[..16]);
+        self.extract_to(&dir)
+    }
+
+    /// 数据是否为模板包
+    pub fn is_package(data: &[u8]) -> bool {
+        // 本地文件头30字节后紧跟文件名
+        data.starts_with(ZIP_MAGIC) && data.get(30..30 + MANIFEST_PATH.len()) == Some(MANIFEST_PATH.as_bytes())
+    }
+}
+
+// === Helpers ===
+
+/// 遍历模板中的资源引用：图片元素路径、元素背景图、画布背景图和子报表模板路径
+fn for_each_asset_ref(template: &mut JasperTemplate, mut visit: impl FnMut(AssetKind, &mut String)) {
+    if let Some(image) = template.canvas.background.image.as_mut() {
+        visit(AssetKind::Image, image);
+    }
+    for element in &mut template.elements {
+        match element.element_type {
+            ElementType::Image => {
+                if let Some(src) = element.content.expression.as_mut() {
+                    visit(AssetKind::Image, src);
+                }
+            }
+            ElementType::Subreport => {
+                if let Some(config) = element.content.subreport.as_mut() {
+                    visit(AssetKind::Subreport, &mut config.template_path);
+                }
+            }
+            _ => {}
+        }
+        if let Some(image) = element.style.background.as_mut().and_then(|background| background.image.as_mut()) {
+            visit(AssetKind::Image, image);
+        }
+    }
+}
+
+/// 收集模板引用的图片和子报表写入包内并改写引用，子报表按其所在目录递归收集
+fn pack_template_assets(
+    template: &mut JasperTemplate,
+    base_dir: Option<&Path>,
+    depth: usize,
+    files: &mut BTreeMap<String, Vec<u8>>,
+    entries: &mut Vec<ManifestEntry>,
+    families: &mut BTreeSet<String>,
+) -> Result<()> {
+    families.extend(font_families(template));
+
+    let mut references = Vec::new();
+    for_each_asset_ref(template, |kind, reference| {
+        if image_source::is_local_path(reference) {
+            references.push((kind, reference.clone()));
+        }
+    });
+
+    let mut packed: BTreeMap<String, String> = BTreeMap::new();
+    for (kind, reference) in references {
+        if packed.contains_key(&reference) {
+            continue;
+        }
+
+        let file_path = resolve(base_dir, &reference);
+        let (path, data) = if kind == AssetKind::Subreport {
+            if depth >= MAX_SUBREPORT_DEPTH {
+                return Err(AppError::ValidationError {
+                    message: format!("Subreports are nested more than {} levels deep", MAX_SUBREPORT_DEPTH),
+                });
+            }
+            let mut subreport = TemplateLoader::load(&file_path.to_string_lossy()).map_err(|e| AppError::FileError {
+                message: format!("Failed to read subreport '{}': {}", file_path.display(), e),
+            })?;
+            pack_template_assets(&mut subreport, file_path.parent(), depth + 1, files, entries, families)?;
+            let data = TemplateSerializer::new().serialize(&subreport)?.into_bytes();
+            let stem = file_path.file_stem().map(|stem| sanitize_file_name(&stem.to_string_lossy())).unwrap_or_default();
+            (format!("subreports/{}-{}.jasper", &sha256_hex(&data)[..12], stem), data)
+        } else {
+            let data = fs::read(&file_path).map_err(|e| AppError::FileError {
+                message: format!("Failed to read image asset '{}': {}", file_path.display(), e),
+            })?;
+            (format!("images/{}-{}", &sha256_hex(&data)[..12], file_name(&file_path)), data)
+        };
+
+        if !files.contains_key(&path) {
+            entries.push(ManifestEntry {
+                path: path.clone(),
+                kind,
+                size: data.len() as u64,
+                sha256: sha256_hex(&data),
+                source: Some(reference.clone()),
+            });
+            files.insert(path.clone(), data);
+        }
+        packed.insert(reference, path);
+    }
+
+    for_each_asset_ref(template, |_, reference| {
+        if let Some(path) = packed.get(reference.as_str()) {
+            *reference = path.clone();
+        }
+    });
+    Ok(())
+}
+
+fn resolve(base_dir: Option<&Path>, reference: &str) -> PathBuf {
+    let path = Path::new(reference.trim());
+    match base_dir {
+        Some(base) if path.is_relative() => base.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| sanitize_file_name(&name.to_string_lossy()))
+        .unwrap_or_else(|| "asset".to_string())
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect()
+}
+
+/// 包内路径只能是相对路径且不能包含 `..`
+fn is_safe_path(path: &str) -> bool {
+    !path.is_empty() && Path::new(path).components().all(|component| matches!(component, Component::Normal(_)))
+}
+
+fn font_families(template: &JasperTemplate) -> BTreeSet<String> {
+    template
+        .elements
+        .iter()
+        .filter_map(|element| element.content.font.as_ref())
+        .map(|font| font.family.clone())
+        .collect()
+}
+
+/// 字体族对应的字体文件（字体族，路径）
+fn find_font_files(families: &BTreeSet<String>, options: &PackageOptions) -> Vec<(String, PathBuf)> {
+    if families.is_empty() || (options.font_dirs.is_empty() && !options.system_fonts) {
+        return Vec::new();
+    }
+
+    let mut db = fontdb::Database::new();
+    if options.system_fonts {
+        db.load_system_fonts();
+    }
+    for dir in &options.font_dirs {
+        db.load_fonts_dir(dir);
+    }
+
+    let mut found = Vec::new();
+    for face in db.faces() {
+        let path = match &face.source {
+            fontdb::Source::File(path) | fontdb::Source::SharedFile(path, _) => path,
+            fontdb::Source::Binary(_) => continue,
+        };
+        if let Some((family, _)) = face.families.iter().find(|(family, _)| families.contains(family)) {
+            found.push((family.clone(), path.clone()));
+        }
+    }
+    found.sort();
+    found.dedup_by(|a, b| a.1 == b.1);
+    found
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, path: &str) -> Result<Vec<u8>> {
+    let mut file = archive.by_name(path).map_err(|e| AppError::SerializationError {
+        message: format!("Package entry '{}' is missing: {}", path, e),
+    })?;
+    let mut content = Vec::new();
+    file.read_to_end(&mut content).map_err(|e| AppError::SerializationError {
+        message: format!("Failed to read package entry '{}': {}", path, e),
+    })?;
+    Ok(content)
+}
+
+fn zip_error(error: zip::result::ZipError) -> AppError {
+    AppError::SerializationError {
+        message: format!("Invalid template package: {}", error),
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    to_hex(&Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{Position, Size, SubreportConfig};
+    use crate::core::template::{
+        DataSchema, DataSource, DataSourceType, FontConfig, FontStyle,
+        FontWeight, TemplateElement,
+    };
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn element(element_type: ElementType, expression: Option<&str>, font: Option<&str>) -> TemplateElement {
+        let mut element = TemplateElement::new(element_type, Position { x: 10.0, y: 10.0 }, Size { width: 100.0, height: 40.0 });
+        element.content.expression = expression.map(str::to_string);
+        element.content.font = font.map(|family| FontConfig {
+            family: family.to_string(),
+            size: 12.0,
+            weight: FontWeight::Normal,
+            style: FontStyle::Normal,
+        });
+        element
+    }
+
+    fn sample_template(dir: &Path) -> JasperTemplate {
+        fs::create_dir_all(dir.join("images")).unwrap();
+        fs::write(dir.join("images/logo.png"), b"\x89PNG logo").unwrap();
+        fs::write(dir.join("paper.png"), b"\x89PNG paper").unwrap();
+
+        let mut template = JasperTemplate::new();
+        template.canvas.background.image = Some(dir.join("paper.png").to_string_lossy().into_owned());
+        template.elements.push(element(ElementType::Image, Some("images/logo.png"), None));
+        template.elements.push(element(ElementType::Image, Some("images/logo.png"), None));
+        template.elements.push(element(ElementType::Image, Some("$F{photo}"), None));
+        template.elements.push(element(ElementType::Text, None, Some("DejaVu Sans")));
+        template.data_sources.push(DataSource {
+            id: "orders".to_string(),
+            name: "Orders".to_string(),
+            source_type: DataSourceType::Json,
+            provider_type: "json".to_string(),
+            config: serde_json::from_value(json!({})).unwrap(),
+            schema: DataSchema {
+                columns: Vec::new(),
+                primary_key: None,
+                indexes: Vec::new(),
+                relationships: Default::default(),
+                metadata: Default::default(),
+            },
+            query: None,
+        });
+        template
+    }
+
+    fn options(dir: &Path) -> PackageOptions {
+        PackageOptions {
+            base_dir: Some(dir.to_path_buf()),
+            sample_data: BTreeMap::from([("orders".to_string(), json!([{ "id": 1, "amount": 9.5 }]))]),
+            font_dirs: vec![PathBuf::from("/usr/share/fonts/truetype/dejavu")],
+            system_fonts: false,
+        }
+    }
+
+    #[test]
+    fn test_pack_write_read_extract() {
+        let dir = tempdir().unwrap();
+        let template = sample_template(dir.path());
+        let package = TemplatePackage::pack(&template, &options(dir.path())).unwrap();
+
+        // 同一图片只打包一次，表达式引用保持不变
+        let images: Vec<&ManifestEntry> =
+            package.manifest.entries.iter().filter(|entry| entry.kind == AssetKind::Image).collect();
+        assert_eq!(images.len(), 2);
+        assert_eq!(package.template.elements[0].content.expression, package.template.elements[1].content.expression);
+        assert!(package.template.elements[0].content.expression.as_deref().unwrap().starts_with("images/"));
+        assert_eq!(package.template.elements[2].content.expression.as_deref(), Some("$F{photo}"));
+        assert_eq!(package.manifest.entries[0].kind, AssetKind::Template);
+
+        let data = package.write().unwrap();
+        assert!(TemplatePackage::is_package(&data));
+
+        let read = TemplatePackage::read(&data).unwrap();
+        assert_eq!(read.manifest, package.manifest);
+        assert_eq!(read.files, package.files);
+        assert!(read.migrations.is_empty());
+        assert_eq!(read.sample_data().unwrap()["orders"], json!([{ "id": 1, "amount": 9.5 }]));
+
+        // 解包后引用指向解包目录中的文件
+        let target = tempdir().unwrap();
+        let extracted = read.extract_to(target.path()).unwrap();
+        let logo = extracted.elements[0].content.expression.clone().unwrap();
+        assert_eq!(fs::read(logo).unwrap(), b"\x89PNG logo");
+        let paper = extracted.canvas.background.image.clone().unwrap();
+        assert_eq!(fs::read(paper).unwrap(), b"\x89PNG paper");
+    }
+
+    #[test]
+    fn test_pack_fonts() {
+        let dir = tempdir().unwrap();
+        let template = sample_template(dir.path());
+        let package = TemplatePackage::pack(&template, &options(dir.path())).unwrap();
+
+        let fonts: Vec<&ManifestEntry> =
+            package.manifest.entries.iter().filter(|entry| entry.kind == AssetKind::Font).collect();
+        if Path::new("/usr/share/fonts/truetype/dejavu").exists() {
+            assert!(!fonts.is_empty());
+            assert!(fonts.iter().all(|entry| entry.source.as_deref() == Some("DejaVu Sans")));
+
+            // 解包后的字体注册到字体管理器
+            let target = tempdir().unwrap();
+            package.extract_to(target.path()).unwrap();
+            assert!(fonts.iter().all(|entry| target.path().join(&entry.path).is_file()));
+            assert!(FontManager::new().has_font("DejaVu Sans"));
+        } else {
+            assert!(fonts.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_pack_subreports() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("sub/stamp.png"), b"\x89PNG stamp").unwrap();
+
+        // 嵌套子报表按各自所在目录解析相对路径
+        let mut detail = JasperTemplate::new();
+        detail.elements.push(element(ElementType::Image, Some("stamp.png"), None));
+        TemplateSerializer::new().save_to_file(&detail, &dir.path().join("sub/detail.jasper").to_string_lossy()).unwrap();
+        let mut items = JasperTemplate::new();
+        let mut nested = element(ElementType::Subreport, None, None);
+        nested.content.subreport = Some(SubreportConfig { template_path: "detail.jasper".to_string(), ..Default::default() });
+        items.elements.push(nested);
+        TemplateSerializer::new().save_to_file(&items, &dir.path().join("sub/items.jasper").to_string_lossy()).unwrap();
+
+        let mut template = JasperTemplate::new();
+        let mut subreport = element(ElementType::Subreport, None, None);
+        subreport.content.subreport = Some(SubreportConfig { template_path: "sub/items.jasper".to_string(), ..Default::default() });
+        template.elements.push(subreport);
+        let package = TemplatePackage::pack(&template, &PackageOptions { base_dir: Some(dir.path().to_path_buf()), ..Default::default() }).unwrap();
+
+        let kinds: Vec<AssetKind> = package.manifest.entries.iter().map(|entry| entry.kind).collect();
+        assert_eq!(kinds.iter().filter(|kind| **kind == AssetKind::Subreport).count(), 2);
+        assert_eq!(kinds.iter().filter(|kind| **kind == AssetKind::Image).count(), 1);
+        let packed_path = &package.template.elements[0].content.subreport.as_ref().unwrap().template_path;
+        assert!(packed_path.starts_with("subreports/"), "{}", packed_path);
+
+        let read = TemplatePackage::read(&package.write().unwrap()).unwrap();
+        let target = tempdir().unwrap();
+        let extracted = read.extract_to(target.path()).unwrap();
+        let items_path = &extracted.elements[0].content.subreport.as_ref().unwrap().template_path;
+        let items = TemplateLoader::load(items_path).unwrap();
+        let detail_path = &items.elements[0].content.subreport.as_ref().unwrap().template_path;
+        let detail = TemplateLoader::load(detail_path).unwrap();
+        let stamp = detail.elements[0].content.expression.clone().unwrap();
+        assert_eq!(fs::read(stamp).unwrap(), b"\x89PNG stamp");
+    }
+
+    #[test]
+    fn test_read_rejects_corrupted_package() {
+        let dir = tempdir().unwrap();
+        let template = sample_template(dir.path());
+        let mut package = TemplatePackage::pack(&template, &options(dir.path())).unwrap();
+        let path = package.files.keys().find(|path| path.starts_with("images/")).unwrap().clone();
+        package.files.insert(path, b"tampered".to_vec());
+
+        let error = TemplatePackage::read(&package.write().unwrap()).unwrap_err().to_string();
+        assert!(error.contains("hash mismatch"), "{}", error);
+        assert!(TemplatePackage::read(b"PK\x03\x04 broken").is_err());
+    }
+
+    #[test]
+    fn test_pack_errors() {
+        let dir = tempdir().unwrap();
+        let mut template = sample_template(dir.path());
+        let mut bad_options = options(dir.path());
+        bad_options.sample_data.insert("missing".to_string(), json!([]));
+        assert!(TemplatePackage::pack(&template, &bad_options).is_err());
+
+        template.elements[0].content.expression = Some("images/missing.png".to_string());
+        assert!(TemplatePackage::pack(&template, &options(dir.path())).is_err());
+
+        assert!(!is_safe_path("../etc/passwd"));
+        assert!(!is_safe_path("/etc/passwd"));
+        assert!(is_safe_path("images/a.png"));
+    }
+}
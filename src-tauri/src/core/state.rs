@@ -2,7 +2,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashSet, HashMap};
 use crate::core::element::{ElementId, ReportElement};
 use crate::core::canvas::CanvasConfig;
+use crate::core::template::{BandConfig, BandType, Canvas, JasperTemplate, ReportBands, TemplateElement};
 use crate::core::history::{History, Operation};
+use crate::core::journal::JournalEntry;
 use crate::errors::{AppError, Result};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +16,14 @@ pub struct AppState {
     pub history: History,
     pub dirty: bool,
     pub template_name: Option<String>,
+    #[serde(default)]
+    pub bands: ReportBands,
+    /// 正在编辑的模板，元素以外的部分（数据源、参数、分组等）保存时原样写回
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<JasperTemplate>,
+    /// 尚未写入自动保存日志的修改
+    #[serde(skip)]
+    journal: Vec<JournalEntry>,
 }
 
 impl Default for AppState {
@@ -32,6 +42,9 @@ impl AppState {
             history: History::new(),
             dirty: false,
             template_name: None,
+            bands: ReportBands::default(),
+            template: None,
+            journal: Vec::new(),
         }
     }
     
@@ -48,7 +61,7 @@ impl AppState {
         };
         
         self.elements.insert(element.id.clone(), element);
-        self.history.push(operation, "Create element".to_string());
+        self.record(operation, "Create element".to_string());
         self.set_dirty(true);
         
         Ok(())
@@ -68,7 +81,7 @@ impl AppState {
         };
         
         self.elements.insert(id.clone(), updated_element);
-        self.history.push(operation, "Update element".to_string());
+        self.record(operation, "Update element".to_string());
         self.set_dirty(true);
         
         Ok(())
@@ -83,7 +96,7 @@ impl AppState {
         let operation = Operation::DeleteElement { element };
         
         self.selected_ids.remove(id);
-        self.history.push(operation, "Delete element".to_string());
+        self.record(operation, "Delete element".to_string());
         self.set_dirty(true);
         
         Ok(())
@@ -205,10 +218,8 @@ impl AppState {
         }
         
         if !operations.is_empty() {
-            self.history.push(
-                Operation::BatchOperation { operations },
-                format!("Paste {} elements", self.clipboard.len()),
-            );
+            let description = format!("Paste {} elements", self.clipboard.len());
+            self.record(Operation::BatchOperation { operations }, description);
             self.set_dirty(true);
         }
         
@@ -219,6 +230,7 @@ impl AppState {
     pub fn undo(&mut self) -> Result<()> {
         let operation = self.history.undo()?;
         self.apply_operation(&operation)?;
+        self.journal.push(JournalEntry::Undo);
         self.set_dirty(true);
         Ok(())
     }
@@ -226,6 +238,7 @@ impl AppState {
     pub fn redo(&mut self) -> Result<()> {
         let operation = self.history.redo()?;
         self.apply_operation(&operation)?;
+        self.journal.push(JournalEntry::Redo);
         self.set_dirty(true);
         Ok(())
     }
@@ -237,13 +250,147 @@ impl AppState {
     pub fn can_redo(&self) -> bool {
         self.history.can_redo()
     }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.journal.push(JournalEntry::ClearHistory);
+        self.set_dirty(true);
+    }
     
     // Canvas operations
     pub fn update_canvas_config(&mut self, config: CanvasConfig) {
+        self.journal.push(JournalEntry::Canvas { config: config.clone() });
         self.canvas = config;
         self.set_dirty(true);
     }
     
+    // Band operations
+    pub fn set_band(&mut self, band_type: BandType, config: Option<BandConfig>) -> Result<()> {
+        if let Some(band) = &config {
+            if band.height < 0.0 {
+                return Err(AppError::ValidationError {
+                    message: format!("Band height must not be negative: {}", band.height),
+                });
+            }
+        } else {
+            // 删除区段时，其中的元素回到画布
+            for element in self.elements.values_mut().filter(|e| e.band == Some(band_type)) {
+                element.band = None;
+            }
+        }
+
+        self.journal.push(JournalEntry::Band { band_type, config: config.clone() });
+        *self.bands.get_mut(band_type) = config;
+        self.set_dirty(true);
+        Ok(())
+    }
+
+    /// 带元素归属的区段定义（elements 列表由元素的 band 字段生成）
+    pub fn bands_with_members(&self) -> ReportBands {
+        let mut bands = self.bands.clone();
+        for band_type in BandType::ALL {
+            if let Some(band) = bands.get_mut(band_type) {
+                band.elements.clear();
+            }
+        }
+
+        let mut members: Vec<&ReportElement> = self.elements.values().filter(|e| e.band.is_some()).collect();
+        members.sort_by_key(|e| e.z_index);
+
+        for element in members {
+            if let Some(Some(band)) = element.band.map(|band_type| bands.get_mut(band_type)) {
+                band.elements.push(element.id.to_string());
+            }
+        }
+        bands
+    }
+
+    // Template editing
+    /// 载入模板供元素命令编辑，元素的区段归属取自模板的区段定义
+    pub fn load_template(&mut self, template: &JasperTemplate) {
+        self.clear();
+        for element in &template.elements {
+            let mut report = ReportElement::from(element);
+            report.band = template.bands.band_of(&element.id);
+            self.elements.insert(report.id.clone(), report);
+        }
+        self.canvas = canvas_config(&template.canvas);
+        self.bands = template.bands.clone();
+        self.template_name = template.metadata.description.clone();
+        self.template = Some(template.clone());
+    }
+
+    /// 把编辑结果写回模板：原有元素和区段成员保持原顺序，新元素按层级追加
+    pub fn to_template(&self) -> JasperTemplate {
+        let mut template = self.template.clone().unwrap_or_default();
+
+        // 画布：只写回编辑过的属性
+        let loaded = canvas_config(&template.canvas);
+        let canvas = &mut template.canvas;
+        if self.canvas.width != loaded.width {
+            canvas.width = self.canvas.width;
+        }
+        if self.canvas.height != loaded.height {
+            canvas.height = self.canvas.height;
+        }
+        if self.canvas.show_grid != loaded.show_grid {
+            canvas.grid.visible = self.canvas.show_grid;
+        }
+        if self.canvas.grid_size != loaded.grid_size {
+            canvas.grid.size = self.canvas.grid_size;
+        }
+        if self.canvas.snap_to_grid != loaded.snap_to_grid {
+            canvas.grid.snap = self.canvas.snap_to_grid;
+        }
+        if self.canvas.background_color != loaded.background_color {
+            canvas.background.color = self.canvas.background_color.clone();
+        }
+
+        // 元素
+        let mut remaining: HashMap<String, &ReportElement> = self.elements
+            .values()
+            .map(|element| (element.id.to_string(), element))
+            .collect();
+        let mut elements: Vec<TemplateElement> = template.elements
+            .iter()
+            .filter_map(|element| remaining.remove(&element.id))
+            .map(TemplateElement::from)
+            .collect();
+        let mut added: Vec<&ReportElement> = remaining.into_values().collect();
+        added.sort_by(|a, b| a.z_index.cmp(&b.z_index).then_with(|| a.id.to_string().cmp(&b.id.to_string())));
+        elements.extend(added.into_iter().map(TemplateElement::from));
+
+        // 区段成员
+        let mut bands = self.bands_with_members();
+        for band_type in BandType::ALL {
+            if let Some(band) = bands.get_mut(band_type) {
+                let mut members: Vec<String> = template.bands
+                    .get(band_type)
+                    .map(|previous| previous.elements.iter().filter(|id| band.elements.contains(id)).cloned().collect())
+                    .unwrap_or_default();
+                for id in &band.elements {
+                    if !members.contains(id) {
+                        members.push(id.clone());
+                    }
+                }
+                band.elements = members;
+            }
+        }
+
+        // 分组区段：去掉已删除或已移入报表区段的元素
+        let element_ids: HashSet<&String> = elements.iter().map(|element| &element.id).collect();
+        let in_report_bands: HashSet<&String> = bands.iter().flat_map(|(_, band)| band.elements.iter()).collect();
+        for group in &mut template.groups {
+            for band in [group.header.as_mut(), group.footer.as_mut()].into_iter().flatten() {
+                band.elements.retain(|id| element_ids.contains(id) && !in_report_bands.contains(id));
+            }
+        }
+
+        template.elements = elements;
+        template.bands = bands;
+        template
+    }
+
     // State management
     pub fn set_dirty(&mut self, dirty: bool) {
         self.dirty = dirty;
@@ -261,9 +408,44 @@ impl AppState {
         self.canvas = CanvasConfig::default();
         self.dirty = false;
         self.template_name = None;
+        self.bands = ReportBands::default();
+        self.template = None;
+        self.journal.clear();
+    }
+
+    // Autosave journal
+    /// 取出上次写入日志之后的修改
+    pub fn take_journal(&mut self) -> Vec<JournalEntry> {
+        std::mem::take(&mut self.journal)
+    }
+
+    /// 在快照上重放日志，恢复崩溃前的编辑状态（含撤销历史）
+    pub fn replay(&mut self, entries: &[JournalEntry]) -> Result<()> {
+        for entry in entries {
+            match entry {
+                JournalEntry::Operation { operation, description } => {
+                    self.apply_operation(operation)?;
+                    self.record(operation.clone(), description.clone());
+                    self.set_dirty(true);
+                }
+                JournalEntry::Undo => self.undo()?,
+                JournalEntry::Redo => self.redo()?,
+                JournalEntry::ClearHistory => self.clear_history(),
+                JournalEntry::Canvas { config } => self.update_canvas_config(config.clone()),
+                JournalEntry::Band { band_type, config } => self.set_band(*band_type, config.clone())?,
+            }
+        }
+        self.journal.clear();
+        Ok(())
     }
     
     // Private helper methods
+    /// 记入撤销历史，同时排入自动保存日志
+    fn record(&mut self, operation: Operation, description: String) {
+        self.journal.push(JournalEntry::Operation { operation: operation.clone(), description: description.clone() });
+        self.history.push(operation, description);
+    }
+
     fn apply_operation(&mut self, operation: &Operation) -> Result<()> {
         match operation {
             Operation::CreateElement { element } => {
@@ -294,6 +476,19 @@ impl AppState {
     }
 }
 
+/// 模板画布对应的设计器画布配置
+fn canvas_config(canvas: &Canvas) -> CanvasConfig {
+    CanvasConfig {
+        width: canvas.width,
+        height: canvas.height,
+        show_grid: canvas.grid.visible,
+        grid_size: canvas.grid.size,
+        snap_to_grid: canvas.grid.snap,
+        background_color: canvas.background.color.clone(),
+        ..CanvasConfig::default()
+    }
+}
+
 // DTO for frontend communication
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AppStateDto {
@@ -306,6 +501,7 @@ pub struct AppStateDto {
     pub redo_description: Option<String>,
     pub dirty: bool,
     pub template_name: Option<String>,
+    pub bands: ReportBands,
 }
 
 impl From<&AppState> for AppStateDto {
@@ -320,6 +516,7 @@ impl From<&AppState> for AppStateDto {
             redo_description: state.history.get_redo_description().map(|s| s.to_string()),
             dirty: state.dirty,
             template_name: state.template_name.clone(),
+            bands: state.bands_with_members(),
         }
     }
 }
\ No newline at end of file
@@ -1,7 +1,8 @@
 // === Jasper Template Serialization Core Types ===
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::core::element::{Position, Size, ReportElement};
+use crate::core::element::{self as el, Position, Size, ReportElement};
+use crate::core::migration::CURRENT_FORMAT_VERSION;
 use crate::errors::{AppError, Result};
 use chrono::{DateTime, Utc};
 
@@ -23,7 +24,7 @@ impl Default for TemplateMetadata {
         let now = Utc::now();
         Self {
             version: "2.0.0".to_string(),
-            format_version: "1.0.0".to_string(),
+            format_version: CURRENT_FORMAT_VERSION.to_string(),
             created_at: now,
             last_modified: now,
             created_by: "jasper-designer-v2".to_string(),
@@ -247,7 +248,7 @@ pub struct QueryParameter {
 }
 
 // === Template Elements ===
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TemplateElement {
     pub id: String,
     pub element_type: ElementType,
@@ -258,9 +259,42 @@ pub struct TemplateElement {
     pub content: ElementContent,
     pub style: ElementStyle,
     pub data_binding: Option<DataBinding>,
+    /// 设计器专有属性，JasperReports 中没有对应概念
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub designer: Option<DesignerProperties>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl TemplateElement {
+    /// 内容和样式为空的可见元素，ID 随机生成
+    pub fn new(element_type: ElementType, position: Position, size: Size) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            element_type,
+            position,
+            size,
+            z_index: 0,
+            visible: true,
+            content: ElementContent::default(),
+            style: ElementStyle::default(),
+            data_binding: None,
+            designer: None,
+        }
+    }
+}
+
+/// 模板字段无法表达、仅供设计器使用的元素属性
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DesignerProperties {
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 设计器元素内容（仅在模板字段不足以还原时保存，如线条端点、圆角、透明度）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<crate::core::element::ElementContent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ElementType {
     Text,
@@ -270,9 +304,12 @@ pub enum ElementType {
     Image,
     Barcode,
     Chart,
+    Subreport,
+    Table,
+    Crosstab,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ElementContent {
     pub text: Option<String>,
     pub expression: Option<String>,
@@ -280,9 +317,27 @@ pub struct ElementContent {
     pub alignment: Option<AlignmentConfig>,
     pub color: Option<String>,
     pub format: Option<String>,
+    /// 子报表配置，仅 Subreport 元素使用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subreport: Option<el::SubreportConfig>,
+    /// 表格配置，仅 Table 元素使用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<el::TableConfig>,
+    /// 交叉表配置，仅 Crosstab 元素使用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crosstab: Option<el::CrosstabConfig>,
+    /// 图表配置，仅 Chart 元素使用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chart: Option<el::ChartConfig>,
+    /// 条码配置，仅 Barcode 元素使用；旧模板没有时按 `expression` 生成二维码
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<el::BarcodeConfig>,
+    /// 图片缩放方式，仅 Image 元素使用；缺省为等比缩放
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale_mode: Option<el::ImageScaleMode>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FontConfig {
     pub family: String,
     pub size: f64,
@@ -301,7 +356,7 @@ impl Default for FontConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FontWeight {
     #[serde(rename = "normal")]
     Normal,
@@ -311,7 +366,7 @@ pub enum FontWeight {
     Light,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FontStyle {
     #[serde(rename = "normal")]
     Normal,
@@ -321,7 +376,7 @@ pub enum FontStyle {
     Oblique,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AlignmentConfig {
     pub horizontal: HorizontalAlignment,
     pub vertical: VerticalAlignment,
@@ -336,7 +391,7 @@ impl Default for AlignmentConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum HorizontalAlignment {
     #[serde(rename = "left")]
     Left,
@@ -348,7 +403,7 @@ pub enum HorizontalAlignment {
     Justify,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum VerticalAlignment {
     #[serde(rename = "top")]
     Top,
@@ -358,27 +413,27 @@ pub enum VerticalAlignment {
     Bottom,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ElementStyle {
     pub background: Option<BackgroundStyle>,
     pub border: Option<BorderStyle>,
     pub padding: Option<PaddingConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BackgroundStyle {
     pub color: Option<String>,
     pub image: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BorderStyle {
     pub width: f64,
     pub color: String,
     pub style: BorderStyleType,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BorderStyleType {
     #[serde(rename = "solid")]
     Solid,
@@ -388,7 +443,7 @@ pub enum BorderStyleType {
     Dotted,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PaddingConfig {
     pub top: f64,
     pub bottom: f64,
@@ -407,14 +462,14 @@ impl Default for PaddingConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DataBinding {
     pub source_id: String,
     pub field_name: String,
     pub validation: Option<ValidationRules>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ValidationRules {
     pub required: bool,
     pub max_length: Option<usize>,
@@ -439,6 +494,78 @@ pub struct Variable {
     pub expression: String,
     pub initial_value: Option<serde_json::Value>,
     pub description: Option<String>,
+    #[serde(default)]
+    pub calculation: Calculation,
+    #[serde(default)]
+    pub reset_type: ResetType,
+    /// `reset_type` 为 Group 时的分组名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reset_group: Option<String>,
+    #[serde(default)]
+    pub increment_type: IncrementType,
+    /// `increment_type` 为 Group 时的分组名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub increment_group: Option<String>,
+}
+
+impl Variable {
+    pub fn new(name: &str, expression: &str, calculation: Calculation) -> Self {
+        Self {
+            name: name.to_string(),
+            var_type: DataType::Number,
+            expression: expression.to_string(),
+            initial_value: None,
+            description: None,
+            calculation,
+            reset_type: ResetType::default(),
+            reset_group: None,
+            increment_type: IncrementType::default(),
+            increment_group: None,
+        }
+    }
+}
+
+/// 变量计算方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Calculation {
+    /// 不聚合，取表达式当前值
+    #[default]
+    Nothing,
+    Count,
+    DistinctCount,
+    Sum,
+    Average,
+    Min,
+    Max,
+    First,
+}
+
+/// 变量重置时机
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResetType {
+    /// 整个报表只在开始时初始化
+    #[default]
+    Report,
+    Page,
+    Column,
+    Group,
+    /// 每条记录都重新初始化
+    None,
+}
+
+/// 变量累加时机
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IncrementType {
+    /// 每条记录累加
+    #[default]
+    None,
+    Report,
+    Page,
+    Column,
+    Group,
 }
 
 // === Groups ===
@@ -449,9 +576,36 @@ pub struct Group {
     pub sort_order: SortOrder,
     pub header: Option<BandConfig>,
     pub footer: Option<BandConfig>,
+    /// 每个分组从新页开始
+    #[serde(default)]
+    pub start_on_new_page: bool,
+    /// 当前页放不下整个分组时移到下一页
+    #[serde(default)]
+    pub keep_together: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Group {
+    pub fn new(name: &str, expression: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            expression: expression.to_string(),
+            sort_order: SortOrder::Ascending,
+            header: None,
+            footer: None,
+            start_on_new_page: false,
+            keep_together: false,
+        }
+    }
+
+    /// 按头、脚顺序遍历分组区段
+    pub fn bands(&self) -> impl Iterator<Item = (&'static str, &BandConfig)> {
+        [("header", self.header.as_ref()), ("footer", self.footer.as_ref())]
+            .into_iter()
+            .filter_map(|(kind, band)| band.map(|band| (kind, band)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SortOrder {
     #[serde(rename = "asc")]
     Ascending,
@@ -463,6 +617,132 @@ pub enum SortOrder {
 pub struct BandConfig {
     pub height: f64,
     pub elements: Vec<String>, // Element IDs
+    #[serde(default)]
+    pub split_type: SplitType,
+}
+
+impl BandConfig {
+    pub fn new(height: f64) -> Self {
+        Self {
+            height,
+            elements: Vec::new(),
+            split_type: SplitType::default(),
+        }
+    }
+}
+
+// === Bands ===
+/// 报表区段类型，元素坐标相对于所在区段的左上角（不含页边距）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BandType {
+    Title,
+    PageHeader,
+    ColumnHeader,
+    Detail,
+    ColumnFooter,
+    PageFooter,
+    Summary,
+}
+
+impl BandType {
+    /// 按输出顺序排列的全部区段类型
+    pub const ALL: [BandType; 7] = [
+        BandType::Title,
+        BandType::PageHeader,
+        BandType::ColumnHeader,
+        BandType::Detail,
+        BandType::ColumnFooter,
+        BandType::PageFooter,
+        BandType::Summary,
+    ];
+}
+
+/// 区段跨页拆分规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitType {
+    /// 放不下时整体移到下一页，超过整页高度时才拆分
+    #[default]
+    Stretch,
+    /// 尽量不拆分，放不下时整体移到下一页
+    Prevent,
+    /// 在当前页剩余空间处立即拆分
+    Immediate,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportBands {
+    pub title: Option<BandConfig>,
+    pub page_header: Option<BandConfig>,
+    pub column_header: Option<BandConfig>,
+    pub detail: Option<BandConfig>,
+    pub column_footer: Option<BandConfig>,
+    pub page_footer: Option<BandConfig>,
+    pub summary: Option<BandConfig>,
+}
+
+impl ReportBands {
+    pub fn get(&self, band_type: BandType) -> Option<&BandConfig> {
+        match band_type {
+            BandType::Title => self.title.as_ref(),
+            BandType::PageHeader => self.page_header.as_ref(),
+            BandType::ColumnHeader => self.column_header.as_ref(),
+            BandType::Detail => self.detail.as_ref(),
+            BandType::ColumnFooter => self.column_footer.as_ref(),
+            BandType::PageFooter => self.page_footer.as_ref(),
+            BandType::Summary => self.summary.as_ref(),
+        }
+    }
+
+    pub fn get_mut(&mut self, band_type: BandType) -> &mut Option<BandConfig> {
+        match band_type {
+            BandType::Title => &mut self.title,
+            BandType::PageHeader => &mut self.page_header,
+            BandType::ColumnHeader => &mut self.column_header,
+            BandType::Detail => &mut self.detail,
+            BandType::ColumnFooter => &mut self.column_footer,
+            BandType::PageFooter => &mut self.page_footer,
+            BandType::Summary => &mut self.summary,
+        }
+    }
+
+    /// 按输出顺序遍历已定义的区段
+    pub fn iter(&self) -> impl Iterator<Item = (BandType, &BandConfig)> {
+        BandType::ALL
+            .into_iter()
+            .filter_map(move |band_type| self.get(band_type).map(|band| (band_type, band)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// 查找元素所在区段
+    pub fn band_of(&self, element_id: &str) -> Option<BandType> {
+        self.iter()
+            .find(|(_, band)| band.elements.iter().any(|id| id == element_id))
+            .map(|(band_type, _)| band_type)
+    }
+}
+
+/// 数据字段格式化设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatSettings {
+    /// 区域标签（zh-CN / en-US ...），决定分组符号、货币符号和月份名称
+    pub locale: String,
+    /// 空值和空白字符串显示的文本
+    #[serde(default)]
+    pub null_text: String,
+}
+
+impl Default for FormatSettings {
+    fn default() -> Self {
+        Self {
+            locale: "zh-CN".to_string(),
+            null_text: String::new(),
+        }
+    }
 }
 
 // === Main Template Structure ===
@@ -475,6 +755,10 @@ pub struct JasperTemplate {
     pub parameters: Vec<Parameter>,
     pub variables: Vec<Variable>,
     pub groups: Vec<Group>,
+    #[serde(default)]
+    pub bands: ReportBands,
+    #[serde(default)]
+    pub formatting: FormatSettings,
 }
 
 impl JasperTemplate {
@@ -487,6 +771,8 @@ impl JasperTemplate {
             parameters: Vec::new(),
             variables: Vec::new(),
             groups: Vec::new(),
+            bands: ReportBands::default(),
+            formatting: FormatSettings::default(),
         }
     }
     
@@ -518,6 +804,50 @@ impl JasperTemplate {
             }
         }
         
+        // Validate bands (including group header/footer bands)
+        let mut band_members = std::collections::HashSet::new();
+        let report_bands = self.bands.iter().map(|(band_type, band)| (format!("{:?}", band_type), band));
+        let group_bands = self.groups.iter().flat_map(|group| {
+            group.bands().map(move |(kind, band)| (format!("group '{}' {}", group.name, kind), band))
+        });
+        for (band_name, band) in report_bands.chain(group_bands) {
+            if band.height < 0.0 {
+                return Err(AppError::ValidationError {
+                    message: format!("Band {} height must not be negative", band_name),
+                });
+            }
+            for element_id in &band.elements {
+                let element = self.get_element_by_id(element_id).ok_or_else(|| AppError::ValidationError {
+                    message: format!("Element '{}' referenced by band {} not found", element_id, band_name),
+                })?;
+                if !band_members.insert(element_id) {
+                    return Err(AppError::ValidationError {
+                        message: format!("Element '{}' is assigned to more than one band", element_id),
+                    });
+                }
+                if element.position.y + element.size.height > band.height {
+                    return Err(AppError::ValidationError {
+                        message: format!("Element '{}' exceeds the height of band {}", element_id, band_name),
+                    });
+                }
+            }
+        }
+
+        // Validate groups
+        let mut group_names = std::collections::HashSet::new();
+        for group in &self.groups {
+            if !group_names.insert(&group.name) {
+                return Err(AppError::ValidationError {
+                    message: format!("Duplicate group name: {}", group.name),
+                });
+            }
+            if group.expression.trim().is_empty() {
+                return Err(AppError::ValidationError {
+                    message: format!("Group '{}' has an empty expression", group.name),
+                });
+            }
+        }
+
         // Validate parameter names are unique
         let mut param_names = std::collections::HashSet::new();
         for param in &self.parameters {
@@ -536,6 +866,25 @@ impl JasperTemplate {
                     message: format!("Duplicate variable name: {}", var.name),
                 });
             }
+
+            // Validate group references of group scoped variables
+            let group_refs = [
+                (var.reset_type == ResetType::Group, &var.reset_group, "reset"),
+                (var.increment_type == IncrementType::Group, &var.increment_group, "increment"),
+            ];
+            for (uses_group, group_name, kind) in group_refs {
+                if !uses_group {
+                    continue;
+                }
+                let group_exists = group_name
+                    .as_ref()
+                    .map_or(false, |name| group_names.contains(name));
+                if !group_exists {
+                    return Err(AppError::ValidationError {
+                        message: format!("Variable '{}' has no valid {} group", var.name, kind),
+                    });
+                }
+            }
         }
         
         Ok(())
@@ -604,119 +953,1015 @@ impl Default for JasperTemplate {
 }
 
 // === Conversion utilities ===
-impl From<&ReportElement> for TemplateElement {
-    fn from(element: &ReportElement) -> Self {
-        let (element_type, content) = match &element.content {
-            crate::core::element::ElementContent::Text { content, style } => {
-                (ElementType::Text, ElementContent {
-                    text: Some(content.clone()),
-                    expression: None,
-                    font: Some(FontConfig {
-                        family: style.font_family.clone(),
-                        size: style.font_size,
-                        weight: match style.font_weight.as_str() {
-                            "bold" => FontWeight::Bold,
-                            "light" => FontWeight::Light,
-                            _ => FontWeight::Normal,
-                        },
-                        style: FontStyle::Normal,
-                    }),
-                    alignment: Some(match style.align {
-                        crate::core::element::TextAlign::Left => AlignmentConfig {
-                            horizontal: HorizontalAlignment::Left,
-                            vertical: VerticalAlignment::Middle,
-                        },
-                        crate::core::element::TextAlign::Center => AlignmentConfig {
-                            horizontal: HorizontalAlignment::Center,
-                            vertical: VerticalAlignment::Middle,
-                        },
-                        crate::core::element::TextAlign::Right => AlignmentConfig {
-                            horizontal: HorizontalAlignment::Right,
-                            vertical: VerticalAlignment::Middle,
-                        },
-                    }),
-                    color: Some(style.color.clone()),
-                    format: None,
-                })
+// 设计器元素与模板元素之间的转换是无损的：模板独有的属性由 ReportElement::origin 保留，
+// 设计器独有的属性写入 TemplateElement::designer。
+
+fn font_config(style: &el::TextStyle, font_style: FontStyle) -> FontConfig {
+    FontConfig {
+        family: style.font_family.clone(),
+        size: style.font_size,
+        weight: match style.font_weight.as_str() {
+            "bold" => FontWeight::Bold,
+            "light" => FontWeight::Light,
+            _ => FontWeight::Normal,
+        },
+        style: font_style,
+    }
+}
+
+fn horizontal_alignment(align: &el::TextAlign) -> HorizontalAlignment {
+    match align {
+        el::TextAlign::Left => HorizontalAlignment::Left,
+        el::TextAlign::Center => HorizontalAlignment::Center,
+        el::TextAlign::Right => HorizontalAlignment::Right,
+    }
+}
+
+/// 图片对齐保存为模板的对齐配置，左上对齐（默认）时不写入
+fn image_alignment(horizontal: el::ImageAlign, vertical: el::ImageAlign) -> Option<AlignmentConfig> {
+    if horizontal == el::ImageAlign::Start && vertical == el::ImageAlign::Start {
+        return None;
+    }
+    Some(AlignmentConfig {
+        horizontal: match horizontal {
+            el::ImageAlign::Start => HorizontalAlignment::Left,
+            el::ImageAlign::Center => HorizontalAlignment::Center,
+            el::ImageAlign::End => HorizontalAlignment::Right,
+        },
+        vertical: match vertical {
+            el::ImageAlign::Start => VerticalAlignment::Top,
+            el::ImageAlign::Center => VerticalAlignment::Middle,
+            el::ImageAlign::End => VerticalAlignment::Bottom,
+        },
+    })
+}
+
+fn template_border_type(style: &el::BorderStyleType) -> BorderStyleType {
+    match style {
+        el::BorderStyleType::Solid => BorderStyleType::Solid,
+        el::BorderStyleType::Dashed => BorderStyleType::Dashed,
+        el::BorderStyleType::Dotted => BorderStyleType::Dotted,
+    }
+}
+
+fn report_border_type(style: &BorderStyleType) -> el::BorderStyleType {
+    match style {
+        BorderStyleType::Solid => el::BorderStyleType::Solid,
+        BorderStyleType::Dashed => el::BorderStyleType::Dashed,
+        BorderStyleType::Dotted => el::BorderStyleType::Dotted,
+    }
+}
+
+/// 文本样式中的边框、背景对应的模板样式
+fn text_box_style(style: &el::TextStyle) -> ElementStyle {
+    ElementStyle {
+        background: style.background.as_ref().map(|background| BackgroundStyle {
+            color: Some(background.color.clone()),
+            image: None,
+        }),
+        border: style.border.as_ref().map(|border| BorderStyle {
+            width: border.width,
+            color: border.color.clone(),
+            style: template_border_type(&border.style),
+        }),
+        padding: style.background.as_ref().and_then(|background| background.padding).map(|padding| PaddingConfig {
+            top: padding,
+            bottom: padding,
+            left: padding,
+            right: padding,
+        }),
+    }
+}
+
+/// 由设计器元素内容生成模板的类型、内容与样式
+fn template_content(content: &el::ElementContent) -> (ElementType, ElementContent, ElementStyle) {
+    let empty = ElementContent::default();
+    let no_style = ElementStyle::default();
+
+    match content {
+        el::ElementContent::Text { content, style } => (
+            ElementType::Text,
+            ElementContent {
+                text: Some(content.clone()),
+                font: Some(font_config(style, FontStyle::Normal)),
+                alignment: Some(AlignmentConfig {
+                    horizontal: horizontal_alignment(&style.align),
+                    vertical: VerticalAlignment::Middle,
+                }),
+                color: Some(style.color.clone()),
+                ..empty
+            },
+            text_box_style(style),
+        ),
+        el::ElementContent::DataField { expression, format, style } => (
+            ElementType::DataField,
+            ElementContent {
+                expression: Some(expression.clone()),
+                font: Some(font_config(style, FontStyle::Normal)),
+                alignment: Some(AlignmentConfig {
+                    horizontal: horizontal_alignment(&style.align),
+                    vertical: VerticalAlignment::Middle,
+                }),
+                color: Some(style.color.clone()),
+                format: format.clone(),
+                ..empty
+            },
+            text_box_style(style),
+        ),
+        el::ElementContent::Rectangle { fill_color, border, .. } => (
+            ElementType::Rectangle,
+            ElementContent {
+                color: fill_color.clone(),
+                ..empty
+            },
+            ElementStyle {
+                border: border.as_ref().map(|border| BorderStyle {
+                    width: border.width,
+                    color: border.color.clone(),
+                    style: template_border_type(&border.style),
+                }),
+                ..no_style
+            },
+        ),
+        el::ElementContent::Line { color, width, line_style, .. } => (
+            ElementType::Line,
+            ElementContent {
+                color: Some(color.clone()),
+                ..empty
+            },
+            ElementStyle {
+                border: Some(BorderStyle {
+                    width: *width,
+                    color: color.clone(),
+                    style: match line_style {
+                        Some(el::LineStyleType::Dashed) => BorderStyleType::Dashed,
+                        Some(el::LineStyleType::Dotted) => BorderStyleType::Dotted,
+                        _ => BorderStyleType::Solid,
+                    },
+                }),
+                ..no_style
+            },
+        ),
+        el::ElementContent::Image { src, alt, scale_mode, horizontal_align, vertical_align } => (
+            ElementType::Image,
+            ElementContent {
+                text: alt.clone(),
+                expression: Some(src.clone()),
+                alignment: image_alignment(*horizontal_align, *vertical_align),
+                scale_mode: (*scale_mode != el::ImageScaleMode::default()).then_some(*scale_mode),
+                ..empty
+            },
+            no_style,
+        ),
+        el::ElementContent::Subreport(config) => (
+            ElementType::Subreport,
+            ElementContent {
+                subreport: Some(config.clone()),
+                ..empty
+            },
+            no_style,
+        ),
+        el::ElementContent::Table(config) => (
+            ElementType::Table,
+            ElementContent {
+                table: Some(config.clone()),
+                ..empty
+            },
+            no_style,
+        ),
+        el::ElementContent::Crosstab(config) => (
+            ElementType::Crosstab,
+            ElementContent {
+                crosstab: Some(config.clone()),
+                ..empty
+            },
+            no_style,
+        ),
+        el::ElementContent::Chart(config) => (
+            ElementType::Chart,
+            ElementContent {
+                chart: Some(config.clone()),
+                ..empty
+            },
+            no_style,
+        ),
+        el::ElementContent::Barcode(config) => (
+            ElementType::Barcode,
+            ElementContent {
+                barcode: Some(config.clone()),
+                ..empty
+            },
+            no_style,
+        ),
+    }
+}
+
+/// 仅由模板字段生成设计器元素内容（不考虑 designer 中保存的内容）
+fn report_content(element: &TemplateElement) -> el::ElementContent {
+    let font = element.content.font.clone().unwrap_or_default();
+    let text_style = el::TextStyle {
+        font_family: font.family,
+        font_size: font.size,
+        font_weight: match font.weight {
+            FontWeight::Bold => "bold",
+            FontWeight::Light => "light",
+            FontWeight::Normal => "normal",
+        }.to_string(),
+        color: element.content.color.clone().unwrap_or_else(|| "#000000".to_string()),
+        align: match element.content.alignment.as_ref().map(|a| &a.horizontal) {
+            Some(HorizontalAlignment::Center) => el::TextAlign::Center,
+            Some(HorizontalAlignment::Right) => el::TextAlign::Right,
+            _ => el::TextAlign::Left,
+        },
+        border: element.style.border.as_ref().map(|border| el::TextBorderStyle {
+            color: border.color.clone(),
+            width: border.width,
+            style: report_border_type(&border.style),
+            radius: None,
+        }),
+        background: element.style.background.as_ref()
+            .and_then(|background| background.color.clone())
+            .map(|color| el::TextBackgroundStyle {
+                color,
+                opacity: None,
+                padding: element.style.padding.as_ref().map(|padding| padding.top),
+            }),
+    };
+    let border = element.style.border.as_ref().map(|border| el::BorderStyle {
+        color: border.color.clone(),
+        width: border.width,
+        style: report_border_type(&border.style),
+    });
+
+    match element.element_type {
+        ElementType::DataField => el::ElementContent::DataField {
+            expression: element.content.expression.clone().unwrap_or_default(),
+            format: element.content.format.clone(),
+            style: text_style,
+        },
+        ElementType::Rectangle => el::ElementContent::Rectangle {
+            fill_color: element.content.color.clone()
+                .or_else(|| element.style.background.as_ref().and_then(|bg| bg.color.clone())),
+            border,
+            corner_radius: None,
+            opacity: None,
+        },
+        ElementType::Line => el::ElementContent::Line {
+            color: element.content.color.clone().unwrap_or_else(|| "#000000".to_string()),
+            width: border.as_ref().map(|b| b.width).unwrap_or(1.0),
+            line_style: match border.map(|b| b.style) {
+                Some(el::BorderStyleType::Dashed) => Some(el::LineStyleType::Dashed),
+                Some(el::BorderStyleType::Dotted) => Some(el::LineStyleType::Dotted),
+                _ => None,
+            },
+            start_cap: None,
+            end_cap: None,
+            opacity: None,
+        },
+        ElementType::Image => {
+            let alignment = element.content.alignment.as_ref();
+            el::ElementContent::Image {
+                src: element.content.expression.clone().unwrap_or_default(),
+                alt: element.content.text.clone(),
+                scale_mode: element.content.scale_mode.unwrap_or_default(),
+                horizontal_align: match alignment.map(|a| &a.horizontal) {
+                    Some(HorizontalAlignment::Center) => el::ImageAlign::Center,
+                    Some(HorizontalAlignment::Right) => el::ImageAlign::End,
+                    _ => el::ImageAlign::Start,
+                },
+                vertical_align: match alignment.map(|a| &a.vertical) {
+                    Some(VerticalAlignment::Middle) => el::ImageAlign::Center,
+                    Some(VerticalAlignment::Bottom) => el::ImageAlign::End,
+                    _ => el::ImageAlign::Start,
+                },
             }
-            crate::core::element::ElementContent::DataField { expression, format, style } => {
-                (ElementType::DataField, ElementContent {
-                    text: None,
-                    expression: Some(expression.clone()),
-                    font: Some(FontConfig {
-                        family: style.font_family.clone(),
-                        size: style.font_size,
-                        weight: match style.font_weight.as_str() {
-                            "bold" => FontWeight::Bold,
-                            "light" => FontWeight::Light,
-                            _ => FontWeight::Normal,
-                        },
-                        style: FontStyle::Normal,
-                    }),
-                    alignment: Some(match style.align {
-                        crate::core::element::TextAlign::Left => AlignmentConfig {
-                            horizontal: HorizontalAlignment::Left,
-                            vertical: VerticalAlignment::Middle,
-                        },
-                        crate::core::element::TextAlign::Center => AlignmentConfig {
-                            horizontal: HorizontalAlignment::Center,
-                            vertical: VerticalAlignment::Middle,
-                        },
-                        crate::core::element::TextAlign::Right => AlignmentConfig {
-                            horizontal: HorizontalAlignment::Right,
-                            vertical: VerticalAlignment::Middle,
-                        },
-                    }),
-                    color: Some(style.color.clone()),
-                    format: format.clone(),
-                })
+        }
+        ElementType::Subreport => el::ElementContent::Subreport(element.content.subreport.clone().unwrap_or_default()),
+        ElementType::Table => el::ElementContent::Table(element.content.table.clone().unwrap_or_default()),
+        ElementType::Crosstab => el::ElementContent::Crosstab(element.content.crosstab.clone().unwrap_or_default()),
+        ElementType::Chart => el::ElementContent::Chart(element.content.chart.clone().unwrap_or_default()),
+        ElementType::Barcode => el::ElementContent::Barcode(element.content.barcode.clone().unwrap_or_else(|| {
+            el::BarcodeConfig {
+                value: element.content.text.clone().unwrap_or_default(),
+                expression: element.content.expression.clone(),
+                ..el::BarcodeConfig::default()
             }
-            crate::core::element::ElementContent::Rectangle { fill_color, .. } => {
-                (ElementType::Rectangle, ElementContent {
-                    text: None,
-                    expression: None,
-                    font: None,
-                    alignment: None,
-                    color: fill_color.clone(),
-                    format: None,
-                })
+        })),
+        ElementType::Text => el::ElementContent::Text {
+            content: element.content.text.clone()
+                .or_else(|| element.content.expression.clone())
+                .unwrap_or_default(),
+            style: text_style,
+        },
+    }
+}
+
+/// 把设计器元素内容的改动写回模板元素，未改动的属性（含模板独有的部分）保持原样
+fn apply_content(element: &mut TemplateElement, content: &el::ElementContent) {
+    let current = report_content(element);
+    let (element_type, derived, derived_style) = template_content(content);
+    if std::mem::discriminant(&current) != std::mem::discriminant(content) {
+        element.element_type = element_type;
+        element.content = derived;
+        element.style = derived_style;
+        return;
+    }
+
+    match (content, &current) {
+        (el::ElementContent::Text { content: text, style }, el::ElementContent::Text { content: old_text, style: old_style }) => {
+            if text != old_text {
+                // 只有表达式的文本元素改写表达式
+                if element.content.text.is_none() && element.content.expression.is_some() {
+                    element.content.expression = Some(text.clone());
+                } else {
+                    element.content.text = Some(text.clone());
+                }
             }
-            crate::core::element::ElementContent::Line { color, .. } => {
-                (ElementType::Line, ElementContent {
-                    text: None,
-                    expression: None,
-                    font: None,
-                    alignment: None,
-                    color: Some(color.clone()),
-                    format: None,
-                })
+            apply_text_style(element, style, old_style, derived, derived_style);
+        }
+        (
+            el::ElementContent::DataField { expression, format, style },
+            el::ElementContent::DataField { expression: old_expression, format: old_format, style: old_style },
+        ) => {
+            if expression != old_expression {
+                element.content.expression = Some(expression.clone());
             }
-            crate::core::element::ElementContent::Image { src, alt } => {
-                (ElementType::Image, ElementContent {
-                    text: alt.clone(),
-                    expression: Some(src.clone()),
-                    font: None,
-                    alignment: None,
-                    color: None,
-                    format: None,
-                })
+            if format != old_format {
+                element.content.format = format.clone();
+            }
+            apply_text_style(element, style, old_style, derived, derived_style);
+        }
+        (
+            el::ElementContent::Rectangle { fill_color, border, .. },
+            el::ElementContent::Rectangle { fill_color: old_fill_color, border: old_border, .. },
+        ) => {
+            if fill_color != old_fill_color {
+                element.content.color = derived.color;
+                if let Some(background) = element.style.background.as_mut() {
+                    background.color = None;
+                }
+            }
+            if border != old_border {
+                element.style.border = derived_style.border;
+            }
+        }
+        (
+            el::ElementContent::Line { color, width, line_style, .. },
+            el::ElementContent::Line { color: old_color, width: old_width, line_style: old_line_style, .. },
+        ) => {
+            if color != old_color {
+                element.content.color = derived.color;
+            }
+            if color != old_color || width != old_width || line_style != old_line_style {
+                element.style.border = derived_style.border;
+            }
+        }
+        (
+            el::ElementContent::Image { src, alt, scale_mode, horizontal_align, vertical_align },
+            el::ElementContent::Image {
+                src: old_src,
+                alt: old_alt,
+                scale_mode: old_scale_mode,
+                horizontal_align: old_horizontal_align,
+                vertical_align: old_vertical_align,
+            },
+        ) => {
+            if src != old_src {
+                element.content.expression = derived.expression;
+            }
+            if alt != old_alt {
+                element.content.text = derived.text;
+            }
+            if scale_mode != old_scale_mode {
+                element.content.scale_mode = derived.scale_mode;
+            }
+            if horizontal_align != old_horizontal_align || vertical_align != old_vertical_align {
+                element.content.alignment = derived.alignment;
+            }
+        }
+        (el::ElementContent::Subreport(config), el::ElementContent::Subreport(old_config)) => {
+            if config != old_config {
+                element.content.subreport = derived.subreport;
+            }
+        }
+        (el::ElementContent::Table(config), el::ElementContent::Table(old_config)) => {
+            if config != old_config {
+                element.content.table = derived.table;
+            }
+        }
+        (el::ElementContent::Crosstab(config), el::ElementContent::Crosstab(old_config)) => {
+            if config != old_config {
+                element.content.crosstab = derived.crosstab;
+            }
+        }
+        (el::ElementContent::Chart(config), el::ElementContent::Chart(old_config)) => {
+            if config != old_config {
+                element.content.chart = derived.chart;
+            }
+        }
+        (el::ElementContent::Barcode(config), el::ElementContent::Barcode(old_config)) => {
+            if config != old_config {
+                element.content.barcode = derived.barcode;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn apply_text_style(
+    element: &mut TemplateElement,
+    style: &el::TextStyle,
+    old_style: &el::TextStyle,
+    derived: ElementContent,
+    derived_style: ElementStyle,
+) {
+    if style.font_family != old_style.font_family
+        || style.font_size != old_style.font_size
+        || style.font_weight != old_style.font_weight
+    {
+        // 保留斜体等设计器无法编辑的字体属性
+        let font_style = element.content.font.as_ref().map_or(FontStyle::Normal, |font| font.style.clone());
+        element.content.font = Some(font_config(style, font_style));
+    }
+    if style.align != old_style.align {
+        let vertical = element.content.alignment.as_ref().map_or(VerticalAlignment::Middle, |a| a.vertical.clone());
+        element.content.alignment = Some(AlignmentConfig {
+            horizontal: horizontal_alignment(&style.align),
+            vertical,
+        });
+    }
+    if style.color != old_style.color {
+        element.content.color = derived.color;
+    }
+    if style.border != old_style.border {
+        element.style.border = derived_style.border;
+    }
+    if style.background != old_style.background {
+        element.style.background = derived_style.background;
+        element.style.padding = derived_style.padding;
+    }
+}
+
+impl From<&ReportElement> for TemplateElement {
+    fn from(element: &ReportElement) -> Self {
+        let mut template = match element.origin.as_deref() {
+            Some(origin) => {
+                let mut template = origin.clone();
+                apply_content(&mut template, &element.content);
+                template
+            }
+            None => {
+                let (element_type, content, style) = template_content(&element.content);
+                Self {
+                    id: String::new(),
+                    element_type,
+                    position: element.position.clone(),
+                    size: element.size.clone(),
+                    z_index: element.z_index,
+                    visible: element.visible,
+                    content,
+                    style,
+                    data_binding: None,
+                    designer: None,
+                }
             }
         };
-        
-        Self {
-            id: element.id.to_string(),
-            element_type,
+        template.id = element.id.to_string();
+        template.position = element.position.clone();
+        template.size = element.size.clone();
+        template.z_index = element.z_index;
+        template.visible = element.visible;
+
+        // 模板字段还原不出的内容原样保存
+        let designer = DesignerProperties {
+            locked: element.locked,
+            name: element.name.clone(),
+            content: (report_content(&template) != element.content).then(|| element.content.clone()),
+        };
+        template.designer = (designer != DesignerProperties::default()).then_some(designer);
+        template
+    }
+}
+
+impl From<&TemplateElement> for ReportElement {
+    fn from(element: &TemplateElement) -> Self {
+        let designer = element.designer.clone().unwrap_or_default();
+        // 模板字段在别处被修改后，designer 中保存的内容已过期，改用模板字段
+        let content = match designer.content {
+            Some(content) if {
+                let mut applied = element.clone();
+                apply_content(&mut applied, &content);
+                applied == *element
+            } => content,
+            _ => report_content(element),
+        };
+
+        let mut report = Self {
+            id: el::ElementId::from_string(&element.id).unwrap_or_default(),
             position: element.position.clone(),
             size: element.size.clone(),
+            content,
             z_index: element.z_index,
             visible: element.visible,
+            locked: designer.locked,
+            name: designer.name,
+            band: None,
+            origin: None,
+        };
+        // 含有设计器无法表达的模板属性时保留原始元素
+        if TemplateElement::from(&report) != *element {
+            report.origin = Some(Box::new(element.clone()));
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::state::AppState;
+    use crate::data::types::AggregateFunction;
+
+    /// 确定性的伪随机数，用于生成往返测试样本
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+
+        fn chance(&mut self) -> bool {
+            self.below(2) == 0
+        }
+
+        fn number(&mut self) -> f64 {
+            self.below(2000) as f64 / 8.0
+        }
+
+        fn text(&mut self) -> String {
+            ["", "金额", "$F{amount}", "Total: ", "#ff0000", "600"][self.below(6) as usize].to_string()
+        }
+
+        fn maybe<T>(&mut self, value: impl FnOnce(&mut Self) -> T) -> Option<T> {
+            if self.chance() { Some(value(self)) } else { None }
+        }
+
+        fn pick<T: Clone>(&mut self, values: &[T]) -> T {
+            values[self.below(values.len() as u64) as usize].clone()
+        }
+    }
+
+    fn text_style(rng: &mut Rng) -> el::TextStyle {
+        el::TextStyle {
+            font_family: rng.pick(&["Arial", "SimSun"]).to_string(),
+            font_size: rng.number(),
+            font_weight: rng.pick(&["normal", "bold", "light", "600"]).to_string(),
+            color: rng.pick(&["#000000", "#336699"]).to_string(),
+            align: rng.pick(&[el::TextAlign::Left, el::TextAlign::Center, el::TextAlign::Right]),
+            border: rng.maybe(|rng| el::TextBorderStyle {
+                color: rng.text(),
+                width: rng.number(),
+                style: rng.pick(&[el::BorderStyleType::Solid, el::BorderStyleType::Dashed, el::BorderStyleType::Dotted]),
+                radius: rng.maybe(Rng::number),
+            }),
+            background: rng.maybe(|rng| el::TextBackgroundStyle {
+                color: rng.text(),
+                opacity: rng.maybe(Rng::number),
+                padding: rng.maybe(Rng::number),
+            }),
+        }
+    }
+
+    fn subreport_config(rng: &mut Rng) -> el::SubreportConfig {
+        el::SubreportConfig {
+            template_path: rng.text(),
+            parameters: (0..rng.below(3))
+                .map(|_| el::SubreportParameter { name: rng.text(), expression: rng.text() })
+                .collect(),
+            data_expression: rng.maybe(Rng::text),
+        }
+    }
+
+    fn table_config(rng: &mut Rng) -> el::TableConfig {
+        el::TableConfig {
+            data_source_id: rng.text(),
+            data_expression: rng.maybe(Rng::text),
+            columns: (0..rng.below(4))
+                .map(|_| el::TableColumn {
+                    header: rng.text(),
+                    expression: rng.text(),
+                    width: rng.number(),
+                    align: rng.pick(&[el::TextAlign::Left, el::TextAlign::Center, el::TextAlign::Right]),
+                    format: rng.maybe(Rng::text),
+                    footer_calculation: rng.maybe(|rng| rng.pick(&[Calculation::Sum, Calculation::Count, Calculation::Average])),
+                    footer_text: rng.maybe(Rng::text),
+                })
+                .collect(),
+            header_height: rng.number(),
+            row_height: rng.number(),
+            show_header: rng.chance(),
+            show_footer: rng.chance(),
+            font_family: rng.text(),
+            font_size: rng.number(),
+            text_color: rng.text(),
+            header_background: rng.maybe(Rng::text),
+            zebra_color: rng.maybe(Rng::text),
+            border: rng.maybe(|rng| el::BorderStyle {
+                color: rng.text(),
+                width: rng.number(),
+                style: rng.pick(&[el::BorderStyleType::Solid, el::BorderStyleType::Dashed, el::BorderStyleType::Dotted]),
+            }),
+        }
+    }
+
+    fn crosstab_group(rng: &mut Rng) -> el::CrosstabGroup {
+        el::CrosstabGroup {
+            field: rng.text(),
+            label: rng.maybe(Rng::text),
+            format: rng.maybe(Rng::text),
+            show_total: rng.chance(),
+        }
+    }
+
+    fn crosstab_config(rng: &mut Rng) -> el::CrosstabConfig {
+        el::CrosstabConfig {
+            data_source_id: rng.text(),
+            data_expression: rng.maybe(Rng::text),
+            row_groups: (0..rng.below(3)).map(|_| crosstab_group(rng)).collect(),
+            column_groups: (0..rng.below(3)).map(|_| crosstab_group(rng)).collect(),
+            measures: (0..rng.below(3))
+                .map(|_| el::CrosstabMeasure {
+                    field: rng.text(),
+                    function: rng.pick(&[AggregateFunction::Sum, AggregateFunction::Count, AggregateFunction::Avg]),
+                    label: rng.maybe(Rng::text),
+                    format: rng.maybe(Rng::text),
+                })
+                .collect(),
+            row_header_width: rng.number(),
+            cell_width: rng.number(),
+            cell_height: rng.number(),
+            total_label: rng.text(),
+            font_family: rng.text(),
+            font_size: rng.number(),
+            text_color: rng.text(),
+            header_background: rng.maybe(Rng::text),
+            total_background: rng.maybe(Rng::text),
+            border: rng.maybe(|rng| el::BorderStyle {
+                color: rng.text(),
+                width: rng.number(),
+                style: rng.pick(&[el::BorderStyleType::Solid, el::BorderStyleType::Dashed, el::BorderStyleType::Dotted]),
+            }),
+        }
+    }
+
+    fn chart_config(rng: &mut Rng) -> el::ChartConfig {
+        el::ChartConfig {
+            chart_type: rng.pick(&[
+                el::ChartType::Bar,
+                el::ChartType::StackedBar,
+                el::ChartType::Line,
+                el::ChartType::Area,
+                el::ChartType::Pie,
+                el::ChartType::Donut,
+                el::ChartType::Scatter,
+            ]),
+            data_source_id: rng.text(),
+            data_expression: rng.maybe(Rng::text),
+            category_expression: rng.text(),
+            series_expression: rng.maybe(Rng::text),
+            value_expression: rng.text(),
+            aggregate: rng.pick(&[AggregateFunction::Sum, AggregateFunction::Count, AggregateFunction::Max]),
+            title: rng.maybe(Rng::text),
+            show_legend: rng.chance(),
+            show_labels: rng.chance(),
+            show_axes: rng.chance(),
+            value_format: rng.maybe(Rng::text),
+            palette: (0..rng.below(4)).map(|_| rng.text()).collect(),
+            font_family: rng.text(),
+            font_size: rng.number(),
+            text_color: rng.text(),
+            data: rng.maybe(|rng| el::ChartData {
+                categories: (0..rng.below(4)).map(|_| rng.text()).collect(),
+                series: (0..rng.below(3))
+                    .map(|_| el::ChartSeries {
+                        name: rng.text(),
+                        values: (0..rng.below(4)).map(|_| rng.maybe(Rng::number)).collect(),
+                        points: (0..rng.below(3)).map(|_| [rng.number(), rng.number()]).collect(),
+                    })
+                    .collect(),
+                locale: rng.maybe(Rng::text),
+            }),
+        }
+    }
+
+    fn barcode_config(rng: &mut Rng) -> el::BarcodeConfig {
+        el::BarcodeConfig {
+            barcode_type: rng.pick(&[
+                el::BarcodeType::QrCode,
+                el::BarcodeType::Code128,
+                el::BarcodeType::Code39,
+                el::BarcodeType::Ean13,
+                el::BarcodeType::Pdf417,
+                el::BarcodeType::DataMatrix,
+            ]),
+            value: rng.text(),
+            expression: rng.maybe(Rng::text),
+            error_correction: rng.pick(&[
+                el::QrErrorCorrection::L,
+                el::QrErrorCorrection::M,
+                el::QrErrorCorrection::Q,
+                el::QrErrorCorrection::H,
+            ]),
+            show_text: rng.chance(),
+            color: rng.text(),
+            background: rng.maybe(Rng::text),
+            font_family: rng.text(),
+            font_size: rng.number(),
+        }
+    }
+
+    fn report_element(rng: &mut Rng) -> ReportElement {
+        let caps = [el::LineCapType::None, el::LineCapType::Arrow, el::LineCapType::Circle, el::LineCapType::Square];
+        let aligns = [el::ImageAlign::Start, el::ImageAlign::Center, el::ImageAlign::End];
+        let content = match rng.below(10) {
+            0 => el::ElementContent::Text { content: rng.text(), style: text_style(rng) },
+            1 => el::ElementContent::DataField {
+                expression: rng.text(),
+                format: rng.maybe(Rng::text),
+                style: text_style(rng),
+            },
+            2 => el::ElementContent::Rectangle {
+                fill_color: rng.maybe(Rng::text),
+                border: rng.maybe(|rng| el::BorderStyle {
+                    color: rng.text(),
+                    width: rng.number(),
+                    style: rng.pick(&[el::BorderStyleType::Solid, el::BorderStyleType::Dashed, el::BorderStyleType::Dotted]),
+                }),
+                corner_radius: rng.maybe(Rng::number),
+                opacity: rng.maybe(Rng::number),
+            },
+            3 => el::ElementContent::Line {
+                color: rng.text(),
+                width: rng.number(),
+                line_style: rng.maybe(|rng| rng.pick(&[
+                    el::LineStyleType::Solid,
+                    el::LineStyleType::Dashed,
+                    el::LineStyleType::Dotted,
+                    el::LineStyleType::DashDot,
+                ])),
+                start_cap: rng.maybe(|rng| rng.pick(&caps)),
+                end_cap: rng.maybe(|rng| rng.pick(&caps)),
+                opacity: rng.maybe(Rng::number),
+            },
+            4 => el::ElementContent::Image {
+                src: rng.text(),
+                alt: rng.maybe(Rng::text),
+                scale_mode: rng.pick(&[el::ImageScaleMode::Clip, el::ImageScaleMode::FillFrame, el::ImageScaleMode::RetainShape]),
+                horizontal_align: rng.pick(&aligns),
+                vertical_align: rng.pick(&aligns),
+            },
+            5 => el::ElementContent::Subreport(subreport_config(rng)),
+            6 => el::ElementContent::Table(table_config(rng)),
+            7 => el::ElementContent::Crosstab(crosstab_config(rng)),
+            8 => el::ElementContent::Chart(chart_config(rng)),
+            _ => el::ElementContent::Barcode(barcode_config(rng)),
+        };
+        ReportElement {
+            id: el::ElementId::new(),
+            position: Position { x: rng.number(), y: rng.number() },
+            size: Size { width: rng.number() + 1.0, height: rng.number() + 1.0 },
             content,
+            z_index: rng.below(10) as i32,
+            visible: rng.chance(),
+            locked: rng.chance(),
+            name: rng.maybe(Rng::text),
+            band: None,
+            origin: None,
+        }
+    }
+
+    fn template_element(rng: &mut Rng, index: usize) -> TemplateElement {
+        TemplateElement {
+            id: if rng.chance() { format!("element_{}", index) } else { uuid::Uuid::new_v4().to_string() },
+            element_type: rng.pick(&[
+                ElementType::Text,
+                ElementType::DataField,
+                ElementType::Rectangle,
+                ElementType::Line,
+                ElementType::Image,
+                ElementType::Barcode,
+                ElementType::Chart,
+                ElementType::Subreport,
+                ElementType::Table,
+                ElementType::Crosstab,
+            ]),
+            position: Position { x: rng.number(), y: rng.number() },
+            size: Size { width: rng.number() + 1.0, height: rng.number() + 1.0 },
+            z_index: rng.below(10) as i32,
+            visible: rng.chance(),
+            content: ElementContent {
+                text: rng.maybe(Rng::text),
+                expression: rng.maybe(Rng::text),
+                font: rng.maybe(|rng| FontConfig {
+                    family: rng.pick(&["Arial", "SimSun"]).to_string(),
+                    size: rng.number(),
+                    weight: rng.pick(&[FontWeight::Normal, FontWeight::Bold, FontWeight::Light]),
+                    style: rng.pick(&[FontStyle::Normal, FontStyle::Italic, FontStyle::Oblique]),
+                }),
+                alignment: rng.maybe(|rng| AlignmentConfig {
+                    horizontal: rng.pick(&[
+                        HorizontalAlignment::Left,
+                        HorizontalAlignment::Center,
+                        HorizontalAlignment::Right,
+                        HorizontalAlignment::Justify,
+                    ]),
+                    vertical: rng.pick(&[VerticalAlignment::Top, VerticalAlignment::Middle, VerticalAlignment::Bottom]),
+                }),
+                color: rng.maybe(Rng::text),
+                format: rng.maybe(Rng::text),
+                subreport: rng.maybe(subreport_config),
+                table: rng.maybe(table_config),
+                crosstab: rng.maybe(crosstab_config),
+                chart: rng.maybe(chart_config),
+                barcode: rng.maybe(barcode_config),
+                scale_mode: rng.maybe(|rng| {
+                    rng.pick(&[el::ImageScaleMode::Clip, el::ImageScaleMode::FillFrame, el::ImageScaleMode::RetainShape])
+                }),
+            },
             style: ElementStyle {
-                background: None,
-                border: None,
-                padding: None,
+                background: rng.maybe(|rng| BackgroundStyle {
+                    color: rng.maybe(Rng::text),
+                    image: rng.maybe(Rng::text),
+                }),
+                border: rng.maybe(|rng| BorderStyle {
+                    width: rng.number(),
+                    color: rng.text(),
+                    style: rng.pick(&[BorderStyleType::Solid, BorderStyleType::Dashed, BorderStyleType::Dotted]),
+                }),
+                padding: rng.maybe(|rng| PaddingConfig {
+                    top: rng.number(),
+                    bottom: rng.number(),
+                    left: rng.number(),
+                    right: rng.number(),
+                }),
             },
-            data_binding: None,
+            data_binding: rng.maybe(|rng| DataBinding {
+                source_id: "orders".to_string(),
+                field_name: rng.text(),
+                validation: None,
+            }),
+            designer: rng.maybe(|rng| DesignerProperties {
+                locked: true,
+                name: rng.maybe(Rng::text),
+                content: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_report_element_round_trip() {
+        let mut rng = Rng(0x5eed);
+        for _ in 0..1000 {
+            let element = report_element(&mut rng);
+            let template = TemplateElement::from(&element);
+            assert_eq!(ReportElement::from(&template), element);
+
+            // 经过模板文件序列化后依然无损
+            let json = serde_json::to_string(&template).unwrap();
+            let loaded: TemplateElement = serde_json::from_str(&json).unwrap();
+            assert_eq!(ReportElement::from(&loaded), element);
+        }
+    }
+
+    #[test]
+    fn test_template_element_round_trip() {
+        let mut rng = Rng(0xfeed);
+        for index in 0..1000 {
+            let element = template_element(&mut rng, index);
+            let report = ReportElement::from(&element);
+            assert_eq!(TemplateElement::from(&report), element);
+
+            // 设计器状态（撤销历史、自动保存快照）经过序列化后依然无损
+            let json = serde_json::to_string(&report).unwrap();
+            let restored: ReportElement = serde_json::from_str(&json).unwrap();
+            assert_eq!(TemplateElement::from(&restored), element);
+        }
+    }
+
+    #[test]
+    fn test_edit_keeps_template_properties() {
+        let mut rng = Rng(42);
+        let mut element = template_element(&mut rng, 0);
+        element.element_type = ElementType::Text;
+        element.content.text = None;
+        element.content.expression = Some("$F{code}".to_string());
+        element.content.font = Some(FontConfig { style: FontStyle::Italic, ..FontConfig::default() });
+        element.content.alignment = Some(AlignmentConfig {
+            horizontal: HorizontalAlignment::Justify,
+            vertical: VerticalAlignment::Bottom,
+        });
+
+        let mut report = ReportElement::from(&element);
+        report.position.x += 5.0;
+        report.locked = true;
+        if let el::ElementContent::Text { content, style } = &mut report.content {
+            *content = "$F{serial}".to_string();
+            style.font_size = 20.0;
+        }
+
+        let saved = TemplateElement::from(&report);
+        assert!(matches!(saved.element_type, ElementType::Text));
+        assert_eq!(saved.position.x, element.position.x + 5.0);
+        assert_eq!(saved.content.expression.as_deref(), Some("$F{serial}"));
+        assert_eq!(saved.content.text, None);
+        let font = saved.content.font.as_ref().unwrap();
+        assert_eq!((font.size, &font.style), (20.0, &FontStyle::Italic));
+        assert_eq!(saved.content.alignment, element.content.alignment);
+        assert_eq!(saved.style, element.style);
+        assert_eq!(saved.data_binding, element.data_binding);
+        assert!(saved.designer.as_ref().unwrap().locked);
+    }
+
+    #[test]
+    fn test_legacy_barcode_uses_expression() {
+        let mut rng = Rng(3);
+        let mut element = template_element(&mut rng, 0);
+        element.element_type = ElementType::Barcode;
+        element.content.expression = Some("$F{verify_url}".to_string());
+        element.content.barcode = None;
+
+        let report = ReportElement::from(&element);
+        match &report.content {
+            el::ElementContent::Barcode(config) => {
+                assert_eq!(config.barcode_type, el::BarcodeType::QrCode);
+                assert_eq!(config.expression.as_deref(), Some("$F{verify_url}"));
+            }
+            other => panic!("expected barcode, got {:?}", other),
         }
+        assert_eq!(TemplateElement::from(&report), element);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_app_state_template_round_trip() {
+        let mut rng = Rng(7);
+        let mut template = JasperTemplate::new();
+        template.metadata.description = Some("回单".to_string());
+        template.elements = (0..6).map(|index| template_element(&mut rng, index)).collect();
+        for element in &mut template.elements {
+            element.data_binding = None;
+            element.position.y = 0.0;
+            element.size.height = 10.0;
+        }
+        let ids: Vec<String> = template.elements.iter().map(|e| e.id.clone()).collect();
+        let mut detail = BandConfig::new(40.0);
+        detail.elements = vec![ids[2].clone(), ids[0].clone()];
+        template.bands.detail = Some(detail);
+        let mut group = Group::new("customer", "$F{customer}");
+        let mut header = BandConfig::new(20.0);
+        header.elements = vec![ids[3].clone(), ids[4].clone()];
+        group.header = Some(header);
+        template.groups.push(group);
+        template.canvas.margins.top = 36.0;
+
+        let mut state = AppState::new();
+        state.load_template(&template);
+        assert_eq!(state.elements.len(), 6);
+        assert_eq!(state.template_name.as_deref(), Some("回单"));
+        let unchanged = state.to_template();
+        assert_eq!(
+            serde_json::to_value(&unchanged).unwrap(),
+            serde_json::to_value(&template).unwrap()
+        );
+
+        // 用元素命令编辑：移动、删除、新建
+        let moved = el::ElementId::from_string(&ids[0]).unwrap();
+        let mut element = state.get_element(&moved).unwrap().clone();
+        element.position.x += 10.0;
+        state.update_element(&moved, element).unwrap();
+        state.delete_element(&el::ElementId::from_string(&ids[3]).unwrap()).unwrap();
+        let mut added = report_element(&mut rng);
+        added.position.y = 0.0;
+        added.size.height = 10.0;
+        added.band = Some(BandType::Detail);
+        state.add_element(added.clone()).unwrap();
+
+        let saved = state.to_template();
+        saved.validate().unwrap();
+        assert_eq!(saved.elements.len(), 6);
+        assert_eq!(saved.elements[0].position.x, template.elements[0].position.x + 10.0);
+        assert_eq!(saved.elements[5].id, added.id.to_string());
+        assert_eq!(
+            saved.bands.detail.as_ref().unwrap().elements,
+            vec![ids[2].clone(), ids[0].clone(), added.id.to_string()]
+        );
+        assert_eq!(saved.groups[0].header.as_ref().unwrap().elements, vec![ids[4].clone()]);
+        assert_eq!(saved.canvas.margins.top, 36.0);
+    }
+}
@@ -1,7 +1,12 @@
 // === Template Serialization Service ===
 use std::fs;
 use std::path::Path;
-use serde_json;
+use serde::{Deserialize, Serialize};
+use serde_json::{self, Value};
+use crate::core::binary::{self, BinarySignature};
+use crate::core::jrxml::{self, JrxmlImport};
+use crate::core::migration::{self, AppliedMigration};
+use crate::core::package::{PackageOptions, TemplatePackage, PACKAGE_EXTENSION};
 use crate::core::template::JasperTemplate;
 use crate::errors::{AppError, Result};
 
@@ -26,11 +31,12 @@ impl TemplateSerializer {
     pub fn serialize(&self, template: &JasperTemplate) -> Result<String> {
         // Validate template before serialization
         template.validate()?;
+        let template = Self::stamp_format_version(template);
         
         let json_string = if self.pretty_print {
-            serde_json::to_string_pretty(template)
+            serde_json::to_string_pretty(&template)
         } else {
-            serde_json::to_string(template)
+            serde_json::to_string(&template)
         }.map_err(|e| AppError::SerializationError {
             message: format!("Failed to serialize template: {}", e),
         })?;
@@ -38,9 +44,33 @@ impl TemplateSerializer {
         Ok(json_string)
     }
     
+    /// 保存时写入当前格式版本，避免下次加载重复迁移
+    fn stamp_format_version(template: &JasperTemplate) -> JasperTemplate {
+        let mut template = template.clone();
+        template.metadata.format_version = migration::CURRENT_FORMAT_VERSION.to_string();
+        template
+    }
+    
     /// Deserialize template from JSON string
     pub fn deserialize(json_data: &str) -> Result<JasperTemplate> {
-        let template: JasperTemplate = serde_json::from_str(json_data)
+        Self::deserialize_migrated(json_data).map(|loaded| loaded.template)
+    }
+    
+    /// Deserialize template from JSON string, upgrading older format versions
+    pub fn deserialize_migrated(json_data: &str) -> Result<LoadedTemplate> {
+        let value: Value = serde_json::from_str(json_data)
+            .map_err(|e| AppError::SerializationError {
+                message: format!("Failed to deserialize template: {}", e),
+            })?;
+        
+        Self::from_value(value)
+    }
+    
+    /// Run format migrations on raw JSON and build the template
+    fn from_value(mut value: Value) -> Result<LoadedTemplate> {
+        let migrations = migration::migrate(&mut value)?;
+        
+        let template: JasperTemplate = serde_json::from_value(value)
             .map_err(|e| AppError::SerializationError {
                 message: format!("Failed to deserialize template: {}", e),
             })?;
@@ -48,7 +78,7 @@ impl TemplateSerializer {
         // Validate deserialized template
         template.validate()?;
         
-        Ok(template)
+        Ok(LoadedTemplate { template, migrations })
     }
     
     /// Save template to file
@@ -71,82 +101,57 @@ impl TemplateSerializer {
     
     /// Load template from file
     pub fn load_from_file(file_path: &str) -> Result<JasperTemplate> {
+        Self::load_migrated(file_path).map(|loaded| loaded.template)
+    }
+    
+    /// Load template from file, upgrading older format versions
+    pub fn load_migrated(file_path: &str) -> Result<LoadedTemplate> {
         let json_data = fs::read_to_string(file_path).map_err(|e| AppError::FileError {
             message: format!("Failed to read template file '{}': {}", file_path, e),
         })?;
         
-        Self::deserialize(&json_data)
+        Self::deserialize_migrated(&json_data)
     }
     
-    /// Serialize to binary format (compressed)
+    /// Serialize to binary format (deflate compressed, checksummed)
     pub fn serialize_binary(&self, template: &JasperTemplate) -> Result<Vec<u8>> {
         template.validate()?;
+        let template = Self::stamp_format_version(template);
         
         // First serialize to JSON
-        let json_data = serde_json::to_vec(template).map_err(|e| AppError::SerializationError {
+        let json_data = serde_json::to_vec(&template).map_err(|e| AppError::SerializationError {
             message: format!("Failed to serialize template to binary: {}", e),
         })?;
         
-        // Add magic header and version
-        let mut binary_data = Vec::new();
-        binary_data.extend_from_slice(b"JASPER2\0"); // Magic number
-        binary_data.extend_from_slice(&1u32.to_le_bytes()); // Version
-        binary_data.extend_from_slice(&(json_data.len() as u32).to_le_bytes()); // Size
-        binary_data.extend_from_slice(&json_data);
-        
-        Ok(binary_data)
+        binary::encode(&json_data)
     }
     
     /// Deserialize from binary format
     pub fn deserialize_binary(binary_data: &[u8]) -> Result<JasperTemplate> {
-        if binary_data.len() < 16 {
-            return Err(AppError::SerializationError {
-                message: "Invalid binary format: too short".to_string(),
-            });
-        }
-        
-        // Check magic number
-        if &binary_data[0..8] != b"JASPER2\0" {
-            return Err(AppError::SerializationError {
-                message: "Invalid binary format: bad magic number".to_string(),
-            });
-        }
-        
-        // Check version
-        let version = u32::from_le_bytes([
-            binary_data[8], binary_data[9], binary_data[10], binary_data[11]
-        ]);
-        
-        if version != 1 {
-            return Err(AppError::SerializationError {
-                message: format!("Unsupported binary format version: {}", version),
-            });
-        }
-        
-        // Get data size
-        let data_size = u32::from_le_bytes([
-            binary_data[12], binary_data[13], binary_data[14], binary_data[15]
-        ]) as usize;
-        
-        if binary_data.len() < 16 + data_size {
-            return Err(AppError::SerializationError {
-                message: "Invalid binary format: incomplete data".to_string(),
-            });
-        }
-        
-        // Extract JSON data
-        let json_data = &binary_data[16..16 + data_size];
+        Self::deserialize_binary_migrated(binary_data).map(|loaded| loaded.template)
+    }
+    
+    /// Deserialize from binary format (version 1 or 2), upgrading older format versions
+    pub fn deserialize_binary_migrated(binary_data: &[u8]) -> Result<LoadedTemplate> {
+        let json_data = binary::decode(binary_data)?;
         
-        let template: JasperTemplate = serde_json::from_slice(json_data)
+        let value: Value = serde_json::from_slice(&json_data)
             .map_err(|e| AppError::SerializationError {
                 message: format!("Failed to deserialize template from binary: {}", e),
             })?;
         
-        template.validate()?;
-        Ok(template)
+        Self::from_value(value)
     }
 }
 
+/// Template read from disk with the format migrations that were applied to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedTemplate {
+    pub template: JasperTemplate,
+    pub migrations: Vec<AppliedMigration>,
+}
+
 impl Default for TemplateSerializer {
     fn default() -> Self {
         Self::new()
@@ -159,6 +164,8 @@ pub enum TemplateFormat {
     Json,
     Binary,
     Jrxml,
+    /// zip 模板包，包含图片、字体和样例数据
+    Package,
     Unknown,
 }
 
@@ -170,6 +177,7 @@ impl TemplateFormat {
                 Some("jasper") => return Self::Json,
                 Some("jbin") => return Self::Binary,
                 Some("jrxml") => return Self::Jrxml,
+                Some(PACKAGE_EXTENSION) => return Self::Package,
                 _ => {}
             }
         }
@@ -179,6 +187,9 @@ impl TemplateFormat {
             if header.len() >= 8 && &header[0..8] == b"JASPER2\0" {
                 return Self::Binary;
             }
+            if TemplatePackage::is_package(&header) {
+                return Self::Package;
+            }
             
             if let Ok(text) = String::from_utf8(header.clone()) {
                 let text_trimmed = text.trim_start();
@@ -198,6 +209,9 @@ impl TemplateFormat {
         if data.len() >= 8 && &data[0..8] == b"JASPER2\0" {
             return Self::Binary;
         }
+        if TemplatePackage::is_package(data) {
+            return Self::Package;
+        }
         
         if let Ok(text) = std::str::from_utf8(data) {
             let text_trimmed = text.trim_start();
@@ -219,23 +233,42 @@ pub struct TemplateLoader;
 impl TemplateLoader {
     /// Load template from any supported format
     pub fn load(file_path: &str) -> Result<JasperTemplate> {
+        let loaded = Self::load_with_migrations(file_path)?;
+        for step in &loaded.migrations {
+            log::info!("Migrated template '{}' from format {} to {}: {}", file_path, step.from, step.to, step.description);
+        }
+        Ok(loaded.template)
+    }
+    
+    /// Load template from any supported format, reporting the format migrations that ran
+    pub fn load_with_migrations(file_path: &str) -> Result<LoadedTemplate> {
         let format = TemplateFormat::detect_from_file(file_path);
         
         match format {
             TemplateFormat::Json => {
-                TemplateSerializer::load_from_file(file_path)
+                TemplateSerializer::load_migrated(file_path)
             }
             TemplateFormat::Binary => {
+                // Signatures are only meaningful against a trusted key, see `verify_signed_binary`
                 let binary_data = fs::read(file_path).map_err(|e| AppError::FileError {
                     message: format!("Failed to read binary template file '{}': {}", file_path, e),
                 })?;
-                TemplateSerializer::deserialize_binary(&binary_data)
+                TemplateSerializer::deserialize_binary_migrated(&binary_data)
             }
             TemplateFormat::Jrxml => {
-                // TODO: Implement JRXML conversion
-                Err(AppError::SerializationError {
-                    message: "JRXML format not yet implemented".to_string(),
-                })
+                let import = Self::import_jrxml(file_path)?;
+                for item in &import.unsupported {
+                    log::warn!("JRXML import '{}' line {}: {}", file_path, item.line, item.message);
+                }
+                Ok(LoadedTemplate { template: import.template, migrations: Vec::new() })
+            }
+            TemplateFormat::Package => {
+                let package_data = fs::read(file_path).map_err(|e| AppError::FileError {
+                    message: format!("Failed to read template package '{}': {}", file_path, e),
+                })?;
+                let package = TemplatePackage::read(&package_data)?;
+                let template = package.extract_to_cache(&package_data)?;
+                Ok(LoadedTemplate { template, migrations: package.migrations })
             }
             TemplateFormat::Unknown => {
                 Err(AppError::FileError {
@@ -245,6 +278,14 @@ impl TemplateLoader {
         }
     }
     
+    /// Import a JRXML file, reporting constructs that could not be mapped
+    pub fn import_jrxml(file_path: &str) -> Result<JrxmlImport> {
+        let xml = fs::read_to_string(file_path).map_err(|e| AppError::FileError {
+            message: format!("Failed to read JRXML file '{}': {}", file_path, e),
+        })?;
+        jrxml::import_jrxml(&xml)
+    }
+    
     /// Save template to specified format
     pub fn save(template: &JasperTemplate, file_path: &str, format: TemplateFormat) -> Result<()> {
         let serializer = TemplateSerializer::new();
@@ -258,13 +299,22 @@ impl TemplateLoader {
                 fs::write(file_path, binary_data).map_err(|e| AppError::FileError {
                     message: format!("Failed to write binary template file '{}': {}", file_path, e),
                 })?;
-                Ok(())
+                Self::remove_signature(file_path)
             }
             TemplateFormat::Jrxml => {
-                // TODO: Implement JRXML conversion
-                Err(AppError::SerializationError {
-                    message: "JRXML format not yet implemented".to_string(),
-                })
+                let xml = jrxml::export_jrxml(template)?;
+                fs::write(file_path, xml).map_err(|e| AppError::FileError {
+                    message: format!("Failed to write JRXML file '{}': {}", file_path, e),
+                })?;
+                Ok(())
+            }
+            TemplateFormat::Package => {
+                let options = PackageOptions {
+                    base_dir: Path::new(file_path).parent().map(Path::to_path_buf),
+                    system_fonts: true,
+                    ..PackageOptions::default()
+                };
+                Self::save_package(&TemplatePackage::pack(template, &options)?, file_path)
             }
             TemplateFormat::Unknown => {
                 Err(AppError::SerializationError {
@@ -275,6 +325,74 @@ impl TemplateLoader {
     }
 }
 
+impl TemplateLoader {
+    /// Save template in binary format with a detached signature file (`<file>.sig`)
+    pub fn save_signed_binary(template: &JasperTemplate, file_path: &str, secret_key: &str) -> Result<BinarySignature> {
+        let binary_data = TemplateSerializer::new().serialize_binary(template)?;
+        let signature = binary::sign(&binary_data, secret_key)?;
+        let signature_json = serde_json::to_string_pretty(&signature).map_err(|e| AppError::SerializationError {
+            message: format!("Failed to serialize signature: {}", e),
+        })?;
+        
+        fs::write(file_path, binary_data).map_err(|e| AppError::FileError {
+            message: format!("Failed to write binary template file '{}': {}", file_path, e),
+        })?;
+        fs::write(binary::signature_path(file_path), signature_json).map_err(|e| AppError::FileError {
+            message: format!("Failed to write signature file for '{}': {}", file_path, e),
+        })?;
+        Ok(signature)
+    }
+    
+    /// Verify a binary template against its detached signature
+    pub fn verify_signed_binary(file_path: &str, trusted_key: Option<&str>) -> Result<BinarySignature> {
+        let signature = Self::read_signature(file_path)?.ok_or_else(|| AppError::ValidationError {
+            message: format!("No signature found for '{}'", file_path),
+        })?;
+        let binary_data = fs::read(file_path).map_err(|e| AppError::FileError {
+            message: format!("Failed to read binary template file '{}': {}", file_path, e),
+        })?;
+        binary::verify(&binary_data, &signature, trusted_key)?;
+        Ok(signature)
+    }
+    
+    /// An unsigned save invalidates the detached signature of the previous content
+    fn remove_signature(file_path: &str) -> Result<()> {
+        let signature_path = binary::signature_path(file_path);
+        if !Path::new(&signature_path).exists() {
+            return Ok(());
+        }
+        fs::remove_file(&signature_path).map_err(|e| AppError::FileError {
+            message: format!("Failed to remove stale signature file '{}': {}", signature_path, e),
+        })
+    }
+
+    fn read_signature(file_path: &str) -> Result<Option<BinarySignature>> {
+        let signature_path = binary::signature_path(file_path);
+        if !Path::new(&signature_path).exists() {
+            return Ok(None);
+        }
+        let signature_json = fs::read_to_string(&signature_path).map_err(|e| AppError::FileError {
+            message: format!("Failed to read signature file '{}': {}", signature_path, e),
+        })?;
+        serde_json::from_str(&signature_json).map(Some).map_err(|e| AppError::SerializationError {
+            message: format!("Invalid signature file '{}': {}", signature_path, e),
+        })
+    }
+    
+    /// Write a template package to disk
+    pub fn save_package(package: &TemplatePackage, file_path: &str) -> Result<()> {
+        let package_data = package.write()?;
+        if let Some(parent) = Path::new(file_path).parent() {
+            fs::create_dir_all(parent).map_err(|e| AppError::FileError {
+                message: format!("Failed to create directory: {}", e),
+            })?;
+        }
+        fs::write(file_path, package_data).map_err(|e| AppError::FileError {
+            message: format!("Failed to write template package '{}': {}", file_path, e),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -317,6 +435,40 @@ mod tests {
         assert_eq!(template.metadata.description, deserialized.metadata.description);
     }
     
+    #[test]
+    fn test_signed_binary_file() {
+        let template = create_test_template();
+        let dir = tempdir().expect("Should create temp dir");
+        let file_path = dir.path().join("signed.jbin");
+        let file_path_str = file_path.to_str().unwrap();
+        
+        let keys = binary::generate_signing_key().expect("Should generate key");
+        TemplateLoader::save_signed_binary(&template, file_path_str, &keys.secret_key).expect("Should save");
+        let signature = TemplateLoader::verify_signed_binary(file_path_str, Some(&keys.public_key))
+            .expect("Should verify");
+        assert_eq!(signature.public_key, keys.public_key);
+        TemplateLoader::load(file_path_str).expect("Should load signed template");
+        
+        // Replace the file content behind the signature's back
+        let mut other = create_test_template();
+        other.metadata.description = Some("Tampered".to_string());
+        let tampered = TemplateSerializer::new().serialize_binary(&other).unwrap();
+        fs::write(&file_path, tampered).unwrap();
+        assert!(TemplateLoader::verify_signed_binary(file_path_str, Some(&keys.public_key)).is_err());
+        
+        // A self-consistent signature made with another key is not trusted
+        let intruder = binary::generate_signing_key().expect("Should generate key");
+        TemplateLoader::save_signed_binary(&other, file_path_str, &intruder.secret_key).expect("Should save");
+        assert!(TemplateLoader::verify_signed_binary(file_path_str, Some(&keys.public_key)).is_err());
+        
+        // An ordinary save drops the stale signature and the template still opens
+        other.metadata.description = Some("Edited".to_string());
+        TemplateLoader::save(&other, file_path_str, TemplateFormat::Binary).expect("Should save");
+        assert!(!Path::new(&binary::signature_path(file_path_str)).exists());
+        let loaded = TemplateLoader::load(file_path_str).expect("Should load re-saved template");
+        assert_eq!(loaded.metadata.description.as_deref(), Some("Edited"));
+    }
+    
     #[test]
     fn test_file_operations() {
         let template = create_test_template();
@@ -347,4 +499,117 @@ mod tests {
         let xml_data = b"<?xml version=\"1.0\"?><jasperReport>";
         assert_eq!(TemplateFormat::detect_from_data(xml_data), TemplateFormat::Jrxml);
     }
+    
+    #[test]
+    fn test_load_migrates_old_format() {
+        let dir = tempdir().expect("Should create temp dir");
+        let file_path = dir.path().join("old.jasper");
+        fs::write(&file_path, include_str!("migration/fixtures/format-1.0.0.jasper")).unwrap();
+        let file_path_str = file_path.to_str().unwrap();
+        
+        let loaded = TemplateLoader::load_with_migrations(file_path_str).expect("Should load old template");
+        assert_eq!(loaded.template.metadata.format_version, migration::CURRENT_FORMAT_VERSION);
+        let steps: Vec<(&str, &str)> = loaded.migrations.iter().map(|m| (m.from.as_str(), m.to.as_str())).collect();
+        assert_eq!(
+            steps,
+            vec![
+                ("1.0.0", "1.1.0"),
+                ("1.1.0", "1.2.0"),
+                ("1.2.0", "1.3.0"),
+                ("1.3.0", "1.4.0"),
+                ("1.4.0", "1.5.0"),
+                ("1.5.0", "1.6.0"),
+                ("1.6.0", "1.7.0"),
+                ("1.7.0", "1.8.0"),
+                ("1.8.0", "1.9.0"),
+            ]
+        );
+        
+        // Version 1 binary files go through the same migrations
+        let mut binary_data = b"JASPER2\0\x01\0\0\0".to_vec();
+        let json_data = fs::read(&file_path).unwrap();
+        binary_data.extend_from_slice(&(json_data.len() as u32).to_le_bytes());
+        binary_data.extend_from_slice(&json_data);
+        let loaded = TemplateSerializer::deserialize_binary_migrated(&binary_data).expect("Should load old binary");
+        assert_eq!(loaded.migrations.len(), steps.len());
+        
+        // Files written by a newer designer are rejected
+        let newer = fs::read_to_string(&file_path).unwrap().replace("\"1.0.0\"", "\"9.0.0\"");
+        fs::write(&file_path, newer).unwrap();
+        assert!(TemplateLoader::load(file_path_str).is_err());
+    }
+    
+    #[test]
+    fn test_save_writes_current_format_version() {
+        let dir = tempdir().expect("Should create temp dir");
+        let old_path = dir.path().join("old.jasper");
+        fs::write(&old_path, include_str!("migration/fixtures/format-1.0.0.jasper")).unwrap();
+        let mut template = TemplateLoader::load(old_path.to_str().unwrap()).expect("Should load old template");
+        template.metadata.format_version = "1.0.0".to_string();
+        
+        let json_path = dir.path().join("saved.jasper");
+        let json_path_str = json_path.to_str().unwrap();
+        TemplateLoader::save(&template, json_path_str, TemplateFormat::Json).expect("Should save template");
+        let raw: Value = serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(raw["metadata"]["format_version"], "1.9.0");
+        assert!(TemplateLoader::load_with_migrations(json_path_str).unwrap().migrations.is_empty());
+        
+        let binary_data = TemplateSerializer::new().serialize_binary(&template).expect("Should serialize binary");
+        let loaded = TemplateSerializer::deserialize_binary_migrated(&binary_data).unwrap();
+        assert_eq!(loaded.template.metadata.format_version, "1.9.0");
+        assert!(loaded.migrations.is_empty());
+    }
+    
+    #[test]
+    fn test_package_save_and_load() {
+        let dir = tempdir().expect("Should create temp dir");
+        fs::write(dir.path().join("logo.png"), b"\x89PNG logo").unwrap();
+        let mut template = create_test_template();
+        template.canvas.background.image = Some("logo.png".to_string());
+        
+        let file_path = dir.path().join("report.jpkg");
+        let file_path_str = file_path.to_str().unwrap();
+        TemplateLoader::save(&template, file_path_str, TemplateFormat::Package).expect("Should save package");
+        
+        let data = fs::read(&file_path).unwrap();
+        assert_eq!(TemplateFormat::detect_from_data(&data), TemplateFormat::Package);
+        
+        // Move the package away from its assets
+        let other_dir = tempdir().expect("Should create temp dir");
+        let moved = other_dir.path().join("moved.bin");
+        fs::rename(&file_path, &moved).unwrap();
+        fs::remove_file(dir.path().join("logo.png")).unwrap();
+        let moved_str = moved.to_str().unwrap();
+        assert_eq!(TemplateFormat::detect_from_file(moved_str), TemplateFormat::Package);
+        
+        let loaded = TemplateLoader::load(moved_str).expect("Should load package");
+        assert_eq!(loaded.metadata.description, template.metadata.description);
+        let image = loaded.canvas.background.image.expect("Should keep background image");
+        assert_eq!(fs::read(image).unwrap(), b"\x89PNG logo");
+    }
+    
+    #[test]
+    fn test_load_jrxml() {
+        let dir = tempdir().expect("Should create temp dir");
+        let file_path = dir.path().join("legacy.jrxml");
+        fs::write(&file_path, r#"<jasperReport name="Legacy" pageWidth="612" pageHeight="792">
+            <detail><band height="20"><ellipse><reportElement x="0" y="0" width="10" height="10"/></ellipse></band></detail>
+        </jasperReport>"#).unwrap();
+        let file_path_str = file_path.to_str().unwrap();
+        
+        let loaded = TemplateLoader::load(file_path_str).expect("Should load JRXML");
+        assert_eq!(loaded.canvas.width, 612.0);
+        assert_eq!(loaded.bands.detail.as_ref().map(|band| band.height), Some(20.0));
+        
+        let import = TemplateLoader::import_jrxml(file_path_str).expect("Should import JRXML");
+        assert_eq!(import.unsupported.len(), 1);
+        assert_eq!(import.unsupported[0].construct, "ellipse");
+        
+        let exported_path = dir.path().join("exported.jrxml");
+        let exported_path_str = exported_path.to_str().unwrap();
+        TemplateLoader::save(&loaded, exported_path_str, TemplateFormat::Jrxml).expect("Should save JRXML");
+        let reloaded = TemplateLoader::load(exported_path_str).expect("Should reload JRXML");
+        assert_eq!(reloaded.canvas.width, 612.0);
+        assert_eq!(reloaded.bands.detail.as_ref().map(|band| band.height), Some(20.0));
+    }
 }
\ No newline at end of file
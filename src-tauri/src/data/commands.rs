@@ -134,6 +134,7 @@ pub async fn get_data_preview(
         sort: None,
         aggregation: None,
         context: None,
+        parameters: Default::default(),
     };
     
     let registry = registry.lock().await;
@@ -159,6 +160,7 @@ pub async fn evaluate_expression(
         sort: None,
         aggregation: None,
         context: req.context,
+        parameters: Default::default(),
     };
     
     let registry = registry.lock().await;
@@ -190,6 +192,7 @@ pub async fn evaluate_expressions_batch(
             sort: None,
             aggregation: None,
             context: req.context.clone(),
+            parameters: Default::default(),
         };
         
         match registry.query_data(&req.source_id, Some(query)).await {
@@ -244,6 +247,7 @@ pub async fn search_data(
         sort: None,
         aggregation: None,
         context: None,
+        parameters: Default::default(),
     };
     
     let registry = registry.lock().await;
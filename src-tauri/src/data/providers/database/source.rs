@@ -1,6 +1,9 @@
 // === 数据库数据源实例实现 - 修复版本 ===
 use crate::data::types::*;
+use crate::data::utils::{bind_sql_parameters, BoundStatement};
 use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
 use serde_json::{json, Value};
 use sqlx::{MySql, Pool, Row, Column, TypeInfo};
 use chrono::Utc;
@@ -80,6 +83,26 @@ impl DatabaseSource {
 }
 
 // 辅助函数：映射MySQL列类型到通用数据类型
+/// 按JSON值类型绑定查询参数
+fn bind_values<'q>(
+    mut query: sqlx::query::Query<'q, MySql, sqlx::mysql::MySqlArguments>,
+    values: &'q [Value],
+) -> sqlx::query::Query<'q, MySql, sqlx::mysql::MySqlArguments> {
+    for value in values {
+        query = match value {
+            Value::Null => query.bind(None::<String>),
+            Value::Bool(b) => query.bind(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => query.bind(i),
+                None => query.bind(n.as_f64().unwrap_or_default()),
+            },
+            Value::String(text) => query.bind(text.as_str()),
+            other => query.bind(other.to_string()),
+        };
+    }
+    query
+}
+
 fn map_column_type(mysql_type: &str) -> DataType {
     let lower = mysql_type.to_lowercase();
     match lower.as_str() {
@@ -133,6 +156,9 @@ fn extract_column_value(row: &sqlx::mysql::MySqlRow, index: usize, data_type: &D
         } else {
             Value::Null
         }
+    } else if let Ok(Some(bytes)) = row.try_get::<Option<Vec<u8>>, _>(index) {
+        // 二进制列（图片等BLOB）转为base64文本，由图片元素解码
+        json!(STANDARD.encode(bytes))
     } else {
         Value::Null
     }
@@ -175,13 +201,14 @@ impl DataSource for DatabaseSource {
         let database = self.config["database"].as_str().unwrap_or("");
         println!("🔄 正在查询数据库: {}", database);
         
-        // 构建SQL查询
+        // 构建SQL查询：查询中的自定义SQL优先，其次是数据源配置的SQL
+        let config_sql = self.config["sql"].as_str()
+            .map(str::trim)
+            .filter(|sql| !sql.is_empty());
         let sql = if let Some(q) = &query {
-            if let Some(path) = &q.path {
-                // path包含自定义SQL查询
-                let mut custom_sql = path.clone();
-                
+            if let Some(custom_sql) = q.path.as_deref().or(config_sql) {
                 // 添加LIMIT限制（如果没有的话）
+                let mut custom_sql = custom_sql.to_string();
                 if let Some(limit) = q.limit {
                     if !custom_sql.to_uppercase().contains("LIMIT") {
                         custom_sql = format!("{} LIMIT {}", custom_sql.trim_end_matches(';'), limit);
@@ -204,13 +231,18 @@ impl DataSource for DatabaseSource {
                     WHERE TABLE_SCHEMA = '{}' 
                     LIMIT 10", database)
         };
+
+        // 报表参数 $P{name} 作为绑定参数传入，避免拼接SQL
+        let empty_parameters = std::collections::HashMap::new();
+        let parameters = query.as_ref().map_or(&empty_parameters, |q| &q.parameters);
+        let BoundStatement { sql, values } = bind_sql_parameters(&sql, parameters)?;
         
-        println!("📋 执行SQL查询: {}", sql);
+        println!("📋 执行SQL查询: {} (参数 {} 个)", sql, values.len());
         
         let start_time = std::time::Instant::now();
         
         // 执行查询，如果失败尝试重连
-        let rows_result = match sqlx::query(&sql).fetch_all(&pool).await {
+        let rows_result = match bind_values(sqlx::query(&sql), &values).fetch_all(&pool).await {
             Ok(rows) => rows,
             Err(e) => {
                 let error_str = e.to_string();
@@ -228,7 +260,7 @@ impl DataSource for DatabaseSource {
                     
                     // 获取新连接池并重试
                     let new_pool = self.get_pool().await?;
-                    sqlx::query(&sql)
+                    bind_values(sqlx::query(&sql), &values)
                         .fetch_all(&new_pool)
                         .await
                         .map_err(|e| {
@@ -1,5 +1,6 @@
 // === JSON数据源实现 - MVP核心 ===
 use crate::data::types::*;
+use crate::data::utils::substitute_path_parameters;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde_json::{json, Value};
@@ -469,8 +470,9 @@ impl DataSource for JsonDataSource {
     async fn get_data(&self, query: Option<DataQuery>) -> Result<DataSet, DataError> {
         let data = match query {
             Some(ref q) if q.path.is_some() => {
-                // 使用JSONPath查询
-                self.query_by_path(&q.path.as_ref().unwrap())?
+                // 使用JSONPath查询，路径中的 $P{name} 替换为参数值
+                let path = substitute_path_parameters(q.path.as_ref().unwrap(), &q.parameters)?;
+                self.query_by_path(&path)?
             }
             _ => {
                 // 返回完整数据
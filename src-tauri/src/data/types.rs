@@ -67,6 +67,9 @@ pub struct DataQuery {
     pub aggregation: Option<AggregationQuery>,
     /// 查询上下文 (用于权限控制)
     pub context: Option<serde_json::Value>,
+    /// 报表参数值，SQL和路径中的 $P{name} 按绑定参数处理
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub parameters: std::collections::HashMap<String, serde_json::Value>,
 }
 
 /// 排序字段
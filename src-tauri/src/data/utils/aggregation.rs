@@ -0,0 +1,159 @@
+// 内存聚合：按 AggregationQuery 对数据行分组汇总
+use crate::data::types::{AggregateFunction, Aggregation, AggregationQuery};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+static NULL: Value = Value::Null;
+
+/// 按字段路径取值，支持 `customer.name` 形式的嵌套字段
+pub fn field_value<'a>(row: &'a Value, path: &str) -> &'a Value {
+    if let Some(value) = row.get(path) {
+        return value;
+    }
+    path.split('.').try_fold(row, |value, key| value.get(key)).unwrap_or(&NULL)
+}
+
+impl Aggregation {
+    /// 结果列名：未指定别名时为 `函数_字段`，如 `sum_amount`
+    pub fn output_name(&self) -> String {
+        self.alias.clone().unwrap_or_else(|| format!("{}_{}", self.function.name(), self.field))
+    }
+}
+
+impl AggregateFunction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AggregateFunction::Count => "count",
+            AggregateFunction::Sum => "sum",
+            AggregateFunction::Avg => "avg",
+            AggregateFunction::Min => "min",
+            AggregateFunction::Max => "max",
+            AggregateFunction::First => "first",
+            AggregateFunction::Last => "last",
+        }
+    }
+
+    /// 汇总一组值，空值不参与计算；求和/平均只统计数值（含数字文本）
+    pub fn apply(&self, values: &[&Value]) -> Value {
+        let present = values.iter().copied().filter(|value| !value.is_null());
+        let numbers = || present.clone().filter_map(number);
+        match self {
+            AggregateFunction::Count => Value::from(present.count()),
+            AggregateFunction::Sum => {
+                let integral = present.clone().all(|value| value.is_i64() || value.is_u64());
+                let sum: f64 = numbers().sum();
+                if integral && sum.abs() < i64::MAX as f64 {
+                    Value::from(sum as i64)
+                } else {
+                    serde_json::Number::from_f64(sum).map_or(Value::Null, Value::Number)
+                }
+            }
+            AggregateFunction::Avg => {
+                let (sum, count) = numbers().fold((0.0, 0usize), |(sum, count), value| (sum + value, count + 1));
+                if count == 0 {
+                    Value::Null
+                } else {
+                    serde_json::Number::from_f64(sum / count as f64).map_or(Value::Null, Value::Number)
+                }
+            }
+            AggregateFunction::Min => present.min_by(|a, b| compare(a, b)).cloned().unwrap_or(Value::Null),
+            AggregateFunction::Max => present.max_by(|a, b| compare(a, b)).cloned().unwrap_or(Value::Null),
+            AggregateFunction::First => present.clone().next().cloned().unwrap_or(Value::Null),
+            AggregateFunction::Last => present.last().cloned().unwrap_or(Value::Null),
+        }
+    }
+}
+
+/// 分组汇总：每个分组输出一行，包含分组字段（键为字段路径）和各聚合结果，分组按首次出现的顺序排列；
+/// 没有分组字段时整个数据集汇总为一行
+pub fn aggregate_rows(rows: &[Value], query: &AggregationQuery) -> Vec<Value> {
+    let mut groups: Vec<(Vec<&Value>, Vec<&Value>)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for row in rows {
+        let key: Vec<&Value> = query.group_by.iter().map(|field| field_value(row, field)).collect();
+        let position = *index
+            .entry(serde_json::to_string(&key).unwrap_or_default())
+            .or_insert_with(|| {
+                groups.push((key, Vec::new()));
+                groups.len() - 1
+            });
+        groups[position].1.push(row);
+    }
+    if groups.is_empty() && query.group_by.is_empty() {
+        groups.push((Vec::new(), Vec::new()));
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, members)| {
+            let mut output = Map::new();
+            for (field, value) in query.group_by.iter().zip(key) {
+                output.insert(field.clone(), value.clone());
+            }
+            for aggregation in &query.aggregations {
+                let values: Vec<&Value> = members.iter().map(|row| field_value(row, &aggregation.field)).collect();
+                output.insert(aggregation.output_name(), aggregation.function.apply(&values));
+            }
+            Value::Object(output)
+        })
+        .collect()
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// 数值按大小比较，其余按文本比较
+fn compare(a: &Value, b: &Value) -> std::cmp::Ordering {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            x.as_f64().partial_cmp(&y.as_f64()).unwrap_or(std::cmp::Ordering::Equal)
+        }
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        _ => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn aggregation(field: &str, function: AggregateFunction) -> Aggregation {
+        Aggregation { field: field.to_string(), function, alias: None }
+    }
+
+    #[test]
+    fn test_aggregate_rows_groups_in_first_seen_order() {
+        let rows = vec![
+            json!({"region": "North", "amount": 10, "order": {"qty": 1}}),
+            json!({"region": "South", "amount": 5.5, "order": {"qty": 2}}),
+            json!({"region": "North", "amount": 20, "order": {"qty": 3}}),
+            json!({"region": "North", "amount": null, "order": {"qty": 4}}),
+        ];
+        let query = AggregationQuery {
+            group_by: vec!["region".to_string()],
+            aggregations: vec![
+                aggregation("amount", AggregateFunction::Sum),
+                aggregation("amount", AggregateFunction::Count),
+                aggregation("order.qty", AggregateFunction::Max),
+                Aggregation { field: "amount".to_string(), function: AggregateFunction::Avg, alias: Some("average".to_string()) },
+            ],
+        };
+
+        let result = aggregate_rows(&rows, &query);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], json!({"region": "North", "sum_amount": 30, "count_amount": 2, "max_order.qty": 4, "average": 15.0}));
+        assert_eq!(result[1]["sum_amount"], json!(5.5));
+    }
+
+    #[test]
+    fn test_aggregate_rows_without_groups_returns_single_row() {
+        let query = AggregationQuery { group_by: vec![], aggregations: vec![aggregation("amount", AggregateFunction::Count)] };
+        assert_eq!(aggregate_rows(&[], &query), vec![json!({"count_amount": 0})]);
+    }
+}
@@ -1,6 +1,8 @@
 // === 表达式解析工具 ===
 use crate::data::types::DataError;
+use crate::expression::{EvalContext, Expression};
 use serde_json::Value;
+use std::collections::HashMap;
 
 /// 表达式求值器
 pub struct ExpressionEvaluator;
@@ -50,8 +52,19 @@ impl ExpressionEvaluator {
         }
     }
     
-    /// 提取表达式中的字段名
+    /// 提取表达式中的字段名（报表表达式取引用字段的顶层名称）
     pub fn extract_field_names(expression: &str) -> Vec<String> {
+        if let Ok(parsed) = Expression::parse(expression) {
+            let mut fields: Vec<String> = Vec::new();
+            for field in parsed.references().fields {
+                let name = field.split(['.', '[']).next().unwrap_or_default().to_string();
+                if !name.is_empty() && !fields.contains(&name) {
+                    fields.push(name);
+                }
+            }
+            return fields;
+        }
+
         let mut fields = Vec::new();
         let parts: Vec<&str> = expression.split('.').collect();
         
@@ -179,8 +192,28 @@ impl ExpressionEvaluator {
             .to_string()
     }
     
+    /// 针对数据集中的一行求值报表表达式
+    pub fn evaluate(expression: &str, row: &Value, parameters: &HashMap<String, Value>) -> Result<Value, DataError> {
+        let variables = HashMap::new();
+        Expression::parse(expression)
+            .and_then(|parsed| parsed.evaluate(&EvalContext::new(row, parameters, &variables)))
+            .map_err(|error| {
+                let span = error.span();
+                DataError::ParseError {
+                    message: format!("{} (in '{}')", error.message(), expression),
+                    line: Some(1),
+                    column: Some(span.start + 1),
+                }
+            })
+    }
+
     /// 检查表达式是否可以缓存
     pub fn is_cacheable(expression: &str) -> bool {
+        // 报表表达式按是否调用非确定性函数判断
+        if let Ok(parsed) = Expression::parse(expression) {
+            return parsed.is_deterministic();
+        }
+
         // 静态表达式可以缓存
         !expression.contains("now()") && 
         !expression.contains("random()") &&
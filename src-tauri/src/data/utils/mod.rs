@@ -1,6 +1,10 @@
 // 工具函数模块
+pub mod aggregation;
 pub mod expression;
+pub mod parameters;
 pub mod validation;
 
+pub use aggregation::*;
 pub use expression::*;
+pub use parameters::*;
 pub use validation::*;
\ No newline at end of file
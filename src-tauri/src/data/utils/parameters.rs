@@ -0,0 +1,139 @@
+// === 查询参数绑定工具 ===
+use crate::data::types::DataError;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 绑定参数后的SQL语句：`$P{name}` 替换为占位符 `?`，值按出现顺序排列
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundStatement {
+    pub sql: String,
+    pub values: Vec<Value>,
+}
+
+/// 将SQL中的 `$P{name}` 转为绑定参数，字符串字面量和注释中的引用保持原样
+pub fn bind_sql_parameters(sql: &str, parameters: &HashMap<String, Value>) -> Result<BoundStatement, DataError> {
+    let mut bound = String::with_capacity(sql.len());
+    let mut values = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut rest = sql;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(open) = quote {
+            // 引号内原样输出，'' 视为转义
+            bound.push(c);
+            rest = &rest[c.len_utf8()..];
+            if c == open {
+                if rest.starts_with(open) {
+                    bound.push(open);
+                    rest = &rest[open.len_utf8()..];
+                } else {
+                    quote = None;
+                }
+            }
+            continue;
+        }
+
+        if c == '\'' || c == '"' || c == '`' {
+            quote = Some(c);
+        } else if rest.starts_with("--") {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            bound.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        } else if let Some(after) = rest.strip_prefix("$P{") {
+            let close = after.find('}').ok_or_else(|| DataError::QueryError {
+                message: "Unclosed parameter reference".to_string(),
+                query: Some(sql.to_string()),
+            })?;
+            let name = after[..close].trim();
+            let value = parameters.get(name).ok_or_else(|| DataError::QueryError {
+                message: format!("Missing value for parameter '{}'", name),
+                query: Some(sql.to_string()),
+            })?;
+
+            // 数组参数展开为 IN 列表
+            match value {
+                Value::Array(items) if !items.is_empty() => {
+                    bound.push_str(&vec!["?"; items.len()].join(", "));
+                    values.extend(items.iter().cloned());
+                }
+                _ => {
+                    bound.push('?');
+                    values.push(value.clone());
+                }
+            }
+            rest = &after[close + 1..];
+            continue;
+        }
+
+        bound.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    Ok(BoundStatement { sql: bound, values })
+}
+
+/// 将JSON路径中的 `$P{name}` 替换为参数值（字符串不带引号）
+pub fn substitute_path_parameters(path: &str, parameters: &HashMap<String, Value>) -> Result<String, DataError> {
+    let mut result = String::with_capacity(path.len());
+    let mut rest = path;
+
+    while let Some(start) = rest.find("$P{") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 3..];
+        let close = after.find('}').ok_or_else(|| DataError::PathNotFound { path: path.to_string() })?;
+        let name = after[..close].trim();
+        match parameters.get(name) {
+            Some(Value::String(text)) => result.push_str(text),
+            Some(Value::Null) | None => {
+                return Err(DataError::QueryError {
+                    message: format!("Missing value for parameter '{}'", name),
+                    query: Some(path.to_string()),
+                })
+            }
+            Some(value) => result.push_str(&value.to_string()),
+        }
+        rest = &after[close + 1..];
+    }
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn params() -> HashMap<String, Value> {
+        HashMap::from([
+            ("customerId".to_string(), json!("C001")),
+            ("from".to_string(), json!("2024-01-01")),
+            ("ids".to_string(), json!([1, 2, 3])),
+            ("index".to_string(), json!(2)),
+        ])
+    }
+
+    #[test]
+    fn test_bind_sql_parameters() {
+        let sql = "SELECT * FROM t WHERE customer_id = $P{customerId} AND day >= $P{ from } AND id IN ($P{ids}) AND note <> '$P{customerId}' -- $P{x}";
+        let bound = bind_sql_parameters(sql, &params()).unwrap();
+        assert_eq!(
+            bound.sql,
+            "SELECT * FROM t WHERE customer_id = ? AND day >= ? AND id IN (?, ?, ?) AND note <> '$P{customerId}' -- $P{x}"
+        );
+        assert_eq!(bound.values, vec![json!("C001"), json!("2024-01-01"), json!(1), json!(2), json!(3)]);
+
+        assert!(bind_sql_parameters("SELECT $P{missing}", &params()).is_err());
+        assert!(bind_sql_parameters("SELECT $P{customerId", &params()).is_err());
+    }
+
+    #[test]
+    fn test_substitute_path_parameters() {
+        assert_eq!(
+            substitute_path_parameters("customers.$P{customerId}.orders[$P{index}]", &params()).unwrap(),
+            "customers.C001.orders[2]"
+        );
+        assert!(substitute_path_parameters("customers.$P{missing}", &params()).is_err());
+    }
+}
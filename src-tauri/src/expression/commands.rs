@@ -0,0 +1,90 @@
+// === Tauri表达式命令 ===
+use crate::expression::functions::FUNCTIONS;
+use crate::expression::{EvalContext, Expression, ExpressionError, References};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CheckExpressionReq {
+    expression: String,
+    /// 可选的样例数据行，提供时返回求值结果
+    #[serde(default)]
+    sample_row: Option<Value>,
+    #[serde(default)]
+    parameters: HashMap<String, Value>,
+    #[serde(default)]
+    variables: HashMap<String, Value>,
+}
+
+/// 表达式检查结果，错误位置用于设计器标注
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpressionCheck {
+    pub valid: bool,
+    pub error: Option<ExpressionError>,
+    pub references: References,
+    pub deterministic: bool,
+    pub value: Option<Value>,
+}
+
+/// 解析表达式并返回错误位置、引用的字段/参数/变量，可选地针对样例行求值
+#[tauri::command]
+pub async fn check_expression(req: CheckExpressionReq) -> Result<ExpressionCheck, String> {
+    let expression = match Expression::parse(&req.expression) {
+        Ok(expression) => expression,
+        Err(error) => {
+            return Ok(ExpressionCheck {
+                valid: false,
+                error: Some(error),
+                references: References::default(),
+                deterministic: true,
+                value: None,
+            })
+        }
+    };
+
+    let mut check = ExpressionCheck {
+        valid: true,
+        error: None,
+        references: expression.references(),
+        deterministic: expression.is_deterministic(),
+        value: None,
+    };
+
+    if let Some(row) = &req.sample_row {
+        match expression.evaluate(&EvalContext::new(row, &req.parameters, &req.variables)) {
+            Ok(value) => check.value = Some(value),
+            Err(error) => {
+                check.valid = false;
+                check.error = Some(error);
+            }
+        }
+    }
+
+    Ok(check)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionDoc {
+    pub name: &'static str,
+    pub min_args: usize,
+    pub max_args: Option<usize>,
+    pub description: &'static str,
+}
+
+/// 列出表达式函数库，用于编辑器自动补全
+#[tauri::command]
+pub async fn list_expression_functions() -> Result<Vec<FunctionDoc>, String> {
+    Ok(FUNCTIONS
+        .iter()
+        .map(|function| FunctionDoc {
+            name: function.name,
+            min_args: function.min_args,
+            max_args: function.max_args,
+            description: function.description,
+        })
+        .collect())
+}
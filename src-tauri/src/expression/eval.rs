@@ -0,0 +1,303 @@
+// === 表达式求值 ===
+use crate::expression::functions;
+use crate::expression::parser::{BinaryOp, Expr, Node, UnaryOp};
+use crate::expression::{ExpressionError, ExpressionResult, Span};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// 求值上下文：当前数据行、报表参数和变量
+pub struct EvalContext<'a> {
+    pub row: &'a Value,
+    pub parameters: &'a HashMap<String, Value>,
+    pub variables: &'a HashMap<String, Value>,
+}
+
+impl<'a> EvalContext<'a> {
+    pub fn new(row: &'a Value, parameters: &'a HashMap<String, Value>, variables: &'a HashMap<String, Value>) -> Self {
+        Self { row, parameters, variables }
+    }
+}
+
+pub fn evaluate(node: &Node, context: &EvalContext) -> ExpressionResult<Value> {
+    match &node.expr {
+        Expr::Literal(value) => Ok(value.clone()),
+        Expr::Field(path) => resolve_path(context.row, path)
+            .ok_or_else(|| ExpressionError::eval(format!("Invalid field path '{}'", path), node.span)),
+        // 未传入的参数视为空值
+        Expr::Parameter(name) => Ok(context.parameters.get(name).cloned().unwrap_or(Value::Null)),
+        Expr::Variable(name) => context
+            .variables
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ExpressionError::eval(format!("Unknown variable '{}'", name), node.span)),
+        Expr::Unary { op, operand } => {
+            let value = evaluate(operand, context)?;
+            match op {
+                UnaryOp::Not => Ok(Value::Bool(!truthy(&value))),
+                UnaryOp::Neg => match &value {
+                    Value::Null => Ok(Value::Null),
+                    _ => {
+                        let number = expect_number(&value, operand.span)?;
+                        Ok(number_value(-number, is_integral(&value)))
+                    }
+                },
+            }
+        }
+        Expr::Binary { op: BinaryOp::And, left, right } => {
+            Ok(Value::Bool(truthy(&evaluate(left, context)?) && truthy(&evaluate(right, context)?)))
+        }
+        Expr::Binary { op: BinaryOp::Or, left, right } => {
+            Ok(Value::Bool(truthy(&evaluate(left, context)?) || truthy(&evaluate(right, context)?)))
+        }
+        Expr::Binary { op, left, right } => {
+            let lhs = evaluate(left, context)?;
+            let rhs = evaluate(right, context)?;
+            binary(*op, &lhs, &rhs, node.span)
+        }
+        Expr::Ternary { condition, then, otherwise } => {
+            if truthy(&evaluate(condition, context)?) {
+                evaluate(then, context)
+            } else {
+                evaluate(otherwise, context)
+            }
+        }
+        Expr::Call { name, args } => {
+            let values = args
+                .iter()
+                .map(|arg| evaluate(arg, context))
+                .collect::<ExpressionResult<Vec<_>>>()?;
+            functions::call(name, &values, node.span)
+        }
+        Expr::Member { object, name } => {
+            let object = evaluate(object, context)?;
+            Ok(object.get(name).cloned().unwrap_or(Value::Null))
+        }
+        Expr::Index { object, index } => {
+            let object = evaluate(object, context)?;
+            let key = evaluate(index, context)?;
+            match &key {
+                Value::Number(_) => {
+                    let position = key
+                        .as_u64()
+                        .ok_or_else(|| ExpressionError::eval("Array index must be a non-negative integer", index.span))?;
+                    Ok(object.get(position as usize).cloned().unwrap_or(Value::Null))
+                }
+                Value::String(key) => Ok(object.get(key).cloned().unwrap_or(Value::Null)),
+                _ => Err(ExpressionError::eval("Index must be a number or a string", index.span)),
+            }
+        }
+    }
+}
+
+fn binary(op: BinaryOp, lhs: &Value, rhs: &Value, span: Span) -> ExpressionResult<Value> {
+    match op {
+        BinaryOp::Add if lhs.is_string() || rhs.is_string() => {
+            Ok(Value::String(format!("{}{}", to_text(lhs), to_text(rhs))))
+        }
+        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => {
+            if lhs.is_null() || rhs.is_null() {
+                return Ok(Value::Null);
+            }
+            let (a, b) = (expect_number(lhs, span)?, expect_number(rhs, span)?);
+            let integral = is_integral(lhs) && is_integral(rhs);
+            let result = match op {
+                BinaryOp::Add => a + b,
+                BinaryOp::Sub => a - b,
+                BinaryOp::Mul => a * b,
+                BinaryOp::Div | BinaryOp::Rem if b == 0.0 => {
+                    return Err(ExpressionError::eval("Division by zero", span))
+                }
+                BinaryOp::Div => return Ok(number_value(a / b, integral && (a / b).fract() == 0.0)),
+                _ => a % b,
+            };
+            Ok(number_value(result, integral))
+        }
+        BinaryOp::Eq => Ok(Value::Bool(equals(lhs, rhs))),
+        BinaryOp::NotEq => Ok(Value::Bool(!equals(lhs, rhs))),
+        BinaryOp::Lt | BinaryOp::LtEq | BinaryOp::Gt | BinaryOp::GtEq => {
+            // 与空值比较总是为假
+            let Some(ordering) = compare(lhs, rhs) else {
+                return Ok(Value::Bool(false));
+            };
+            Ok(Value::Bool(match op {
+                BinaryOp::Lt => ordering == Ordering::Less,
+                BinaryOp::LtEq => ordering != Ordering::Greater,
+                BinaryOp::Gt => ordering == Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            }))
+        }
+        BinaryOp::And | BinaryOp::Or => unreachable!("logical operators short-circuit in evaluate"),
+    }
+}
+
+/// 按字段路径取值，字段不存在返回 Null；路径语法错误返回 None
+pub fn resolve_path(row: &Value, path: &str) -> Option<Value> {
+    // 优先按完整字段名匹配（列名本身可能包含点）
+    if let Some(value) = row.get(path) {
+        return Some(value.clone());
+    }
+
+    let mut current = row;
+    for segment in path.split('.') {
+        let (name, indexes) = match segment.find('[') {
+            Some(pos) => (&segment[..pos], &segment[pos..]),
+            None => (segment, ""),
+        };
+        if name.is_empty() && indexes.is_empty() {
+            return None;
+        }
+        if !name.is_empty() {
+            current = match current.get(name) {
+                Some(value) => value,
+                None => return Some(Value::Null),
+            };
+        }
+
+        let mut rest = indexes;
+        while !rest.is_empty() {
+            let close = rest.find(']')?;
+            let index: usize = rest.get(1..close)?.trim().parse().ok()?;
+            current = match current.get(index) {
+                Some(value) => value,
+                None => return Some(Value::Null),
+            };
+            rest = &rest[close + 1..];
+        }
+    }
+
+    Some(current.clone())
+}
+
+/// 条件判断：空值、false、0 和空字符串为假
+pub fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().map_or(false, |n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+/// 拼接用文本：空值为空字符串，字符串不带引号
+pub fn to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// 数字或数字字符串转为浮点数
+pub fn to_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+pub fn expect_number(value: &Value, span: Span) -> ExpressionResult<f64> {
+    to_number(value).ok_or_else(|| ExpressionError::eval(format!("Expected a number, got {}", value), span))
+}
+
+fn is_integral(value: &Value) -> bool {
+    match value {
+        Value::Number(n) => n.is_i64() || n.is_u64(),
+        Value::String(s) => s.trim().parse::<i64>().is_ok(),
+        _ => false,
+    }
+}
+
+/// 整数结果保持整数表示
+pub fn number_value(number: f64, integral: bool) -> Value {
+    if integral && number.fract() == 0.0 && number.abs() < 9.0e15 {
+        Value::from(number as i64)
+    } else {
+        serde_json::Number::from_f64(number).map(Value::Number).unwrap_or(Value::Null)
+    }
+}
+
+fn equals(lhs: &Value, rhs: &Value) -> bool {
+    match (lhs, rhs) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        _ => lhs == rhs,
+    }
+}
+
+fn compare(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Value::Null, _) | (_, Value::Null) => None,
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => to_number(lhs)?.partial_cmp(&to_number(rhs)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::expression::Expression;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    fn eval(source: &str) -> Value {
+        let row = json!({
+            "name": "Alice",
+            "amount": 1200.5,
+            "qty": 3,
+            "price": "2.5",
+            "account": { "owner": "王五", "cards": ["6222", "6228"] },
+            "empty": null
+        });
+        let parameters = HashMap::from([("rate".to_string(), json!(0.1))]);
+        let variables = HashMap::from([("total".to_string(), json!(10))]);
+        let context = super::EvalContext::new(&row, &parameters, &variables);
+        Expression::parse(source).unwrap().evaluate(&context).unwrap()
+    }
+
+    #[test]
+    fn test_arithmetic_and_concatenation() {
+        assert_eq!(eval("$F{qty} * 2 + 1"), json!(7));
+        assert_eq!(eval("$F{qty} / 2"), json!(1.5));
+        assert_eq!(eval("$F{qty} * $F{price}"), json!(7.5));
+        assert_eq!(eval("-$F{qty} % 2"), json!(-1));
+        assert_eq!(eval("$F{amount} * 2"), json!(2401.0));
+        assert_eq!(eval("'Hi ' + $F{name} + ', ' + $V{total}"), json!("Hi Alice, 10"));
+        assert_eq!(eval("$F{empty} + 1"), Value::Null);
+    }
+
+    #[test]
+    fn test_comparison_logic_and_ternary() {
+        assert_eq!(eval("$F{amount} > 1000 && $F{name} == 'Alice'"), json!(true));
+        assert_eq!(eval("!($F{qty} >= 3) || $F{empty} != null"), json!(false));
+        assert_eq!(eval("$F{empty} == null ? '-' : $F{empty}"), json!("-"));
+        assert_eq!(eval("$F{empty} < 1"), json!(false));
+        assert_eq!(eval("1 == 1.0"), json!(true));
+    }
+
+    #[test]
+    fn test_references_and_paths() {
+        assert_eq!(eval("account.owner"), json!("王五"));
+        assert_eq!(eval("$F{account.cards[1]}"), json!("6228"));
+        assert_eq!(eval("${account}.cards[0]"), json!("6222"));
+        assert_eq!(eval("account['owner']"), json!("王五"));
+        assert_eq!(eval("missing"), Value::Null);
+        assert_eq!(eval("$P{missing}"), Value::Null);
+    }
+
+    #[test]
+    fn test_evaluation_errors_carry_positions() {
+        let row = json!({ "name": "x" });
+        let empty = HashMap::new();
+        let context = super::EvalContext::new(&row, &empty, &empty);
+        let error = |source: &str| Expression::parse(source).unwrap().evaluate(&context).unwrap_err();
+
+        assert_eq!(error("1 + $V{nope}").span(), crate::expression::Span::new(4, 12));
+        assert_eq!(error("10 / (1 - 1)").message(), "Division by zero");
+        assert!(error("$F{name} * 2").message().contains("Expected a number"));
+        assert!(error("$F{name[x]}").message().contains("Invalid field path"));
+    }
+}
@@ -0,0 +1,193 @@
+// === 表达式函数库 ===
+use crate::expression::eval::{expect_number, number_value, to_number, to_text};
+use crate::expression::{ExpressionError, ExpressionResult, Span};
+use crate::format::{locale, Formatter};
+use chrono::Local;
+use serde_json::Value;
+
+/// 函数签名：名称、最少参数、最多参数（None 为不限）、说明
+pub struct FunctionInfo {
+    pub name: &'static str,
+    pub min_args: usize,
+    pub max_args: Option<usize>,
+    pub description: &'static str,
+}
+
+pub const FUNCTIONS: &[FunctionInfo] = &[
+    FunctionInfo { name: "upper", min_args: 1, max_args: Some(1), description: "转为大写" },
+    FunctionInfo { name: "lower", min_args: 1, max_args: Some(1), description: "转为小写" },
+    FunctionInfo { name: "trim", min_args: 1, max_args: Some(1), description: "去除首尾空白" },
+    FunctionInfo { name: "length", min_args: 1, max_args: Some(1), description: "字符串长度或数组元素个数" },
+    FunctionInfo { name: "substring", min_args: 2, max_args: Some(3), description: "substring(text, start[, end])，按字符从0计数" },
+    FunctionInfo { name: "concat", min_args: 0, max_args: None, description: "拼接全部参数" },
+    FunctionInfo { name: "contains", min_args: 2, max_args: Some(2), description: "是否包含子串" },
+    FunctionInfo { name: "replace", min_args: 3, max_args: Some(3), description: "replace(text, from, to)" },
+    FunctionInfo { name: "coalesce", min_args: 1, max_args: None, description: "返回第一个非空参数" },
+    FunctionInfo { name: "round", min_args: 1, max_args: Some(2), description: "round(number[, digits])，四舍五入" },
+    FunctionInfo { name: "floor", min_args: 1, max_args: Some(1), description: "向下取整" },
+    FunctionInfo { name: "ceil", min_args: 1, max_args: Some(1), description: "向上取整" },
+    FunctionInfo { name: "abs", min_args: 1, max_args: Some(1), description: "绝对值" },
+    FunctionInfo { name: "min", min_args: 1, max_args: None, description: "最小值" },
+    FunctionInfo { name: "max", min_args: 1, max_args: None, description: "最大值" },
+    FunctionInfo { name: "number", min_args: 1, max_args: Some(1), description: "转为数字" },
+    FunctionInfo { name: "string", min_args: 1, max_args: Some(1), description: "转为文本" },
+    FunctionInfo { name: "format_number", min_args: 2, max_args: Some(3), description: "format_number(number, '#,##0.00'[, 'zh-CN'])，支持 '大写金额'" },
+    FunctionInfo { name: "format_date", min_args: 2, max_args: Some(3), description: "format_date(date, 'yyyy-MM-dd'[, 'zh-CN'])" },
+    FunctionInfo { name: "now", min_args: 0, max_args: Some(0), description: "当前日期时间" },
+    FunctionInfo { name: "today", min_args: 0, max_args: Some(0), description: "当前日期" },
+];
+
+/// 函数名不区分大小写，忽略下划线（FORMAT_DATE / formatDate 均可）
+fn normalize(name: &str) -> String {
+    name.chars().filter(|c| *c != '_').flat_map(char::to_lowercase).collect()
+}
+
+pub fn lookup(name: &str) -> Option<&'static FunctionInfo> {
+    let normalized = normalize(name);
+    FUNCTIONS.iter().find(|function| normalize(function.name) == normalized)
+}
+
+/// 每次求值结果可能不同的函数
+pub fn is_volatile(name: &str) -> bool {
+    matches!(normalize(name).as_str(), "now" | "today" | "random" | "uuid")
+}
+
+pub fn call(name: &str, args: &[Value], span: Span) -> ExpressionResult<Value> {
+    let function = lookup(name).ok_or_else(|| ExpressionError::eval(format!("Unknown function '{}'", name), span))?;
+    if args.len() < function.min_args || function.max_args.map_or(false, |max| args.len() > max) {
+        let expected = match function.max_args {
+            Some(max) if max == function.min_args => format!("{}", max),
+            Some(max) => format!("{} to {}", function.min_args, max),
+            None => format!("at least {}", function.min_args),
+        };
+        return Err(ExpressionError::eval(
+            format!("Function '{}' expects {} argument(s), got {}", function.name, expected, args.len()),
+            span,
+        ));
+    }
+
+    let text = |index: usize| to_text(&args[index]);
+    let number = |index: usize| expect_number(&args[index], span);
+
+    // 除 coalesce/concat 外，首个参数为空时结果为空
+    if !matches!(function.name, "coalesce" | "concat" | "string" | "now" | "today") && args.first().map_or(false, Value::is_null) {
+        return Ok(Value::Null);
+    }
+
+    let value = match function.name {
+        "upper" => Value::String(text(0).to_uppercase()),
+        "lower" => Value::String(text(0).to_lowercase()),
+        "trim" => Value::String(text(0).trim().to_string()),
+        "length" => match &args[0] {
+            Value::Array(items) => Value::from(items.len()),
+            other => Value::from(to_text(other).chars().count()),
+        },
+        "substring" => {
+            let chars: Vec<char> = text(0).chars().collect();
+            let start = (number(1)?.max(0.0) as usize).min(chars.len());
+            let end = match args.get(2) {
+                Some(_) => (number(2)?.max(0.0) as usize).clamp(start, chars.len()),
+                None => chars.len(),
+            };
+            Value::String(chars[start..end].iter().collect())
+        }
+        "concat" => Value::String(args.iter().map(to_text).collect()),
+        "contains" => Value::Bool(text(0).contains(&text(1))),
+        "replace" => Value::String(text(0).replace(&text(1), &text(2))),
+        "coalesce" => args.iter().find(|value| !value.is_null()).cloned().unwrap_or(Value::Null),
+        "round" => {
+            let digits = match args.get(1) {
+                Some(_) => number(1)? as i32,
+                None => 0,
+            };
+            let factor = 10f64.powi(digits);
+            // 先按十进制修正浮点误差，再四舍五入（远离零）
+            let scaled = format!("{:.12}", number(0)? * factor).parse::<f64>().unwrap_or_default();
+            number_value(scaled.round() / factor, digits <= 0)
+        }
+        "floor" => number_value(number(0)?.floor(), true),
+        "ceil" => number_value(number(0)?.ceil(), true),
+        "abs" => number_value(number(0)?.abs(), args[0].is_i64() || args[0].is_u64()),
+        "min" | "max" => {
+            let mut best: Option<&Value> = None;
+            for value in args.iter().filter(|value| !value.is_null()) {
+                let candidate = expect_number(value, span)?;
+                let better = best.map_or(true, |best| {
+                    let current = to_number(best).unwrap_or_default();
+                    if function.name == "min" { candidate < current } else { candidate > current }
+                });
+                if better {
+                    best = Some(value);
+                }
+            }
+            best.cloned().unwrap_or(Value::Null)
+        }
+        "number" => match to_number(&args[0]) {
+            Some(value) => number_value(value, value.fract() == 0.0),
+            None => return Err(ExpressionError::eval(format!("Cannot convert {} to a number", args[0]), span)),
+        },
+        "string" => Value::String(text(0)),
+        "format_number" | "format_date" => {
+            let locale = args.get(2).map_or(locale::DEFAULT_LOCALE.to_string(), to_text);
+            if function.name == "format_number" {
+                number(0)?;
+            }
+            Formatter::new(&locale)
+                .try_format(&args[0], Some(&text(1)))
+                .map(Value::String)
+                .map_err(|e| ExpressionError::eval(e.to_string(), span))?
+        }
+        "now" => Value::String(Local::now().format("%Y-%m-%d %H:%M:%S").to_string()),
+        "today" => Value::String(Local::now().format("%Y-%m-%d").to_string()),
+        other => unreachable!("function '{}' is registered but not implemented", other),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::expression::{EvalContext, Expression};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    fn eval(source: &str) -> Result<Value, String> {
+        let row = json!({ "name": "alice", "amount": 1234.565, "date": "2024-03-05 08:09:10", "nick": null });
+        let empty = HashMap::new();
+        Expression::parse(source)
+            .unwrap()
+            .evaluate(&EvalContext::new(&row, &empty, &empty))
+            .map_err(|e| e.message().to_string())
+    }
+
+    #[test]
+    fn test_string_functions() {
+        assert_eq!(eval("upper($F{name})").unwrap(), json!("ALICE"));
+        assert_eq!(eval("LOWER('ABC')").unwrap(), json!("abc"));
+        assert_eq!(eval("substring('报表设计器', 2)").unwrap(), json!("设计器"));
+        assert_eq!(eval("substring($F{name}, 1, 3)").unwrap(), json!("li"));
+        assert_eq!(eval("coalesce($F{nick}, $F{name}, 'x')").unwrap(), json!("alice"));
+        assert_eq!(eval("concat('a', 1, null, true)").unwrap(), json!("a1true"));
+        assert_eq!(eval("length(upper($F{nick}))").unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_number_and_date_functions() {
+        assert_eq!(eval("round($F{amount}, 2)").unwrap(), json!(1234.57));
+        assert_eq!(eval("round(2.5)").unwrap(), json!(3));
+        assert_eq!(eval("round(-2.5)").unwrap(), json!(-3));
+        assert_eq!(eval("max(1, 5.5, null, 3)").unwrap(), json!(5.5));
+        assert_eq!(eval("formatNumber($F{amount}, '#,##0.0')").unwrap(), json!("1,234.6"));
+        assert_eq!(eval("format_date($F{date}, 'yyyy/MM/dd')").unwrap(), json!("2024/03/05"));
+        assert_eq!(eval("format_date($F{date}, 'd MMMM', 'en_US')").unwrap(), json!("5 March"));
+        assert_eq!(eval("format_number($F{amount}, '#,##0.00', 'de-DE')").unwrap(), json!("1.234,57"));
+        assert_eq!(eval("format_number(120, '大写金额')").unwrap(), json!("壹佰贰拾元整"));
+    }
+
+    #[test]
+    fn test_function_errors() {
+        assert!(eval("nope(1)").unwrap_err().contains("Unknown function"));
+        assert!(eval("upper()").unwrap_err().contains("expects 1 argument"));
+        assert!(eval("round('x')").unwrap_err().contains("Expected a number"));
+        assert!(eval("format_date($F{name}, 'yyyy')").unwrap_err().contains("Cannot format"));
+    }
+}
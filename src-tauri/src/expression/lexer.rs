@@ -0,0 +1,250 @@
+// === 表达式词法分析 ===
+use crate::expression::{ExpressionError, Span};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Number(f64),
+    Integer(i64),
+    Str(String),
+    Ident(String),
+    /// $F{字段} 或 ${字段}
+    Field(String),
+    /// $P{参数}
+    Parameter(String),
+    /// $V{变量}
+    Variable(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+    Not,
+    Question,
+    Colon,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Dot,
+    Eof,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+/// 词法分析，位置以字符偏移计
+pub fn tokenize(source: &str) -> Result<Vec<Token>, ExpressionError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+        if c.is_whitespace() {
+            pos += 1;
+            continue;
+        }
+
+        let start = pos;
+        let kind = match c {
+            '$' => {
+                let (kind, end) = read_reference(&chars, pos)?;
+                pos = end;
+                kind
+            }
+            '"' | '\'' => {
+                let (text, end) = read_string(&chars, pos)?;
+                pos = end;
+                TokenKind::Str(text)
+            }
+            c if c.is_ascii_digit() => {
+                let (kind, end) = read_number(&chars, pos)?;
+                pos = end;
+                kind
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while pos < chars.len() && (chars[pos].is_alphanumeric() || chars[pos] == '_') {
+                    pos += 1;
+                }
+                TokenKind::Ident(chars[start..pos].iter().collect())
+            }
+            _ => {
+                let next = chars.get(pos + 1).copied();
+                let (kind, width) = match (c, next) {
+                    ('=', Some('=')) => (TokenKind::Eq, 2),
+                    ('!', Some('=')) => (TokenKind::NotEq, 2),
+                    ('<', Some('=')) => (TokenKind::LtEq, 2),
+                    ('>', Some('=')) => (TokenKind::GtEq, 2),
+                    ('<', Some('>')) => (TokenKind::NotEq, 2),
+                    ('&', Some('&')) => (TokenKind::And, 2),
+                    ('|', Some('|')) => (TokenKind::Or, 2),
+                    ('+', _) => (TokenKind::Plus, 1),
+                    ('-', _) => (TokenKind::Minus, 1),
+                    ('*', _) => (TokenKind::Star, 1),
+                    ('/', _) => (TokenKind::Slash, 1),
+                    ('%', _) => (TokenKind::Percent, 1),
+                    ('<', _) => (TokenKind::Lt, 1),
+                    ('>', _) => (TokenKind::Gt, 1),
+                    ('!', _) => (TokenKind::Not, 1),
+                    ('?', _) => (TokenKind::Question, 1),
+                    (':', _) => (TokenKind::Colon, 1),
+                    ('(', _) => (TokenKind::LParen, 1),
+                    (')', _) => (TokenKind::RParen, 1),
+                    ('[', _) => (TokenKind::LBracket, 1),
+                    (']', _) => (TokenKind::RBracket, 1),
+                    (',', _) => (TokenKind::Comma, 1),
+                    ('.', _) => (TokenKind::Dot, 1),
+                    ('=', _) => {
+                        return Err(ExpressionError::parse("Unexpected '=', use '==' for comparison", start, start + 1))
+                    }
+                    _ => return Err(ExpressionError::parse(format!("Unexpected character '{}'", c), start, start + 1)),
+                };
+                pos += width;
+                kind
+            }
+        };
+
+        tokens.push(Token { kind, span: Span::new(start, pos) });
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::Eof,
+        span: Span::new(chars.len(), chars.len()),
+    });
+    Ok(tokens)
+}
+
+/// 读取 $F{..} / $P{..} / $V{..} / ${..} 引用
+fn read_reference(chars: &[char], start: usize) -> Result<(TokenKind, usize), ExpressionError> {
+    let (prefix, open) = match chars.get(start + 1) {
+        Some('{') => ('F', start + 1),
+        Some(&c @ ('F' | 'P' | 'V')) if chars.get(start + 2) == Some(&'{') => (c, start + 2),
+        _ => {
+            return Err(ExpressionError::parse(
+                "Expected a reference like $F{name}, $P{name} or $V{name}",
+                start,
+                start + 1,
+            ))
+        }
+    };
+
+    let close = (open + 1..chars.len())
+        .find(|&index| chars[index] == '}')
+        .ok_or_else(|| ExpressionError::parse("Unclosed reference, missing '}'", start, chars.len()))?;
+    let name: String = chars[open + 1..close].iter().collect::<String>().trim().to_string();
+    if name.is_empty() {
+        return Err(ExpressionError::parse("Empty reference name", start, close + 1));
+    }
+
+    let kind = match prefix {
+        'P' => TokenKind::Parameter(name),
+        'V' => TokenKind::Variable(name),
+        _ => TokenKind::Field(name),
+    };
+    Ok((kind, close + 1))
+}
+
+fn read_string(chars: &[char], start: usize) -> Result<(String, usize), ExpressionError> {
+    let quote = chars[start];
+    let mut text = String::new();
+    let mut pos = start + 1;
+
+    while pos < chars.len() {
+        match chars[pos] {
+            c if c == quote => return Ok((text, pos + 1)),
+            '\\' => {
+                let escaped = chars
+                    .get(pos + 1)
+                    .ok_or_else(|| ExpressionError::parse("Unterminated string", start, chars.len()))?;
+                text.push(match escaped {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    other => *other,
+                });
+                pos += 2;
+            }
+            c => {
+                text.push(c);
+                pos += 1;
+            }
+        }
+    }
+
+    Err(ExpressionError::parse("Unterminated string", start, chars.len()))
+}
+
+fn read_number(chars: &[char], start: usize) -> Result<(TokenKind, usize), ExpressionError> {
+    let mut pos = start;
+    while pos < chars.len() && chars[pos].is_ascii_digit() {
+        pos += 1;
+    }
+
+    // 小数点后必须是数字，否则视为成员访问
+    let is_float = chars.get(pos) == Some(&'.') && chars.get(pos + 1).map_or(false, |c| c.is_ascii_digit());
+    if is_float {
+        pos += 1;
+        while pos < chars.len() && chars[pos].is_ascii_digit() {
+            pos += 1;
+        }
+    }
+
+    let text: String = chars[start..pos].iter().collect();
+    let invalid = || ExpressionError::parse(format!("Invalid number '{}'", text), start, pos);
+    let kind = if is_float {
+        TokenKind::Number(text.parse().map_err(|_| invalid())?)
+    } else {
+        match text.parse::<i64>() {
+            Ok(integer) => TokenKind::Integer(integer),
+            Err(_) => TokenKind::Number(text.parse().map_err(|_| invalid())?),
+        }
+    };
+    Ok((kind, pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(source: &str) -> Vec<TokenKind> {
+        tokenize(source).unwrap().into_iter().map(|token| token.kind).collect()
+    }
+
+    #[test]
+    fn test_tokenize_references_and_operators() {
+        assert_eq!(
+            kinds("$F{amount} * 1.5 >= ${ limit } && $P{x} != 'a\\'b'"),
+            vec![
+                TokenKind::Field("amount".to_string()),
+                TokenKind::Star,
+                TokenKind::Number(1.5),
+                TokenKind::GtEq,
+                TokenKind::Field("limit".to_string()),
+                TokenKind::And,
+                TokenKind::Parameter("x".to_string()),
+                TokenKind::NotEq,
+                TokenKind::Str("a'b".to_string()),
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_errors_carry_positions() {
+        assert_eq!(tokenize("1 + $F{name").unwrap_err().span(), Span::new(4, 11));
+        assert_eq!(tokenize("a = 1").unwrap_err().span(), Span::new(2, 3));
+        assert_eq!(tokenize("\"abc").unwrap_err().span(), Span::new(0, 4));
+    }
+}
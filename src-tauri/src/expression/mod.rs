@@ -0,0 +1,134 @@
+pub mod commands;
+pub mod eval;
+pub mod functions;
+pub mod lexer;
+pub mod parser;
+
+// Re-export main types for convenience
+pub use eval::EvalContext;
+pub use parser::{parse, Expr, Node};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use thiserror::Error;
+
+/// 表达式中的位置区间（字符偏移，左闭右开）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// 覆盖两个区间的最小区间
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
+
+// Expression-specific error types
+#[derive(Debug, Clone, Error, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ExpressionError {
+    #[error("Parse error at {start}: {message}")]
+    Parse { message: String, start: usize, end: usize },
+
+    #[error("Evaluation error at {start}: {message}")]
+    Eval { message: String, start: usize, end: usize },
+}
+
+impl ExpressionError {
+    pub fn parse(message: impl Into<String>, start: usize, end: usize) -> Self {
+        Self::Parse { message: message.into(), start, end }
+    }
+
+    pub fn eval(message: impl Into<String>, span: Span) -> Self {
+        Self::Eval { message: message.into(), start: span.start, end: span.end }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Parse { start, end, .. } | Self::Eval { start, end, .. } => Span::new(*start, *end),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Parse { message, .. } | Self::Eval { message, .. } => message,
+        }
+    }
+}
+
+pub type ExpressionResult<T> = Result<T, ExpressionError>;
+
+/// 已解析的表达式
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    source: String,
+    root: Node,
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> ExpressionResult<Self> {
+        Ok(Self {
+            source: source.to_string(),
+            root: parse(source)?,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn root(&self) -> &Node {
+        &self.root
+    }
+
+    /// 针对一行数据求值
+    pub fn evaluate(&self, context: &EvalContext) -> ExpressionResult<Value> {
+        eval::evaluate(&self.root, context)
+    }
+
+    /// 表达式引用的字段、参数和变量
+    pub fn references(&self) -> References {
+        let mut references = References::default();
+        self.root.walk(&mut |node| match &node.expr {
+            Expr::Field(name) => {
+                references.fields.insert(name.clone());
+            }
+            Expr::Parameter(name) => {
+                references.parameters.insert(name.clone());
+            }
+            Expr::Variable(name) => {
+                references.variables.insert(name.clone());
+            }
+            _ => {}
+        });
+        references
+    }
+
+    /// 不含非确定性函数（now/today/random/uuid）时结果可以缓存
+    pub fn is_deterministic(&self) -> bool {
+        let mut deterministic = true;
+        self.root.walk(&mut |node| {
+            if let Expr::Call { name, .. } = &node.expr {
+                deterministic &= !functions::is_volatile(name);
+            }
+        });
+        deterministic
+    }
+}
+
+/// 表达式引用汇总
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct References {
+    pub fields: BTreeSet<String>,
+    pub parameters: BTreeSet<String>,
+    pub variables: BTreeSet<String>,
+}
@@ -0,0 +1,300 @@
+// === 表达式语法分析 ===
+use crate::expression::lexer::{tokenize, Token, TokenKind};
+use crate::expression::{ExpressionError, Span};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+}
+
+/// 语法树节点
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    /// 字段引用，名称可以是路径（a.b[0]）
+    Field(String),
+    Parameter(String),
+    Variable(String),
+    Unary { op: UnaryOp, operand: Box<Node> },
+    Binary { op: BinaryOp, left: Box<Node>, right: Box<Node> },
+    Ternary { condition: Box<Node>, then: Box<Node>, otherwise: Box<Node> },
+    Call { name: String, args: Vec<Node> },
+    Member { object: Box<Node>, name: String },
+    Index { object: Box<Node>, index: Box<Node> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub expr: Expr,
+    pub span: Span,
+}
+
+impl Node {
+    fn new(expr: Expr, span: Span) -> Self {
+        Self { expr, span }
+    }
+
+    /// 深度优先遍历全部节点
+    pub fn walk<'a>(&'a self, visit: &mut impl FnMut(&'a Node)) {
+        visit(self);
+        match &self.expr {
+            Expr::Unary { operand, .. } => operand.walk(visit),
+            Expr::Binary { left, right, .. } => {
+                left.walk(visit);
+                right.walk(visit);
+            }
+            Expr::Ternary { condition, then, otherwise } => {
+                condition.walk(visit);
+                then.walk(visit);
+                otherwise.walk(visit);
+            }
+            Expr::Call { args, .. } => args.iter().for_each(|arg| arg.walk(visit)),
+            Expr::Member { object, .. } => object.walk(visit),
+            Expr::Index { object, index } => {
+                object.walk(visit);
+                index.walk(visit);
+            }
+            Expr::Literal(_) | Expr::Field(_) | Expr::Parameter(_) | Expr::Variable(_) => {}
+        }
+    }
+}
+
+/// 解析表达式文本为语法树
+pub fn parse(source: &str) -> Result<Node, ExpressionError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let node = parser.ternary()?;
+
+    let token = parser.peek();
+    if token.kind != TokenKind::Eof {
+        return Err(ExpressionError::parse("Unexpected token after end of expression", token.span.start, token.span.end));
+    }
+    Ok(node)
+}
+
+/// 递归下降解析器，优先级从低到高：三元 > || > && > 相等 > 比较 > 加减 > 乘除 > 一元 > 后缀
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if token.kind != TokenKind::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        if &self.peek().kind == kind {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind, what: &str) -> Result<Token, ExpressionError> {
+        if self.peek().kind == kind {
+            return Ok(self.advance());
+        }
+        let token = self.peek();
+        let found = if token.kind == TokenKind::Eof { "end of expression" } else { "unexpected token" };
+        Err(ExpressionError::parse(format!("Expected {}, found {}", what, found), token.span.start, token.span.end))
+    }
+
+    fn ternary(&mut self) -> Result<Node, ExpressionError> {
+        let condition = self.binary(0)?;
+        if !self.eat(&TokenKind::Question) {
+            return Ok(condition);
+        }
+        let then = self.ternary()?;
+        self.expect(TokenKind::Colon, "':' in conditional expression")?;
+        let otherwise = self.ternary()?;
+
+        let span = condition.span.to(otherwise.span);
+        Ok(Node::new(
+            Expr::Ternary {
+                condition: Box::new(condition),
+                then: Box::new(then),
+                otherwise: Box::new(otherwise),
+            },
+            span,
+        ))
+    }
+
+    /// 二元运算符按优先级爬升解析，全部左结合
+    fn binary(&mut self, min_level: usize) -> Result<Node, ExpressionError> {
+        const LEVELS: [&[(TokenKind, BinaryOp)]; 6] = [
+            &[(TokenKind::Or, BinaryOp::Or)],
+            &[(TokenKind::And, BinaryOp::And)],
+            &[(TokenKind::Eq, BinaryOp::Eq), (TokenKind::NotEq, BinaryOp::NotEq)],
+            &[
+                (TokenKind::Lt, BinaryOp::Lt),
+                (TokenKind::LtEq, BinaryOp::LtEq),
+                (TokenKind::Gt, BinaryOp::Gt),
+                (TokenKind::GtEq, BinaryOp::GtEq),
+            ],
+            &[(TokenKind::Plus, BinaryOp::Add), (TokenKind::Minus, BinaryOp::Sub)],
+            &[(TokenKind::Star, BinaryOp::Mul), (TokenKind::Slash, BinaryOp::Div), (TokenKind::Percent, BinaryOp::Rem)],
+        ];
+
+        if min_level >= LEVELS.len() {
+            return self.unary();
+        }
+
+        let mut left = self.binary(min_level + 1)?;
+        loop {
+            let op = LEVELS[min_level]
+                .iter()
+                .find(|(kind, _)| kind == &self.peek().kind)
+                .map(|(_, op)| *op);
+            let Some(op) = op else { break };
+            self.advance();
+
+            let right = self.binary(min_level + 1)?;
+            let span = left.span.to(right.span);
+            left = Node::new(Expr::Binary { op, left: Box::new(left), right: Box::new(right) }, span);
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Node, ExpressionError> {
+        let op = match self.peek().kind {
+            TokenKind::Minus => UnaryOp::Neg,
+            TokenKind::Not => UnaryOp::Not,
+            _ => return self.postfix(),
+        };
+        let start = self.advance().span;
+        let operand = self.unary()?;
+        let span = start.to(operand.span);
+        Ok(Node::new(Expr::Unary { op, operand: Box::new(operand) }, span))
+    }
+
+    fn postfix(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.primary()?;
+        loop {
+            if self.eat(&TokenKind::Dot) {
+                let token = self.advance();
+                let TokenKind::Ident(name) = token.kind else {
+                    return Err(ExpressionError::parse("Expected a member name after '.'", token.span.start, token.span.end));
+                };
+                let span = node.span.to(token.span);
+                node = Node::new(Expr::Member { object: Box::new(node), name }, span);
+            } else if self.eat(&TokenKind::LBracket) {
+                let index = self.ternary()?;
+                let close = self.expect(TokenKind::RBracket, "']'")?;
+                let span = node.span.to(close.span);
+                node = Node::new(Expr::Index { object: Box::new(node), index: Box::new(index) }, span);
+            } else {
+                return Ok(node);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Node, ExpressionError> {
+        let token = self.advance();
+        let expr = match token.kind {
+            TokenKind::Integer(value) => Expr::Literal(Value::from(value)),
+            TokenKind::Number(value) => Expr::Literal(
+                serde_json::Number::from_f64(value).map(Value::Number).unwrap_or(Value::Null),
+            ),
+            TokenKind::Str(text) => Expr::Literal(Value::String(text)),
+            TokenKind::Field(name) => Expr::Field(name),
+            TokenKind::Parameter(name) => Expr::Parameter(name),
+            TokenKind::Variable(name) => Expr::Variable(name),
+            TokenKind::LParen => {
+                let inner = self.ternary()?;
+                let close = self.expect(TokenKind::RParen, "')'")?;
+                return Ok(Node::new(inner.expr, token.span.to(close.span)));
+            }
+            TokenKind::Ident(name) => match name.as_str() {
+                "true" => Expr::Literal(Value::Bool(true)),
+                "false" => Expr::Literal(Value::Bool(false)),
+                "null" => Expr::Literal(Value::Null),
+                _ if self.peek().kind == TokenKind::LParen => return self.call(name, token.span),
+                // 裸标识符按字段处理，兼容旧模板中的 customerName / a.b 写法
+                _ => Expr::Field(name),
+            },
+            TokenKind::Eof => {
+                return Err(ExpressionError::parse("Unexpected end of expression", token.span.start, token.span.end))
+            }
+            _ => return Err(ExpressionError::parse("Unexpected token", token.span.start, token.span.end)),
+        };
+        Ok(Node::new(expr, token.span))
+    }
+
+    fn call(&mut self, name: String, name_span: Span) -> Result<Node, ExpressionError> {
+        self.expect(TokenKind::LParen, "'('")?;
+        let mut args = Vec::new();
+        if self.peek().kind != TokenKind::RParen {
+            loop {
+                args.push(self.ternary()?);
+                if !self.eat(&TokenKind::Comma) {
+                    break;
+                }
+            }
+        }
+        let close = self.expect(TokenKind::RParen, "')' after function arguments")?;
+        Ok(Node::new(Expr::Call { name, args }, name_span.to(close.span)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precedence_and_spans() {
+        let node = parse("1 + 2 * 3 == 7 ? 'a' : 'b'").unwrap();
+        let Expr::Ternary { condition, .. } = &node.expr else { panic!("expected ternary") };
+        let Expr::Binary { op: BinaryOp::Eq, left, .. } = &condition.expr else { panic!("expected ==") };
+        let Expr::Binary { op: BinaryOp::Add, right, .. } = &left.expr else { panic!("expected +") };
+        assert!(matches!(right.expr, Expr::Binary { op: BinaryOp::Mul, .. }));
+        assert_eq!(right.span, Span::new(4, 9));
+        assert_eq!(node.span, Span::new(0, 26));
+    }
+
+    #[test]
+    fn test_member_index_and_calls() {
+        let node = parse("upper(account.cards[0])").unwrap();
+        let Expr::Call { name, args } = &node.expr else { panic!("expected call") };
+        assert_eq!(name, "upper");
+        let Expr::Index { object, .. } = &args[0].expr else { panic!("expected index") };
+        assert!(matches!(&object.expr, Expr::Member { name, .. } if name == "cards"));
+    }
+
+    #[test]
+    fn test_parse_errors_carry_positions() {
+        assert_eq!(parse("1 +").unwrap_err().span(), Span::new(3, 3));
+        assert_eq!(parse("(1 + 2").unwrap_err().span(), Span::new(6, 6));
+        assert_eq!(parse("a b").unwrap_err().span(), Span::new(2, 3));
+        assert_eq!(parse("x ? 1").unwrap_err().span(), Span::new(5, 5));
+        assert_eq!(parse("round(1,)").unwrap_err().span(), Span::new(8, 9));
+    }
+}
@@ -0,0 +1,133 @@
+// === 图表数据填充 ===
+use crate::core::chart::series_name;
+use crate::core::element::{ChartConfig, ChartData, ChartSeries, ChartType};
+use crate::fill::{FillEngine, FillResult};
+use serde_json::Value;
+
+/// 按记录求值分类、系列和数值表达式：分类和系列按首次出现的顺序排列，
+/// 同一分类和系列的多条记录按 `aggregate` 汇总；散点图的每条记录是一个点
+pub(crate) fn chart_data(engine: &FillEngine, config: &ChartConfig, rows: &[Value], locale: &str) -> FillResult<ChartData> {
+    let mut categories: Vec<String> = Vec::new();
+    let mut series: Vec<(String, Vec<Vec<Value>>, Vec<[f64; 2]>)> = Vec::new();
+    for row in rows {
+        let category = engine.evaluate_expression(&config.category_expression, row)?;
+        let value = engine.evaluate_expression(&config.value_expression, row)?;
+        let name = match &config.series_expression {
+            Some(expression) => engine.format_value(&engine.evaluate_expression(expression, row)?, None),
+            None => series_name(&config.value_expression),
+        };
+        let index = match series.iter().position(|(existing, _, _)| *existing == name) {
+            Some(index) => index,
+            None => {
+                series.push((name, Vec::new(), Vec::new()));
+                series.len() - 1
+            }
+        };
+
+        if config.chart_type == ChartType::Scatter {
+            if let (Some(x), Some(y)) = (number(&category), number(&value)) {
+                series[index].2.push([x, y]);
+            }
+            continue;
+        }
+        let category = engine.format_value(&category, None);
+        let position = match categories.iter().position(|existing| *existing == category) {
+            Some(position) => position,
+            None => {
+                categories.push(category);
+                categories.len() - 1
+            }
+        };
+        let values = &mut series[index].1;
+        if values.len() <= position {
+            values.resize(position + 1, Vec::new());
+        }
+        values[position].push(value);
+    }
+
+    let series = series
+        .into_iter()
+        .map(|(name, groups, points)| ChartSeries {
+            name,
+            values: (0..categories.len())
+                .map(|position| {
+                    let values: Vec<&Value> = groups.get(position).map(|values| values.iter().collect()).unwrap_or_default();
+                    if values.is_empty() {
+                        return None;
+                    }
+                    number(&config.aggregate.apply(&values))
+                })
+                .collect(),
+            points,
+        })
+        .collect();
+    Ok(ChartData { categories, series, locale: Some(locale.to_string()) })
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::types::AggregateFunction;
+    use serde_json::json;
+
+    fn rows() -> Vec<Value> {
+        vec![
+            json!({"month": "Jan", "region": "North", "amount": 10}),
+            json!({"month": "Jan", "region": "South", "amount": 4}),
+            json!({"month": "Feb", "region": "North", "amount": 7}),
+            json!({"month": "Jan", "region": "North", "amount": 5}),
+            json!({"month": "Mar", "region": "South", "amount": "2.5"}),
+        ]
+    }
+
+    #[test]
+    fn test_chart_data_groups_by_category_and_series() {
+        let config = ChartConfig {
+            category_expression: "$F{month}".to_string(),
+            series_expression: Some("$F{region}".to_string()),
+            value_expression: "$F{amount}".to_string(),
+            ..ChartConfig::default()
+        };
+        let data = chart_data(&FillEngine::new(), &config, &rows(), "en-US").unwrap();
+
+        assert_eq!(data.categories, vec!["Jan", "Feb", "Mar"]);
+        assert_eq!(data.series.len(), 2);
+        assert_eq!(data.series[0].name, "North");
+        assert_eq!(data.series[0].values, vec![Some(15.0), Some(7.0), None]);
+        assert_eq!(data.series[1].values, vec![Some(4.0), None, Some(2.5)]);
+        assert_eq!(data.locale.as_deref(), Some("en-US"));
+    }
+
+    #[test]
+    fn test_chart_data_single_series_and_scatter() {
+        let config = ChartConfig {
+            category_expression: "$F{month}".to_string(),
+            value_expression: "$F{amount}".to_string(),
+            aggregate: AggregateFunction::Count,
+            ..ChartConfig::default()
+        };
+        let data = chart_data(&FillEngine::new(), &config, &rows(), "en-US").unwrap();
+        assert_eq!(data.series.len(), 1);
+        assert_eq!(data.series[0].name, "amount");
+        assert_eq!(data.series[0].values, vec![Some(3.0), Some(1.0), Some(1.0)]);
+
+        let scatter = ChartConfig {
+            chart_type: ChartType::Scatter,
+            category_expression: "$F{x}".to_string(),
+            value_expression: "$F{y}".to_string(),
+            ..ChartConfig::default()
+        };
+        let points = vec![json!({"x": 1, "y": 2}), json!({"x": "bad", "y": 3}), json!({"x": 4.5, "y": -1})];
+        let data = chart_data(&FillEngine::new(), &scatter, &points, "en-US").unwrap();
+        assert!(data.categories.is_empty());
+        assert_eq!(data.series[0].points, vec![[1.0, 2.0], [4.5, -1.0]]);
+    }
+}
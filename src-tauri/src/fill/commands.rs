@@ -0,0 +1,76 @@
+// === Tauri报表填充命令 ===
+use crate::core::template::{JasperTemplate, Parameter};
+use crate::data::ManagedDataRegistry;
+use crate::fill::parameters::{resolve_parameters, ParameterValidation};
+use crate::fill::{FillEngine, FilledReport};
+use crate::format::Formatter;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::State;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FillReportReq {
+    template: JasperTemplate,
+    #[serde(default)]
+    parameters: HashMap<String, Value>,
+    /// 模板文件路径，子报表的相对路径按其所在目录解析
+    #[serde(default)]
+    template_path: Option<String>,
+}
+
+/// 使用模板绑定的数据源填充报表，返回可逐页渲染的页面模型
+#[tauri::command]
+pub async fn fill_report(
+    req: FillReportReq,
+    registry: State<'_, ManagedDataRegistry>
+) -> Result<FilledReport, String> {
+    let parameters = resolve_parameters(&req.template.parameters, &req.parameters);
+    if !parameters.valid {
+        return Err(format!("Invalid report parameters: {}", parameters.error_message()));
+    }
+
+    // REPORT_LOCALE 参数覆盖模板的区域设置
+    let settings = &req.template.formatting;
+    let locale = parameters.values.get("REPORT_LOCALE").and_then(Value::as_str).unwrap_or(&settings.locale);
+    let formatter = Formatter::new(locale).with_null_text(settings.null_text.clone());
+
+    let mut engine = FillEngine::with_parameters(parameters.values).with_formatter(formatter);
+    if let Some(base_dir) = req.template_path.as_deref().and_then(|path| Path::new(path).parent()) {
+        engine = engine.with_base_dir(base_dir);
+    }
+
+    let registry = registry.lock().await;
+    engine
+        .fill_template(&req.template, &registry)
+        .await
+        .map_err(|e| format!("Failed to fill report: {}", e))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TemplateParametersReq {
+    template: JasperTemplate,
+}
+
+/// 获取模板声明的参数，用于运行前提示用户输入
+#[tauri::command]
+pub async fn get_template_parameters(req: TemplateParametersReq) -> Result<Vec<Parameter>, String> {
+    Ok(req.template.parameters)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ValidateParametersReq {
+    template: JasperTemplate,
+    #[serde(default)]
+    values: HashMap<String, Value>,
+}
+
+/// 按模板参数定义校验用户输入，返回转换后的参数值和逐项错误
+#[tauri::command]
+pub async fn validate_parameter_values(req: ValidateParametersReq) -> Result<ParameterValidation, String> {
+    Ok(resolve_parameters(&req.template.parameters, &req.values))
+}
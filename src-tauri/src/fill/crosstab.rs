@@ -0,0 +1,220 @@
+// === 交叉表填充 ===
+use crate::core::crosstab::{with_totals, Crosstab};
+use crate::core::element::{CrosstabConfig, CrosstabGroup};
+use crate::data::types::{Aggregation, AggregationQuery};
+use crate::data::utils::{aggregate_rows, field_value};
+use crate::fill::layout::{compare_values, Expansion};
+use crate::fill::FillEngine;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 交叉表展开为单元格，列表头跨页时重复
+pub(crate) fn fill_crosstab(engine: &FillEngine, config: &CrosstabConfig, rows: &[Value]) -> Expansion {
+    let format = |value: &Value, format: Option<&str>| engine.format_value(value, format);
+    let grid = pivot(config, rows).grid(config, &format);
+    Expansion {
+        elements: grid.elements,
+        height: grid.height,
+        header: Some((grid.header_height, grid.header)),
+    }
+}
+
+/// 透视汇总：行列条目按组值排序并插入小计/总计，每个单元格按行列前缀分组汇总全部度量
+pub(crate) fn pivot(config: &CrosstabConfig, rows: &[Value]) -> Crosstab {
+    let row_entries = with_totals(&config.row_groups, group_keys(&config.row_groups, rows));
+    let column_entries = with_totals(&config.column_groups, group_keys(&config.column_groups, rows));
+
+    let aggregations: Vec<Aggregation> = config
+        .measures
+        .iter()
+        .enumerate()
+        .map(|(index, measure)| Aggregation {
+            field: measure.field.clone(),
+            function: measure.function.clone(),
+            alias: Some(format!("measure_{}", index)),
+        })
+        .collect();
+    let fields = |groups: &[CrosstabGroup]| groups.iter().map(|group| group.field.clone()).collect::<Vec<_>>();
+    let (row_fields, column_fields) = (fields(&config.row_groups), fields(&config.column_groups));
+
+    // 每种（行前缀长度, 列前缀长度）组合汇总一次，按组值查找
+    let mut summaries: HashMap<(usize, usize), HashMap<String, Value>> = HashMap::new();
+    let mut values = Vec::with_capacity(row_entries.len());
+    for row_entry in &row_entries {
+        let mut cells = Vec::with_capacity(column_entries.len());
+        for column_entry in &column_entries {
+            let summary = summaries.entry((row_entry.len(), column_entry.len())).or_insert_with(|| {
+                let group_by: Vec<String> = row_fields[..row_entry.len()]
+                    .iter()
+                    .chain(&column_fields[..column_entry.len()])
+                    .cloned()
+                    .collect();
+                let query = AggregationQuery { group_by: group_by.clone(), aggregations: aggregations.clone() };
+                aggregate_rows(rows, &query)
+                    .into_iter()
+                    .map(|summary| {
+                        let key: Vec<&Value> = group_by.iter().map(|field| &summary[field]).collect();
+                        (serde_json::to_string(&key).unwrap_or_default(), summary)
+                    })
+                    .collect()
+            });
+            let key: Vec<&Value> = row_entry.iter().chain(column_entry).collect();
+            let cell = summary.get(&serde_json::to_string(&key).unwrap_or_default());
+            cells.push(
+                (0..aggregations.len())
+                    .map(|index| cell.map_or(Value::Null, |cell| cell[format!("measure_{}", index)].clone()))
+                    .collect(),
+            );
+        }
+        values.push(cells);
+    }
+
+    Crosstab { rows: row_entries, columns: column_entries, values }
+}
+
+/// 数据中出现过的完整组值，按组值排序去重
+fn group_keys(groups: &[CrosstabGroup], rows: &[Value]) -> Vec<Vec<Value>> {
+    if groups.is_empty() {
+        return Vec::new();
+    }
+    let mut keys: Vec<Vec<Value>> = rows
+        .iter()
+        .map(|row| groups.iter().map(|group| field_value(row, &group.field).clone()).collect())
+        .collect();
+    keys.sort_by(|a: &Vec<Value>, b: &Vec<Value>| {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| compare_values(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    keys.dedup();
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{CrosstabMeasure, ElementContent, Position, ReportElement, Size};
+    use crate::core::template::{BandType, PageMargins, SplitType};
+    use crate::data::types::AggregateFunction;
+    use crate::fill::{BandLayout, LayoutEngine, PageSetup};
+    use serde_json::json;
+
+    fn group(field: &str, show_total: bool) -> CrosstabGroup {
+        CrosstabGroup { field: field.to_string(), label: None, format: None, show_total }
+    }
+
+    fn measure(field: &str, function: AggregateFunction) -> CrosstabMeasure {
+        CrosstabMeasure { field: field.to_string(), function, label: None, format: None }
+    }
+
+    fn sales() -> Vec<Value> {
+        vec![
+            json!({"region": "North", "city": "Oslo", "year": 2024, "amount": 10}),
+            json!({"region": "South", "city": "Rome", "year": 2023, "amount": 7}),
+            json!({"region": "North", "city": "Bergen", "year": 2023, "amount": 5}),
+            json!({"region": "North", "city": "Oslo", "year": 2023, "amount": 3}),
+            json!({"region": "South", "city": "Rome", "year": 2024, "amount": 1}),
+        ]
+    }
+
+    #[test]
+    fn test_pivot_computes_subtotals_and_grand_totals() {
+        let config = CrosstabConfig {
+            row_groups: vec![group("region", true), group("city", true)],
+            column_groups: vec![group("year", true)],
+            measures: vec![measure("amount", AggregateFunction::Sum), measure("amount", AggregateFunction::Count)],
+            ..CrosstabConfig::default()
+        };
+        let crosstab = pivot(&config, &sales());
+
+        assert_eq!(crosstab.rows, vec![
+            vec![json!("North"), json!("Bergen")],
+            vec![json!("North"), json!("Oslo")],
+            vec![json!("North")],
+            vec![json!("South"), json!("Rome")],
+            vec![json!("South")],
+            vec![],
+        ]);
+        assert_eq!(crosstab.columns, vec![vec![json!(2023)], vec![json!(2024)], vec![]]);
+        let sums: Vec<Vec<Value>> = crosstab.values.iter().map(|row| row.iter().map(|cell| cell[0].clone()).collect()).collect();
+        assert_eq!(sums, vec![
+            vec![json!(5), Value::Null, json!(5)],
+            vec![json!(3), json!(10), json!(13)],
+            vec![json!(8), json!(10), json!(18)],
+            vec![json!(7), json!(1), json!(8)],
+            vec![json!(7), json!(1), json!(8)],
+            vec![json!(15), json!(11), json!(26)],
+        ]);
+        assert_eq!(crosstab.values[5][2][1], json!(5));
+    }
+
+    #[test]
+    fn test_pivot_skips_hidden_totals() {
+        let config = CrosstabConfig {
+            row_groups: vec![group("region", false), group("city", false)],
+            column_groups: vec![],
+            measures: vec![measure("amount", AggregateFunction::Max)],
+            ..CrosstabConfig::default()
+        };
+        let crosstab = pivot(&config, &sales());
+        assert_eq!(crosstab.rows.len(), 3);
+        assert_eq!(crosstab.columns, vec![Vec::<Value>::new()]);
+        assert_eq!(crosstab.values[1][0][0], json!(10));
+    }
+
+    #[test]
+    fn test_crosstab_fill_repeats_column_header() {
+        let config = CrosstabConfig {
+            data_expression: Some("$F{sales}".to_string()),
+            row_groups: vec![group("city", true)],
+            column_groups: vec![group("year", true)],
+            measures: vec![CrosstabMeasure { format: Some("#,##0.00".to_string()), ..measure("amount", AggregateFunction::Sum) }],
+            ..CrosstabConfig::default()
+        };
+        let crosstab = ReportElement::new(
+            ElementContent::Crosstab(config),
+            Position::new(0.0, 0.0).unwrap(),
+            Size::new(260.0, 40.0).unwrap(),
+        )
+        .unwrap();
+        let mut layout = LayoutEngine::new(PageSetup {
+            width: 300.0,
+            height: 100.0,
+            margins: PageMargins { top: 10.0, bottom: 10.0, left: 10.0, right: 10.0 },
+        });
+        layout.add_band(BandType::Detail, BandLayout {
+            elements: vec![crosstab],
+            ..BandLayout::new(40.0, SplitType::Immediate)
+        });
+        let pages = layout.layout(&FillEngine::new(), &[json!({ "sales": sales() })]).unwrap();
+
+        let texts = |page: usize| -> Vec<(String, f64)> {
+            pages[page]
+                .elements
+                .iter()
+                .filter_map(|element| match &element.content {
+                    ElementContent::Text { content, .. } => Some((content.clone(), element.position.y)),
+                    _ => None,
+                })
+                .collect()
+        };
+        // 表头加 3 个城市和总计共 5 行，第一页只放得下 4 行
+        assert_eq!(pages.len(), 2);
+        assert_eq!(&texts(0)[..4], &[
+            ("city".to_string(), 10.0),
+            ("2023".to_string(), 10.0),
+            ("2024".to_string(), 10.0),
+            ("Total".to_string(), 10.0),
+        ]);
+        let second = texts(1);
+        assert_eq!(&second[..4], &texts(0)[..4]);
+        assert_eq!(&second[4..], &[
+            ("Total".to_string(), 30.0),
+            ("15.00".to_string(), 30.0),
+            ("11.00".to_string(), 30.0),
+            ("26.00".to_string(), 30.0),
+        ]);
+    }
+}
@@ -0,0 +1,553 @@
+// === 报表填充引擎 ===
+use crate::core::element::{
+    BarcodeConfig, ChartConfig, CrosstabConfig, ElementContent, ElementId, Position, ReportElement, SubreportConfig, TableConfig,
+};
+use crate::core::image_source::{self, ImageData, ImageSourceError};
+use crate::core::template::{self, BandType, JasperTemplate};
+use crate::expression::{EvalContext, Expression, ExpressionError};
+use crate::data::{DataQuery, DataSet, DataSourceRegistry, SortDirection, SortField};
+use crate::fill::layout::{BandLayout, Expansion};
+use crate::fill::preload::Preloaded;
+use crate::fill::{chart, crosstab, table};
+use crate::fill::{FillError, FillResult, FilledPage, FilledReport, LayoutEngine, PageSetup};
+use crate::format::Formatter;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 报表填充引擎：模板 + 数据集 -> 填充后的页面
+pub struct FillEngine {
+    parameters: HashMap<String, Value>,
+    formatter: Formatter,
+    /// 模板所在目录，子报表的相对路径按它解析
+    base_dir: Option<PathBuf>,
+    preloaded: Arc<Preloaded>,
+}
+
+impl FillEngine {
+    pub fn new() -> Self {
+        Self::with_parameters(HashMap::new())
+    }
+
+    pub fn with_parameters(parameters: HashMap<String, Value>) -> Self {
+        Self {
+            parameters,
+            formatter: Formatter::default(),
+            base_dir: None,
+            preloaded: Arc::default(),
+        }
+    }
+
+    /// 使用指定的区域和空值文本格式化数据字段
+    pub fn with_formatter(mut self, formatter: Formatter) -> Self {
+        self.formatter = formatter;
+        self
+    }
+
+    /// 模板文件所在目录，用于解析子报表路径
+    pub fn with_base_dir(mut self, base_dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(base_dir.into());
+        self
+    }
+
+    /// 使用预先加载的子报表、表格、交叉表和图表数据
+    pub fn with_preloaded(mut self, preloaded: Preloaded) -> Self {
+        self.preloaded = Arc::new(preloaded);
+        self
+    }
+
+    pub fn parameters(&self) -> &HashMap<String, Value> {
+        &self.parameters
+    }
+
+    /// 填充模板：查询模板绑定的数据源并填充全部元素
+    pub async fn fill_template(
+        &self,
+        template: &JasperTemplate,
+        registry: &DataSourceRegistry,
+    ) -> FillResult<FilledReport> {
+        let data = match template.data_sources.first() {
+            Some(source) => registry
+                .query_data(&source.id, Some(self.convert_query(source.query.as_ref())))
+                .await
+                .map_err(|e| FillError::DataSourceError {
+                    message: format!("Failed to query data source '{}': {}", source.id, e),
+                })?,
+            None => Self::empty_data_set(),
+        };
+
+        // 子报表的模板和数据、表格数据在布局前加载
+        let mut preloaded = Preloaded::default();
+        preloaded.load(self, template, &data.rows, registry, 0).await?;
+        let engine = self
+            .subreport_engine(self.parameters.clone(), self.base_dir.clone())
+            .with_preloaded(preloaded);
+
+        // 定义了区段时按区段布局分页，否则每条记录一页（单据模式）
+        let mut report = if template.bands.is_empty() {
+            let elements: Vec<ReportElement> = template.elements.iter().map(ReportElement::from).collect();
+            engine.fill(&elements, &data)?
+        } else {
+            engine.fill_bands(&LayoutEngine::from_template(template), &data)?
+        };
+        report.page_width = template.canvas.width;
+        report.page_height = template.canvas.height;
+        if template.data_sources.len() > 1 {
+            report.warnings.push(format!(
+                "Only the first data source is used for filling, {} ignored",
+                template.data_sources.len() - 1
+            ));
+        }
+        Ok(report)
+    }
+
+    /// 填充元素列表：每条数据记录生成一页
+    pub fn fill(&self, elements: &[ReportElement], data: &DataSet) -> FillResult<FilledReport> {
+        let (page_width, page_height) = Self::layout_bounds(elements);
+        let mut warnings = Vec::new();
+
+        // 无数据时仍输出一页，字段留空
+        let empty_row = Value::Object(Default::default());
+        let rows: Vec<&Value> = if data.rows.is_empty() {
+            warnings.push("Data set is empty, report filled without records".to_string());
+            vec![&empty_row]
+        } else {
+            data.rows.iter().collect()
+        };
+
+        let page = BandLayout {
+            elements: elements.iter().filter(|e| e.visible).cloned().collect(),
+            ..BandLayout::new(page_height, template::SplitType::default())
+        };
+        let pages = rows
+            .into_iter()
+            .enumerate()
+            .map(|(index, row)| {
+                let elements = page
+                    .expand_elements(self, row)?
+                    .elements
+                    .iter()
+                    .map(|e| self.fill_element(e, row))
+                    .collect::<FillResult<Vec<_>>>()?;
+
+                Ok(FilledPage {
+                    page_number: index + 1,
+                    elements,
+                })
+            })
+            .collect::<FillResult<Vec<_>>>()?;
+
+        Ok(FilledReport {
+            page_width,
+            page_height,
+            pages,
+            record_count: data.rows.len(),
+            warnings,
+        })
+    }
+
+    /// 按区段布局填充，明细区段随数据行重复并自动分页
+    pub fn fill_bands(&self, layout: &LayoutEngine, data: &DataSet) -> FillResult<FilledReport> {
+        let mut warnings = Vec::new();
+        if layout.band(BandType::Detail).is_none() {
+            warnings.push("No detail band defined, data rows are not printed".to_string());
+        }
+        if data.rows.is_empty() {
+            warnings.push("Data set is empty, report filled without records".to_string());
+        }
+
+        let pages = layout.layout(self, &data.rows)?;
+        Ok(FilledReport {
+            page_width: layout.page_setup().width,
+            page_height: layout.page_setup().height,
+            pages,
+            record_count: data.rows.len(),
+            warnings,
+        })
+    }
+
+    /// 填充单个元素：数据字段求值并格式化为文本，条码表达式求值为条码内容，其他元素原样复制
+    pub fn fill_element(&self, element: &ReportElement, row: &Value) -> FillResult<ReportElement> {
+        self.fill_element_with_variables(element, row, &HashMap::new())
+    }
+
+    /// 填充单个元素，表达式可以引用 $V{变量}
+    pub fn fill_element_with_variables(
+        &self,
+        element: &ReportElement,
+        row: &Value,
+        variables: &HashMap<String, Value>,
+    ) -> FillResult<ReportElement> {
+        let mut filled = element.clone();
+        // 每页元素使用新ID，避免不同页之间的渲染缓存冲突
+        filled.id = ElementId::new();
+
+        if let ElementContent::DataField { expression, format, style } = &element.content {
+            let value = self.evaluate(expression, row, variables)?;
+            filled.content = ElementContent::Text {
+                content: self.formatter.format(&value, format.as_deref()),
+                style: style.clone(),
+            };
+        }
+        if let ElementContent::Barcode(config) = &element.content {
+            if let Some(expression) = config.expression.as_deref().filter(|e| !e.trim().is_empty()) {
+                let value = self.evaluate(expression, row, variables)?;
+                filled.content = ElementContent::Barcode(BarcodeConfig {
+                    value: self.formatter.format(&value, None),
+                    expression: None,
+                    ..config.clone()
+                });
+            }
+        }
+        if let ElementContent::Image { src, .. } = &mut filled.content {
+            if let Some(image) = self.load_image(src, row, variables)? {
+                *src = image.to_data_uri();
+            }
+        }
+
+        Ok(filled)
+    }
+
+    /// 填充子报表：子模板按元素宽度连续排版（不分页、无页脚），返回相对子报表元素左上角的元素和内容高度
+    pub(crate) fn fill_subreport(
+        &self,
+        element: &ReportElement,
+        config: &SubreportConfig,
+        row: &Value,
+    ) -> FillResult<Expansion> {
+        let input = self.preloaded.input(self, config, row)?;
+        // 默认不输出任何内容（同 JasperReports 的 whenNoDataType=NoPages）
+        if input.rows.is_empty() {
+            return Ok(Expansion { elements: Vec::new(), height: 0.0, header: None });
+        }
+
+        let setup = PageSetup {
+            width: element.size.width,
+            height: f64::INFINITY,
+            margins: template::PageMargins { top: 0.0, bottom: 0.0, left: 0.0, right: 0.0 },
+        };
+        let layout = if input.template.bands.is_empty() {
+            // 单据模式的子模板：全部元素作为明细区段
+            let elements: Vec<ReportElement> = input.template.elements.iter().map(ReportElement::from).collect();
+            let mut layout = LayoutEngine::new(setup);
+            layout.add_band(BandType::Detail, BandLayout {
+                elements: elements.iter().filter(|e| e.visible).cloned().collect(),
+                ..BandLayout::new(Self::layout_bounds(&elements).1, template::SplitType::default())
+            });
+            layout
+        } else {
+            LayoutEngine::from_template(input.template).into_continuous(setup)
+        };
+        let (elements, height) = layout.layout_flow(&input.engine, &input.rows)?;
+        Ok(Expansion { elements, height, header: None })
+    }
+
+    /// 填充表格：数据取自预加载的数据源或父记录中的数组
+    pub(crate) fn fill_table(&self, config: &TableConfig, row: &Value) -> FillResult<Expansion> {
+        let rows = self.preloaded.table_rows(self, &config.data_source_id, config.data_expression.as_deref(), row)?;
+        table::fill_table(self, config, &rows)
+    }
+
+    /// 填充交叉表：数据来源同表格
+    pub(crate) fn fill_crosstab(&self, config: &CrosstabConfig, row: &Value) -> FillResult<Expansion> {
+        let rows = self.preloaded.table_rows(self, &config.data_source_id, config.data_expression.as_deref(), row)?;
+        Ok(crosstab::fill_crosstab(self, config, &rows))
+    }
+
+    /// 填充图表：汇总后的数据写入图表配置，元素大小不变
+    pub(crate) fn fill_chart(&self, element: &ReportElement, config: &ChartConfig, row: &Value) -> FillResult<Expansion> {
+        let rows = self.preloaded.table_rows(self, &config.data_source_id, config.data_expression.as_deref(), row)?;
+        let data = chart::chart_data(self, config, &rows, self.formatter.locale().tag)?;
+        let mut filled = element.clone();
+        filled.id = ElementId::new();
+        filled.position = Position { x: 0.0, y: 0.0 };
+        filled.content = ElementContent::Chart(ChartConfig { data: Some(data), ..config.clone() });
+        Ok(Expansion { elements: vec![filled], height: element.size.height, header: None })
+    }
+
+    /// 按格式模式格式化值，空值显示为模板设置的空值文本
+    pub fn format_value(&self, value: &Value, format: Option<&str>) -> String {
+        self.formatter.format(value, format)
+    }
+
+    /// 加载图片元素的图片，嵌入为数据 URI 后各种导出都不再依赖模板目录；
+    /// 文件缺失或数据无法识别时保留占位框
+    fn load_image(&self, src: &str, row: &Value, variables: &HashMap<String, Value>) -> FillResult<Option<ImageData>> {
+        let result = if image_source::is_expression(src) {
+            let value = self.evaluate(src, row, variables)?;
+            image_source::from_value(&value, self.base_dir.as_deref())
+        } else {
+            image_source::load(src, self.base_dir.as_deref())
+        };
+        match result {
+            Ok(image) => Ok(Some(image)),
+            Err(ImageSourceError::Empty) => Ok(None),
+            Err(error) => {
+                log::warn!("Image '{}' could not be loaded: {}", src, error);
+                Ok(None)
+            }
+        }
+    }
+
+    /// 子报表路径：相对路径按模板所在目录解析
+    pub(crate) fn subreport_path(&self, template_path: &str) -> PathBuf {
+        let path = Path::new(template_path);
+        match &self.base_dir {
+            Some(base_dir) if path.is_relative() => base_dir.join(path),
+            _ => path.to_path_buf(),
+        }
+    }
+
+    /// 子报表使用的引擎：沿用格式化设置和预加载的数据
+    pub(crate) fn subreport_engine(&self, parameters: HashMap<String, Value>, base_dir: Option<PathBuf>) -> Self {
+        Self {
+            parameters,
+            formatter: self.formatter.clone(),
+            base_dir,
+            preloaded: Arc::clone(&self.preloaded),
+        }
+    }
+
+    /// 表达式求值（不含变量），语法见 `crate::expression`
+    pub fn evaluate_expression(&self, expression: &str, row: &Value) -> FillResult<Value> {
+        self.evaluate(expression, row, &HashMap::new())
+    }
+
+    /// 表达式求值，额外支持 $V{变量}、运算符和函数
+    pub fn evaluate(&self, expression: &str, row: &Value, variables: &HashMap<String, Value>) -> FillResult<Value> {
+        if expression.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+
+        let to_fill_error = |error: ExpressionError| FillError::ExpressionError {
+            expression: expression.to_string(),
+            message: error.to_string(),
+        };
+        Expression::parse(expression)
+            .map_err(to_fill_error)?
+            .evaluate(&EvalContext::new(row, &self.parameters, variables))
+            .map_err(to_fill_error)
+    }
+
+    /// 模板数据查询转换为数据层查询，报表参数作为绑定参数传入
+    pub(crate) fn convert_query(&self, query: Option<&template::DataQuery>) -> DataQuery {
+        // 查询级参数的默认值可被报表参数覆盖
+        let mut parameters: HashMap<String, Value> = query
+            .map(|query| {
+                query
+                    .parameters
+                    .iter()
+                    .filter_map(|param| param.default.clone().map(|value| (param.name.clone(), value)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        parameters.extend(self.parameters.iter().map(|(name, value)| (name.clone(), value.clone())));
+
+        let Some(query) = query else {
+            return DataQuery {
+                parameters,
+                ..Default::default()
+            };
+        };
+
+        let sort = query.sort.as_ref().map(|sort| {
+            sort.split(',')
+                .filter_map(|item| {
+                    let mut parts = item.split_whitespace();
+                    let field = parts.next()?.to_string();
+                    let direction = match parts.next() {
+                        Some(dir) if dir.eq_ignore_ascii_case("desc") => SortDirection::Desc,
+                        _ => SortDirection::Asc,
+                    };
+                    Some(SortField { field, direction })
+                })
+                .collect()
+        });
+
+        DataQuery {
+            // 数据库数据源通过 path 传递自定义SQL
+            path: query.sql.clone().or_else(|| query.path.clone()),
+            filter: query.filter.clone(),
+            limit: query.limit.map(|v| v as usize),
+            offset: query.offset.map(|v| v as usize),
+            sort,
+            aggregation: None,
+            context: None,
+            parameters,
+        }
+    }
+
+    fn empty_data_set() -> DataSet {
+        DataSet {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            total_count: 0,
+            metadata: None,
+            cached: false,
+            cache_time: None,
+            checksum: None,
+        }
+    }
+
+    /// 元素列表的页面尺寸（取元素覆盖范围）
+    fn layout_bounds(elements: &[ReportElement]) -> (f64, f64) {
+        elements.iter().filter(|e| e.visible).fold((0.0_f64, 0.0_f64), |(w, h), e| {
+            (w.max(e.position.x + e.size.width), h.max(e.position.y + e.size.height))
+        })
+    }
+}
+
+impl Default for FillEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{ImageAlign, ImageScaleMode, Position, Size, TextAlign, TextStyle};
+    use serde_json::json;
+
+    fn data_field(expression: &str, format: Option<&str>) -> ReportElement {
+        ReportElement::new(
+            ElementContent::DataField {
+                expression: expression.to_string(),
+                format: format.map(str::to_string),
+                style: TextStyle {
+                    font_family: "Arial".to_string(),
+                    font_size: 12.0,
+                    font_weight: "normal".to_string(),
+                    color: "#000000".to_string(),
+                    align: TextAlign::Left,
+                    border: None,
+                    background: None,
+                },
+            },
+            Position::new(10.0, 10.0).unwrap(),
+            Size::new(100.0, 20.0).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn data_set(rows: Vec<Value>) -> DataSet {
+        DataSet {
+            total_count: rows.len(),
+            rows,
+            ..FillEngine::empty_data_set()
+        }
+    }
+
+    fn text_of(element: &ReportElement) -> &str {
+        match &element.content {
+            ElementContent::Text { content, .. } => content,
+            other => panic!("expected text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_fill_one_page_per_row() {
+        let engine = FillEngine::new();
+        let elements = vec![data_field("${customerName}", None), data_field("$F{amount}", Some("#,##0.00"))];
+        let data = data_set(vec![
+            json!({"customerName": "张三", "amount": 1234.5}),
+            json!({"customerName": "李四", "amount": 99}),
+        ]);
+
+        let report = engine.fill(&elements, &data).unwrap();
+        assert_eq!(report.page_count(), 2);
+        assert_eq!(report.record_count, 2);
+        assert_eq!(text_of(&report.pages[0].elements[0]), "张三");
+        assert_eq!(text_of(&report.pages[0].elements[1]), "1,234.50");
+        assert_eq!(text_of(&report.pages[1].elements[1]), "99.00");
+        assert_ne!(report.pages[0].elements[0].id, report.pages[1].elements[0].id);
+    }
+
+    #[test]
+    fn test_fill_empty_data_set() {
+        let report = FillEngine::new().fill(&[data_field("name", None)], &data_set(vec![])).unwrap();
+        assert_eq!(report.page_count(), 1);
+        assert_eq!(text_of(&report.pages[0].elements[0]), "");
+        assert!(!report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_evaluate_nested_paths_and_parameters() {
+        let mut parameters = HashMap::new();
+        parameters.insert("bank".to_string(), json!("工商银行"));
+        let engine = FillEngine::with_parameters(parameters);
+        let row = json!({"account": {"owner": "王五", "cards": ["6222", "6228"]}});
+
+        assert_eq!(engine.evaluate_expression("account.owner", &row).unwrap(), json!("王五"));
+        assert_eq!(engine.evaluate_expression("$F{account.cards[1]}", &row).unwrap(), json!("6228"));
+        assert_eq!(engine.evaluate_expression("missing", &row).unwrap(), Value::Null);
+        assert_eq!(engine.evaluate_expression("$P{bank}", &row).unwrap(), json!("工商银行"));
+        assert!(engine.evaluate_expression("account.cards[x]", &row).is_err());
+    }
+
+    #[test]
+    fn test_fill_element_uses_formatter() {
+        let engine = FillEngine::new().with_formatter(Formatter::new("de-DE").with_null_text("-"));
+        let row = json!({"amount": 1234.5, "note": null});
+
+        let filled = engine.fill_element(&data_field("$F{amount}", Some("¤ #,##0.00")), &row).unwrap();
+        assert_eq!(text_of(&filled), "€ 1.234,50");
+        let filled = engine.fill_element(&data_field("$F{note}", None), &row).unwrap();
+        assert_eq!(text_of(&filled), "-");
+    }
+
+    #[test]
+    fn test_fill_barcode_expression() {
+        let barcode = ReportElement::new(
+            ElementContent::Barcode(BarcodeConfig {
+                value: "ignored".to_string(),
+                expression: Some("$F{serial}".to_string()),
+                ..BarcodeConfig::default()
+            }),
+            Position::new(0.0, 0.0).unwrap(),
+            Size::new(100.0, 40.0).unwrap(),
+        )
+        .unwrap();
+        let filled = FillEngine::new().fill_element(&barcode, &json!({"serial": 590123412345u64})).unwrap();
+        match filled.content {
+            ElementContent::Barcode(config) => {
+                assert_eq!(config.value, "590123412345");
+                assert_eq!(config.expression, None);
+            }
+            other => panic!("expected barcode, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_fill_image_from_field() {
+        let pixel = image::DynamicImage::ImageRgb8(image::RgbImage::new(2, 2));
+        let photo = ImageData::from_image(&pixel).unwrap();
+        let encoded = photo.to_data_uri().split_once(',').unwrap().1.to_string();
+        let element = |src: &str| {
+            ReportElement::new(
+                ElementContent::Image {
+                    src: src.to_string(),
+                    alt: None,
+                    scale_mode: ImageScaleMode::default(),
+                    horizontal_align: ImageAlign::default(),
+                    vertical_align: ImageAlign::default(),
+                },
+                Position::new(0.0, 0.0).unwrap(),
+                Size::new(40.0, 40.0).unwrap(),
+            )
+            .unwrap()
+        };
+
+        let engine = FillEngine::new();
+        let row = json!({"photo": encoded});
+        let filled = engine.fill_element(&element("$F{photo}"), &row).unwrap();
+        assert!(matches!(filled.content, ElementContent::Image { ref src, .. } if *src == photo.to_data_uri()));
+        let shorthand = engine.fill_element(&element("${photo}"), &row).unwrap();
+        assert!(matches!(shorthand.content, ElementContent::Image { ref src, .. } if *src == photo.to_data_uri()));
+
+        // 缺失的文件保留原路径，渲染为占位框
+        let missing = engine.fill_element(&element("missing-logo.png"), &row).unwrap();
+        assert!(matches!(missing.content, ElementContent::Image { ref src, .. } if src == "missing-logo.png"));
+    }
+}
@@ -0,0 +1,933 @@
+// === 区段布局引擎 ===
+use crate::core::element::{ElementContent, Position, ReportElement};
+use crate::core::template::{BandConfig, BandType, Canvas, JasperTemplate, PageMargins, ReportBands, SortOrder, SplitType, Variable};
+use crate::fill::{FillEngine, FillError, FillResult, FilledPage, VariableCalculator, VariableScope};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// 页面设置（单位与画布一致，默认pt）
+#[derive(Debug, Clone)]
+pub struct PageSetup {
+    pub width: f64,
+    pub height: f64,
+    pub margins: PageMargins,
+}
+
+impl PageSetup {
+    pub fn from_canvas(canvas: &Canvas) -> Self {
+        Self {
+            width: canvas.width,
+            height: canvas.height,
+            margins: canvas.margins.clone(),
+        }
+    }
+
+    pub fn content_top(&self) -> f64 {
+        self.margins.top
+    }
+
+    pub fn content_bottom(&self) -> f64 {
+        self.height - self.margins.bottom
+    }
+}
+
+/// 参与布局的区段，元素坐标相对于区段左上角
+#[derive(Debug, Clone)]
+pub struct BandLayout {
+    pub height: f64,
+    pub split_type: SplitType,
+    pub elements: Vec<ReportElement>,
+    /// 区段跨页时需要在新页顶部重复输出的表头
+    pub repeated_headers: Vec<RepeatedHeader>,
+}
+
+/// 表格跨页时重复的表头
+#[derive(Debug, Clone)]
+pub struct RepeatedHeader {
+    /// 表格在区段中的上下边界
+    pub top: f64,
+    pub bottom: f64,
+    pub height: f64,
+    /// 表头元素，纵坐标相对于表头顶部
+    pub elements: Vec<ReportElement>,
+}
+
+/// 子报表、表格等元素填充后的输出，坐标相对于元素左上角
+pub(crate) struct Expansion {
+    pub elements: Vec<ReportElement>,
+    pub height: f64,
+    /// 跨页时重复的表头高度及元素
+    pub header: Option<(f64, Vec<ReportElement>)>,
+}
+
+impl BandLayout {
+    pub fn new(height: f64, split_type: SplitType) -> Self {
+        Self {
+            height,
+            split_type,
+            elements: Vec::new(),
+            repeated_headers: Vec::new(),
+        }
+    }
+
+    /// 展开子报表、表格、交叉表和图表：输出内联到区段中，区段随内容拉伸，位于其下方的元素随之下移
+    pub(crate) fn expand_elements(&self, engine: &FillEngine, row: &Value) -> FillResult<Cow<'_, BandLayout>> {
+        let expandable = |element: &ReportElement| matches!(
+            element.content,
+            ElementContent::Subreport(_) | ElementContent::Table(_) | ElementContent::Crosstab(_) | ElementContent::Chart(_)
+        );
+        let mut expanded: Vec<usize> = (0..self.elements.len()).filter(|&index| expandable(&self.elements[index])).collect();
+        if expanded.is_empty() {
+            return Ok(Cow::Borrowed(self));
+        }
+        let bottom = |element: &ReportElement| element.position.y + element.size.height;
+        expanded.sort_by(|&a, &b| bottom(&self.elements[a]).total_cmp(&bottom(&self.elements[b])));
+
+        // 底边位于 y 之上的元素拉伸造成的最大下移量
+        let shift = |grown: &[(f64, f64)], y: f64| {
+            grown.iter().filter(|(bottom, _)| *bottom <= y).map(|(_, shift)| *shift).fold(0.0, f64::max)
+        };
+        let mut grown: Vec<(f64, f64)> = Vec::new();
+        let mut outputs = HashMap::new();
+        for index in expanded {
+            let element = &self.elements[index];
+            let expansion = match &element.content {
+                ElementContent::Subreport(config) => engine.fill_subreport(element, config, row)?,
+                ElementContent::Table(config) => engine.fill_table(config, row)?,
+                ElementContent::Crosstab(config) => engine.fill_crosstab(config, row)?,
+                ElementContent::Chart(config) => engine.fill_chart(element, config, row)?,
+                _ => unreachable!(),
+            };
+            let top_shift = shift(&grown, element.position.y);
+            grown.push((bottom(element), top_shift + (expansion.height - element.size.height).max(0.0)));
+            outputs.insert(index, (expansion, top_shift));
+        }
+
+        let mut band = BandLayout {
+            repeated_headers: self.repeated_headers.clone(),
+            ..BandLayout::new(self.height + shift(&grown, self.height), self.split_type)
+        };
+        for (index, element) in self.elements.iter().enumerate() {
+            let Some((expansion, top_shift)) = outputs.remove(&index) else {
+                let mut element = element.clone();
+                element.position.y += shift(&grown, element.position.y);
+                band.elements.push(element);
+                continue;
+            };
+
+            let top = element.position.y + top_shift;
+            let place = |mut child: ReportElement, y: f64| {
+                child.position.x += element.position.x;
+                child.position.y += y;
+                child
+            };
+            if let Some((height, header)) = expansion.header {
+                band.repeated_headers.push(RepeatedHeader {
+                    top,
+                    bottom: top + expansion.height,
+                    height,
+                    elements: header.into_iter().map(|child| place(child, 0.0)).collect(),
+                });
+            }
+            band.elements.extend(expansion.elements.into_iter().map(|child| place(child, top)));
+        }
+
+        Ok(Cow::Owned(band))
+    }
+}
+
+/// 分组布局：分组表达式的值变化时输出组脚和组头
+#[derive(Debug, Clone)]
+pub struct GroupLayout {
+    pub name: String,
+    pub expression: String,
+    pub sort_order: SortOrder,
+    pub header: Option<BandLayout>,
+    pub footer: Option<BandLayout>,
+    pub start_on_new_page: bool,
+    pub keep_together: bool,
+}
+
+/// 区段布局引擎：标题/页眉/列头/分组/明细/列脚/页脚/汇总，按内容区域分页
+pub struct LayoutEngine {
+    setup: PageSetup,
+    bands: HashMap<BandType, BandLayout>,
+    /// 分组由外到内排列
+    groups: Vec<GroupLayout>,
+    variables: Vec<Variable>,
+    /// 未归属区段的元素，按绝对坐标绘制在每一页
+    background: BandLayout,
+}
+
+impl LayoutEngine {
+    pub fn new(setup: PageSetup) -> Self {
+        Self {
+            setup,
+            bands: HashMap::new(),
+            groups: Vec::new(),
+            variables: Vec::new(),
+            background: BandLayout::new(0.0, SplitType::default()),
+        }
+    }
+
+    /// 从模板构建：区段成员由 `BandConfig.elements` 决定
+    pub fn from_template(template: &JasperTemplate) -> Self {
+        let group_members: HashSet<&str> = template
+            .groups
+            .iter()
+            .flat_map(|group| group.bands())
+            .flat_map(|(_, band)| band.elements.iter().map(String::as_str))
+            .collect();
+
+        let elements: Vec<ReportElement> = template
+            .elements
+            .iter()
+            .filter(|element| !group_members.contains(element.id.as_str()))
+            .map(|element| {
+                let mut report_element = ReportElement::from(element);
+                report_element.band = template.bands.band_of(&element.id);
+                report_element
+            })
+            .collect();
+
+        let mut engine = Self::from_elements(&elements, &template.bands, PageSetup::from_canvas(&template.canvas));
+        for group in &template.groups {
+            engine.add_group(GroupLayout {
+                name: group.name.clone(),
+                expression: group.expression.clone(),
+                sort_order: group.sort_order,
+                header: group.header.as_ref().map(|band| Self::band_from_config(template, band)),
+                footer: group.footer.as_ref().map(|band| Self::band_from_config(template, band)),
+                start_on_new_page: group.start_on_new_page,
+                keep_together: group.keep_together,
+            });
+        }
+        for variable in &template.variables {
+            engine.add_variable(variable.clone());
+        }
+        engine
+    }
+
+    /// 从设计器元素构建：区段成员由 `ReportElement.band` 决定
+    pub fn from_elements(elements: &[ReportElement], bands: &ReportBands, setup: PageSetup) -> Self {
+        let mut engine = Self::new(setup);
+
+        for (band_type, band) in bands.iter() {
+            engine.add_band(band_type, BandLayout::new(band.height, band.split_type));
+        }
+
+        let mut sorted: Vec<&ReportElement> = elements.iter().filter(|e| e.visible).collect();
+        sorted.sort_by_key(|e| e.z_index);
+        for element in sorted {
+            match element.band.and_then(|band_type| engine.bands.get_mut(&band_type)) {
+                Some(band) => band.elements.push(element.clone()),
+                None => engine.background.elements.push(element.clone()),
+            }
+        }
+
+        engine
+    }
+
+    fn band_from_config(template: &JasperTemplate, config: &BandConfig) -> BandLayout {
+        let mut elements: Vec<_> = config
+            .elements
+            .iter()
+            .filter_map(|id| template.get_element_by_id(id))
+            .filter(|element| element.visible)
+            .collect();
+        elements.sort_by_key(|element| element.z_index);
+
+        BandLayout {
+            elements: elements.into_iter().map(ReportElement::from).collect(),
+            ..BandLayout::new(config.height, config.split_type)
+        }
+    }
+
+    pub fn add_band(&mut self, band_type: BandType, band: BandLayout) {
+        self.bands.insert(band_type, band);
+    }
+
+    /// 追加一个更内层的分组
+    pub fn add_group(&mut self, group: GroupLayout) {
+        self.groups.push(group);
+    }
+
+    pub fn add_variable(&mut self, variable: Variable) {
+        self.variables.push(variable);
+    }
+
+    pub fn page_setup(&self) -> &PageSetup {
+        &self.setup
+    }
+
+    pub fn band(&self, band_type: BandType) -> Option<&BandLayout> {
+        self.bands.get(&band_type)
+    }
+
+    pub fn groups(&self) -> &[GroupLayout] {
+        &self.groups
+    }
+
+    /// 改为连续排版（子报表）：使用给定的页面设置并去掉页脚
+    pub(crate) fn into_continuous(mut self, setup: PageSetup) -> Self {
+        self.setup = setup;
+        self.bands.remove(&BandType::PageFooter);
+        self
+    }
+
+    /// 执行布局：数据行按分组排序，分组切换处输出组脚/组头，明细区段按数据行重复，内容区域放满后换页
+    pub fn layout(&self, engine: &FillEngine, rows: &[Value]) -> FillResult<Vec<FilledPage>> {
+        Ok(self.run(engine, rows)?.pages)
+    }
+
+    /// 连续排版的布局结果：全部元素和内容总高度（页面高度不限时只有一页）
+    pub(crate) fn layout_flow(&self, engine: &FillEngine, rows: &[Value]) -> FillResult<(Vec<ReportElement>, f64)> {
+        let paginator = self.run(engine, rows)?;
+        let elements = paginator.pages.into_iter().flat_map(|page| page.elements).collect();
+        Ok((elements, paginator.y))
+    }
+
+    fn run<'a>(&'a self, engine: &'a FillEngine, rows: &[Value]) -> FillResult<Paginator<'a>> {
+        let empty_row = Value::Object(Default::default());
+        let (rows, keys) = self.sort_rows(engine, rows)?;
+        let first_row = rows.first().copied().unwrap_or(&empty_row);
+
+        let mut paginator = Paginator::new(self, engine)?;
+        paginator.start_page(first_row, true)?;
+
+
+        let detail = self.band(BandType::Detail);
+        for (index, row) in rows.iter().copied().enumerate() {
+            // 第一个分组值发生变化的层级，其内层分组全部切换
+            let level = match index {
+                0 => 0,
+                _ => (0..self.groups.len())
+                    .find(|&level| keys[index][level] != keys[index - 1][level])
+                    .unwrap_or(self.groups.len()),
+            };
+
+            if index > 0 {
+                self.close_groups(&mut paginator, level, rows[index - 1])?;
+            }
+            paginator.begin_row(row)?;
+
+            for group_level in level..self.groups.len() {
+                let group = &self.groups[group_level];
+                let needs_break = if group.start_on_new_page {
+                    true
+                } else if group.keep_together {
+                    let height = self.group_height(group_level, index, &keys).0;
+                    height > paginator.available() && height <= paginator.fresh_page_available()
+                } else {
+                    false
+                };
+                if needs_break && !paginator.at_page_start() {
+                    paginator.break_page(row)?;
+                }
+                if let Some(header) = &group.header {
+                    paginator.place_band(header, row)?;
+                }
+            }
+
+            if let Some(detail) = detail {
+                paginator.place_band(detail, row)?;
+            }
+        }
+
+        let last_row = rows.last().copied().unwrap_or(&empty_row);
+        if !rows.is_empty() {
+            self.close_groups(&mut paginator, 0, last_row)?;
+        }
+        paginator.variables.increment(engine, VariableScope::Report, last_row)?;
+        if let Some(summary) = self.band(BandType::Summary) {
+            paginator.place_band(summary, last_row)?;
+        }
+
+        paginator.finish_page(last_row)?;
+        Ok(paginator)
+    }
+
+    /// 结束 `level` 及其内层分组：累加分组变量，由内到外输出组脚，再重置分组变量
+    fn close_groups(&self, paginator: &mut Paginator<'_>, level: usize, row: &Value) -> FillResult<()> {
+        if level >= self.groups.len() {
+            return Ok(());
+        }
+        paginator.variables.increment(paginator.engine, VariableScope::Group(level), row)?;
+        for group in self.groups[level..].iter().rev() {
+            if let Some(footer) = &group.footer {
+                paginator.place_band(footer, row)?;
+            }
+        }
+        paginator.variables.reset(VariableScope::Group(level));
+        Ok(())
+    }
+
+    /// 计算每行的分组值，并按分组顺序稳定排序
+    fn sort_rows<'r>(&self, engine: &FillEngine, rows: &'r [Value]) -> FillResult<(Vec<&'r Value>, Vec<Vec<Value>>)> {
+        let mut keyed = rows
+            .iter()
+            .map(|row| {
+                let keys = self
+                    .groups
+                    .iter()
+                    .map(|group| engine.evaluate_expression(&group.expression, row))
+                    .collect::<FillResult<Vec<_>>>()?;
+                Ok((row, keys))
+            })
+            .collect::<FillResult<Vec<_>>>()?;
+
+        if !self.groups.is_empty() {
+            keyed.sort_by(|(_, a), (_, b)| {
+                self.groups
+                    .iter()
+                    .zip(a.iter().zip(b.iter()))
+                    .map(|(group, (a, b))| match group.sort_order {
+                        SortOrder::Ascending => compare_values(a, b),
+                        SortOrder::Descending => compare_values(b, a),
+                    })
+                    .find(|ordering| *ordering != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+
+        Ok(keyed.into_iter().unzip())
+    }
+
+    /// 从 `start` 行开始的分组占用的总高度及其结束行
+    fn group_height(&self, level: usize, start: usize, keys: &[Vec<Value>]) -> (f64, usize) {
+        let end = (start + 1..keys.len())
+            .find(|&index| keys[index][..=level] != keys[start][..=level])
+            .unwrap_or(keys.len());
+
+        let group = &self.groups[level];
+        let mut height = group.header.as_ref().map_or(0.0, |band| band.height)
+            + group.footer.as_ref().map_or(0.0, |band| band.height);
+
+        if level + 1 < self.groups.len() {
+            let mut index = start;
+            while index < end {
+                let (inner, inner_end) = self.group_height(level + 1, index, keys);
+                height += inner;
+                index = inner_end;
+            }
+        } else if let Some(detail) = self.band(BandType::Detail) {
+            height += detail.height * (end - start) as f64;
+        }
+
+        (height, end)
+    }
+}
+
+/// 分组值比较：空值在前，数字按大小，其余按文本
+pub(crate) fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Less,
+        (_, Value::Null) => Ordering::Greater,
+        (Value::Number(a), Value::Number(b)) => {
+            let (a, b) = (a.as_f64().unwrap_or(0.0), b.as_f64().unwrap_or(0.0));
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        }
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (a, b) => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+/// 分页状态
+struct Paginator<'a> {
+    layout: &'a LayoutEngine,
+    engine: &'a FillEngine,
+    pages: Vec<FilledPage>,
+    /// 当前页下一个区段的起始位置
+    y: f64,
+    /// 页眉/列头之后的起始位置，用于判断当前页是否还没有内容
+    body_top: f64,
+    /// 最近一次完整输出的数据行，页脚/列脚按它取值
+    last_row: Option<Value>,
+    variables: VariableCalculator,
+    /// 已累加但尚未输出任何区段的数据行及其累加前的变量状态
+    pending: Option<(Value, VariableCalculator)>,
+    record_count: usize,
+}
+
+impl<'a> Paginator<'a> {
+    fn new(layout: &'a LayoutEngine, engine: &'a FillEngine) -> FillResult<Self> {
+        let paginator = Self {
+            layout,
+            engine,
+            pages: Vec::new(),
+            y: layout.setup.content_top(),
+            body_top: layout.setup.content_top(),
+            last_row: None,
+            variables: VariableCalculator::new(&layout.variables, &layout.groups)?,
+            pending: None,
+            record_count: 0,
+        };
+
+        // 页眉页脚等固定区段占满页面时无法继续分页
+        let fixed: f64 = [BandType::PageHeader, BandType::ColumnHeader, BandType::ColumnFooter, BandType::PageFooter]
+            .into_iter()
+            .filter_map(|band_type| layout.band(band_type))
+            .map(|band| band.height)
+            .sum();
+        if layout.setup.content_bottom() - layout.setup.content_top() - fixed <= 0.0 {
+            return Err(FillError::TemplateError {
+                message: "Page content area is too small for the header and footer bands".to_string(),
+            });
+        }
+
+        Ok(paginator)
+    }
+
+    /// 新开一页并输出页眉、列头（首页先输出标题）
+    fn start_page(&mut self, row: &Value, first_page: bool) -> FillResult<()> {
+        let mut page = FilledPage {
+            page_number: self.pages.len() + 1,
+            elements: Vec::new(),
+        };
+        let values = self.variable_values();
+        for element in &self.layout.background.expand_elements(self.engine, row)?.elements {
+            page.elements.push(self.engine.fill_element_with_variables(element, row, &values)?);
+        }
+        self.pages.push(page);
+        self.y = self.layout.setup.content_top();
+
+        if first_page {
+            if let Some(title) = self.layout.band(BandType::Title) {
+                let title = title.expand_elements(self.engine, row)?;
+                self.emit(&title, row)?;
+                self.y += title.height;
+            }
+        }
+        for band_type in [BandType::PageHeader, BandType::ColumnHeader] {
+            if let Some(band) = self.layout.band(band_type) {
+                let band = band.expand_elements(self.engine, row)?;
+                self.emit(&band, row)?;
+                self.y += band.height;
+            }
+        }
+
+        self.body_top = self.y;
+        Ok(())
+    }
+
+    /// 结束当前页：列脚紧随内容，页脚固定在内容区域底部
+    fn finish_page(&mut self, row: &Value) -> FillResult<()> {
+        self.variables.increment(self.engine, VariableScope::Page, row)?;
+        self.variables.increment(self.engine, VariableScope::Column, row)?;
+        if let Some(column_footer) = self.layout.band(BandType::ColumnFooter) {
+            self.emit(column_footer, row)?;
+            self.y += column_footer.height;
+        }
+        if let Some(page_footer) = self.layout.band(BandType::PageFooter) {
+            self.y = self.layout.setup.content_bottom() - page_footer.height;
+            self.emit(page_footer, row)?;
+        }
+        Ok(())
+    }
+
+    fn break_page(&mut self, row: &Value) -> FillResult<()> {
+        // 当前行还没有输出时不计入本页：回退变量，换页后重新累加
+        let pending = self.pending.take().map(|(pending_row, before)| {
+            self.variables = before;
+            pending_row
+        });
+
+        let previous = self.last_row.clone().unwrap_or_else(|| row.clone());
+        self.finish_page(&previous)?;
+        self.variables.reset(VariableScope::Page);
+        self.variables.reset(VariableScope::Column);
+        if let Some(pending_row) = pending {
+            self.variables.increment(self.engine, VariableScope::Record, &pending_row)?;
+        }
+        self.start_page(row, false)
+    }
+
+    /// 开始处理一条数据记录：按记录累加变量
+    fn begin_row(&mut self, row: &Value) -> FillResult<()> {
+        self.record_count += 1;
+        if !self.variables.is_empty() {
+            self.pending = Some((row.clone(), self.variables.clone()));
+        }
+        self.variables.increment(self.engine, VariableScope::Record, row)
+    }
+
+    /// 变量值，包含内置的 PAGE_NUMBER 和 REPORT_COUNT
+    fn variable_values(&self) -> HashMap<String, Value> {
+        let mut values = self.variables.values();
+        values.insert("PAGE_NUMBER".to_string(), Value::from(self.pages.len()));
+        values.insert("REPORT_COUNT".to_string(), Value::from(self.record_count));
+        values
+    }
+
+    /// 当前页可用于内容区段的剩余高度（预留列脚和页脚）
+    fn available(&self) -> f64 {
+        let reserved: f64 = [BandType::ColumnFooter, BandType::PageFooter]
+            .into_iter()
+            .filter_map(|band_type| self.layout.band(band_type))
+            .map(|band| band.height)
+            .sum();
+        self.layout.setup.content_bottom() - reserved - self.y
+    }
+
+    /// 新开一页（非首页）时内容区段的可用高度
+    fn fresh_page_available(&self) -> f64 {
+        let header: f64 = [BandType::PageHeader, BandType::ColumnHeader]
+            .into_iter()
+            .filter_map(|band_type| self.layout.band(band_type))
+            .map(|band| band.height)
+            .sum();
+        self.available() + self.y - self.layout.setup.content_top() - header
+    }
+
+    fn at_page_start(&self) -> bool {
+        self.y <= self.body_top
+    }
+
+    /// 放置可跨页区段（明细、汇总），子报表和表格展开后的区段按拉伸后的高度分页
+    fn place_band(&mut self, band: &BandLayout, row: &Value) -> FillResult<()> {
+        let band = band.expand_elements(self.engine, row)?;
+        let band = band.as_ref();
+        if band.height <= self.available() {
+            self.emit(band, row)?;
+            self.y += band.height;
+            self.last_row = Some(row.clone());
+            return Ok(());
+        }
+
+        // Stretch/Prevent：先整体移到下一页，仍放不下时再拆分
+        if band.split_type != SplitType::Immediate && !self.at_page_start() {
+            self.break_page(row)?;
+            if band.height <= self.available() {
+                self.emit(band, row)?;
+                self.y += band.height;
+                self.last_row = Some(row.clone());
+                return Ok(());
+            }
+        }
+
+        // 跨页拆分时各页页脚都取当前行
+        self.last_row = Some(row.clone());
+        self.split_band(band, row)
+    }
+
+    /// 按页拆分区段：跨越分页线的元素整体移到下一页
+    fn split_band(&mut self, band: &BandLayout, row: &Value) -> FillResult<()> {
+        let mut placed = vec![false; band.elements.len()];
+        let mut offset = 0.0;
+
+        loop {
+            if self.available() <= 0.0 {
+                self.break_page(row)?;
+            }
+
+            let cut = offset + self.available();
+            let mut next = cut.min(band.height);
+            for (index, element) in band.elements.iter().enumerate() {
+                if placed[index] {
+                    continue;
+                }
+                let top = element.position.y;
+                let bottom = top + element.size.height;
+                if top >= cut {
+                    continue;
+                }
+                // 起始于本段顶部的元素即使超高也必须输出，保证分页能推进
+                if bottom <= cut || top <= offset {
+                    self.emit_element(element, row, offset)?;
+                    placed[index] = true;
+                } else {
+                    next = next.min(top);
+                }
+            }
+
+            if placed.iter().all(|p| *p) {
+                // 剩余部分只有空白，不再为其换页
+                self.y += (band.height - offset).min(self.available()).max(0.0);
+                break;
+            }
+
+            self.y += next - offset;
+            offset = next;
+            self.break_page(row)?;
+            self.repeat_headers(band, offset, row)?;
+        }
+
+        Ok(())
+    }
+
+    /// 表格在分页线处被拆开时，在新页顶部重复输出表头
+    fn repeat_headers(&mut self, band: &BandLayout, offset: f64, row: &Value) -> FillResult<()> {
+        for header in &band.repeated_headers {
+            if header.top + header.height <= offset && offset < header.bottom {
+                for element in &header.elements {
+                    self.emit_element(element, row, 0.0)?;
+                }
+                self.y += header.height;
+            }
+        }
+        Ok(())
+    }
+
+    /// 在当前位置输出整个区段
+    fn emit(&mut self, band: &BandLayout, row: &Value) -> FillResult<()> {
+        self.pending = None;
+        for element in &band.elements {
+            self.emit_element(element, row, 0.0)?;
+        }
+        Ok(())
+    }
+
+    fn emit_element(&mut self, element: &ReportElement, row: &Value, offset: f64) -> FillResult<()> {
+        self.pending = None;
+        let mut filled = self.engine.fill_element_with_variables(element, row, &self.variable_values())?;
+        filled.position = Position {
+            x: self.layout.setup.margins.left + element.position.x,
+            y: self.y + element.position.y - offset,
+        };
+        filled.band = None;
+
+        let page = self.pages.last_mut().expect("page started before emitting elements");
+        page.elements.push(filled);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{ElementContent, Size, TextAlign, TextStyle};
+    use serde_json::json;
+
+    fn field(expression: &str, y: f64, height: f64) -> ReportElement {
+        ReportElement::new(
+            ElementContent::DataField {
+                expression: expression.to_string(),
+                format: None,
+                style: TextStyle {
+                    font_family: "Arial".to_string(),
+                    font_size: 10.0,
+                    font_weight: "normal".to_string(),
+                    color: "#000000".to_string(),
+                    align: TextAlign::Left,
+                    border: None,
+                    background: None,
+                },
+            },
+            Position::new(0.0, y).unwrap(),
+            Size::new(100.0, height).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn band(height: f64, split_type: SplitType, elements: Vec<ReportElement>) -> BandLayout {
+        BandLayout { elements, ..BandLayout::new(height, split_type) }
+    }
+
+    fn small_page() -> PageSetup {
+        PageSetup {
+            width: 200.0,
+            height: 200.0,
+            margins: PageMargins { top: 10.0, bottom: 10.0, left: 10.0, right: 10.0 },
+        }
+    }
+
+    fn rows(count: usize) -> Vec<Value> {
+        (1..=count).map(|i| json!({ "id": i })).collect()
+    }
+
+    fn text_positions(page: &FilledPage) -> Vec<(String, f64)> {
+        page.elements
+            .iter()
+            .filter_map(|e| match &e.content {
+                ElementContent::Text { content, .. } => Some((content.clone(), e.position.y)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detail_paginates_with_header_footer_and_summary() {
+        let mut layout = LayoutEngine::new(small_page());
+        layout.add_band(BandType::Title, band(30.0, SplitType::Stretch, vec![]));
+        layout.add_band(BandType::PageHeader, band(20.0, SplitType::Stretch, vec![]));
+        layout.add_band(BandType::Detail, band(30.0, SplitType::Stretch, vec![field("id", 0.0, 30.0)]));
+        layout.add_band(BandType::PageFooter, band(20.0, SplitType::Stretch, vec![field("id", 0.0, 20.0)]));
+        layout.add_band(BandType::Summary, band(40.0, SplitType::Stretch, vec![field("id", 0.0, 20.0)]));
+
+        let pages = layout.layout(&FillEngine::new(), &rows(10)).unwrap();
+        assert_eq!(pages.len(), 3);
+
+        // 首页：标题30 + 页眉20 之后放3行明细，页脚固定在底部
+        let first = text_positions(&pages[0]);
+        assert_eq!(first[..3], [("1".to_string(), 60.0), ("2".to_string(), 90.0), ("3".to_string(), 120.0)]);
+        assert_eq!(first[3], ("3".to_string(), 170.0));
+
+        // 后续页没有标题，每页4行
+        let second = text_positions(&pages[1]);
+        assert_eq!(second[0], ("4".to_string(), 30.0));
+        assert_eq!(second.len(), 5);
+
+        // 汇总区段紧随最后一行明细
+        let third = text_positions(&pages[2]);
+        assert!(third.contains(&("10".to_string(), 90.0)));
+        assert!(third.contains(&("10".to_string(), 120.0)));
+        assert!(pages.iter().all(|p| p.elements.iter().all(|e| e.position.x == 10.0)));
+    }
+
+    #[test]
+    fn test_prevent_moves_band_to_next_page() {
+        let mut layout = LayoutEngine::new(small_page());
+        layout.add_band(BandType::Detail, band(100.0, SplitType::Prevent, vec![field("id", 0.0, 20.0), field("id", 80.0, 20.0)]));
+
+        let pages = layout.layout(&FillEngine::new(), &rows(2)).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(text_positions(&pages[1]), vec![("2".to_string(), 10.0), ("2".to_string(), 90.0)]);
+    }
+
+    #[test]
+    fn test_immediate_split_and_oversized_band() {
+        let mut layout = LayoutEngine::new(small_page());
+        layout.add_band(BandType::Detail, band(100.0, SplitType::Immediate, vec![field("id", 0.0, 20.0), field("id", 80.0, 20.0)]));
+
+        // 第二行在剩余80的位置拆分，跨线元素移到下一页顶部
+        let pages = layout.layout(&FillEngine::new(), &rows(2)).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(text_positions(&pages[0]), vec![("1".to_string(), 10.0), ("1".to_string(), 90.0), ("2".to_string(), 110.0)]);
+        assert_eq!(text_positions(&pages[1]), vec![("2".to_string(), 10.0)]);
+
+        // 超过整页高度的区段被拆成多页
+        let mut tall = LayoutEngine::new(small_page());
+        tall.add_band(BandType::Detail, band(400.0, SplitType::Stretch, vec![field("id", 0.0, 20.0), field("id", 300.0, 20.0)]));
+        let pages = tall.layout(&FillEngine::new(), &rows(1)).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(text_positions(&pages[1]), vec![("1".to_string(), 130.0)]);
+    }
+
+    #[test]
+    fn test_header_footer_larger_than_page() {
+        let mut layout = LayoutEngine::new(small_page());
+        layout.add_band(BandType::PageHeader, band(100.0, SplitType::Stretch, vec![]));
+        layout.add_band(BandType::PageFooter, band(100.0, SplitType::Stretch, vec![]));
+        assert!(layout.layout(&FillEngine::new(), &rows(1)).is_err());
+    }
+
+    fn group(name: &str, header: Option<BandLayout>, footer: Option<BandLayout>) -> GroupLayout {
+        GroupLayout {
+            name: name.to_string(),
+            expression: format!("$F{{{}}}", name),
+            sort_order: SortOrder::Ascending,
+            header,
+            footer,
+            start_on_new_page: false,
+            keep_together: false,
+        }
+    }
+
+    fn texts(pages: &[FilledPage]) -> Vec<String> {
+        pages.iter().flat_map(text_positions).map(|(text, _)| text).collect()
+    }
+
+    fn account_rows() -> Vec<Value> {
+        vec![
+            json!({ "account": "B", "date": "d1", "id": 1 }),
+            json!({ "account": "A", "date": "d2", "id": 2 }),
+            json!({ "account": "A", "date": "d1", "id": 3 }),
+            json!({ "account": "B", "date": "d1", "id": 4 }),
+        ]
+    }
+
+    #[test]
+    fn test_nested_groups_sort_rows_and_emit_headers_footers() {
+        let mut layout = LayoutEngine::new(PageSetup { height: 1000.0, ..small_page() });
+        layout.add_band(BandType::Detail, band(10.0, SplitType::Stretch, vec![field("id", 0.0, 10.0)]));
+        layout.add_group(group(
+            "account",
+            Some(band(10.0, SplitType::Stretch, vec![field("account", 0.0, 10.0)])),
+            Some(band(10.0, SplitType::Stretch, vec![field("account", 0.0, 10.0)])),
+        ));
+        layout.add_group(group("date", Some(band(10.0, SplitType::Stretch, vec![field("date", 0.0, 10.0)])), None));
+
+        let pages = layout.layout(&FillEngine::new(), &account_rows()).unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(texts(&pages), ["A", "d1", "3", "d2", "2", "A", "B", "d1", "1", "4", "B"]);
+
+        // 降序排列
+        layout.groups[0].sort_order = SortOrder::Descending;
+        let pages = layout.layout(&FillEngine::new(), &account_rows()).unwrap();
+        assert_eq!(texts(&pages)[..4], ["B", "d1", "1", "4"]);
+    }
+
+    #[test]
+    fn test_group_start_on_new_page() {
+        let mut layout = LayoutEngine::new(PageSetup { height: 1000.0, ..small_page() });
+        layout.add_band(BandType::Detail, band(10.0, SplitType::Stretch, vec![field("id", 0.0, 10.0)]));
+        let mut account = group("account", Some(band(10.0, SplitType::Stretch, vec![field("account", 0.0, 10.0)])), None);
+        account.start_on_new_page = true;
+        layout.add_group(account);
+
+        let pages = layout.layout(&FillEngine::new(), &account_rows()).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(texts(&pages[..1]), ["A", "2", "3"]);
+        assert_eq!(text_positions(&pages[1])[0], ("B".to_string(), 10.0));
+    }
+
+    #[test]
+    fn test_group_keep_together() {
+        let rows: Vec<Value> = ["A", "A", "A", "B", "B", "B"]
+            .iter()
+            .map(|account| json!({ "account": account }))
+            .collect();
+        let build = |keep_together: bool| {
+            let mut layout = LayoutEngine::new(small_page());
+            layout.add_band(BandType::Detail, band(30.0, SplitType::Stretch, vec![field("account", 0.0, 30.0)]));
+            let mut account = group("account", Some(band(20.0, SplitType::Stretch, vec![field("account", 0.0, 20.0)])), None);
+            account.keep_together = keep_together;
+            layout.add_group(account);
+            layout
+        };
+
+        // 分组B需要110，首页只剩70：整体移到下一页
+        let pages = build(true).layout(&FillEngine::new(), &rows).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(text_positions(&pages[0]).len(), 4);
+        assert_eq!(text_positions(&pages[1])[0], ("B".to_string(), 10.0));
+
+        let pages = build(false).layout(&FillEngine::new(), &rows).unwrap();
+        assert_eq!(text_positions(&pages[0]).len(), 6);
+    }
+
+    #[test]
+    fn test_variables_with_page_and_group_reset() {
+        use crate::core::template::{Calculation, ResetType};
+
+        let mut layout = LayoutEngine::new(small_page());
+        layout.add_band(BandType::Detail, band(40.0, SplitType::Stretch, vec![field("$V{balance}", 0.0, 40.0)]));
+        layout.add_band(BandType::PageFooter, band(20.0, SplitType::Stretch, vec![field("$V{page_total}", 0.0, 20.0)]));
+        layout.add_group(group("account", None, Some(band(20.0, SplitType::Stretch, vec![field("$V{account_total}", 0.0, 20.0)]))));
+        layout.add_band(BandType::Summary, band(20.0, SplitType::Stretch, vec![field("$V{REPORT_COUNT}", 0.0, 20.0)]));
+
+        let mut page_total = Variable::new("page_total", "$F{amount}", Calculation::Sum);
+        page_total.reset_type = ResetType::Page;
+        let mut account_total = Variable::new("account_total", "$F{amount}", Calculation::Sum);
+        account_total.reset_type = ResetType::Group;
+        account_total.reset_group = Some("account".to_string());
+        layout.add_variable(Variable::new("balance", "$F{amount}", Calculation::Sum));
+        layout.add_variable(page_total);
+        layout.add_variable(account_total);
+
+        let rows: Vec<Value> = [("A", 10), ("A", 20), ("A", 30), ("B", 1), ("B", 2)]
+            .iter()
+            .map(|(account, amount)| json!({ "account": account, "amount": amount }))
+            .collect();
+        let pages = layout.layout(&FillEngine::new(), &rows).unwrap();
+        assert_eq!(pages.len(), 2);
+
+        // 首页可容纳160：3行明细 + A组脚，B的首行移到下一页，不计入首页合计
+        assert_eq!(texts(&pages[..1]), ["10", "30", "60", "60", "60"]);
+        assert_eq!(texts(&pages[1..]), ["61", "63", "3", "5", "3"]);
+    }
+}
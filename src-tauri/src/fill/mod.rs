@@ -0,0 +1,39 @@
+pub mod engine;
+pub mod layout;
+pub mod model;
+pub mod parameters;
+pub mod variables;
+pub mod preload;
+pub mod table;
+pub mod crosstab;
+pub mod chart;
+pub mod commands;
+
+// Re-export main types for convenience
+pub use engine::FillEngine;
+pub use layout::{BandLayout, GroupLayout, LayoutEngine, PageSetup};
+pub use model::{FilledPage, FilledReport};
+pub use preload::Preloaded;
+pub use variables::{VariableCalculator, VariableScope};
+
+// Fill-specific error types
+pub mod error {
+    use serde::{Deserialize, Serialize};
+    use thiserror::Error;
+
+    #[derive(Debug, Error, Serialize, Deserialize)]
+    pub enum FillError {
+        #[error("Data source error: {message}")]
+        DataSourceError { message: String },
+
+        #[error("Expression error in '{expression}': {message}")]
+        ExpressionError { expression: String, message: String },
+
+        #[error("Template error: {message}")]
+        TemplateError { message: String },
+    }
+
+    pub type FillResult<T> = Result<T, FillError>;
+}
+
+pub use error::{FillError, FillResult};
@@ -0,0 +1,27 @@
+// === 填充结果模型 ===
+use serde::{Deserialize, Serialize};
+use crate::core::element::ReportElement;
+
+/// 填充后的报表（可直接交给预览渲染器逐页渲染）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilledReport {
+    pub page_width: f64,
+    pub page_height: f64,
+    pub pages: Vec<FilledPage>,
+    /// 参与填充的数据行数
+    pub record_count: usize,
+    pub warnings: Vec<String>,
+}
+
+/// 填充后的单页内容，数据字段已替换为求值后的文本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilledPage {
+    pub page_number: usize,
+    pub elements: Vec<ReportElement>,
+}
+
+impl FilledReport {
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+}
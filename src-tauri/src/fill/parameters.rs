@@ -0,0 +1,213 @@
+// === 报表参数校验 ===
+use crate::core::template::{DataType, Parameter};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 单个参数的校验问题
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParameterIssue {
+    pub name: String,
+    pub message: String,
+}
+
+/// 参数校验结果：`values` 为补全默认值并转换类型后的参数
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParameterValidation {
+    pub valid: bool,
+    pub values: HashMap<String, Value>,
+    pub errors: Vec<ParameterIssue>,
+    pub warnings: Vec<ParameterIssue>,
+}
+
+impl ParameterValidation {
+    /// 汇总错误信息，用于命令返回
+    pub fn error_message(&self) -> String {
+        self.errors
+            .iter()
+            .map(|issue| format!("{}: {}", issue.name, issue.message))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// 按模板参数定义校验参数值：补全默认值、检查必填项并按声明类型转换
+pub fn resolve_parameters(definitions: &[Parameter], supplied: &HashMap<String, Value>) -> ParameterValidation {
+    let mut values = HashMap::new();
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    for definition in definitions {
+        let value = supplied
+            .get(&definition.name)
+            .filter(|value| !is_blank(value))
+            .or_else(|| definition.default.as_ref().filter(|value| !is_blank(value)));
+
+        let Some(value) = value else {
+            if definition.required {
+                errors.push(ParameterIssue {
+                    name: definition.name.clone(),
+                    message: "Value is required".to_string(),
+                });
+            } else {
+                values.insert(definition.name.clone(), Value::Null);
+            }
+            continue;
+        };
+
+        match coerce_value(value, &definition.param_type) {
+            Ok(value) => {
+                values.insert(definition.name.clone(), value);
+            }
+            Err(message) => errors.push(ParameterIssue {
+                name: definition.name.clone(),
+                message,
+            }),
+        }
+    }
+
+    let mut unknown: Vec<&String> = supplied
+        .keys()
+        .filter(|name| !definitions.iter().any(|definition| &definition.name == *name))
+        .collect();
+    unknown.sort();
+    for name in unknown {
+        warnings.push(ParameterIssue {
+            name: name.clone(),
+            message: "Parameter is not declared by the template".to_string(),
+        });
+        values.insert(name.clone(), supplied[name].clone());
+    }
+
+    ParameterValidation {
+        valid: errors.is_empty(),
+        values,
+        errors,
+        warnings,
+    }
+}
+
+fn is_blank(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(text) => text.trim().is_empty(),
+        _ => false,
+    }
+}
+
+/// 将输入值转换为参数声明的类型，日期统一为ISO格式文本
+fn coerce_value(value: &Value, data_type: &DataType) -> Result<Value, String> {
+    match (data_type, value) {
+        (DataType::String, Value::String(_)) => Ok(value.clone()),
+        (DataType::String, Value::Number(_) | Value::Bool(_)) => Ok(Value::String(value.to_string())),
+        (DataType::Number, Value::Number(_)) => Ok(value.clone()),
+        (DataType::Number, Value::String(text)) => {
+            let text = text.trim();
+            if let Ok(integer) = text.parse::<i64>() {
+                Ok(Value::from(integer))
+            } else {
+                text.parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number)
+                    .ok_or_else(|| format!("'{}' is not a number", text))
+            }
+        }
+        (DataType::Boolean, Value::Bool(_)) => Ok(value.clone()),
+        (DataType::Boolean, Value::String(text)) => match text.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" => Ok(Value::Bool(true)),
+            "false" | "0" | "no" => Ok(Value::Bool(false)),
+            _ => Err(format!("'{}' is not a boolean", text)),
+        },
+        (DataType::Date, Value::String(text)) => parse_date(text.trim())
+            .map(|date| Value::String(date.format("%Y-%m-%d").to_string()))
+            .ok_or_else(|| format!("'{}' is not a date (expected yyyy-MM-dd)", text)),
+        (DataType::DateTime, Value::String(text)) => parse_datetime(text.trim())
+            .map(|datetime| Value::String(datetime.format("%Y-%m-%d %H:%M:%S").to_string()))
+            .ok_or_else(|| format!("'{}' is not a date time", text)),
+        (DataType::Array, Value::Array(_)) | (DataType::Object, Value::Object(_)) | (DataType::Null, _) => Ok(value.clone()),
+        (expected, _) => Err(format!("Expected {:?} value, got {}", expected, value)),
+    }
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(text, "%Y/%m/%d"))
+        .ok()
+        .or_else(|| parse_datetime(text).map(|datetime| datetime.date()))
+}
+
+fn parse_datetime(text: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|datetime| datetime.naive_local())
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S"))
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parameter(name: &str, param_type: DataType, required: bool, default: Option<Value>) -> Parameter {
+        Parameter {
+            name: name.to_string(),
+            param_type,
+            default,
+            description: None,
+            required,
+        }
+    }
+
+    #[test]
+    fn test_resolve_parameters() {
+        let definitions = vec![
+            parameter("customerId", DataType::String, true, None),
+            parameter("from", DataType::Date, false, Some(json!("2024/01/01"))),
+            parameter("limit", DataType::Number, false, None),
+            parameter("active", DataType::Boolean, false, None),
+        ];
+        let supplied = HashMap::from([
+            ("customerId".to_string(), json!(1001)),
+            ("limit".to_string(), json!(" 20 ")),
+            ("active".to_string(), json!("yes")),
+            ("extra".to_string(), json!("x")),
+        ]);
+
+        let result = resolve_parameters(&definitions, &supplied);
+        assert!(result.valid);
+        assert_eq!(result.values["customerId"], json!("1001"));
+        assert_eq!(result.values["from"], json!("2024-01-01"));
+        assert_eq!(result.values["limit"], json!(20));
+        assert_eq!(result.values["active"], json!(true));
+        assert_eq!(result.warnings[0].name, "extra");
+    }
+
+    #[test]
+    fn test_resolve_parameters_errors() {
+        let definitions = vec![
+            parameter("customerId", DataType::String, true, None),
+            parameter("from", DataType::Date, false, None),
+            parameter("amount", DataType::Number, false, None),
+        ];
+        let supplied = HashMap::from([
+            ("customerId".to_string(), json!("  ")),
+            ("from".to_string(), json!("2024-13-01")),
+            ("amount".to_string(), json!("abc")),
+        ]);
+
+        let result = resolve_parameters(&definitions, &supplied);
+        assert!(!result.valid);
+        let names: Vec<&str> = result.errors.iter().map(|issue| issue.name.as_str()).collect();
+        assert_eq!(names, ["customerId", "from", "amount"]);
+    }
+}
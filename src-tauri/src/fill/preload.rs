@@ -0,0 +1,365 @@
+// === 填充前预加载（子报表、表格、交叉表和图表数据） ===
+use crate::core::element::SubreportConfig;
+use crate::core::template::{ElementType, JasperTemplate};
+use crate::core::template_service::TemplateLoader;
+use crate::data::{DataSet, DataSourceRegistry};
+use crate::fill::parameters::resolve_parameters;
+use crate::fill::{FillEngine, FillError, FillResult};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+
+/// 子报表最大嵌套层数，超出时视为循环引用
+const MAX_DEPTH: usize = 8;
+
+/// 填充前预先加载的子报表模板和数据、表格/交叉表/图表绑定的数据源（布局过程是同步的，不能在分页时查询数据源）
+#[derive(Debug, Default)]
+pub struct Preloaded {
+    templates: HashMap<PathBuf, JasperTemplate>,
+    /// 按模板路径和参数值缓存的子报表数据
+    data: HashMap<String, DataSet>,
+    /// 按数据源 ID 和参数值缓存的表格、交叉表和图表数据
+    tables: HashMap<String, DataSet>,
+}
+
+/// 子报表单次填充所需的模板、引擎和数据行
+pub(crate) struct SubreportInput<'a> {
+    pub template: &'a JasperTemplate,
+    pub engine: FillEngine,
+    pub rows: Vec<Value>,
+}
+
+impl Preloaded {
+    /// 加载模板中的表格、交叉表、图表数据和全部子报表：父报表每条记录求值一次子报表参数，未指定数据表达式时按参数查询子报表数据源
+    pub(crate) fn load<'a>(
+        &'a mut self,
+        engine: &'a FillEngine,
+        template: &'a JasperTemplate,
+        rows: &'a [Value],
+        registry: &'a DataSourceRegistry,
+        depth: usize,
+    ) -> Pin<Box<dyn Future<Output = FillResult<()>> + Send + 'a>> {
+        Box::pin(async move {
+            self.load_tables(engine, template, registry).await?;
+
+            let configs: Vec<&SubreportConfig> = template
+                .elements
+                .iter()
+                .filter(|element| element.element_type == ElementType::Subreport)
+                .filter_map(|element| element.content.subreport.as_ref())
+                .collect();
+            if configs.is_empty() {
+                return Ok(());
+            }
+            if depth >= MAX_DEPTH {
+                return Err(FillError::TemplateError {
+                    message: format!("Preloaded are nested more than {} levels deep", MAX_DEPTH),
+                });
+            }
+
+            let empty_row = Value::Object(Default::default());
+            let rows: Vec<&Value> = if rows.is_empty() { vec![&empty_row] } else { rows.iter().collect() };
+            for config in configs {
+                let path = engine.subreport_path(&config.template_path);
+                if !self.templates.contains_key(&path) {
+                    let child = TemplateLoader::load(&path.to_string_lossy()).map_err(|e| FillError::TemplateError {
+                        message: format!("Failed to load subreport '{}': {}", config.template_path, e),
+                    })?;
+                    self.templates.insert(path.clone(), child);
+                }
+
+                for row in &rows {
+                    let child = &self.templates[&path];
+                    let child_engine = child_engine(engine, &path, child, config, row)?;
+                    let child_rows = match &config.data_expression {
+                        Some(expression) => data_rows(engine.evaluate_expression(expression, row)?),
+                        None => {
+                            let key = data_key(&path, &child_engine);
+                            if !self.data.contains_key(&key) {
+                                let data = query_child(&child_engine, child, registry).await?;
+                                self.data.insert(key.clone(), data);
+                            }
+                            self.data[&key].rows.clone()
+                        }
+                    };
+
+                    // 嵌套子报表的模板同样需要预先加载
+                    let child = self.templates[&path].clone();
+                    self.load(&child_engine, &child, &child_rows, registry, depth + 1).await?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// 查询表格、交叉表和图表绑定的数据源（使用数据表达式的除外）
+    async fn load_tables(&mut self, engine: &FillEngine, template: &JasperTemplate, registry: &DataSourceRegistry) -> FillResult<()> {
+        let sources = template.elements.iter().filter_map(|element| match element.element_type {
+            ElementType::Table => element.content.table.as_ref().map(|config| (&config.data_source_id, &config.data_expression)),
+            ElementType::Crosstab => element.content.crosstab.as_ref().map(|config| (&config.data_source_id, &config.data_expression)),
+            ElementType::Chart => element.content.chart.as_ref().map(|config| (&config.data_source_id, &config.data_expression)),
+            _ => None,
+        });
+        for (source_id, _) in sources.filter(|(_, expression)| expression.is_none()) {
+            let key = table_key(source_id, engine);
+            if self.tables.contains_key(&key) {
+                continue;
+            }
+            let source = template.get_data_source_by_id(source_id).ok_or_else(|| FillError::TemplateError {
+                message: format!("Table data source '{}' is not defined in the template", source_id),
+            })?;
+            let data = registry
+                .query_data(&source.id, Some(engine.convert_query(source.query.as_ref())))
+                .await
+                .map_err(|e| FillError::DataSourceError {
+                    message: format!("Failed to query table data source '{}': {}", source.id, e),
+                })?;
+            self.tables.insert(key, data);
+        }
+        Ok(())
+    }
+
+    /// 父记录对应的表格、交叉表或图表数据行：有数据表达式时取其结果，否则取预加载的数据源
+    pub(crate) fn table_rows(
+        &self,
+        engine: &FillEngine,
+        data_source_id: &str,
+        data_expression: Option<&str>,
+        row: &Value,
+    ) -> FillResult<Vec<Value>> {
+        match data_expression {
+            Some(expression) => Ok(data_rows(engine.evaluate_expression(expression, row)?)),
+            None => self
+                .tables
+                .get(&table_key(data_source_id, engine))
+                .map(|data| data.rows.clone())
+                .ok_or_else(|| FillError::TemplateError {
+                    message: format!("Table data source '{}' was not loaded before filling", data_source_id),
+                }),
+        }
+    }
+
+    /// 取出父记录对应的子报表输入
+    pub(crate) fn input(&self, engine: &FillEngine, config: &SubreportConfig, row: &Value) -> FillResult<SubreportInput<'_>> {
+        let path = engine.subreport_path(&config.template_path);
+        let template = self.templates.get(&path).ok_or_else(|| FillError::TemplateError {
+            message: format!("Subreport '{}' was not loaded before filling", config.template_path),
+        })?;
+        let child_engine = child_engine(engine, &path, template, config, row)?;
+        let rows = match &config.data_expression {
+            Some(expression) => data_rows(engine.evaluate_expression(expression, row)?),
+            None => self
+                .data
+                .get(&data_key(&path, &child_engine))
+                .map(|data| data.rows.clone())
+                .unwrap_or_default(),
+        };
+
+        Ok(SubreportInput {
+            template,
+            engine: child_engine,
+            rows,
+        })
+    }
+}
+
+/// 子报表填充引擎：参数由父记录求值并按子模板的参数定义补全默认值
+fn child_engine(
+    engine: &FillEngine,
+    path: &Path,
+    template: &JasperTemplate,
+    config: &SubreportConfig,
+    row: &Value,
+) -> FillResult<FillEngine> {
+    let supplied = config
+        .parameters
+        .iter()
+        .map(|parameter| Ok((parameter.name.clone(), engine.evaluate_expression(&parameter.expression, row)?)))
+        .collect::<FillResult<HashMap<_, _>>>()?;
+    let resolved = resolve_parameters(&template.parameters, &supplied);
+    if !resolved.valid {
+        return Err(FillError::TemplateError {
+            message: format!(
+                "Invalid parameters for subreport '{}': {}",
+                config.template_path,
+                resolved.error_message()
+            ),
+        });
+    }
+
+    Ok(engine.subreport_engine(resolved.values, path.parent().map(Path::to_path_buf)))
+}
+
+async fn query_child(engine: &FillEngine, template: &JasperTemplate, registry: &DataSourceRegistry) -> FillResult<DataSet> {
+    let Some(source) = template.data_sources.first() else {
+        return Ok(DataSet {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            total_count: 0,
+            metadata: None,
+            cached: false,
+            cache_time: None,
+            checksum: None,
+        });
+    };
+    registry
+        .query_data(&source.id, Some(engine.convert_query(source.query.as_ref())))
+        .await
+        .map_err(|e| FillError::DataSourceError {
+            message: format!("Failed to query subreport data source '{}': {}", source.id, e),
+        })
+}
+
+/// 数据缓存键：模板路径 + 按名称排序的参数值
+fn data_key(path: &Path, engine: &FillEngine) -> String {
+    format!("{}|{}", path.display(), parameters_key(engine))
+}
+
+fn table_key(source_id: &str, engine: &FillEngine) -> String {
+    format!("{}|{}", source_id, parameters_key(engine))
+}
+
+fn parameters_key(engine: &FillEngine) -> String {
+    let parameters: BTreeMap<&String, &Value> = engine.parameters().iter().collect();
+    serde_json::to_string(&parameters).unwrap_or_default()
+}
+
+/// 数据表达式的结果转换为数据行：数组逐项作为记录，空值没有记录
+fn data_rows(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(rows) => rows,
+        Value::Null => Vec::new(),
+        other => vec![other],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{self as el, Position, Size, SubreportParameter};
+    use crate::core::template::{BandConfig, DataType, PageMargins, Parameter, TemplateElement};
+    use crate::core::template_service::TemplateSerializer;
+    use crate::data::storage::{ConfigStorage, MemoryConfigStorage};
+    use crate::fill::LayoutEngine;
+    use serde_json::json;
+
+    fn element(id: &str, element_type: ElementType, y: f64, height: f64) -> TemplateElement {
+        TemplateElement {
+            id: id.to_string(),
+            ..TemplateElement::new(element_type, Position { x: 0.0, y }, Size { width: 100.0, height })
+        }
+    }
+
+    fn field(id: &str, expression: &str, y: f64) -> TemplateElement {
+        let mut field = element(id, ElementType::DataField, y, 20.0);
+        field.content.expression = Some(expression.to_string());
+        field
+    }
+
+    fn band(height: f64, elements: &[&TemplateElement]) -> BandConfig {
+        BandConfig {
+            elements: elements.iter().map(|element| element.id.clone()).collect(),
+            ..BandConfig::new(height)
+        }
+    }
+
+    fn texts(report: &[crate::fill::FilledPage]) -> Vec<Vec<(String, f64)>> {
+        report
+            .iter()
+            .map(|page| {
+                page.elements
+                    .iter()
+                    .filter_map(|element| match &element.content {
+                        el::ElementContent::Text { content, .. } => Some((content.clone(), element.position.y)),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_subreport_stretches_and_paginates() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // 子报表：标题显示传入的参数，明细逐行输出父记录中的明细项
+        let mut child = JasperTemplate::new();
+        child.parameters.push(Parameter {
+            name: "customer".to_string(),
+            param_type: DataType::String,
+            default: None,
+            description: None,
+            required: true,
+        });
+        let title = field("child_title", "$P{customer}", 0.0);
+        let item = field("child_item", "$F{item}", 0.0);
+        child.bands.title = Some(band(20.0, &[&title]));
+        child.bands.detail = Some(band(20.0, &[&item]));
+        child.elements = vec![title, item];
+        TemplateSerializer::new()
+            .save_to_file(&child, &dir.path().join("items.json").to_string_lossy())
+            .unwrap();
+
+        let mut parent = JasperTemplate::new();
+        parent.canvas.width = 200.0;
+        parent.canvas.height = 200.0;
+        parent.canvas.margins = PageMargins { top: 10.0, bottom: 10.0, left: 10.0, right: 10.0 };
+        let name = field("name", "$F{name}", 0.0);
+        let mut subreport = element("items", ElementType::Subreport, 20.0, 20.0);
+        subreport.content.subreport = Some(SubreportConfig {
+            template_path: "items.json".to_string(),
+            parameters: vec![SubreportParameter { name: "customer".to_string(), expression: "$F{name}".to_string() }],
+            data_expression: Some("$F{items}".to_string()),
+        });
+        let after = field("after", "\"end \" + $F{name}", 40.0);
+        parent.bands.detail = Some(band(60.0, &[&name, &subreport, &after]));
+        parent.elements = vec![name, subreport, after];
+
+        let rows = vec![
+            json!({"name": "A", "items": (1..=10).map(|i| json!({"item": format!("a{}", i)})).collect::<Vec<_>>()}),
+            json!({"name": "B", "items": [{"item": "b1"}]}),
+            json!({"name": "C", "items": []}),
+        ];
+        let registry = DataSourceRegistry::new(Box::new(MemoryConfigStorage::new()) as Box<dyn ConfigStorage>);
+        let engine = FillEngine::new().with_base_dir(dir.path());
+        let mut preloaded = Preloaded::default();
+        preloaded.load(&engine, &parent, &rows, &registry, 0).await.unwrap();
+        let engine = engine.with_preloaded(preloaded);
+
+        let pages = LayoutEngine::from_template(&parent).layout(&engine, &rows).unwrap();
+        let pages = texts(&pages);
+        assert!(pages.len() >= 2, "stretched band should continue on the next page");
+        for page in &pages {
+            assert!(page.iter().all(|(_, y)| *y >= 10.0 && *y + 20.0 <= 190.0));
+        }
+
+        let all: Vec<&str> = pages.iter().flatten().map(|(text, _)| text.as_str()).collect();
+        let mut expected = vec!["A", "A"];
+        let items: Vec<String> = (1..=10).map(|i| format!("a{}", i)).collect();
+        expected.extend(items.iter().map(String::as_str));
+        expected.extend(["end A", "B", "B", "b1", "end B", "C", "end C"]);
+        assert_eq!(all, expected);
+
+        // 子报表下方的元素随内容下移：B 只有一行明细，"end B" 紧随标题和明细之后
+        let y_of = |text: &str| pages.iter().flatten().find(|(t, _)| t == text).map(|(_, y)| *y).unwrap();
+        assert_eq!(y_of("end B") - y_of("B"), 60.0);
+        assert_eq!(y_of("end C") - y_of("C"), 40.0);
+    }
+
+    #[tokio::test]
+    async fn test_missing_subreport_template() {
+        let mut parent = JasperTemplate::new();
+        let mut subreport = element("items", ElementType::Subreport, 0.0, 20.0);
+        subreport.content.subreport = Some(SubreportConfig {
+            template_path: "/nonexistent/items.json".to_string(),
+            ..Default::default()
+        });
+        parent.elements = vec![subreport];
+
+        let registry = DataSourceRegistry::new(Box::new(MemoryConfigStorage::new()) as Box<dyn ConfigStorage>);
+        let result = Preloaded::default().load(&FillEngine::new(), &parent, &[], &registry, 0).await;
+        assert!(matches!(result, Err(FillError::TemplateError { .. })));
+    }
+}
@@ -0,0 +1,148 @@
+// === 表格填充 ===
+use crate::core::element::TableConfig;
+use crate::core::template::Variable;
+use crate::fill::layout::Expansion;
+use crate::fill::{FillEngine, FillResult, VariableCalculator, VariableScope};
+use serde_json::Value;
+
+/// 表格展开为单元格：表头、逐条记录的数据行（偶数行斑马纹）和表尾汇总
+pub(crate) fn fill_table(engine: &FillEngine, config: &TableConfig, rows: &[Value]) -> FillResult<Expansion> {
+    // 表尾汇总复用报表变量的计算规则，每列一个变量
+    let totals: Vec<Variable> = config
+        .columns
+        .iter()
+        .enumerate()
+        .filter_map(|(index, column)| {
+            let calculation = column.footer_calculation?;
+            Some(Variable::new(&format!("column_{}", index), &column.expression, calculation))
+        })
+        .collect();
+    let mut calculator = VariableCalculator::new(&totals, &[])?;
+
+    let mut elements = config.header_cells();
+    for (index, row) in rows.iter().enumerate() {
+        let texts = config
+            .columns
+            .iter()
+            .map(|column| {
+                let value = engine.evaluate_expression(&column.expression, row)?;
+                Ok(engine.format_value(&value, column.format.as_deref()))
+            })
+            .collect::<FillResult<Vec<_>>>()?;
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        elements.extend(config.body_cells(index, &texts));
+        calculator.increment(engine, VariableScope::Record, row)?;
+    }
+
+    if config.show_footer {
+        let totals = calculator.values();
+        let texts: Vec<String> = config
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| match totals.get(&format!("column_{}", index)) {
+                Some(total) => engine.format_value(total, column.format.as_deref()),
+                None => column.footer_text.clone().unwrap_or_default(),
+            })
+            .collect();
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        elements.extend(config.footer_cells(rows.len(), &texts));
+    }
+
+    Ok(Expansion {
+        elements,
+        height: config.height(rows.len()),
+        header: config.show_header.then(|| (config.header_height, config.header_cells())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{
+        BorderStyle, BorderStyleType, ElementContent, Position, ReportElement, Size, TableColumn, TextAlign,
+    };
+    use crate::core::template::{BandType, Calculation, PageMargins, SplitType};
+    use crate::fill::{BandLayout, LayoutEngine, PageSetup};
+    use serde_json::json;
+
+    fn column(header: &str, expression: &str, format: Option<&str>) -> TableColumn {
+        TableColumn {
+            header: header.to_string(),
+            expression: expression.to_string(),
+            width: 80.0,
+            align: TextAlign::Left,
+            format: format.map(str::to_string),
+            footer_calculation: None,
+            footer_text: None,
+        }
+    }
+
+    #[test]
+    fn test_table_repeats_header_on_each_page() {
+        let mut amount = column("Amount", "$F{amount}", Some("#,##0.00"));
+        amount.footer_calculation = Some(Calculation::Sum);
+        let mut item = column("Item", "$F{item}", None);
+        item.footer_text = Some("Total".to_string());
+        let config = TableConfig {
+            data_expression: Some("$F{lines}".to_string()),
+            columns: vec![item, amount],
+            show_footer: true,
+            zebra_color: Some("#eeeeee".to_string()),
+            border: Some(BorderStyle { color: "#000000".to_string(), width: 0.5, style: BorderStyleType::Solid }),
+            ..TableConfig::default()
+        };
+        let table = ReportElement::new(
+            ElementContent::Table(config),
+            Position::new(0.0, 0.0).unwrap(),
+            Size::new(160.0, 30.0).unwrap(),
+        )
+        .unwrap();
+
+        let mut layout = LayoutEngine::new(PageSetup {
+            width: 200.0,
+            height: 200.0,
+            margins: PageMargins { top: 10.0, bottom: 10.0, left: 10.0, right: 10.0 },
+        });
+        layout.add_band(BandType::Detail, BandLayout {
+            elements: vec![table],
+            ..BandLayout::new(30.0, SplitType::Immediate)
+        });
+        let lines: Vec<Value> = (1..=12).map(|i| json!({"item": format!("item {}", i), "amount": i})).collect();
+        let pages = layout.layout(&FillEngine::new(), &[json!({ "lines": lines })]).unwrap();
+
+        let texts = |page: usize| -> Vec<(String, f64)> {
+            pages[page]
+                .elements
+                .iter()
+                .filter_map(|element| match &element.content {
+                    ElementContent::Text { content, .. } => Some((content.clone(), element.position.y)),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(pages.len(), 2);
+        let first = texts(0);
+        assert_eq!(&first[..2], &[("Item".to_string(), 10.0), ("Amount".to_string(), 10.0)]);
+        assert_eq!(first.len(), 2 + 8 * 2);
+        let second = texts(1);
+        assert_eq!(&second[..3], &[
+            ("Item".to_string(), 10.0),
+            ("Amount".to_string(), 10.0),
+            ("item 9".to_string(), 30.0),
+        ]);
+        assert_eq!(&second[second.len() - 2..], &[("Total".to_string(), 110.0), ("78.00".to_string(), 110.0)]);
+
+        // 偶数数据行使用斑马纹背景
+        let fills: Vec<Option<&str>> = pages[0]
+            .elements
+            .iter()
+            .filter_map(|element| match &element.content {
+                ElementContent::Rectangle { fill_color, .. } => Some(fill_color.as_deref()),
+                _ => None,
+            })
+            .step_by(2)
+            .collect();
+        assert_eq!(fills[..4], [None, None, Some("#eeeeee"), None]);
+    }
+}
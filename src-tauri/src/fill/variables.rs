@@ -0,0 +1,274 @@
+// === 报表变量计算 ===
+use crate::core::template::{Calculation, IncrementType, ResetType, Variable};
+use crate::fill::layout::compare_values;
+use crate::fill::{FillEngine, FillError, FillResult, GroupLayout};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// 变量重置/累加的作用范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableScope {
+    Report,
+    Page,
+    Column,
+    /// 分组（按由外到内的层级）
+    Group(usize),
+    /// 每条记录
+    Record,
+}
+
+/// 单个变量的累加状态
+#[derive(Debug, Clone)]
+struct VariableState {
+    definition: Variable,
+    reset: VariableScope,
+    increment: VariableScope,
+    count: u64,
+    sum: f64,
+    integral: bool,
+    distinct: HashSet<String>,
+    value: Value,
+}
+
+impl VariableState {
+    fn reset(&mut self) {
+        self.count = 0;
+        self.distinct.clear();
+        self.value = self.definition.initial_value.clone().unwrap_or(Value::Null);
+        self.sum = self.value.as_f64().unwrap_or(0.0);
+        self.integral = self.value.is_null() || self.value.is_i64() || self.value.is_u64();
+
+        if self.value.is_null() && matches!(self.definition.calculation, Calculation::Count | Calculation::DistinctCount) {
+            self.value = Value::from(0);
+        }
+    }
+
+    fn accumulate(&mut self, value: Value) {
+        match self.definition.calculation {
+            Calculation::Nothing => self.value = value,
+            Calculation::Count => {
+                if !value.is_null() {
+                    self.count += 1;
+                    self.value = Value::from(self.count);
+                }
+            }
+            Calculation::DistinctCount => {
+                if !value.is_null() {
+                    self.distinct.insert(value.to_string());
+                    self.value = Value::from(self.distinct.len());
+                }
+            }
+            Calculation::Sum => {
+                if let Some(number) = numeric(&value) {
+                    self.integral &= number.fract() == 0.0;
+                    self.sum += number;
+                    self.value = number_value(self.sum, self.integral);
+                }
+            }
+            Calculation::Average => {
+                if let Some(number) = numeric(&value) {
+                    self.count += 1;
+                    self.sum += number;
+                    self.value = number_value(self.sum / self.count as f64, false);
+                }
+            }
+            Calculation::Min | Calculation::Max => {
+                if value.is_null() {
+                    return;
+                }
+                let wanted = match self.definition.calculation {
+                    Calculation::Min => Ordering::Less,
+                    _ => Ordering::Greater,
+                };
+                if self.value.is_null() || compare_values(&value, &self.value) == wanted {
+                    self.value = value;
+                }
+            }
+            Calculation::First => {
+                if self.count == 0 {
+                    self.value = value;
+                }
+                self.count += 1;
+            }
+        }
+    }
+}
+
+/// 变量计算器：按定义顺序求值，后定义的变量可以引用前面的变量
+#[derive(Debug, Clone, Default)]
+pub struct VariableCalculator {
+    states: Vec<VariableState>,
+}
+
+impl VariableCalculator {
+    pub fn new(definitions: &[Variable], groups: &[GroupLayout]) -> FillResult<Self> {
+        let group_index = |variable: &Variable, name: &Option<String>| {
+            name.as_ref()
+                .and_then(|name| groups.iter().position(|group| &group.name == name))
+                .map(VariableScope::Group)
+                .ok_or_else(|| FillError::TemplateError {
+                    message: format!("Variable '{}' references an unknown group", variable.name),
+                })
+        };
+
+        let mut states = Vec::with_capacity(definitions.len());
+        for definition in definitions {
+            let reset = match definition.reset_type {
+                ResetType::Report => VariableScope::Report,
+                ResetType::Page => VariableScope::Page,
+                ResetType::Column => VariableScope::Column,
+                ResetType::Group => group_index(definition, &definition.reset_group)?,
+                ResetType::None => VariableScope::Record,
+            };
+            let increment = match definition.increment_type {
+                IncrementType::None => VariableScope::Record,
+                IncrementType::Report => VariableScope::Report,
+                IncrementType::Page => VariableScope::Page,
+                IncrementType::Column => VariableScope::Column,
+                IncrementType::Group => group_index(definition, &definition.increment_group)?,
+            };
+
+            let mut state = VariableState {
+                definition: definition.clone(),
+                reset,
+                increment,
+                count: 0,
+                sum: 0.0,
+                integral: true,
+                distinct: HashSet::new(),
+                value: Value::Null,
+            };
+            state.reset();
+            states.push(state);
+        }
+
+        Ok(Self { states })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// 当前全部变量值
+    pub fn values(&self) -> HashMap<String, Value> {
+        self.states
+            .iter()
+            .map(|state| (state.definition.name.clone(), state.value.clone()))
+            .collect()
+    }
+
+    /// 重置作用范围内的变量；分组范围同时重置其内层分组
+    pub fn reset(&mut self, scope: VariableScope) {
+        for state in &mut self.states {
+            if scope_matches(state.reset, scope) {
+                state.reset();
+            }
+        }
+    }
+
+    /// 在作用范围结束（或每条记录）时累加对应变量
+    pub fn increment(&mut self, engine: &FillEngine, scope: VariableScope, row: &Value) -> FillResult<()> {
+        for index in 0..self.states.len() {
+            if !scope_matches(self.states[index].increment, scope) {
+                continue;
+            }
+            if scope == VariableScope::Record && self.states[index].reset == VariableScope::Record {
+                self.states[index].reset();
+            }
+
+            let values = self.values();
+            let value = engine.evaluate(&self.states[index].definition.expression, row, &values)?;
+            self.states[index].accumulate(value);
+        }
+        Ok(())
+    }
+}
+
+fn scope_matches(variable_scope: VariableScope, scope: VariableScope) -> bool {
+    match (variable_scope, scope) {
+        (VariableScope::Group(level), VariableScope::Group(broken)) => level >= broken,
+        (a, b) => a == b,
+    }
+}
+
+fn numeric(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// 整数结果保持整数表示，避免显示为 "3.0"
+fn number_value(number: f64, integral: bool) -> Value {
+    if integral && number.abs() < 1e15 {
+        Value::from(number as i64)
+    } else {
+        serde_json::Number::from_f64(number).map(Value::Number).unwrap_or(Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn calculate(variable: Variable, rows: &[Value]) -> Value {
+        let mut calculator = VariableCalculator::new(&[variable], &[]).unwrap();
+        for row in rows {
+            calculator.increment(&FillEngine::new(), VariableScope::Record, row).unwrap();
+        }
+        calculator.values().remove("v").unwrap()
+    }
+
+    #[test]
+    fn test_calculations() {
+        let rows: Vec<Value> = [3, 1, 3, 5].iter().map(|n| json!({ "n": n })).collect();
+        let expected = [
+            (Calculation::Nothing, json!(5)),
+            (Calculation::Count, json!(4)),
+            (Calculation::DistinctCount, json!(3)),
+            (Calculation::Sum, json!(12)),
+            (Calculation::Average, json!(3.0)),
+            (Calculation::Min, json!(1)),
+            (Calculation::Max, json!(5)),
+            (Calculation::First, json!(3)),
+        ];
+        for (calculation, value) in expected {
+            assert_eq!(calculate(Variable::new("v", "$F{n}", calculation), &rows), value, "{:?}", calculation);
+        }
+
+        // 空值不参与计数与求和
+        let rows = vec![json!({ "n": 1.5 }), json!({ "n": null }), json!({ "n": "2" })];
+        assert_eq!(calculate(Variable::new("v", "$F{n}", Calculation::Sum), &rows), json!(3.5));
+        assert_eq!(calculate(Variable::new("v", "$F{n}", Calculation::Count), &rows), json!(2));
+        assert_eq!(calculate(Variable::new("v", "$F{n}", Calculation::Count), &[]), json!(0));
+    }
+
+    #[test]
+    fn test_reset_scopes_and_initial_value() {
+        let mut balance = Variable::new("balance", "$F{amount}", Calculation::Sum);
+        balance.initial_value = Some(json!(100));
+        let mut page_total = Variable::new("page_total", "$F{amount}", Calculation::Sum);
+        page_total.reset_type = ResetType::Page;
+
+        let mut calculator = VariableCalculator::new(&[balance, page_total], &[]).unwrap();
+        let engine = FillEngine::new();
+        calculator.increment(&engine, VariableScope::Record, &json!({ "amount": 10 })).unwrap();
+        calculator.reset(VariableScope::Page);
+        calculator.increment(&engine, VariableScope::Record, &json!({ "amount": -4 })).unwrap();
+
+        let values = calculator.values();
+        assert_eq!(values["balance"], json!(106));
+        assert_eq!(values["page_total"], json!(-4));
+    }
+
+    #[test]
+    fn test_unknown_group_is_rejected() {
+        let mut variable = Variable::new("v", "$F{n}", Calculation::Sum);
+        variable.reset_type = ResetType::Group;
+        variable.reset_group = Some("missing".to_string());
+        assert!(VariableCalculator::new(&[variable], &[]).is_err());
+    }
+}
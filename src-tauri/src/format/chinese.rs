@@ -0,0 +1,117 @@
+// === 中文大写金额 ===
+use crate::format::number::Decimal;
+
+const DIGITS: [&str; 10] = ["零", "壹", "贰", "叁", "肆", "伍", "陆", "柒", "捌", "玖"];
+/// 节内单位（个、拾、佰、仟）
+const UNITS: [&str; 4] = ["", "拾", "佰", "仟"];
+/// 每四位一节的节单位
+const SECTIONS: [&str; 4] = ["", "万", "亿", "万亿"];
+
+/// 金额转为中文大写（银行票据写法），四舍五入到分；超过万亿位时返回 None
+pub fn to_chinese_upper(amount: &Decimal) -> Option<String> {
+    let mut amount = amount.clone();
+    amount.round(2);
+    if amount.int_digits.len() > SECTIONS.len() * 4 {
+        return None;
+    }
+
+    let jiao = amount.frac_digits.first().copied().unwrap_or(0) as usize;
+    let fen = amount.frac_digits.get(1).copied().unwrap_or(0) as usize;
+    let has_yuan = !amount.int_digits.is_empty();
+
+    let mut text = String::new();
+    if amount.negative && !amount.is_zero() {
+        text.push('负');
+    }
+    if amount.is_zero() {
+        text.push_str("零元整");
+        return Some(text);
+    }
+
+    if has_yuan {
+        text.push_str(&integer_upper(&amount.int_digits));
+        text.push('元');
+    }
+    match (jiao, fen) {
+        (0, 0) => text.push('整'),
+        (0, fen) => {
+            if has_yuan {
+                text.push('零');
+            }
+            text.push_str(DIGITS[fen]);
+            text.push('分');
+        }
+        (jiao, fen) => {
+            text.push_str(DIGITS[jiao]);
+            text.push('角');
+            if fen > 0 {
+                text.push_str(DIGITS[fen]);
+                text.push('分');
+            }
+        }
+    }
+    Some(text)
+}
+
+/// 整数部分：连续的零只读一个“零”，节末尾的零不读
+fn integer_upper(digits: &[u8]) -> String {
+    let mut text = String::new();
+    let mut zero_pending = false;
+    let padded_len = digits.len().div_ceil(4) * 4;
+    let padding = padded_len - digits.len();
+
+    for (section_index, section) in (0..padded_len).step_by(4).enumerate() {
+        let section_unit = SECTIONS[padded_len / 4 - section_index - 1];
+        let mut section_empty = true;
+
+        for position in 0..4 {
+            let index = section + position;
+            let digit = if index < padding { 0 } else { digits[index - padding] as usize };
+            if digit == 0 {
+                zero_pending = zero_pending || !text.is_empty();
+                continue;
+            }
+            if zero_pending {
+                text.push('零');
+                zero_pending = false;
+            }
+            text.push_str(DIGITS[digit]);
+            text.push_str(UNITS[3 - position]);
+            section_empty = false;
+        }
+
+        if !section_empty {
+            text.push_str(section_unit);
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upper(amount: &str) -> String {
+        to_chinese_upper(&Decimal::parse(amount).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_chinese_upper_amounts() {
+        assert_eq!(upper("120"), "壹佰贰拾元整");
+        assert_eq!(upper("0"), "零元整");
+        assert_eq!(upper("0.5"), "伍角");
+        assert_eq!(upper("0.05"), "伍分");
+        assert_eq!(upper("10.05"), "壹拾元零伍分");
+        assert_eq!(upper("1234.56"), "壹仟贰佰叁拾肆元伍角陆分");
+        assert_eq!(upper("100010"), "壹拾万零壹拾元整");
+        assert_eq!(upper("1000001"), "壹佰万零壹元整");
+        assert_eq!(upper("100000000"), "壹亿元整");
+        assert_eq!(upper("100000001.999"), "壹亿零贰元整");
+        assert_eq!(upper("-3.2"), "负叁元贰角");
+    }
+
+    #[test]
+    fn test_amount_out_of_range() {
+        assert!(to_chinese_upper(&Decimal::parse("12345678901234567").unwrap()).is_none());
+    }
+}
@@ -0,0 +1,88 @@
+// === Tauri格式化命令 ===
+use crate::format::{locale, FormatPattern, Formatter, LocaleInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FormatSampleReq {
+    values: Vec<Value>,
+    #[serde(default)]
+    format: Option<String>,
+    #[serde(default)]
+    locale: Option<String>,
+    #[serde(default)]
+    null_text: Option<String>,
+}
+
+/// 格式预览结果：每个样例值对应一条，值与模式不匹配时 error 说明原因
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormattedSample {
+    pub value: Value,
+    pub text: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatPreview {
+    /// 实际使用的区域（未知区域会回退）
+    pub locale: String,
+    pub samples: Vec<FormattedSample>,
+}
+
+/// 按格式模式预览样例值，供设计器显示格式化效果；模式无效时返回错误
+#[tauri::command]
+pub async fn format_sample_values(req: FormatSampleReq) -> Result<FormatPreview, String> {
+    if let Some(pattern) = req.format.as_deref() {
+        let pattern = pattern.rsplit_once('|').map_or(pattern, |(pattern, _)| pattern);
+        if !pattern.trim().is_empty() {
+            FormatPattern::parse(pattern).map_err(|e| e.to_string())?;
+        }
+    }
+
+    let mut formatter = Formatter::new(req.locale.as_deref().unwrap_or(locale::DEFAULT_LOCALE));
+    if let Some(null_text) = req.null_text {
+        formatter = formatter.with_null_text(null_text);
+    }
+
+    let samples = req
+        .values
+        .into_iter()
+        .map(|value| match formatter.try_format(&value, req.format.as_deref()) {
+            Ok(text) => FormattedSample { value, text, error: None },
+            Err(error) => FormattedSample {
+                text: formatter.format(&value, req.format.as_deref()),
+                value,
+                error: Some(error.to_string()),
+            },
+        })
+        .collect();
+
+    Ok(FormatPreview {
+        locale: formatter.locale().tag.to_string(),
+        samples,
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleSummary {
+    pub tag: &'static str,
+    pub currency_symbol: &'static str,
+    pub currency_code: &'static str,
+}
+
+/// 列出支持的区域，用于格式设置面板
+#[tauri::command]
+pub async fn list_format_locales() -> Result<Vec<LocaleSummary>, String> {
+    Ok(LocaleInfo::all()
+        .iter()
+        .map(|locale| LocaleSummary {
+            tag: locale.tag,
+            currency_symbol: locale.currency_symbol,
+            currency_code: locale.currency_code,
+        })
+        .collect())
+}
@@ -0,0 +1,211 @@
+// === 日期格式（SimpleDateFormat 风格） ===
+use crate::format::{FormatError, FormatResult, LocaleInfo};
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use serde_json::Value;
+
+/// 模式片段：字段字母及重复次数，或原样输出的文字
+#[derive(Debug, Clone, PartialEq)]
+enum DateToken {
+    Field(char, usize),
+    Literal(String),
+}
+
+/// 支持的字段字母
+const FIELD_LETTERS: &str = "yMLdEuaHkKhmsSDwZX";
+
+/// 已解析的日期模式
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatePattern {
+    tokens: Vec<DateToken>,
+}
+
+impl DatePattern {
+    pub fn parse(pattern: &str) -> FormatResult<Self> {
+        let invalid = |message: String| FormatError::InvalidDatePattern {
+            pattern: pattern.to_string(),
+            message,
+        };
+        let mut tokens: Vec<DateToken> = Vec::new();
+        let mut literal = String::new();
+        let mut chars = pattern.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c == '\'' {
+                if chars.peek() == Some(&'\'') {
+                    chars.next();
+                    literal.push('\'');
+                    continue;
+                }
+                // 引号内的文字原样输出，'' 表示单引号
+                loop {
+                    match chars.next() {
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            literal.push('\'');
+                        }
+                        Some('\'') => break,
+                        Some(other) => literal.push(other),
+                        None => return Err(invalid("unterminated quote".to_string())),
+                    }
+                }
+            } else if c.is_ascii_alphabetic() {
+                if !FIELD_LETTERS.contains(c) {
+                    return Err(invalid(format!("unsupported pattern letter '{}'", c)));
+                }
+                let mut count = 1;
+                while chars.peek() == Some(&c) {
+                    chars.next();
+                    count += 1;
+                }
+                if !literal.is_empty() {
+                    tokens.push(DateToken::Literal(std::mem::take(&mut literal)));
+                }
+                tokens.push(DateToken::Field(c, count));
+            } else {
+                literal.push(c);
+            }
+        }
+        if !literal.is_empty() {
+            tokens.push(DateToken::Literal(literal));
+        }
+
+        Ok(Self { tokens })
+    }
+
+    pub fn format(&self, datetime: &DateTime<FixedOffset>, locale: &LocaleInfo) -> String {
+        let mut text = String::new();
+        for token in &self.tokens {
+            match token {
+                DateToken::Literal(literal) => text.push_str(literal),
+                DateToken::Field(letter, count) => text.push_str(&format_field(*letter, *count, datetime, locale)),
+            }
+        }
+        text
+    }
+}
+
+fn pad(value: impl Into<i64>, width: usize) -> String {
+    let value = value.into();
+    if value < 0 {
+        format!("-{:0width$}", -value, width = width)
+    } else {
+        format!("{:0width$}", value, width = width)
+    }
+}
+
+fn format_field(letter: char, count: usize, datetime: &DateTime<FixedOffset>, locale: &LocaleInfo) -> String {
+    let weekday = datetime.weekday().num_days_from_monday() as usize;
+    match letter {
+        // yy 为两位年份，其余按位数补零
+        'y' if count == 2 => pad(datetime.year().rem_euclid(100), 2),
+        'y' => pad(datetime.year(), count),
+        'M' | 'L' => match count {
+            1 | 2 => pad(datetime.month(), count),
+            3 => locale.month_abbreviations[datetime.month0() as usize].to_string(),
+            _ => locale.month_names[datetime.month0() as usize].to_string(),
+        },
+        'd' => pad(datetime.day(), count),
+        'E' if count >= 4 => locale.weekday_names[weekday].to_string(),
+        'E' => locale.weekday_abbreviations[weekday].to_string(),
+        'u' => pad(weekday as i64 + 1, count),
+        'a' => locale.am_pm[(datetime.hour() >= 12) as usize].to_string(),
+        'H' => pad(datetime.hour(), count),
+        'k' => pad(if datetime.hour() == 0 { 24 } else { datetime.hour() }, count),
+        'K' => pad(datetime.hour() % 12, count),
+        'h' => pad(if datetime.hour() % 12 == 0 { 12 } else { datetime.hour() % 12 }, count),
+        'm' => pad(datetime.minute(), count),
+        's' => pad(datetime.second(), count),
+        'S' => pad(datetime.timestamp_subsec_millis().min(999), count),
+        'D' => pad(datetime.ordinal(), count),
+        'w' => pad(datetime.iso_week().week(), count),
+        'Z' => format_offset(datetime.offset(), false, true),
+        'X' if count == 1 => format_offset(datetime.offset(), false, false),
+        'X' => format_offset(datetime.offset(), count >= 3, true),
+        other => unreachable!("pattern letter '{}' is accepted but not formatted", other),
+    }
+}
+
+/// 时区偏移：+08 / +0800 / +08:00
+fn format_offset(offset: &FixedOffset, colon: bool, minutes: bool) -> String {
+    let seconds = offset.local_minus_utc();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let (hours, mins) = (seconds.abs() / 3600, seconds.abs() % 3600 / 60);
+    match (minutes, colon) {
+        (false, _) => format!("{}{:02}", sign, hours),
+        (true, true) => format!("{}{:02}:{:02}", sign, hours, mins),
+        (true, false) => format!("{}{:02}{:02}", sign, hours, mins),
+    }
+}
+
+/// 日期值解析：RFC3339、常见日期时间文本，数字视为毫秒时间戳；无时区的值按本地时区处理
+pub fn parse_datetime(value: &Value) -> Option<DateTime<FixedOffset>> {
+    match value {
+        Value::Number(number) => {
+            let millis = number.as_i64().or_else(|| number.as_f64().map(|n| n as i64))?;
+            Local.timestamp_millis_opt(millis).single().map(|dt| dt.fixed_offset())
+        }
+        Value::String(text) => {
+            let text = text.trim();
+            if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+                return Some(datetime);
+            }
+            let naive = ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M", "%Y/%m/%d %H:%M:%S"]
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+                .or_else(|| {
+                    ["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d"]
+                        .iter()
+                        .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+                        .and_then(|date| date.and_hms_opt(0, 0, 0))
+                })?;
+            Some(
+                Local
+                    .from_local_datetime(&naive)
+                    .earliest()
+                    .map(|dt| dt.fixed_offset())
+                    .unwrap_or_else(|| naive.and_utc().fixed_offset()),
+            )
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn format(value: &str, pattern: &str, locale: &str) -> String {
+        let datetime = parse_datetime(&json!(value)).unwrap();
+        DatePattern::parse(pattern).unwrap().format(&datetime, LocaleInfo::lookup(locale))
+    }
+
+    #[test]
+    fn test_numeric_fields() {
+        let value = "2024-03-05 08:09:10.042";
+        assert_eq!(format(value, "yyyy-MM-dd HH:mm:ss.SSS", "zh-CN"), "2024-03-05 08:09:10.042");
+        assert_eq!(format(value, "yy/M/d", "zh-CN"), "24/3/5");
+        assert_eq!(format(value, "yyyy年MM月dd日", "zh-CN"), "2024年03月05日");
+        assert_eq!(format(value, "D 'day' w", "en-US"), "65 day 10");
+        assert_eq!(format("2024-03-05 00:30:00", "k:mm K h", "zh-CN"), "24:30 0 12");
+        assert_eq!(format("2024-03-05", "yyyyMMdd", "zh-CN"), "20240305");
+    }
+
+    #[test]
+    fn test_localized_names() {
+        let value = "2024-03-05T14:09:10+08:00";
+        assert_eq!(format(value, "EEEE, MMMM d, yyyy h:mm a", "en-US"), "Tuesday, March 5, 2024 2:09 PM");
+        assert_eq!(format(value, "EEE d. MMM", "de-DE"), "Di. 5. März");
+        assert_eq!(format(value, "yyyy年M月d日 EEEE ah点", "zh-CN"), "2024年3月5日 星期二 下午2点");
+        assert_eq!(format(value, "Z|X|XXX", "zh-CN"), "+0800|+08|+08:00");
+        assert_eq!(format(value, "'o''clock' HH''", "en-US"), "o'clock 14'");
+    }
+
+    #[test]
+    fn test_invalid_patterns_and_values() {
+        assert!(DatePattern::parse("yyyy-QQ").is_err());
+        assert!(DatePattern::parse("yyyy 'open").is_err());
+        assert!(parse_datetime(&json!("yesterday")).is_none());
+        assert!(parse_datetime(&json!(1_700_000_000_000i64)).is_some());
+    }
+}
@@ -0,0 +1,140 @@
+// === 区域设置 ===
+
+/// 格式化使用的区域信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocaleInfo {
+    pub tag: &'static str,
+    pub grouping_separator: char,
+    pub decimal_separator: char,
+    pub currency_symbol: &'static str,
+    pub currency_code: &'static str,
+    pub month_names: [&'static str; 12],
+    pub month_abbreviations: [&'static str; 12],
+    /// 从星期一开始
+    pub weekday_names: [&'static str; 7],
+    pub weekday_abbreviations: [&'static str; 7],
+    pub am_pm: [&'static str; 2],
+}
+
+/// 未指定或无法识别区域时使用的区域
+pub const DEFAULT_LOCALE: &str = "zh-CN";
+
+const EN_MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December",
+];
+const EN_MONTHS_SHORT: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+const EN_WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+const EN_WEEKDAYS_SHORT: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+const ZH_MONTHS: [&str; 12] = ["一月", "二月", "三月", "四月", "五月", "六月", "七月", "八月", "九月", "十月", "十一月", "十二月"];
+const ZH_MONTHS_SHORT: [&str; 12] = ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"];
+const ZH_WEEKDAYS: [&str; 7] = ["星期一", "星期二", "星期三", "星期四", "星期五", "星期六", "星期日"];
+const ZH_WEEKDAYS_SHORT: [&str; 7] = ["周一", "周二", "周三", "周四", "周五", "周六", "周日"];
+
+const ZH_CN: LocaleInfo = LocaleInfo {
+    tag: "zh-CN",
+    grouping_separator: ',',
+    decimal_separator: '.',
+    currency_symbol: "¥",
+    currency_code: "CNY",
+    month_names: ZH_MONTHS,
+    month_abbreviations: ZH_MONTHS_SHORT,
+    weekday_names: ZH_WEEKDAYS,
+    weekday_abbreviations: ZH_WEEKDAYS_SHORT,
+    am_pm: ["上午", "下午"],
+};
+
+const LOCALES: &[LocaleInfo] = &[
+    ZH_CN,
+    LocaleInfo { tag: "zh-HK", currency_symbol: "HK$", currency_code: "HKD", ..ZH_CN },
+    LocaleInfo { tag: "zh-TW", currency_symbol: "NT$", currency_code: "TWD", ..ZH_CN },
+    LocaleInfo {
+        tag: "en-US",
+        grouping_separator: ',',
+        decimal_separator: '.',
+        currency_symbol: "$",
+        currency_code: "USD",
+        month_names: EN_MONTHS,
+        month_abbreviations: EN_MONTHS_SHORT,
+        weekday_names: EN_WEEKDAYS,
+        weekday_abbreviations: EN_WEEKDAYS_SHORT,
+        am_pm: ["AM", "PM"],
+    },
+    LocaleInfo {
+        tag: "en-GB",
+        grouping_separator: ',',
+        decimal_separator: '.',
+        currency_symbol: "£",
+        currency_code: "GBP",
+        month_names: EN_MONTHS,
+        month_abbreviations: EN_MONTHS_SHORT,
+        weekday_names: EN_WEEKDAYS,
+        weekday_abbreviations: EN_WEEKDAYS_SHORT,
+        am_pm: ["AM", "PM"],
+    },
+    LocaleInfo {
+        tag: "de-DE",
+        grouping_separator: '.',
+        decimal_separator: ',',
+        currency_symbol: "€",
+        currency_code: "EUR",
+        month_names: [
+            "Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August", "September", "Oktober", "November", "Dezember",
+        ],
+        month_abbreviations: ["Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.", "Dez."],
+        weekday_names: ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"],
+        weekday_abbreviations: ["Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa.", "So."],
+        am_pm: ["AM", "PM"],
+    },
+    LocaleInfo {
+        tag: "fr-FR",
+        grouping_separator: '\u{202f}',
+        decimal_separator: ',',
+        currency_symbol: "€",
+        currency_code: "EUR",
+        month_names: [
+            "janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre", "décembre",
+        ],
+        month_abbreviations: ["janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.", "déc."],
+        weekday_names: ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+        weekday_abbreviations: ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+        am_pm: ["AM", "PM"],
+    },
+    LocaleInfo {
+        tag: "ja-JP",
+        grouping_separator: ',',
+        decimal_separator: '.',
+        currency_symbol: "￥",
+        currency_code: "JPY",
+        month_names: ZH_MONTHS_SHORT,
+        month_abbreviations: ZH_MONTHS_SHORT,
+        weekday_names: ["月曜日", "火曜日", "水曜日", "木曜日", "金曜日", "土曜日", "日曜日"],
+        weekday_abbreviations: ["月", "火", "水", "木", "金", "土", "日"],
+        am_pm: ["午前", "午後"],
+    },
+];
+
+impl LocaleInfo {
+    /// 按区域标签查找（zh_CN / zh-cn / zh 均可），未知区域使用 zh-CN
+    pub fn lookup(tag: &str) -> &'static LocaleInfo {
+        let normalized = tag.trim().replace('_', "-").to_lowercase();
+        LOCALES
+            .iter()
+            .find(|locale| locale.tag.to_lowercase() == normalized)
+            .or_else(|| {
+                let language = normalized.split('-').next().unwrap_or_default();
+                LOCALES.iter().find(|locale| locale.tag.split('-').next() == Some(language))
+            })
+            .unwrap_or(&LOCALES[0])
+    }
+
+    /// 全部支持的区域，第一个为默认区域
+    pub fn all() -> &'static [LocaleInfo] {
+        LOCALES
+    }
+
+    pub fn is_supported(tag: &str) -> bool {
+        let normalized = tag.trim().replace('_', "-").to_lowercase();
+        LOCALES.iter().any(|locale| locale.tag.to_lowercase() == normalized)
+    }
+}
@@ -0,0 +1,191 @@
+pub mod chinese;
+pub mod commands;
+pub mod date;
+pub mod locale;
+pub mod number;
+
+// Re-export main types for convenience
+pub use date::DatePattern;
+pub use locale::LocaleInfo;
+pub use number::NumberPattern;
+
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+
+// Format-specific error types
+#[derive(Debug, Clone, Error, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum FormatError {
+    #[error("Invalid number pattern '{pattern}': {message}")]
+    InvalidNumberPattern { pattern: String, message: String },
+
+    #[error("Invalid date pattern '{pattern}': {message}")]
+    InvalidDatePattern { pattern: String, message: String },
+
+    #[error("Cannot format {value} with pattern '{pattern}'")]
+    UnsupportedValue { value: String, pattern: String },
+}
+
+pub type FormatResult<T> = Result<T, FormatError>;
+
+/// 格式模式的种类，按模式内容自动识别
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatPattern {
+    /// DecimalFormat 风格：#,##0.00 / ¤#,##0.00;(¤#,##0.00) / 0.0%
+    Number(NumberPattern),
+    /// SimpleDateFormat 风格：yyyy-MM-dd HH:mm:ss / EEEE, MMMM d
+    Date(DatePattern),
+    /// 中文大写金额：壹佰贰拾元整
+    ChineseUpper,
+}
+
+/// 中文大写金额的模式关键字
+const CHINESE_UPPER_KEYWORDS: &[&str] = &["chinese_upper", "大写金额"];
+
+impl FormatPattern {
+    /// 解析格式模式：含 0/# 的为数字模式，其余按日期模式解析
+    pub fn parse(pattern: &str) -> FormatResult<Self> {
+        let trimmed = pattern.trim();
+        if CHINESE_UPPER_KEYWORDS.iter().any(|keyword| keyword.eq_ignore_ascii_case(trimmed)) {
+            return Ok(Self::ChineseUpper);
+        }
+        if number::is_number_pattern(pattern) {
+            NumberPattern::parse(pattern).map(Self::Number)
+        } else {
+            DatePattern::parse(pattern).map(Self::Date)
+        }
+    }
+}
+
+/// 值格式化器：区域设置 + 空值显示文本
+#[derive(Debug, Clone)]
+pub struct Formatter {
+    locale: &'static LocaleInfo,
+    null_text: String,
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Self::new(locale::DEFAULT_LOCALE)
+    }
+}
+
+impl Formatter {
+    pub fn new(locale: &str) -> Self {
+        Self {
+            locale: LocaleInfo::lookup(locale),
+            null_text: String::new(),
+        }
+    }
+
+    /// 空值和空白字符串显示的文本
+    pub fn with_null_text(mut self, null_text: impl Into<String>) -> Self {
+        self.null_text = null_text.into();
+        self
+    }
+
+    pub fn locale(&self) -> &'static LocaleInfo {
+        self.locale
+    }
+
+    /// 格式化为显示文本，模式无效或值类型不匹配时退回原始文本
+    pub fn format(&self, value: &Value, pattern: Option<&str>) -> String {
+        self.try_format(value, pattern).unwrap_or_else(|_| plain_text(value))
+    }
+
+    /// 格式化为显示文本；模式末尾的 `|文本` 覆盖空值显示文本（如 `#,##0.00|--`）
+    pub fn try_format(&self, value: &Value, pattern: Option<&str>) -> FormatResult<String> {
+        let (pattern, null_text) = match pattern.and_then(|p| p.rsplit_once('|')) {
+            Some((pattern, null_text)) => (Some(pattern), null_text),
+            None => (pattern, self.null_text.as_str()),
+        };
+
+        if is_blank(value) {
+            return Ok(null_text.to_string());
+        }
+        let Some(pattern) = pattern.filter(|p| !p.trim().is_empty()) else {
+            return Ok(plain_text(value));
+        };
+
+        match FormatPattern::parse(pattern)? {
+            FormatPattern::Number(number) => {
+                let decimal = number::Decimal::from_value(value).ok_or_else(|| unsupported(value, pattern))?;
+                Ok(number.format(&decimal, self.locale))
+            }
+            FormatPattern::Date(date) => {
+                let datetime = date::parse_datetime(value).ok_or_else(|| unsupported(value, pattern))?;
+                Ok(date.format(&datetime, self.locale))
+            }
+            FormatPattern::ChineseUpper => {
+                let decimal = number::Decimal::from_value(value).ok_or_else(|| unsupported(value, pattern))?;
+                chinese::to_chinese_upper(&decimal).ok_or_else(|| unsupported(value, pattern))
+            }
+        }
+    }
+}
+
+/// 空值、空字符串和全空白字符串按空值显示
+fn is_blank(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(text) => text.trim().is_empty(),
+        _ => false,
+    }
+}
+
+/// 无格式时的显示文本：字符串不带引号
+fn plain_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn unsupported(value: &Value, pattern: &str) -> FormatError {
+    FormatError::UnsupportedValue {
+        value: value.to_string(),
+        pattern: pattern.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_format_value() {
+        let formatter = Formatter::default();
+        assert_eq!(formatter.format(&json!(0.125), Some("0.0%")), "12.5%");
+        assert_eq!(formatter.format(&json!(-1234567.891), Some("¥#,##0.00")), "-¥1,234,567.89");
+        assert_eq!(formatter.format(&json!("2024-03-05 08:09:10"), Some("yyyy年MM月dd日")), "2024年03月05日");
+        assert_eq!(formatter.format(&json!(120), Some("大写金额")), "壹佰贰拾元整");
+        assert_eq!(formatter.format(&json!(true), None), "true");
+        assert_eq!(formatter.format(&json!("N/A"), Some("#,##0.00")), "N/A");
+    }
+
+    #[test]
+    fn test_null_and_blank_display() {
+        let formatter = Formatter::new("en-US").with_null_text("-");
+        assert_eq!(formatter.format(&Value::Null, Some("0.00")), "-");
+        assert_eq!(formatter.format(&json!("  "), None), "-");
+        assert_eq!(formatter.format(&Value::Null, Some("0.00|N/A")), "N/A");
+        assert_eq!(formatter.format(&json!(5), Some("0.00|N/A")), "5.00");
+        assert_eq!(Formatter::default().format(&Value::Null, Some("0.00")), "");
+    }
+
+    #[test]
+    fn test_try_format_errors() {
+        let formatter = Formatter::default();
+        assert!(matches!(
+            formatter.try_format(&json!("abc"), Some("#,##0")),
+            Err(FormatError::UnsupportedValue { .. })
+        ));
+        assert!(matches!(
+            formatter.try_format(&json!("2024-01-01"), Some("yyyy-QQ")),
+            Err(FormatError::InvalidDatePattern { .. })
+        ));
+    }
+}
@@ -0,0 +1,482 @@
+// === 数字格式（DecimalFormat 风格） ===
+use crate::format::{FormatError, FormatResult, LocaleInfo};
+use serde_json::Value;
+
+/// 十进制数：按数字位存储，避免浮点舍入误差
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decimal {
+    pub negative: bool,
+    /// 整数部分，无前导零（空表示 0）
+    pub int_digits: Vec<u8>,
+    /// 小数部分，无末尾零
+    pub frac_digits: Vec<u8>,
+}
+
+impl Decimal {
+    /// 数字或数字字符串转为十进制数
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(number) if number.is_f64() => Self::from_f64(number.as_f64()?),
+            Value::Number(number) => Self::parse(&number.to_string()),
+            Value::String(text) => {
+                let text = text.trim();
+                Self::parse(text).or_else(|| Self::from_f64(text.parse().ok()?))
+            }
+            _ => None,
+        }
+    }
+
+    /// 浮点数按最短往返表示转换（1234.565 -> 1234.565）
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        Self::parse(&format!("{}", value))
+    }
+
+    /// 解析 `-123.45` 形式的十进制文本
+    pub fn parse(text: &str) -> Option<Self> {
+        let (negative, unsigned) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if int_part.is_empty() && frac_part.is_empty() {
+            return None;
+        }
+        let digits = |part: &str| -> Option<Vec<u8>> {
+            part.chars().map(|c| c.to_digit(10).map(|d| d as u8)).collect()
+        };
+
+        let mut decimal = Self {
+            negative,
+            int_digits: digits(int_part)?,
+            frac_digits: digits(frac_part)?,
+        };
+        decimal.normalize();
+        Some(decimal)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.int_digits.is_empty() && self.frac_digits.is_empty()
+    }
+
+    /// 乘以 10 的 `places` 次方（可为负）
+    pub fn shift(&mut self, places: i32) {
+        if places > 0 {
+            for _ in 0..places {
+                let digit = if self.frac_digits.is_empty() { 0 } else { self.frac_digits.remove(0) };
+                self.int_digits.push(digit);
+            }
+        } else {
+            for _ in 0..places.unsigned_abs() {
+                let digit = self.int_digits.pop().unwrap_or(0);
+                self.frac_digits.insert(0, digit);
+            }
+        }
+        self.normalize();
+    }
+
+    /// 保留 `places` 位小数，四舍五入（HALF_UP）
+    pub fn round(&mut self, places: usize) {
+        if self.frac_digits.len() <= places {
+            return;
+        }
+        let round_up = self.frac_digits[places] >= 5;
+        self.frac_digits.truncate(places);
+
+        if round_up {
+            let mut carry = true;
+            for digit in self.frac_digits.iter_mut().rev().chain(self.int_digits.iter_mut().rev()) {
+                if *digit == 9 {
+                    *digit = 0;
+                } else {
+                    *digit += 1;
+                    carry = false;
+                    break;
+                }
+            }
+            if carry {
+                self.int_digits.insert(0, 1);
+            }
+        }
+        self.normalize();
+    }
+
+    fn normalize(&mut self) {
+        let leading = self.int_digits.iter().take_while(|d| **d == 0).count();
+        self.int_digits.drain(..leading);
+        while self.frac_digits.last() == Some(&0) {
+            self.frac_digits.pop();
+        }
+    }
+}
+
+/// 前后缀中的组成部分
+#[derive(Debug, Clone, PartialEq)]
+enum AffixPart {
+    Literal(String),
+    /// `¤`：区域货币符号
+    CurrencySymbol,
+    /// `¤¤`：ISO 货币代码
+    CurrencyCode,
+}
+
+type Affix = Vec<AffixPart>;
+
+/// 已解析的数字模式
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberPattern {
+    positive_prefix: Affix,
+    positive_suffix: Affix,
+    /// `;` 之后的负数子模式前后缀，缺省时在正数前缀前加 `-`
+    negative_affixes: Option<(Affix, Affix)>,
+    min_int: usize,
+    min_frac: usize,
+    max_frac: usize,
+    /// 分组位数，0 表示不分组
+    grouping: usize,
+    /// 百分号乘以 100（2），千分号乘以 1000（3）
+    multiplier: i32,
+    /// 科学计数法的最少指数位数
+    exponent_digits: Option<usize>,
+}
+
+/// 模式中的字符：引号内的文字不参与解析
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Special(char),
+    Quoted(char),
+}
+
+fn tokenize(pattern: &str) -> FormatResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        if c == '\'' {
+            // '' 表示单引号本身
+            if chars.peek() == Some(&'\'') {
+                chars.next();
+                tokens.push(Token::Quoted('\''));
+            } else {
+                quoted = !quoted;
+            }
+        } else if quoted {
+            tokens.push(Token::Quoted(c));
+        } else {
+            tokens.push(Token::Special(c));
+        }
+    }
+
+    if quoted {
+        return Err(invalid(pattern, "unterminated quote"));
+    }
+    Ok(tokens)
+}
+
+/// 模式中包含未加引号的 0 或 # 时视为数字模式
+pub fn is_number_pattern(pattern: &str) -> bool {
+    tokenize(pattern).map_or(false, |tokens| {
+        tokens.iter().any(|token| matches!(token, Token::Special('0' | '#')))
+    })
+}
+
+fn invalid(pattern: &str, message: &str) -> FormatError {
+    FormatError::InvalidNumberPattern {
+        pattern: pattern.to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// 单个子模式：前缀、数字部分、后缀
+struct SubPattern {
+    prefix: Affix,
+    number: String,
+    suffix: Affix,
+    multiplier: i32,
+}
+
+fn push_literal(affix: &mut Affix, c: char) {
+    match affix.last_mut() {
+        Some(AffixPart::Literal(text)) => text.push(c),
+        _ => affix.push(AffixPart::Literal(c.to_string())),
+    }
+}
+
+fn parse_subpattern(pattern: &str, tokens: &[Token]) -> FormatResult<SubPattern> {
+    let mut sub = SubPattern {
+        prefix: Vec::new(),
+        number: String::new(),
+        suffix: Vec::new(),
+        multiplier: 0,
+    };
+    // 0: 前缀，1: 数字部分，2: 后缀
+    let mut phase = 0;
+    let mut index = 0;
+
+    while index < tokens.len() {
+        let token = tokens[index];
+        index += 1;
+
+        let number_char = match token {
+            Token::Special(c @ ('0' | '#' | ',' | '.')) => Some(c),
+            Token::Special('E') if phase == 1 => Some('E'),
+            _ => None,
+        };
+        if let Some(c) = number_char {
+            if phase == 2 {
+                return Err(invalid(pattern, "digits must be contiguous"));
+            }
+            phase = 1;
+            sub.number.push(c);
+            if c == 'E' {
+                while index < tokens.len() && tokens[index] == Token::Special('0') {
+                    sub.number.push('0');
+                    index += 1;
+                }
+            }
+            continue;
+        }
+        if phase == 1 {
+            phase = 2;
+        }
+
+        let affix = if phase == 0 { &mut sub.prefix } else { &mut sub.suffix };
+        match token {
+            Token::Special('¤') => {
+                if index < tokens.len() && tokens[index] == Token::Special('¤') {
+                    index += 1;
+                    affix.push(AffixPart::CurrencyCode);
+                } else {
+                    affix.push(AffixPart::CurrencySymbol);
+                }
+            }
+            Token::Special('%') => {
+                sub.multiplier = 2;
+                push_literal(affix, '%');
+            }
+            Token::Special('\u{2030}') => {
+                sub.multiplier = 3;
+                push_literal(affix, '\u{2030}');
+            }
+            Token::Special(c) | Token::Quoted(c) => push_literal(affix, c),
+        }
+    }
+
+    Ok(sub)
+}
+
+impl NumberPattern {
+    pub fn parse(pattern: &str) -> FormatResult<Self> {
+        let tokens = tokenize(pattern)?;
+        let (positive, negative) = match tokens.iter().position(|t| *t == Token::Special(';')) {
+            Some(split) => (&tokens[..split], Some(&tokens[split + 1..])),
+            None => (&tokens[..], None),
+        };
+
+        let sub = parse_subpattern(pattern, positive)?;
+        if !sub.number.contains(['0', '#']) {
+            return Err(invalid(pattern, "missing digit placeholders"));
+        }
+
+        let (mantissa, exponent) = match sub.number.split_once('E') {
+            Some((mantissa, exponent)) if !exponent.is_empty() => (mantissa, Some(exponent.len())),
+            Some(_) => return Err(invalid(pattern, "exponent requires at least one '0'")),
+            None => (sub.number.as_str(), None),
+        };
+        let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if frac_part.contains(['.', ',']) {
+            return Err(invalid(pattern, "unexpected character in fraction part"));
+        }
+
+        let grouping = match int_part.rfind(',') {
+            Some(position) if position + 1 == int_part.len() => {
+                return Err(invalid(pattern, "grouping separator must be followed by digits"))
+            }
+            Some(position) if exponent.is_none() => int_part.len() - position - 1,
+            _ => 0,
+        };
+
+        let negative_affixes = match negative {
+            Some(tokens) if !tokens.is_empty() => {
+                let sub = parse_subpattern(pattern, tokens)?;
+                Some((sub.prefix, sub.suffix))
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            positive_prefix: sub.prefix,
+            positive_suffix: sub.suffix,
+            negative_affixes,
+            min_int: int_part.chars().filter(|c| *c == '0').count(),
+            min_frac: frac_part.chars().filter(|c| *c == '0').count(),
+            max_frac: frac_part.len(),
+            grouping,
+            multiplier: sub.multiplier,
+            exponent_digits: exponent,
+        })
+    }
+
+    pub fn format(&self, value: &Decimal, locale: &LocaleInfo) -> String {
+        let mut value = value.clone();
+        value.shift(self.multiplier);
+
+        let body = match self.exponent_digits {
+            Some(digits) => self.format_scientific(&mut value, digits, locale),
+            None => {
+                value.round(self.max_frac);
+                self.format_fixed(&value, self.min_int, self.grouping, locale)
+            }
+        };
+
+        // 舍入后为零时不显示负号
+        if value.negative && !value.is_zero() {
+            match &self.negative_affixes {
+                Some((prefix, suffix)) => format!("{}{}{}", render(prefix, locale), body, render(suffix, locale)),
+                None => format!(
+                    "-{}{}{}",
+                    render(&self.positive_prefix, locale),
+                    body,
+                    render(&self.positive_suffix, locale)
+                ),
+            }
+        } else {
+            format!("{}{}{}", render(&self.positive_prefix, locale), body, render(&self.positive_suffix, locale))
+        }
+    }
+
+    fn format_fixed(&self, value: &Decimal, min_int: usize, grouping: usize, locale: &LocaleInfo) -> String {
+        let mut int_digits: Vec<char> = value.int_digits.iter().map(|d| char::from(b'0' + d)).collect();
+        while int_digits.len() < min_int {
+            int_digits.insert(0, '0');
+        }
+        if int_digits.is_empty() && value.frac_digits.is_empty() && self.min_frac == 0 {
+            int_digits.push('0');
+        }
+
+        let mut text = String::new();
+        for (index, digit) in int_digits.iter().enumerate() {
+            if grouping > 0 && index > 0 && (int_digits.len() - index) % grouping == 0 {
+                text.push(locale.grouping_separator);
+            }
+            text.push(*digit);
+        }
+
+        let mut frac: String = value.frac_digits.iter().map(|d| char::from(b'0' + d)).collect();
+        while frac.len() < self.min_frac {
+            frac.push('0');
+        }
+        if !frac.is_empty() {
+            text.push(locale.decimal_separator);
+            text.push_str(&frac);
+        }
+        text
+    }
+
+    /// 科学计数法：整数位数取模式中的最少整数位（至少 1 位）
+    fn format_scientific(&self, value: &mut Decimal, exponent_digits: usize, locale: &LocaleInfo) -> String {
+        let int_count = self.min_int.max(1);
+        let mut exponent: i64 = 0;
+
+        if !value.is_zero() {
+            // 数值 = 0.d1d2... × 10^position
+            let position = if value.int_digits.is_empty() {
+                -(value.frac_digits.iter().take_while(|d| **d == 0).count() as i64)
+            } else {
+                value.int_digits.len() as i64
+            };
+            exponent = position - int_count as i64;
+            value.shift(-exponent as i32);
+            value.round(self.max_frac);
+            if value.int_digits.len() > int_count {
+                exponent += 1;
+                value.shift(-1);
+                value.round(self.max_frac);
+            }
+        }
+
+        let mantissa = self.format_fixed(value, int_count, 0, locale);
+        let sign = if exponent < 0 { "-" } else { "" };
+        format!("{}E{}{:0width$}", mantissa, sign, exponent.abs(), width = exponent_digits)
+    }
+}
+
+fn render(affix: &Affix, locale: &LocaleInfo) -> String {
+    affix
+        .iter()
+        .map(|part| match part {
+            AffixPart::Literal(text) => text.as_str(),
+            AffixPart::CurrencySymbol => locale.currency_symbol,
+            AffixPart::CurrencyCode => locale.currency_code,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn format(value: Value, pattern: &str, locale: &str) -> String {
+        let decimal = Decimal::from_value(&value).unwrap();
+        NumberPattern::parse(pattern).unwrap().format(&decimal, LocaleInfo::lookup(locale))
+    }
+
+    #[test]
+    fn test_decimal_rounding() {
+        let mut decimal = Decimal::parse("1234.565").unwrap();
+        decimal.round(2);
+        assert_eq!(decimal, Decimal::parse("1234.57").unwrap());
+
+        let mut decimal = Decimal::parse("-9.995").unwrap();
+        decimal.round(2);
+        assert_eq!(decimal, Decimal::parse("-10").unwrap());
+
+        assert_eq!(Decimal::from_value(&json!(" 1e3 ")), Decimal::parse("1000"));
+        assert_eq!(Decimal::from_value(&json!(true)), None);
+    }
+
+    #[test]
+    fn test_grouping_and_fraction_digits() {
+        assert_eq!(format(json!(1234567.891), "#,##0.00", "zh-CN"), "1,234,567.89");
+        assert_eq!(format(json!(1234567.891), "#,##0.00", "de-DE"), "1.234.567,89");
+        assert_eq!(format(json!(12345678), "#,####", "zh-CN"), "1234,5678");
+        assert_eq!(format(json!(0.5), "#.##", "zh-CN"), ".5");
+        assert_eq!(format(json!(0), "#.##", "zh-CN"), "0");
+        assert_eq!(format(json!(7), "000", "zh-CN"), "007");
+        assert_eq!(format(json!(2.5), "0", "zh-CN"), "3");
+        assert_eq!(format(json!(1.2), "0.0##", "zh-CN"), "1.2");
+        assert_eq!(format(json!(-0.001), "0.00", "zh-CN"), "0.00");
+    }
+
+    #[test]
+    fn test_affixes_currency_and_percent() {
+        assert_eq!(format(json!(1234.5), "¤#,##0.00", "en-US"), "$1,234.50");
+        assert_eq!(format(json!(1234.5), "#,##0.00 ¤¤", "de-DE"), "1.234,50 EUR");
+        assert_eq!(format(json!(-1234.5), "¤#,##0.00;(¤#,##0.00)", "zh-CN"), "(¥1,234.50)");
+        assert_eq!(format(json!(-3), "0 'pcs'", "zh-CN"), "-3 pcs");
+        assert_eq!(format(json!(0.1234), "0.0%", "zh-CN"), "12.3%");
+        assert_eq!(format(json!(0.1234), "0'%'", "zh-CN"), "0%");
+        assert_eq!(format(json!(0.0125), "0\u{2030}", "zh-CN"), "13\u{2030}");
+        assert_eq!(format(json!("8"), "0''", "zh-CN"), "8'");
+    }
+
+    #[test]
+    fn test_scientific_notation() {
+        assert_eq!(format(json!(12345), "0.###E0", "zh-CN"), "1.235E4");
+        assert_eq!(format(json!(0.00012), "00.0E00", "zh-CN"), "12.0E-05");
+        assert_eq!(format(json!(9.99), "0.0E0", "zh-CN"), "1.0E1");
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        assert!(NumberPattern::parse("abc").is_err());
+        assert!(NumberPattern::parse("#,##0.00'").is_err());
+        assert!(NumberPattern::parse("#,").is_err());
+        assert!(NumberPattern::parse("0.0.0").is_err());
+        assert!(NumberPattern::parse("0 x 0").is_err());
+    }
+}
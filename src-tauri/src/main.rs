@@ -6,11 +6,15 @@ mod commands;
 mod errors;
 mod data;
 mod preview;
+mod fill;
+mod expression;
+mod format;
 mod types;
 
 use tauri::Manager;
 use std::sync::Arc;
 use tokio::sync::{RwLock, Mutex};
+use crate::core::library::TemplateLibrary;
 use crate::core::state::AppState;
 use crate::data::DataSourceRegistry;
 
@@ -42,9 +46,31 @@ async fn main() {
     // Use unified type definition to match commands.rs expectations
     let data_registry = Mutex::new(data_registry);
 
+    // 模板库：设置、索引和最近打开列表与数据源配置放在同一目录
+    // 索引由文件监视在后台建立，不阻塞启动
+    let template_library = TemplateLibrary::open(&app_data_dir).unwrap_or_else(|e| {
+        eprintln!("Warning: Failed to load template library: {}", e);
+        TemplateLibrary::new(&app_data_dir)
+    });
+    let template_library = Arc::new(Mutex::new(template_library));
+    crate::core::library::spawn_watcher(template_library.clone(), crate::core::library::WATCH_INTERVAL);
+
+    // 自动保存日志：上次异常退出的编辑移入恢复目录，由前端询问是否恢复
+    let session_journal = crate::core::journal::SessionJournal::start(&app_data_dir)
+        .or_else(|e| {
+            eprintln!("Warning: Failed to start autosave journal: {}", e);
+            crate::core::journal::SessionJournal::start(&std::env::temp_dir().join("jasper-designer"))
+        })
+        .expect("failed to start autosave journal");
+    let session_journal = Arc::new(Mutex::new(session_journal));
+    crate::core::journal::spawn_autosave(app_state.clone(), session_journal.clone(), crate::core::journal::SNAPSHOT_INTERVAL);
+    let exit_journal = session_journal.clone();
+
     tauri::Builder::default()
         .manage(app_state)
         .manage(data_registry)
+        .manage(template_library)
+        .manage(session_journal)
         .invoke_handler(tauri::generate_handler![
             commands::element::create_element,
             commands::element::update_element,
@@ -65,13 +91,22 @@ async fn main() {
             commands::canvas::screen_to_canvas,
             commands::canvas::canvas_to_screen,
             commands::canvas::snap_to_grid,
+            commands::band::get_report_bands,
+            commands::band::update_report_band,
+            commands::band::remove_report_band,
             commands::history::undo,
             commands::history::redo,
+            commands::recovery::get_recovery_info,
+            commands::recovery::restore_session,
+            commands::recovery::discard_recovery,
             commands::file::save_template,
             commands::file::load_template,
             commands::file::new_template,
+            commands::file::open_template_for_editing,
+            commands::file::get_edited_template,
             commands::file::export_json,
             commands::file::get_recent_templates,
+            commands::file::clear_recent_templates,
             commands::toggle_devtools,
             // Preview commands
             preview::commands::generate_preview,
@@ -109,9 +144,18 @@ async fn main() {
             data::commands::get_example_configs,
             // Template management commands
             commands::template::load_jasper_template,
+            commands::template::load_template_with_migrations,
+            commands::template::import_jrxml_template,
+            commands::template::export_template_jrxml,
+            commands::template::save_template_package,
+            commands::template::open_template_package,
+            commands::template::generate_template_signing_key,
+            commands::template::save_signed_template,
+            commands::template::verify_template_signature,
             commands::template::save_jasper_template,
             commands::template::save_template_as,
             commands::template::validate_template,
+            commands::template::lint_template,
             commands::template::create_empty_template,
             commands::template::get_template_info,
             commands::template::detect_template_format,
@@ -119,8 +163,15 @@ async fn main() {
             commands::template::import_template_json,
             commands::template::clone_template,
             commands::template::generate_template_preview,
+            commands::template::diff_templates,
             commands::template::merge_templates,
             commands::template::extract_template_elements,
+            // Template library commands
+            commands::library::get_template_library_settings,
+            commands::library::set_template_library_root,
+            commands::library::refresh_template_library,
+            commands::library::search_template_library,
+            commands::library::list_template_library_tags,
             // Database-specific commands
             data::commands::test_database_connection,
             data::commands::load_database_schema,
@@ -132,6 +183,16 @@ async fn main() {
             data::commands::explain_query,
             data::commands::get_table_sample,
             // data::commands::get_table_sample,
+            // Report fill commands
+            fill::commands::fill_report,
+            fill::commands::get_template_parameters,
+            fill::commands::validate_parameter_values,
+            // Expression commands
+            expression::commands::check_expression,
+            expression::commands::list_expression_functions,
+            // Format commands
+            format::commands::format_sample_values,
+            format::commands::list_format_locales,
         ])
         .setup(|app| {
             let window = app.get_window("main").unwrap();
@@ -150,6 +211,16 @@ async fn main() {
             
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(move |_app, event| {
+            // 正常退出时删除会话日志，下次启动不会提示恢复
+            if let tauri::RunEvent::Exit = event {
+                if let Ok(mut journal) = exit_journal.try_lock() {
+                    if let Err(e) = journal.close() {
+                        eprintln!("Warning: Failed to close autosave journal: {}", e);
+                    }
+                }
+            }
+        });
 }
\ No newline at end of file
@@ -1,17 +1,588 @@
 use crate::preview::{PreviewError, PreviewResult};
 use crate::preview::formats::{FormatRenderer, OutputFormat, RenderOptions, RenderQuality};
-use crate::types::preview_types::ExcelOptions;
+use crate::types::preview_types::{CellMappingStrategy, ExcelOptions};
+use crate::core::element::{
+    BorderStyleType, ElementContent, ReportElement, TextAlign, TextBackgroundStyle, TextBorderStyle, TextStyle,
+};
 use async_trait::async_trait;
+use crate::core::image_source::{self, ImageData};
+use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, FormatPattern, Image, Workbook, Worksheet, XlsxError};
 use std::collections::HashMap;
 
+/// 同一行/列判定的位置容差（像素）
+const POSITION_TOLERANCE: f64 = 4.0;
+/// 未设置列宽/行高时 Excel 的默认尺寸（像素），用于定位图片
+const DEFAULT_COLUMN_WIDTH: f64 = 64.0;
+const DEFAULT_ROW_HEIGHT: f64 = 20.0;
+
 pub struct ExcelRenderer {
     // Excel渲染器配置和状态
 }
 
+/// 待写入单元格的内容项（由报表元素或SVG文本提取）
+#[derive(Debug, Clone)]
+struct CellItem {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    text: String,
+    style: Option<TextStyle>,
+}
+
+/// 浮动在工作表上的图片，位置和尺寸已按缩放方式和对齐计算好
+#[derive(Debug, Clone)]
+struct ImageItem {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    image: ImageData,
+}
+
+/// 单元格在工作表中的位置（含合并范围）
+#[derive(Debug, Clone, Copy)]
+struct CellPlacement {
+    first_row: u32,
+    first_col: u16,
+    last_row: u32,
+    last_col: u16,
+}
+
+/// 映射结果：单元格位置及行列尺寸（像素）
+#[derive(Default)]
+struct SheetLayout {
+    placements: Vec<(usize, CellPlacement)>,
+    column_widths: Vec<f64>,
+    row_heights: Vec<f64>,
+    /// 第一行第一列左上角对应的报表坐标
+    origin: (f64, f64),
+}
+
 impl ExcelRenderer {
     pub fn new() -> Self {
         Self {}
     }
+
+    /// 直接从报表元素生成XLSX（保留样式和单元格结构）
+    pub fn render_elements(
+        &self,
+        elements: &[ReportElement],
+        options: &RenderOptions,
+    ) -> PreviewResult<Vec<u8>> {
+        let items = Self::items_from_elements(elements);
+        let images = Self::images_from_elements(elements);
+        self.write_workbook(&items, &images, &Self::extract_excel_options(options))
+    }
+
+    fn extract_excel_options(options: &RenderOptions) -> ExcelOptions {
+        options.excel_options.clone().unwrap_or_default()
+    }
+
+    /// 从报表元素提取单元格内容，仅文本和数据字段会产生单元格
+    fn items_from_elements(elements: &[ReportElement]) -> Vec<CellItem> {
+        let mut sorted: Vec<&ReportElement> = elements.iter().filter(|e| e.visible).collect();
+        sorted.sort_by_key(|e| e.z_index);
+
+        // 已出现的矩形：文字落在其中时按单元格处理（表格、交叉表的单元格是矩形加内缩文字）
+        let mut frames: Vec<&ReportElement> = Vec::new();
+        sorted
+            .into_iter()
+            .filter_map(|element| {
+                let (text, mut style) = match &element.content {
+                    ElementContent::Text { content, style } => (content.clone(), style.clone()),
+                    ElementContent::DataField { expression, style, .. } => {
+                        let text = if expression.is_empty() {
+                            "[数据字段]".to_string()
+                        } else {
+                            format!("[{}]", expression)
+                        };
+                        (text, style.clone())
+                    }
+                    ElementContent::Rectangle { .. } => {
+                        frames.push(element);
+                        return None;
+                    }
+                    _ => return None,
+                };
+
+                let frame = frames.iter().rev().find(|frame| Self::contains(frame, element));
+                let bounds = frame.copied().unwrap_or(element);
+                if let Some(ElementContent::Rectangle { fill_color, border, .. }) = frame.map(|frame| &frame.content) {
+                    if style.background.is_none() {
+                        style.background = fill_color.as_ref().map(|color| TextBackgroundStyle {
+                            color: color.clone(),
+                            opacity: None,
+                            padding: None,
+                        });
+                    }
+                    if style.border.is_none() {
+                        style.border = border.as_ref().map(|border| TextBorderStyle {
+                            color: border.color.clone(),
+                            width: border.width,
+                            style: border.style.clone(),
+                            radius: None,
+                        });
+                    }
+                }
+
+                Some(CellItem {
+                    x: bounds.position.x,
+                    y: bounds.position.y,
+                    width: bounds.size.width,
+                    height: bounds.size.height,
+                    text,
+                    style: Some(style),
+                })
+            })
+            .collect()
+    }
+
+    /// 提取已嵌入或绝对路径的图片，无法加载的图片不输出
+    fn images_from_elements(elements: &[ReportElement]) -> Vec<ImageItem> {
+        elements
+            .iter()
+            .filter(|element| element.visible)
+            .filter_map(|element| {
+                let ElementContent::Image { src, scale_mode, horizontal_align, vertical_align, .. } = &element.content else {
+                    return None;
+                };
+                if !image_source::is_resolved(src) {
+                    return None;
+                }
+                let size = (element.size.width, element.size.height);
+                let fitted = image_source::load(src, None)
+                    .and_then(|image| image_source::fit(&image, *scale_mode, *horizontal_align, *vertical_align, size));
+                match fitted {
+                    Ok((placement, image)) => Some(ImageItem {
+                        x: element.position.x + placement.x,
+                        y: element.position.y + placement.y,
+                        width: placement.width,
+                        height: placement.height,
+                        image,
+                    }),
+                    Err(e) => {
+                        log::warn!("Skipped image in element '{}': {}", element.id.to_string(), e);
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// 文字元素完全位于矩形之内（允许半个像素的误差）
+    fn contains(frame: &ReportElement, element: &ReportElement) -> bool {
+        const EPSILON: f64 = 0.5;
+        element.position.x >= frame.position.x - EPSILON
+            && element.position.y >= frame.position.y - EPSILON
+            && element.position.x + element.size.width <= frame.position.x + frame.size.width + EPSILON
+            && element.position.y + element.size.height <= frame.position.y + frame.size.height + EPSILON
+    }
+
+    /// 从SVG中提取文本（有损，仅保留文字与位置）
+    fn items_from_svg(svg_data: &str) -> PreviewResult<Vec<CellItem>> {
+        let document = roxmltree::Document::parse(svg_data)
+            .map_err(|e| PreviewError::RenderError {
+                message: format!("Failed to parse SVG: {}", e),
+            })?;
+
+        let mut items = Vec::new();
+        for group in document.descendants().filter(|n| n.has_attribute("data-element-id")) {
+            let (offset_x, offset_y) = group
+                .attribute("transform")
+                .and_then(Self::parse_translate)
+                .unwrap_or((0.0, 0.0));
+
+            let text: Vec<String> = group
+                .children()
+                .filter(|n| n.has_tag_name("text"))
+                .filter_map(|n| n.text().map(str::to_string))
+                .collect();
+            if text.is_empty() {
+                continue;
+            }
+
+            let font_size = group
+                .children()
+                .find(|n| n.has_tag_name("text"))
+                .and_then(|n| n.attribute("font-size"))
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(12.0);
+            let bounds = group.children().find(|n| n.has_tag_name("rect"));
+            let dimension = |name: &str| bounds.and_then(|r| r.attribute(name)).and_then(|v| v.parse::<f64>().ok());
+
+            items.push(CellItem {
+                x: offset_x,
+                y: offset_y,
+                width: dimension("width").unwrap_or(font_size * 6.0),
+                height: dimension("height").unwrap_or(font_size * 1.2 * text.len() as f64),
+                text: text.join("\n"),
+                style: None,
+            });
+        }
+
+        Ok(items)
+    }
+
+    fn parse_translate(transform: &str) -> Option<(f64, f64)> {
+        let args = transform.trim().strip_prefix("translate(")?.strip_suffix(')')?;
+        let mut values = args
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<f64>().ok());
+        let x = values.next()??;
+        let y = values.next().flatten().unwrap_or(0.0);
+        Some((x, y))
+    }
+
+    /// 生成工作簿
+    fn write_workbook(&self, items: &[CellItem], images: &[ImageItem], options: &ExcelOptions) -> PreviewResult<Vec<u8>> {
+        let layout = match options.cell_mapping_strategy {
+            CellMappingStrategy::PositionBased => Self::position_layout(items),
+            CellMappingStrategy::ContentBased => Self::content_layout(items),
+            CellMappingStrategy::TableBased => Self::table_layout(items),
+        };
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        Self::write_sheet(worksheet, items, &layout, options).map_err(Self::xlsx_error)?;
+        Self::insert_images(worksheet, images, &layout).map_err(Self::xlsx_error)?;
+
+        workbook.save_to_buffer().map_err(Self::xlsx_error)
+    }
+
+    fn write_sheet(
+        worksheet: &mut Worksheet,
+        items: &[CellItem],
+        layout: &SheetLayout,
+        options: &ExcelOptions,
+    ) -> Result<(), XlsxError> {
+        let sheet_name = if options.sheet_name.trim().is_empty() { "Report" } else { options.sheet_name.as_str() };
+        worksheet.set_name(sheet_name)?;
+
+        for (index, width) in layout.column_widths.iter().enumerate() {
+            worksheet.set_column_width_pixels(index as u16, width.round().clamp(1.0, u16::MAX as f64) as u16)?;
+        }
+        for (index, height) in layout.row_heights.iter().enumerate() {
+            worksheet.set_row_height_pixels(index as u32, height.round().clamp(1.0, 409.0 / 0.75) as u16)?;
+        }
+
+        for (item_index, placement) in &layout.placements {
+            let item = &items[*item_index];
+            let format = match (&item.style, options.include_formatting) {
+                (Some(style), true) => Self::cell_format(style),
+                _ => Format::new(),
+            };
+            let format = if item.text.contains('\n') { format.set_text_wrap() } else { format };
+
+            if placement.last_row > placement.first_row || placement.last_col > placement.first_col {
+                worksheet.merge_range(
+                    placement.first_row,
+                    placement.first_col,
+                    placement.last_row,
+                    placement.last_col,
+                    "",
+                    &format,
+                )?;
+            }
+
+            match Self::numeric_value(&item.text) {
+                Some(number) => {
+                    worksheet.write_number_with_format(placement.first_row, placement.first_col, number, &format)?;
+                }
+                None => {
+                    worksheet.write_string_with_format(placement.first_row, placement.first_col, &item.text, &format)?;
+                }
+            }
+        }
+
+        if options.freeze_header && !layout.placements.is_empty() {
+            worksheet.set_freeze_panes(1, 0)?;
+        }
+        if options.auto_fit_columns {
+            worksheet.autofit();
+        }
+
+        Ok(())
+    }
+
+    /// 图片按报表坐标换算出所在单元格和单元格内偏移，以原尺寸浮动插入
+    fn insert_images(worksheet: &mut Worksheet, images: &[ImageItem], layout: &SheetLayout) -> Result<(), XlsxError> {
+        for item in images {
+            let (col, x_offset) = Self::anchor(&layout.column_widths, DEFAULT_COLUMN_WIDTH, item.x - layout.origin.0);
+            let (row, y_offset) = Self::anchor(&layout.row_heights, DEFAULT_ROW_HEIGHT, item.y - layout.origin.1);
+
+            let mut image = Image::new_from_buffer(&item.image.bytes)?;
+            // 缩放比例与 set_scale_to_size 相同：按 96 DPI 换算图片的显示像素
+            let scale_width = item.width / image.width() * (image.width_dpi() / 96.0);
+            let scale_height = item.height / image.height() * (image.height_dpi() / 96.0);
+            image.set_scale_width(scale_width).set_scale_height(scale_height);
+            worksheet.insert_image_with_offset(row as u32, col as u16, &image, x_offset, y_offset)?;
+        }
+        Ok(())
+    }
+
+    /// 坐标所在的行/列序号及其内的偏移（像素）
+    fn anchor(sizes: &[f64], default: f64, position: f64) -> (usize, u32) {
+        let mut index = 0;
+        let mut remaining = position.max(0.0);
+        loop {
+            let size = sizes.get(index).copied().unwrap_or(default).max(1.0);
+            if remaining < size {
+                return (index, remaining.round() as u32);
+            }
+            remaining -= size;
+            index += 1;
+        }
+    }
+
+    /// 位置映射：以元素边界构造网格，跨多个网格的元素合并单元格
+    fn position_layout(items: &[CellItem]) -> SheetLayout {
+        let x_edges = Self::collect_edges(items.iter().flat_map(|i| [i.x, i.x + i.width]));
+        let y_edges = Self::collect_edges(items.iter().flat_map(|i| [i.y, i.y + i.height]));
+        if x_edges.len() < 2 || y_edges.len() < 2 {
+            return SheetLayout::default();
+        }
+
+        let edge_index = |edges: &[f64], value: f64| {
+            edges
+                .iter()
+                .position(|edge| (edge - value).abs() <= POSITION_TOLERANCE)
+                .unwrap_or(0)
+        };
+
+        let mut occupied: Vec<CellPlacement> = Vec::new();
+        let mut placements = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            let first_col = edge_index(&x_edges, item.x);
+            let last_col = edge_index(&x_edges, item.x + item.width).saturating_sub(1).max(first_col);
+            let first_row = edge_index(&y_edges, item.y);
+            let last_row = edge_index(&y_edges, item.y + item.height).saturating_sub(1).max(first_row);
+
+            let mut placement = CellPlacement {
+                first_row: first_row as u32,
+                first_col: first_col as u16,
+                last_row: last_row as u32,
+                last_col: last_col as u16,
+            };
+
+            // 与已放置元素重叠时退化为单个单元格；起始单元格也被占用则跳过
+            if occupied.iter().any(|other| Self::overlaps(&placement, other)) {
+                placement.last_row = placement.first_row;
+                placement.last_col = placement.first_col;
+                if occupied.iter().any(|other| Self::overlaps(&placement, other)) {
+                    continue;
+                }
+            }
+
+            occupied.push(placement);
+            placements.push((index, placement));
+        }
+
+        SheetLayout {
+            placements,
+            column_widths: x_edges.windows(2).map(|w| w[1] - w[0]).collect(),
+            row_heights: y_edges.windows(2).map(|w| w[1] - w[0]).collect(),
+            origin: (x_edges[0], y_edges[0]),
+        }
+    }
+
+    /// 内容映射：按行聚类，每行内按从左到右顺序依次写入
+    fn content_layout(items: &[CellItem]) -> SheetLayout {
+        let rows = Self::group_rows(items);
+
+        let placements = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, members)| {
+                members.iter().enumerate().map(move |(col, index)| {
+                    (*index, CellPlacement {
+                        first_row: row as u32,
+                        first_col: col as u16,
+                        last_row: row as u32,
+                        last_col: col as u16,
+                    })
+                })
+            })
+            .collect();
+
+        SheetLayout {
+            placements,
+            ..Default::default()
+        }
+    }
+
+    /// 表格映射：按左边界聚类出列，按行聚类出行，首行视为表头
+    fn table_layout(items: &[CellItem]) -> SheetLayout {
+        let columns = Self::collect_edges(items.iter().map(|i| i.x));
+        let rows = Self::group_rows(items);
+
+        let mut column_widths = vec![0.0_f64; columns.len()];
+        let mut placements = Vec::new();
+        for (row, members) in rows.iter().enumerate() {
+            let mut last_col: Option<usize> = None;
+            for index in members {
+                let item = &items[*index];
+                let nearest = columns
+                    .iter()
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| (*a - item.x).abs().total_cmp(&(*b - item.x).abs()))
+                    .map(|(col, _)| col)
+                    .unwrap_or(0);
+                // 同一行两个元素落在同一列时顺延到下一列
+                let col = match last_col {
+                    Some(previous) if nearest <= previous => previous + 1,
+                    _ => nearest,
+                };
+                last_col = Some(col);
+
+                if col >= column_widths.len() {
+                    column_widths.resize(col + 1, 0.0);
+                }
+                column_widths[col] = column_widths[col].max(item.width);
+                placements.push((*index, CellPlacement {
+                    first_row: row as u32,
+                    first_col: col as u16,
+                    last_row: row as u32,
+                    last_col: col as u16,
+                }));
+            }
+        }
+
+        SheetLayout {
+            placements,
+            column_widths,
+            row_heights: Vec::new(),
+            ..Default::default()
+        }
+    }
+
+    /// 将元素按垂直位置分组为行，行内按水平位置排序
+    fn group_rows(items: &[CellItem]) -> Vec<Vec<usize>> {
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by(|a, b| items[*a].y.total_cmp(&items[*b].y).then(items[*a].x.total_cmp(&items[*b].x)));
+
+        let mut rows: Vec<(f64, Vec<usize>)> = Vec::new();
+        for index in order {
+            let item = &items[index];
+            match rows.last_mut() {
+                Some((top, members)) if (item.y - *top).abs() <= POSITION_TOLERANCE => members.push(index),
+                _ => rows.push((item.y, vec![index])),
+            }
+        }
+
+        rows.into_iter()
+            .map(|(_, mut members)| {
+                members.sort_by(|a, b| items[*a].x.total_cmp(&items[*b].x));
+                members
+            })
+            .collect()
+    }
+
+    /// 排序去重坐标（容差内视为同一条边）
+    fn collect_edges(values: impl Iterator<Item = f64>) -> Vec<f64> {
+        let mut values: Vec<f64> = values.filter(|v| v.is_finite()).collect();
+        values.sort_by(|a, b| a.total_cmp(b));
+
+        let mut edges: Vec<f64> = Vec::new();
+        for value in values {
+            if edges.last().map_or(true, |last| value - last > POSITION_TOLERANCE) {
+                edges.push(value);
+            }
+        }
+        edges
+    }
+
+    fn overlaps(a: &CellPlacement, b: &CellPlacement) -> bool {
+        a.first_row <= b.last_row && b.first_row <= a.last_row && a.first_col <= b.last_col && b.first_col <= a.last_col
+    }
+
+    /// 将文本样式转换为单元格格式
+    fn cell_format(style: &TextStyle) -> Format {
+        let mut format = Format::new()
+            .set_font_name(style.font_family.as_str())
+            .set_font_size(style.font_size)
+            .set_align(match style.align {
+                TextAlign::Left => FormatAlign::Left,
+                TextAlign::Center => FormatAlign::Center,
+                TextAlign::Right => FormatAlign::Right,
+            })
+            .set_align(FormatAlign::VerticalCenter);
+
+        if style.font_weight == "bold" || style.font_weight.parse::<u16>().map_or(false, |w| w >= 600) {
+            format = format.set_bold();
+        }
+        if let Some(color) = parse_color(&style.color) {
+            format = format.set_font_color(color);
+        }
+        if let Some(background) = &style.background {
+            if let Some(color) = parse_color(&background.color) {
+                format = format.set_pattern(FormatPattern::Solid).set_background_color(color);
+            }
+        }
+        if let Some(border) = &style.border {
+            let border_type = match border.style {
+                BorderStyleType::Dashed => FormatBorder::Dashed,
+                BorderStyleType::Dotted => FormatBorder::Dotted,
+                BorderStyleType::Solid if border.width >= 3.0 => FormatBorder::Thick,
+                BorderStyleType::Solid if border.width >= 2.0 => FormatBorder::Medium,
+                BorderStyleType::Solid => FormatBorder::Thin,
+            };
+            format = format.set_border(border_type);
+            if let Some(color) = parse_color(&border.color) {
+                format = format.set_border_color(color);
+            }
+        }
+
+        format
+    }
+
+    /// 数值文本写为数字单元格（保留前导零的编码类文本）
+    fn numeric_value(text: &str) -> Option<f64> {
+        let trimmed = text.trim();
+        let digits = trimmed.trim_start_matches('-');
+        if digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.") {
+            return None;
+        }
+        trimmed.parse::<f64>().ok().filter(|v| v.is_finite())
+    }
+
+    fn xlsx_error(error: XlsxError) -> PreviewError {
+        PreviewError::RenderError {
+            message: format!("Failed to write Excel workbook: {}", error),
+        }
+    }
+}
+
+/// 解析 #rgb / #rrggbb 及常用颜色名；透明色返回 None
+fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim().to_ascii_lowercase();
+    if let Some(hex) = value.strip_prefix('#') {
+        let rgb = match hex.len() {
+            3 => {
+                let expanded: String = hex.chars().flat_map(|c| [c, c]).collect();
+                u32::from_str_radix(&expanded, 16).ok()?
+            }
+            6 => u32::from_str_radix(hex, 16).ok()?,
+            8 => u32::from_str_radix(&hex[..6], 16).ok()?,
+            _ => return None,
+        };
+        return Some(Color::RGB(rgb));
+    }
+
+    let rgb = match value.as_str() {
+        "black" => 0x000000,
+        "white" => 0xFFFFFF,
+        "red" => 0xFF0000,
+        "green" => 0x008000,
+        "blue" => 0x0000FF,
+        "yellow" => 0xFFFF00,
+        "orange" => 0xFFA500,
+        "gray" | "grey" => 0x808080,
+        "purple" => 0x800080,
+        _ => return None,
+    };
+    Some(Color::RGB(rgb))
 }
 
 #[async_trait]
@@ -19,28 +590,36 @@ impl FormatRenderer for ExcelRenderer {
     async fn render(
         &self,
         svg_data: &str,
-        _options: &RenderOptions,
+        options: &RenderOptions,
     ) -> PreviewResult<Vec<u8>> {
-        // TODO: 实现Excel渲染逻辑
-        // 这里先返回占位符实现
-        Err(PreviewError::RenderError {
-            message: "Excel renderer not implemented yet".to_string(),
-        })
+        // SVG输入会丢失样式，优先使用 render_elements
+        let items = Self::items_from_svg(svg_data)?;
+        self.write_workbook(&items, &[], &Self::extract_excel_options(options))
     }
-    
+
     fn supported_formats(&self) -> Vec<OutputFormat> {
         vec![OutputFormat::Excel]
     }
-    
+
     fn validate_options(&self, options: &RenderOptions) -> PreviewResult<()> {
         match options.format {
-            OutputFormat::Excel => Ok(()),
+            OutputFormat::Excel => {
+                if let Some(excel_options) = &options.excel_options {
+                    let name = &excel_options.sheet_name;
+                    if name.chars().count() > 31 || name.contains(['[', ']', ':', '*', '?', '/', '\\']) {
+                        return Err(PreviewError::InvalidOptions {
+                            details: format!("Invalid worksheet name: {}", name),
+                        });
+                    }
+                }
+                Ok(())
+            }
             _ => Err(PreviewError::UnsupportedFormat {
                 format: format!("{:?}", options.format),
             }),
         }
     }
-    
+
     fn default_options(&self) -> RenderOptions {
         RenderOptions {
             format: OutputFormat::Excel,
@@ -51,9 +630,180 @@ impl FormatRenderer for ExcelRenderer {
             custom_properties: HashMap::new(),
         }
     }
-    
-    async fn estimate_render_time(&self, _svg_data: &str) -> PreviewResult<u64> {
-        // TODO: 实现渲染时间估算
-        Ok(1000) // 默认1000ms估算
+
+    async fn estimate_render_time(&self, svg_data: &str) -> PreviewResult<u64> {
+        // 基础时间 + 每个文本单元格5ms
+        let cells = svg_data.matches("<text").count() as u64;
+        Ok((200 + cells * 5).min(2000))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::element::{Position, Size};
+
+    fn text_element(content: &str, x: f64, y: f64, width: f64) -> ReportElement {
+        ReportElement::new(
+            ElementContent::Text {
+                content: content.to_string(),
+                style: TextStyle {
+                    font_family: "Arial".to_string(),
+                    font_size: 12.0,
+                    font_weight: "bold".to_string(),
+                    color: "#333333".to_string(),
+                    align: TextAlign::Left,
+                    border: None,
+                    background: None,
+                },
+            },
+            Position::new(x, y).unwrap(),
+            Size::new(width, 20.0).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn sample_items() -> Vec<CellItem> {
+        ExcelRenderer::items_from_elements(&[
+            text_element("Name", 0.0, 0.0, 100.0),
+            text_element("Amount", 100.0, 0.0, 80.0),
+            text_element("Alice", 0.0, 20.0, 100.0),
+            text_element("12.5", 101.0, 21.0, 80.0),
+            text_element("Total", 0.0, 40.0, 180.0),
+        ])
+    }
+
+    #[test]
+    fn test_table_layout_aligns_columns() {
+        let items = sample_items();
+        let layout = ExcelRenderer::table_layout(&items);
+
+        let cell = |text: &str| {
+            let (_, placement) = layout.placements.iter().find(|(i, _)| items[*i].text == text).unwrap();
+            (placement.first_row, placement.first_col)
+        };
+        assert_eq!(cell("Name"), (0, 0));
+        assert_eq!(cell("Amount"), (0, 1));
+        assert_eq!(cell("12.5"), (1, 1));
+        assert_eq!(cell("Total"), (2, 0));
+    }
+
+    #[test]
+    fn test_position_layout_merges_wide_elements() {
+        let items = sample_items();
+        let layout = ExcelRenderer::position_layout(&items);
+
+        let (_, total) = layout.placements.iter().find(|(i, _)| items[*i].text == "Total").unwrap();
+        assert_eq!((total.first_col, total.last_col), (0, 1));
+        assert_eq!(layout.column_widths, vec![100.0, 80.0]);
+    }
+
+    #[test]
+    fn test_content_layout_packs_rows() {
+        let items = sample_items();
+        let layout = ExcelRenderer::content_layout(&items);
+        assert_eq!(layout.placements.len(), 5);
+        assert!(layout.placements.iter().all(|(_, p)| p.first_col <= 1));
+    }
+
+    #[test]
+    fn test_grid_cells_inherit_rectangle_style() {
+        let config = crate::core::element::TableConfig {
+            columns: vec![crate::core::element::TableColumn {
+                header: "Name".to_string(),
+                expression: "$F{name}".to_string(),
+                width: 80.0,
+                align: TextAlign::Left,
+                format: None,
+                footer_calculation: None,
+                footer_text: None,
+            }],
+            header_background: Some("#dddddd".to_string()),
+            border: Some(crate::core::element::BorderStyle {
+                color: "#000000".to_string(),
+                width: 1.0,
+                style: BorderStyleType::Solid,
+            }),
+            ..Default::default()
+        };
+        let items = ExcelRenderer::items_from_elements(&config.header_cells());
+
+        assert_eq!(items.len(), 1);
+        assert_eq!((items[0].x, items[0].width), (0.0, 80.0));
+        let style = items[0].style.as_ref().unwrap();
+        assert_eq!(style.background.as_ref().map(|b| b.color.as_str()), Some("#dddddd"));
+        assert_eq!(style.border.as_ref().map(|b| b.width), Some(1.0));
+    }
+
+    #[test]
+    fn test_numeric_value_detection() {
+        assert_eq!(ExcelRenderer::numeric_value("12.5"), Some(12.5));
+        assert_eq!(ExcelRenderer::numeric_value("-3"), Some(-3.0));
+        assert_eq!(ExcelRenderer::numeric_value("007"), None);
+        assert_eq!(ExcelRenderer::numeric_value("abc"), None);
+    }
+
+    #[test]
+    fn test_render_elements_produces_xlsx() {
+        let renderer = ExcelRenderer::new();
+        let elements = vec![text_element("Name", 0.0, 0.0, 100.0), text_element("42", 0.0, 20.0, 100.0)];
+
+        for strategy in [CellMappingStrategy::PositionBased, CellMappingStrategy::ContentBased, CellMappingStrategy::TableBased] {
+            let mut options = renderer.default_options();
+            options.excel_options = Some(ExcelOptions {
+                cell_mapping_strategy: strategy,
+                freeze_header: true,
+                auto_fit_columns: true,
+                ..ExcelOptions::default()
+            });
+
+            let data = renderer.render_elements(&elements, &options).unwrap();
+            // XLSX 为 zip 容器
+            assert!(data.starts_with(b"PK"));
+        }
+    }
+
+    #[test]
+    fn test_image_anchor_and_embedding() {
+        // 列宽 100、50，之后为默认宽度
+        assert_eq!(ExcelRenderer::anchor(&[100.0, 50.0], DEFAULT_COLUMN_WIDTH, 30.0), (0, 30));
+        assert_eq!(ExcelRenderer::anchor(&[100.0, 50.0], DEFAULT_COLUMN_WIDTH, 120.0), (1, 20));
+        assert_eq!(ExcelRenderer::anchor(&[100.0, 50.0], DEFAULT_COLUMN_WIDTH, 220.0), (3, 6));
+
+        let logo = ImageData::from_image(&image::DynamicImage::ImageRgb8(image::RgbImage::new(8, 4))).unwrap();
+        let image = ReportElement::new(
+            ElementContent::Image {
+                src: logo.to_data_uri(),
+                alt: None,
+                scale_mode: crate::core::element::ImageScaleMode::RetainShape,
+                horizontal_align: crate::core::element::ImageAlign::Center,
+                vertical_align: crate::core::element::ImageAlign::Center,
+            },
+            Position::new(0.0, 20.0).unwrap(),
+            Size::new(40.0, 40.0).unwrap(),
+        )
+        .unwrap();
+        let elements = vec![text_element("Name", 0.0, 0.0, 100.0), image];
+
+        let images = ExcelRenderer::images_from_elements(&elements);
+        assert_eq!(images.len(), 1);
+        assert_eq!((images[0].x, images[0].y, images[0].width, images[0].height), (0.0, 30.0, 40.0, 20.0));
+
+        // 相对路径不按进程工作目录读取，即使该目录下恰好有同名文件
+        let icon = |src: String| {
+            let mut element = elements[1].clone();
+            if let ElementContent::Image { src: image_src, .. } = &mut element.content {
+                *image_src = src;
+            }
+            element
+        };
+        assert!(std::path::Path::new("icons/32x32.png").is_file());
+        assert!(ExcelRenderer::images_from_elements(&[icon("icons/32x32.png".to_string())]).is_empty());
+        let absolute = std::fs::canonicalize("icons/32x32.png").unwrap();
+        assert_eq!(ExcelRenderer::images_from_elements(&[icon(absolute.to_string_lossy().into_owned())]).len(), 1);
+
+        let renderer = ExcelRenderer::new();
+        let data = renderer.render_elements(&elements, &renderer.default_options()).unwrap();
+        assert!(data.windows(b"xl/media/image1.png".len()).any(|window| window == b"xl/media/image1.png"));
     }
-}
\ No newline at end of file
+}
@@ -1,11 +1,34 @@
 use crate::preview::{PreviewError, PreviewResult};
 use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use printpdf::*;
+use usvg::fontdb;
+
+/// 中文文本的后备字体（仅使用可整体嵌入的TrueType字体）
+const CJK_FALLBACK_FAMILIES: &[&str] = &[
+    "Microsoft YaHei",
+    "SimHei",
+    "SimSun",
+    "WenQuanYi Zen Hei",
+    "WenQuanYi Micro Hei",
+    "Noto Sans SC",
+    "Noto Sans CJK SC",
+    "Source Han Sans SC",
+    "PingFang SC",
+    "Arial Unicode MS",
+];
+
+/// 运行期间注册的字体文件（如模板包中的字体），与系统字体一起查找
+static REGISTERED_FONTS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
 
 /// PDF字体管理器
 pub struct FontManager {
     font_cache: HashMap<String, IndirectFontRef>,
     builtin_fonts: HashMap<String, BuiltinFont>,
+    /// 系统字体和注册的字体，仅在需要嵌入外部字体时才加载
+    system_fonts: Option<fontdb::Database>,
 }
 
 impl FontManager {
@@ -23,9 +46,122 @@ impl FontManager {
         Self {
             font_cache: HashMap::new(),
             builtin_fonts,
+            system_fonts: None,
+        }
+    }
+
+    /// 注册字体文件，之后创建的字体管理器都能查找到其中的字体
+    pub fn register_font_file(path: &Path) {
+        let mut fonts = REGISTERED_FONTS.lock().unwrap_or_else(|e| e.into_inner());
+        if !fonts.iter().any(|registered| registered == path) {
+            fonts.push(path.to_path_buf());
+        }
+    }
+
+    /// 根据文本内容选择字体
+    ///
+    /// 内置字体仅支持 WinAnsi 字符集，包含中文等字符时会尝试嵌入系统字体，
+    /// 找不到可用字体时退回内置字体（不支持的字符将被忽略）。
+    pub fn get_font_for_text(
+        &mut self,
+        doc: &PdfDocumentReference,
+        font_family: &str,
+        font_weight: &str,
+        text: &str,
+    ) -> PreviewResult<IndirectFontRef> {
+        if Self::is_builtin_encodable(text) {
+            return self.get_font(doc, font_family, font_weight);
+        }
+
+        let font_key = format!("external:{}:{}", font_family, font_weight);
+        if let Some(font_ref) = self.font_cache.get(&font_key) {
+            return Ok(font_ref.clone());
+        }
+
+        match self.load_system_font_data(font_family, font_weight) {
+            Some(data) => {
+                let font_ref = doc.add_external_font(Cursor::new(data))
+                    .map_err(|e| PreviewError::FontError {
+                        message: format!("Failed to embed system font: {}", e),
+                    })?;
+                self.font_cache.insert(font_key, font_ref.clone());
+                Ok(font_ref)
+            }
+            None => {
+                log::warn!("No embeddable font found for '{}', falling back to builtin font", font_family);
+                self.get_font(doc, font_family, font_weight)
+            }
         }
     }
 
+    /// 字体是否可用：内置字体、已安装的系统字体或注册的字体
+    pub fn has_font(&mut self, font_family: &str) -> bool {
+        if self.builtin_fonts.contains_key(font_family) {
+            return true;
+        }
+        self.system_fonts().faces().any(|face| {
+            face.families.iter().any(|(family, _)| family.eq_ignore_ascii_case(font_family))
+        })
+    }
+
+    fn system_fonts(&mut self) -> &fontdb::Database {
+        self.system_fonts.get_or_insert_with(|| {
+            let mut db = fontdb::Database::new();
+            db.load_system_fonts();
+            for path in REGISTERED_FONTS.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+                if let Err(e) = db.load_font_file(path) {
+                    log::warn!("Failed to load font file '{}': {}", path.display(), e);
+                }
+            }
+            db
+        })
+    }
+
+    /// 文本是否可以用内置字体（WinAnsi编码）输出
+    pub fn is_builtin_encodable(text: &str) -> bool {
+        text.chars().all(|c| (c as u32) < 0x100)
+    }
+
+    /// 从系统字体库中查找并读取字体数据
+    fn load_system_font_data(&mut self, font_family: &str, font_weight: &str) -> Option<Vec<u8>> {
+        let db = self.system_fonts();
+
+        let weight = if font_weight.contains("bold") || font_weight.parse::<u16>().map_or(false, |w| w >= 600) {
+            fontdb::Weight::BOLD
+        } else {
+            fontdb::Weight::NORMAL
+        };
+
+        let candidates = std::iter::once(font_family).chain(CJK_FALLBACK_FAMILIES.iter().copied());
+        for family in candidates {
+            let query = fontdb::Query {
+                families: &[fontdb::Family::Name(family)],
+                weight,
+                ..Default::default()
+            };
+
+            let Some(id) = db.query(&query) else { continue };
+            let Some(face) = db.face(id) else { continue };
+            // printpdf 按整个文件嵌入字体，不支持字体集合及CFF轮廓
+            let embeddable = face.index == 0 && match &face.source {
+                fontdb::Source::File(path) | fontdb::Source::SharedFile(path, _) => path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map_or(false, |ext| ext.eq_ignore_ascii_case("ttf")),
+                fontdb::Source::Binary(_) => true,
+            };
+            if !embeddable {
+                continue;
+            }
+
+            if let Some(data) = db.with_face_data(id, |data, _| data.to_vec()) {
+                return Some(data);
+            }
+        }
+
+        None
+    }
+
     /// 获取或创建字体引用
     pub fn get_font(
         &mut self,
@@ -71,12 +207,35 @@ impl FontManager {
         &self,
         text: &str,
         font_size: f64,
-        _font_family: &str,
+        font_family: &str,
     ) -> f64 {
-        // 简化的文本宽度计算
-        // 实际项目中可能需要更精确的字体度量
-        let avg_char_width = font_size * 0.6; // 近似值
-        text.len() as f64 * avg_char_width
+        // 等宽字体按0.6em计算，比例字体取平均字宽，全角字符按1em计算
+        let narrow_width = match self.builtin_fonts.get(font_family) {
+            Some(BuiltinFont::Courier) => 0.6,
+            _ => 0.55,
+        };
+
+        text.chars()
+            .map(|c| if Self::is_wide_char(c) { 1.0 } else { narrow_width })
+            .sum::<f64>()
+            * font_size
+    }
+
+    /// 是否为全角字符（中日韩文字及全角标点）
+    fn is_wide_char(c: char) -> bool {
+        matches!(c as u32,
+            0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+        )
     }
 
     /// 清理字体缓存
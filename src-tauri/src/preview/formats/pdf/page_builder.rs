@@ -12,8 +12,8 @@ impl PageBuilder {
         Self { options }
     }
 
-    /// 创建PDF文档
-    pub fn create_document(&self, title: &str) -> PreviewResult<(PdfDocumentReference, PdfPageIndex)> {
+    /// 创建PDF文档，返回文档及首页的页面和图层索引
+    pub fn create_document(&self, title: &str) -> PreviewResult<(PdfDocumentReference, PdfPageIndex, PdfLayerIndex)> {
         let (width, height) = self.calculate_page_dimensions();
         
         let (doc, page1, layer1) = PdfDocument::new(
@@ -23,7 +23,7 @@ impl PageBuilder {
             "Main Layer"
         );
 
-        Ok((doc, page1))
+        Ok((doc, page1, layer1))
     }
 
     /// 计算页面尺寸（毫米）
@@ -56,6 +56,21 @@ impl PageBuilder {
         (content_x, content_y, content_width, content_height)
     }
 
+    /// 内容区域在页面坐标系中的位置（单位：pt，原点在左下角）
+    /// 返回 (左, 上, 宽, 高)
+    pub fn content_area_points(&self) -> (f64, f64, f64, f64) {
+        let (_, page_height) = self.calculate_page_dimensions();
+        let (x, _, width, height) = self.calculate_content_area();
+        let margins = &self.options.margins;
+
+        (
+            mm_to_pt(x),
+            mm_to_pt(page_height - margins.top),
+            mm_to_pt(width),
+            mm_to_pt(height),
+        )
+    }
+
     /// 获取默认PDF选项
     pub fn default_options() -> PdfOptions {
         PdfOptions {
@@ -85,4 +100,9 @@ impl PageBuilder {
 
         Ok((page_index, layer_index))
     }
-}
\ No newline at end of file
+}
+
+/// 毫米转换为点（1pt = 1/72 英寸）
+pub fn mm_to_pt(mm: f64) -> f64 {
+    mm * 72.0 / 25.4
+}
@@ -1,13 +1,139 @@
+use crate::core::image_source;
 use crate::preview::{PreviewError, PreviewResult};
 use crate::preview::formats::pdf::{FontManager, PageBuilder};
+use printpdf::lopdf::content::Operation;
+use printpdf::lopdf::Object;
 use printpdf::*;
+use std::f64::consts::{FRAC_PI_2, TAU};
 
-/// SVG到PDF转换器（简化实现）
+const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
+
+/// 贝塞尔曲线逼近圆弧的控制点系数
+const KAPPA: f64 = 0.552_284_75;
+
+/// SVG到PDF转换器
+///
+/// 直接遍历 `SvgConverter::elements_to_svg` 生成的SVG树，输出矢量PDF绘图指令。
+/// 坐标映射与图片渲染保持一致：viewBox 左上角对齐内容区域左上角，1个SVG单位对应1pt，
+/// 内容超出页面内容区域时整体等比缩小。
 pub struct SvgToPdfConverter {
     font_manager: FontManager,
     page_builder: PageBuilder,
 }
 
+/// 二维仿射变换 [a b c d e f]，与SVG/PDF矩阵定义一致
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Transform {
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+    e: f64,
+    f: f64,
+}
+
+impl Transform {
+    fn identity() -> Self {
+        Self { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0 }
+    }
+
+    fn translate(tx: f64, ty: f64) -> Self {
+        Self { e: tx, f: ty, ..Self::identity() }
+    }
+
+    fn scale(sx: f64, sy: f64) -> Self {
+        Self { a: sx, d: sy, ..Self::identity() }
+    }
+
+    /// 绕原点旋转，角度单位为度
+    fn rotate(degrees: f64) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self { a: cos, b: sin, c: -sin, d: cos, ..Self::identity() }
+    }
+
+    fn skew(x_degrees: f64, y_degrees: f64) -> Self {
+        Self { b: y_degrees.to_radians().tan(), c: x_degrees.to_radians().tan(), ..Self::identity() }
+    }
+
+    /// 先应用 `other`，再应用 `self`
+    fn multiply(&self, other: &Transform) -> Self {
+        Self {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            e: self.a * other.e + self.c * other.f + self.e,
+            f: self.b * other.e + self.d * other.f + self.f,
+        }
+    }
+
+    fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.a * x + self.c * y + self.e, self.b * x + self.d * y + self.f)
+    }
+
+    /// 线宽、字号等标量的缩放系数
+    fn scale_factor(&self) -> f64 {
+        (self.a * self.d - self.b * self.c).abs().sqrt()
+    }
+
+    /// 解析SVG transform属性，支持 translate / scale / rotate / skewX / skewY / matrix；
+    /// 无法识别的变换函数忽略并记录警告
+    fn parse(value: &str) -> PreviewResult<Self> {
+        let mut result = Self::identity();
+        let mut rest = value.trim();
+
+        while !rest.is_empty() {
+            let open = rest.find('(').ok_or_else(|| Self::parse_error(value))?;
+            let close = rest.find(')').ok_or_else(|| Self::parse_error(value))?;
+            let name = rest[..open].trim().trim_start_matches(',').trim();
+            let args = parse_number_list(&rest[open + 1..close]);
+
+            let transform = match (name, args.as_slice()) {
+                ("translate", [tx]) => Self::translate(*tx, 0.0),
+                ("translate", [tx, ty]) => Self::translate(*tx, *ty),
+                ("scale", [s]) => Self::scale(*s, *s),
+                ("scale", [sx, sy]) => Self::scale(*sx, *sy),
+                ("rotate", [angle]) => Self::rotate(*angle),
+                ("rotate", [angle, cx, cy]) => Self::translate(*cx, *cy)
+                    .multiply(&Self::rotate(*angle))
+                    .multiply(&Self::translate(-cx, -cy)),
+                ("skewX", [angle]) => Self::skew(*angle, 0.0),
+                ("skewY", [angle]) => Self::skew(0.0, *angle),
+                ("matrix", [a, b, c, d, e, f]) => Self { a: *a, b: *b, c: *c, d: *d, e: *e, f: *f },
+                _ => {
+                    log::warn!("Ignoring unsupported SVG transform '{}' in '{}'", name, value);
+                    Self::identity()
+                }
+            };
+
+            result = result.multiply(&transform);
+            rest = rest[close + 1..].trim();
+        }
+
+        Ok(result)
+    }
+
+    fn parse_error(value: &str) -> PreviewError {
+        PreviewError::RenderError {
+            message: format!("Invalid SVG transform: {}", value),
+        }
+    }
+}
+
+/// 绘制上下文（随分组继承）
+#[derive(Debug, Clone, Copy)]
+struct DrawContext {
+    transform: Transform,
+    opacity: f64,
+}
+
+/// 描边属性
+struct StrokeStyle {
+    color: (f64, f64, f64),
+    width: f64,
+    dash_array: Vec<f64>,
+}
+
 impl SvgToPdfConverter {
     pub fn new(page_builder: PageBuilder) -> Self {
         Self {
@@ -16,14 +142,731 @@ impl SvgToPdfConverter {
         }
     }
 
-    /// 将SVG转换为PDF字节（简化实现）
-    pub fn convert_svg_to_pdf(&mut self, _svg_data: &str) -> PreviewResult<Vec<u8>> {
-        // 简化实现：返回一个基本的PDF文档
-        // TODO: 实现完整的SVG到PDF转换
-        
-        // 暂时返回一个简单的PDF标识符
-        let basic_pdf = b"%PDF-1.4\n1 0 obj<</Type/Catalog/Pages 2 0 R>>endobj\n2 0 obj<</Type/Pages/Kids[3 0 R]/Count 1>>endobj\n3 0 obj<</Type/Page/Parent 2 0 R/MediaBox[0 0 612 792]>>endobj\nxref\n0 4\n0000000000 65535 f \n0000000010 00000 n \n0000000053 00000 n \n0000000100 00000 n \ntrailer<</Size 4/Root 1 0 R>>\nstartxref\n149\n%%EOF".to_vec();
-        
-        Ok(basic_pdf)
+    /// 将SVG转换为PDF字节
+    pub fn convert_svg_to_pdf(&mut self, svg_data: &str) -> PreviewResult<Vec<u8>> {
+        let document = roxmltree::Document::parse(svg_data)
+            .map_err(|e| PreviewError::RenderError {
+                message: format!("Failed to parse SVG: {}", e),
+            })?;
+        let root = document.root_element();
+
+        let (doc, page, layer) = self.page_builder.create_document("Jasper Report")?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        let context = DrawContext {
+            transform: self.viewport_transform(&root),
+            opacity: 1.0,
+        };
+        self.draw_children(&doc, &layer, &root, context)?;
+
+        doc.save_to_bytes()
+            .map_err(|e| PreviewError::RenderError {
+                message: format!("Failed to write PDF: {}", e),
+            })
+    }
+
+    /// 计算 viewBox 到页面坐标（pt，原点左下角）的映射
+    fn viewport_transform(&self, root: &roxmltree::Node) -> Transform {
+        let (view_x, view_y, view_width, view_height) = root
+            .attribute("viewBox")
+            .map(parse_number_list)
+            .and_then(|values| match values.as_slice() {
+                [x, y, w, h] if *w > 0.0 && *h > 0.0 => Some((*x, *y, *w, *h)),
+                _ => None,
+            })
+            .unwrap_or_else(|| {
+                let width = root.attribute("width").and_then(parse_length).unwrap_or(800.0);
+                let height = root.attribute("height").and_then(parse_length).unwrap_or(600.0);
+                (0.0, 0.0, width, height)
+            });
+
+        let (left, top, width, height) = self.page_builder.content_area_points();
+        let scale = (width / view_width).min(height / view_height).min(1.0);
+
+        // 平移viewBox原点 -> 缩放并翻转Y轴 -> 移动到内容区域左上角
+        Transform::translate(left, top)
+            .multiply(&Transform::scale(scale, -scale))
+            .multiply(&Transform::translate(-view_x, -view_y))
+    }
+
+    /// 绘制子节点
+    fn draw_children(
+        &mut self,
+        doc: &PdfDocumentReference,
+        layer: &PdfLayerReference,
+        node: &roxmltree::Node,
+        context: DrawContext,
+    ) -> PreviewResult<()> {
+        for child in node.children().filter(|n| n.is_element()) {
+            self.draw_node(doc, layer, &child, context)?;
+        }
+        Ok(())
+    }
+
+    /// 绘制单个SVG节点
+    fn draw_node(
+        &mut self,
+        doc: &PdfDocumentReference,
+        layer: &PdfLayerReference,
+        node: &roxmltree::Node,
+        parent: DrawContext,
+    ) -> PreviewResult<()> {
+        if node.attribute("display") == Some("none") || node.attribute("visibility") == Some("hidden") {
+            return Ok(());
+        }
+
+        let mut context = parent;
+        if let Some(transform) = node.attribute("transform") {
+            context.transform = parent.transform.multiply(&Transform::parse(transform)?);
+        }
+        context.opacity *= number_attr(node, "opacity").unwrap_or(1.0).clamp(0.0, 1.0);
+
+        match node.tag_name().name() {
+            "g" | "svg" => self.draw_children(doc, layer, node, context),
+            "rect" => {
+                Self::draw_rect(layer, node, &context);
+                Ok(())
+            }
+            "line" => {
+                Self::draw_line(layer, node, &context);
+                Ok(())
+            }
+            "circle" => {
+                Self::draw_circle(layer, node, &context);
+                Ok(())
+            }
+            "path" => {
+                Self::draw_path(layer, node, &context);
+                Ok(())
+            }
+            "image" => {
+                Self::draw_image(layer, node, &context);
+                Ok(())
+            }
+            "text" => self.draw_text(doc, layer, node, &context),
+            // defs、title等非绘制节点直接忽略
+            _ => Ok(()),
+        }
+    }
+
+    /// 绘制矩形（支持圆角）
+    fn draw_rect(layer: &PdfLayerReference, node: &roxmltree::Node, context: &DrawContext) {
+        let x = number_attr(node, "x").unwrap_or(0.0);
+        let y = number_attr(node, "y").unwrap_or(0.0);
+        let width = number_attr(node, "width").unwrap_or(0.0);
+        let height = number_attr(node, "height").unwrap_or(0.0);
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+
+        let fill = Self::fill_color(node, context);
+        let stroke = Self::stroke_style(node, context);
+        if fill.is_none() && stroke.is_none() {
+            return;
+        }
+
+        // rx/ry 缺省时互相继承，且不超过边长的一半
+        let rx = number_attr(node, "rx");
+        let ry = number_attr(node, "ry");
+        let rx = rx.or(ry).unwrap_or(0.0).clamp(0.0, width / 2.0);
+        let ry = ry.or(Some(rx)).unwrap_or(0.0).clamp(0.0, height / 2.0);
+
+        let mut path = PathBuilder::new(context.transform);
+        if rx > 0.0 && ry > 0.0 {
+            let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+            let (right, bottom) = (x + width, y + height);
+            path.move_to(x + rx, y);
+            path.line_to(right - rx, y);
+            path.curve_to((right - rx + kx, y), (right, y + ry - ky), (right, y + ry));
+            path.line_to(right, bottom - ry);
+            path.curve_to((right, bottom - ry + ky), (right - rx + kx, bottom), (right - rx, bottom));
+            path.line_to(x + rx, bottom);
+            path.curve_to((x + rx - kx, bottom), (x, bottom - ry + ky), (x, bottom - ry));
+            path.line_to(x, y + ry);
+            path.curve_to((x, y + ry - ky), (x + rx - kx, y), (x + rx, y));
+        } else {
+            path.move_to(x, y);
+            path.line_to(x + width, y);
+            path.line_to(x + width, y + height);
+            path.line_to(x, y + height);
+        }
+        path.close();
+
+        Self::paint_path(layer, path, fill, stroke.as_ref());
+    }
+
+    /// 绘制嵌入为数据 URI 的位图；PDF 图片不带透明通道，透明部分按白色背景合成
+    fn draw_image(layer: &PdfLayerReference, node: &roxmltree::Node, context: &DrawContext) {
+        let x = number_attr(node, "x").unwrap_or(0.0);
+        let y = number_attr(node, "y").unwrap_or(0.0);
+        let width = number_attr(node, "width").unwrap_or(0.0);
+        let height = number_attr(node, "height").unwrap_or(0.0);
+        let href = node.attribute("href").or_else(|| node.attribute((XLINK_NS, "href")));
+        let (Some(href), true) = (href, width > 0.0 && height > 0.0) else {
+            return;
+        };
+        let decoded = match image_source::load(href, None).and_then(|image| image.decode()) {
+            Ok(decoded) => decoded.to_rgba8(),
+            Err(e) => {
+                log::warn!("Skipped image that could not be decoded: {}", e);
+                return;
+            }
+        };
+
+        let mut image_data = Vec::with_capacity(decoded.as_raw().len() / 4 * 3);
+        for pixel in decoded.pixels() {
+            let alpha = pixel[3] as f64 / 255.0 * context.opacity;
+            image_data.extend(pixel.0[..3].iter().map(|c| (*c as f64 * alpha + 255.0 * (1.0 - alpha)).round() as u8));
+        }
+
+        // Y轴翻转后，图片左下角对应SVG中的 (x, y + height)
+        let (left, bottom) = context.transform.apply(x, y + height);
+        let scale = context.transform.scale_factor();
+        let xobject = ImageXObject {
+            width: Px(decoded.width() as usize),
+            height: Px(decoded.height() as usize),
+            color_space: ColorSpace::Rgb,
+            bits_per_component: ColorBits::Bit8,
+            interpolate: true,
+            image_data,
+            image_filter: None,
+            clipping_bbox: None,
+        };
+        // 72 DPI 时一个像素对应 1pt
+        Image::from(xobject).add_to_layer(layer.clone(), ImageTransform {
+            translate_x: Some(Mm::from(Pt(left as f32))),
+            translate_y: Some(Mm::from(Pt(bottom as f32))),
+            scale_x: Some((width * scale / decoded.width() as f64) as f32),
+            scale_y: Some((height * scale / decoded.height() as f64) as f32),
+            dpi: Some(72.0),
+            ..Default::default()
+        });
+    }
+
+    /// 绘制直线
+    fn draw_line(layer: &PdfLayerReference, node: &roxmltree::Node, context: &DrawContext) {
+        let Some(stroke) = Self::stroke_style(node, context) else {
+            return;
+        };
+
+        let mut path = PathBuilder::new(context.transform);
+        path.move_to(number_attr(node, "x1").unwrap_or(0.0), number_attr(node, "y1").unwrap_or(0.0));
+        path.line_to(number_attr(node, "x2").unwrap_or(0.0), number_attr(node, "y2").unwrap_or(0.0));
+
+        Self::paint_path(layer, path, None, Some(&stroke));
+    }
+
+    /// 绘制圆形
+    fn draw_circle(layer: &PdfLayerReference, node: &roxmltree::Node, context: &DrawContext) {
+        let cx = number_attr(node, "cx").unwrap_or(0.0);
+        let cy = number_attr(node, "cy").unwrap_or(0.0);
+        let r = number_attr(node, "r").unwrap_or(0.0);
+        if r <= 0.0 {
+            return;
+        }
+
+        let fill = Self::fill_color(node, context);
+        let stroke = Self::stroke_style(node, context);
+        if fill.is_none() && stroke.is_none() {
+            return;
+        }
+
+        let k = r * KAPPA;
+        let mut path = PathBuilder::new(context.transform);
+        path.move_to(cx + r, cy);
+        path.curve_to((cx + r, cy + k), (cx + k, cy + r), (cx, cy + r));
+        path.curve_to((cx - k, cy + r), (cx - r, cy + k), (cx - r, cy));
+        path.curve_to((cx - r, cy - k), (cx - k, cy - r), (cx, cy - r));
+        path.curve_to((cx + k, cy - r), (cx + r, cy - k), (cx + r, cy));
+        path.close();
+
+        Self::paint_path(layer, path, fill, stroke.as_ref());
+    }
+
+    /// 绘制路径，支持全部SVG路径命令（含相对坐标形式）；二次曲线和圆弧转换为三次贝塞尔曲线
+    fn draw_path(layer: &PdfLayerReference, node: &roxmltree::Node, context: &DrawContext) {
+        let Some(data) = node.attribute("d") else {
+            return;
+        };
+        let fill = Self::fill_color(node, context);
+        let stroke = Self::stroke_style(node, context);
+        if fill.is_none() && stroke.is_none() {
+            return;
+        }
+
+        let mut path = PathBuilder::new(context.transform);
+        let (mut x, mut y) = (0.0, 0.0);
+        let mut start = (0.0, 0.0);
+        // 上一段曲线的第二个控制点，S/T 命令按当前点镜像得到第一个控制点
+        let mut last_cubic: Option<(f64, f64)> = None;
+        let mut last_quad: Option<(f64, f64)> = None;
+        for (command, args) in path_commands(data) {
+            let (previous_cubic, previous_quad) = (last_cubic.take(), last_quad.take());
+            let relative = command.is_ascii_lowercase();
+            // 相对命令的坐标以当前点为原点
+            let origin = |x: f64, y: f64| if relative { (x, y) } else { (0.0, 0.0) };
+            match command.to_ascii_uppercase() {
+                'M' => {
+                    for (index, point) in args.chunks_exact(2).enumerate() {
+                        let (ox, oy) = origin(x, y);
+                        (x, y) = (ox + point[0], oy + point[1]);
+                        if index == 0 {
+                            path.move_to(x, y);
+                            start = (x, y);
+                        } else {
+                            path.line_to(x, y);
+                        }
+                    }
+                }
+                'L' => {
+                    for point in args.chunks_exact(2) {
+                        let (ox, oy) = origin(x, y);
+                        (x, y) = (ox + point[0], oy + point[1]);
+                        path.line_to(x, y);
+                    }
+                }
+                'H' => {
+                    for value in &args {
+                        x = origin(x, y).0 + value;
+                        path.line_to(x, y);
+                    }
+                }
+                'V' => {
+                    for value in &args {
+                        y = origin(x, y).1 + value;
+                        path.line_to(x, y);
+                    }
+                }
+                'C' => {
+                    for curve in args.chunks_exact(6) {
+                        let (ox, oy) = origin(x, y);
+                        let c2 = (ox + curve[2], oy + curve[3]);
+                        path.curve_to((ox + curve[0], oy + curve[1]), c2, (ox + curve[4], oy + curve[5]));
+                        (x, y) = (ox + curve[4], oy + curve[5]);
+                        last_cubic = Some(c2);
+                    }
+                }
+                'S' => {
+                    let mut previous = previous_cubic;
+                    for curve in args.chunks_exact(4) {
+                        let (ox, oy) = origin(x, y);
+                        let c1 = previous.map_or((x, y), |(cx, cy)| (2.0 * x - cx, 2.0 * y - cy));
+                        let c2 = (ox + curve[0], oy + curve[1]);
+                        path.curve_to(c1, c2, (ox + curve[2], oy + curve[3]));
+                        (x, y) = (ox + curve[2], oy + curve[3]);
+                        previous = Some(c2);
+                    }
+                    last_cubic = previous;
+                }
+                'Q' => {
+                    for curve in args.chunks_exact(4) {
+                        let (ox, oy) = origin(x, y);
+                        let control = (ox + curve[0], oy + curve[1]);
+                        let end = (ox + curve[2], oy + curve[3]);
+                        path.quad_to((x, y), control, end);
+                        (x, y) = end;
+                        last_quad = Some(control);
+                    }
+                }
+                'T' => {
+                    let mut previous = previous_quad;
+                    for point in args.chunks_exact(2) {
+                        let (ox, oy) = origin(x, y);
+                        let control = previous.map_or((x, y), |(cx, cy)| (2.0 * x - cx, 2.0 * y - cy));
+                        let end = (ox + point[0], oy + point[1]);
+                        path.quad_to((x, y), control, end);
+                        (x, y) = end;
+                        previous = Some(control);
+                    }
+                    last_quad = previous;
+                }
+                'A' => {
+                    for arc in args.chunks_exact(7) {
+                        let (ox, oy) = origin(x, y);
+                        let end = (ox + arc[5], oy + arc[6]);
+                        for [c1, c2, to] in arc_to_curves((x, y), (arc[0], arc[1]), arc[2], arc[3] != 0.0, arc[4] != 0.0, end) {
+                            path.curve_to(c1, c2, to);
+                        }
+                        (x, y) = end;
+                    }
+                }
+                'Z' => {
+                    path.close();
+                    (x, y) = start;
+                }
+                other => log::warn!("Ignoring unknown SVG path command '{}'", other),
+            }
+        }
+
+        Self::paint_path(layer, path, fill, stroke.as_ref());
+    }
+
+    /// 绘制文本
+    fn draw_text(
+        &mut self,
+        doc: &PdfDocumentReference,
+        layer: &PdfLayerReference,
+        node: &roxmltree::Node,
+        context: &DrawContext,
+    ) -> PreviewResult<()> {
+        let text: String = node
+            .descendants()
+            .filter(|n| n.is_text())
+            .filter_map(|n| n.text())
+            .collect();
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+
+        let Some(color) = Self::fill_color(node, context) else {
+            return Ok(());
+        };
+
+        let font_family = style_attr(node, "font-family")
+            .and_then(|family| family.split(',').next())
+            .map(|family| family.trim().trim_matches(|c| c == '\'' || c == '"'))
+            .unwrap_or("Arial");
+        let font_weight = style_attr(node, "font-weight").unwrap_or("normal");
+        let font_size = style_number(node, "font-size").unwrap_or(16.0);
+        let x = number_attr(node, "x").unwrap_or(0.0);
+        let y = number_attr(node, "y").unwrap_or(0.0);
+
+        // 水平对齐：按文本宽度偏移起点
+        let text_width = self.font_manager.calculate_text_width(&text, font_size, font_family);
+        let x = match style_attr(node, "text-anchor") {
+            Some("middle") => x - text_width / 2.0,
+            Some("end") => x - text_width,
+            _ => x,
+        };
+
+        // 垂直对齐：与图片渲染器一致（按常见无衬线字体 ascent≈0.9em、x-height≈0.52em 估算）
+        let y = match node.attribute("dominant-baseline") {
+            Some("hanging") => y + font_size * 0.72,
+            Some("text-before-edge") => y + font_size * 0.9,
+            Some("middle") => y + font_size * 0.26,
+            Some("central") => y + font_size * 0.35,
+            _ => y,
+        };
+
+        let font = self.font_manager.get_font_for_text(doc, font_family, font_weight, &text)?;
+        let (page_x, page_y) = context.transform.apply(x, y);
+        let page_font_size = font_size * context.transform.scale_factor();
+
+        layer.save_graphics_state();
+        layer.add_operation(color_operation("rg", color));
+        layer.begin_text_section();
+        layer.set_font(&font, page_font_size as f32);
+        layer.add_operation(Operation::new(
+            "Tm",
+            vec![1.into(), 0.into(), 0.into(), 1.into(), real(page_x), real(page_y)],
+        ));
+        layer.write_text(text, &font);
+        layer.end_text_section();
+        layer.restore_graphics_state();
+
+        Ok(())
+    }
+
+    /// 输出路径并填充/描边
+    fn paint_path(
+        layer: &PdfLayerReference,
+        path: PathBuilder,
+        fill: Option<(f64, f64, f64)>,
+        stroke: Option<&StrokeStyle>,
+    ) {
+        layer.save_graphics_state();
+
+        if let Some(color) = fill {
+            layer.add_operation(color_operation("rg", color));
+        }
+        if let Some(stroke) = stroke {
+            layer.add_operation(color_operation("RG", stroke.color));
+            layer.add_operation(Operation::new("w", vec![real(stroke.width)]));
+            let dashes = stroke.dash_array.iter().map(|v| real(*v)).collect();
+            layer.add_operation(Operation::new("d", vec![Object::Array(dashes), 0.into()]));
+        }
+
+        for operation in path.operations {
+            layer.add_operation(operation);
+        }
+
+        let paint = match (fill.is_some(), stroke.is_some()) {
+            (true, true) => "B",
+            (true, false) => "f",
+            (false, true) => "S",
+            (false, false) => "n",
+        };
+        layer.add_operation(Operation::new(paint, vec![]));
+
+        layer.restore_graphics_state();
+    }
+
+    /// 解析填充颜色（已叠加透明度）
+    fn fill_color(node: &roxmltree::Node, context: &DrawContext) -> Option<(f64, f64, f64)> {
+        let color = parse_color(style_attr(node, "fill").unwrap_or("black"))?;
+        let opacity = context.opacity * style_number(node, "fill-opacity").unwrap_or(1.0).clamp(0.0, 1.0);
+        Self::visible_color(color, opacity)
+    }
+
+    /// 解析描边属性（已叠加透明度和变换缩放）
+    fn stroke_style(node: &roxmltree::Node, context: &DrawContext) -> Option<StrokeStyle> {
+        let color = parse_color(style_attr(node, "stroke")?)?;
+        let width = style_number(node, "stroke-width").unwrap_or(1.0);
+        if width <= 0.0 {
+            return None;
+        }
+
+        let opacity = context.opacity * style_number(node, "stroke-opacity").unwrap_or(1.0).clamp(0.0, 1.0);
+        let color = Self::visible_color(color, opacity)?;
+        let scale = context.transform.scale_factor();
+
+        let dash_array = match style_attr(node, "stroke-dasharray") {
+            Some(value) if value.trim() != "none" => {
+                let mut dashes: Vec<f64> = parse_number_list(value).into_iter().map(|v| v * scale).collect();
+                // 奇数个值时按SVG规范重复一次；全零视为实线
+                if dashes.len() % 2 == 1 {
+                    dashes.extend(dashes.clone());
+                }
+                if dashes.iter().all(|v| *v <= 0.0) {
+                    dashes.clear();
+                }
+                dashes
+            }
+            _ => Vec::new(),
+        };
+
+        Some(StrokeStyle {
+            color,
+            width: width * scale,
+            dash_array,
+        })
+    }
+
+    /// 透明度处理：PDF输出以白色纸张为底，通过与白色混合近似透明效果
+    fn visible_color(color: (f64, f64, f64), opacity: f64) -> Option<(f64, f64, f64)> {
+        if opacity <= 0.0 {
+            return None;
+        }
+        let blend = |c: f64| c * opacity + (1.0 - opacity);
+        Some((blend(color.0), blend(color.1), blend(color.2)))
+    }
+}
+
+/// 路径构建器，将SVG坐标变换到页面坐标后生成PDF路径指令
+struct PathBuilder {
+    transform: Transform,
+    operations: Vec<Operation>,
+}
+
+impl PathBuilder {
+    fn new(transform: Transform) -> Self {
+        Self { transform, operations: Vec::new() }
+    }
+
+    fn move_to(&mut self, x: f64, y: f64) {
+        let (x, y) = self.transform.apply(x, y);
+        self.operations.push(Operation::new("m", vec![real(x), real(y)]));
+    }
+
+    fn line_to(&mut self, x: f64, y: f64) {
+        let (x, y) = self.transform.apply(x, y);
+        self.operations.push(Operation::new("l", vec![real(x), real(y)]));
+    }
+
+    fn curve_to(&mut self, c1: (f64, f64), c2: (f64, f64), end: (f64, f64)) {
+        let mut operands = Vec::with_capacity(6);
+        for (x, y) in [c1, c2, end] {
+            let (x, y) = self.transform.apply(x, y);
+            operands.push(real(x));
+            operands.push(real(y));
+        }
+        self.operations.push(Operation::new("c", operands));
+    }
+
+    /// 二次贝塞尔曲线，按升阶公式转换为三次曲线
+    fn quad_to(&mut self, from: (f64, f64), control: (f64, f64), end: (f64, f64)) {
+        let towards = |p: (f64, f64)| (p.0 + (control.0 - p.0) * 2.0 / 3.0, p.1 + (control.1 - p.1) * 2.0 / 3.0);
+        self.curve_to(towards(from), towards(end), end);
     }
-}
\ No newline at end of file
+
+    fn close(&mut self) {
+        self.operations.push(Operation::new("h", vec![]));
+    }
+}
+
+/// 把SVG椭圆弧（端点参数形式）拆成不超过90°的三次贝塞尔曲线段，返回每段的两个控制点和终点
+///
+/// 按 SVG 规范附录 F.6.5 换算圆心参数，半径不足以连接两端时等比放大。
+fn arc_to_curves(
+    from: (f64, f64),
+    radii: (f64, f64),
+    rotation: f64,
+    large_arc: bool,
+    sweep: bool,
+    to: (f64, f64),
+) -> Vec<[(f64, f64); 3]> {
+    let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+    if from == to {
+        return Vec::new();
+    }
+    if rx == 0.0 || ry == 0.0 {
+        return vec![[from, to, to]];
+    }
+
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let (dx, dy) = ((from.0 - to.0) / 2.0, (from.1 - to.1) / 2.0);
+    let (x1, y1) = (cos * dx + sin * dy, -sin * dx + cos * dy);
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut coefficient = (numerator / denominator).max(0.0).sqrt();
+    if large_arc == sweep {
+        coefficient = -coefficient;
+    }
+    let (cx1, cy1) = (coefficient * rx * y1 / ry, -coefficient * ry * x1 / rx);
+    let center = (
+        cos * cx1 - sin * cy1 + (from.0 + to.0) / 2.0,
+        sin * cx1 + cos * cy1 + (from.1 + to.1) / 2.0,
+    );
+
+    let angle = |(ux, uy): (f64, f64), (vx, vy): (f64, f64)| (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+    let start_vector = ((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let end_vector = ((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+    let start_angle = angle((1.0, 0.0), start_vector);
+    let mut sweep_angle = angle(start_vector, end_vector);
+    if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= TAU;
+    } else if sweep && sweep_angle < 0.0 {
+        sweep_angle += TAU;
+    }
+
+    let segments = (sweep_angle.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = sweep_angle / segments as f64;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let point = |t: f64| {
+        let (s, c) = t.sin_cos();
+        (center.0 + rx * c * cos - ry * s * sin, center.1 + rx * c * sin + ry * s * cos)
+    };
+    let tangent = |t: f64| {
+        let (s, c) = t.sin_cos();
+        (-rx * s * cos - ry * c * sin, -rx * s * sin + ry * c * cos)
+    };
+
+    (0..segments)
+        .map(|index| {
+            let (t1, t2) = (start_angle + step * index as f64, start_angle + step * (index + 1) as f64);
+            let (p1, p2) = (point(t1), point(t2));
+            let (d1, d2) = (tangent(t1), tangent(t2));
+            [(p1.0 + k * d1.0, p1.1 + k * d1.1), (p2.0 - k * d2.0, p2.1 - k * d2.1), p2]
+        })
+        .collect()
+}
+
+fn real(value: f64) -> Object {
+    Object::Real(value as f32)
+}
+
+fn color_operation(operator: &str, (r, g, b): (f64, f64, f64)) -> Operation {
+    Operation::new(operator, vec![real(r), real(g), real(b)])
+}
+
+fn number_attr(node: &roxmltree::Node, name: &str) -> Option<f64> {
+    node.attribute(name).and_then(parse_length)
+}
+
+/// 可继承的表现属性（填充、描边、字体等）：节点自身没有时取最近的祖先分组上的值
+fn style_attr<'a>(node: &roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.ancestors().find_map(|ancestor| ancestor.attribute(name))
+}
+
+fn style_number(node: &roxmltree::Node, name: &str) -> Option<f64> {
+    style_attr(node, name).and_then(parse_length)
+}
+
+/// 解析长度值，忽略 px/pt 等单位后缀
+fn parse_length(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')))
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+/// 把路径数据拆成命令和参数，参数以逗号或空白分隔
+fn path_commands(data: &str) -> Vec<(char, Vec<f64>)> {
+    let mut commands = Vec::new();
+    let mut current: Option<(char, usize)> = None;
+    for (index, c) in data.char_indices() {
+        if c.is_ascii_alphabetic() && c != 'e' && c != 'E' {
+            if let Some((command, from)) = current {
+                commands.push((command, parse_number_list(&data[from..index])));
+            }
+            current = Some((c, index + 1));
+        }
+    }
+    if let Some((command, from)) = current {
+        commands.push((command, parse_number_list(&data[from..])));
+    }
+    commands
+}
+
+/// 解析以逗号或空白分隔的数值列表
+fn parse_number_list(value: &str) -> Vec<f64> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| s.parse().ok())
+        .collect()
+}
+
+/// 解析SVG颜色，返回0~1范围的RGB；none/transparent 返回 None
+fn parse_color(value: &str) -> Option<(f64, f64, f64)> {
+    let value = value.trim().to_ascii_lowercase();
+    let rgb = |r: u8, g: u8, b: u8| Some((r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0));
+
+    if let Some(hex) = value.strip_prefix('#') {
+        let digit = |i: usize, len: usize| u8::from_str_radix(hex.get(i..i + len)?, 16).ok();
+        return match hex.len() {
+            3 => rgb(digit(0, 1)? * 17, digit(1, 1)? * 17, digit(2, 1)? * 17),
+            6 | 8 => rgb(digit(0, 2)?, digit(2, 2)?, digit(4, 2)?),
+            _ => None,
+        };
+    }
+
+    if let Some(args) = value.strip_prefix("rgb(").or_else(|| value.strip_prefix("rgba(")) {
+        let parts: Vec<f64> = parse_number_list(args.trim_end_matches(')'));
+        if parts.len() >= 3 {
+            let channel = |v: f64| v.clamp(0.0, 255.0) as u8;
+            return rgb(channel(parts[0]), channel(parts[1]), channel(parts[2]));
+        }
+        return None;
+    }
+
+    match value.as_str() {
+        "black" => rgb(0, 0, 0),
+        "white" => rgb(255, 255, 255),
+        "red" => rgb(255, 0, 0),
+        "green" => rgb(0, 128, 0),
+        "blue" => rgb(0, 0, 255),
+        "yellow" => rgb(255, 255, 0),
+        "orange" => rgb(255, 165, 0),
+        "purple" => rgb(128, 0, 128),
+        "gray" | "grey" => rgb(128, 128, 128),
+        "lightgray" | "lightgrey" => rgb(211, 211, 211),
+        "darkgray" | "darkgrey" => rgb(169, 169, 169),
+        "silver" => rgb(192, 192, 192),
+        "navy" => rgb(0, 0, 128),
+        "teal" => rgb(0, 128, 128),
+        "maroon" => rgb(128, 0, 0),
+        "olive" => rgb(128, 128, 0),
+        "lime" => rgb(0, 255, 0),
+        "aqua" | "cyan" => rgb(0, 255, 255),
+        "fuchsia" | "magenta" => rgb(255, 0, 255),
+        _ => None,
+    }
+}
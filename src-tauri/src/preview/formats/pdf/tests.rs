@@ -182,4 +182,177 @@ mod tests {
         assert_eq!(print_config.compress_images, false);
         assert_eq!(print_config.pdf_version, "2.0");
     }
+    #[test]
+    fn test_svg_to_pdf_vector_output() {
+        use crate::preview::formats::pdf::{PageBuilder, SvgToPdfConverter};
+
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 200 100">
+            <g transform="translate(10, 20)">
+                <rect x="0" y="0" width="80" height="30" rx="4" ry="4" fill="#ff0000" fill-opacity="0.5" stroke="#000" stroke-width="2" stroke-dasharray="5,5"/>
+                <line x1="0" y1="40" x2="100" y2="40" stroke="blue" stroke-width="1" stroke-dasharray="none"/>
+                <text x="0" y="50" font-family="Arial" font-size="12" fill="#333333" text-anchor="start" dominant-baseline="hanging">Hello PDF</text>
+            </g>
+        </svg>"##;
+
+        let mut converter = SvgToPdfConverter::new(PageBuilder::new(create_test_pdf_options()));
+        let pdf = converter.convert_svg_to_pdf(svg).unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+
+        let document = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+        let page_id = *document.get_pages().values().next().unwrap();
+        let content = document.get_and_decode_page_content(page_id).unwrap();
+        let operators: Vec<&str> = content.operations.iter().map(|op| op.operator.as_str()).collect();
+
+        // 圆角矩形：曲线路径 + 填充描边；虚线；直线描边；文本
+        assert!(operators.contains(&"c"));
+        assert!(operators.contains(&"B"));
+        assert!(operators.contains(&"S"));
+        assert!(operators.contains(&"d"));
+        assert!(operators.contains(&"Tj"));
+
+        // 矩形左上角位于内容区域左上角偏移(10, 20)pt处
+        let first_move = content.operations.iter().find(|op| op.operator == "m").unwrap();
+        let x = first_move.operands[0].as_float().unwrap() as f64;
+        let y = first_move.operands[1].as_float().unwrap() as f64;
+        let (left, top, _, _) = PageBuilder::new(create_test_pdf_options()).content_area_points();
+        assert!((x - (left + 10.0 + 4.0)).abs() < 0.01);
+        assert!((y - (top - 20.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_svg_to_pdf_paths_and_circles() {
+        use crate::preview::formats::pdf::{PageBuilder, SvgToPdfConverter};
+
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 200 100">
+            <path d="M10 10L60 10C70 10 70 30 60 30Z" fill="#4e79a7"/>
+            <path d="m100 10 h20 v20 l-20 0z" fill="none" stroke="#000000"/>
+            <path d="M0 0A10 10 0 0 1 20 20" fill="#000000"/>
+            <circle cx="150" cy="50" r="5" fill="#e15759"/>
+        </svg>"##;
+
+        let mut converter = SvgToPdfConverter::new(PageBuilder::new(create_test_pdf_options()));
+        let pdf = converter.convert_svg_to_pdf(svg).unwrap();
+        let document = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+        let page_id = *document.get_pages().values().next().unwrap();
+        let content = document.get_and_decode_page_content(page_id).unwrap();
+        let count = |operator: &str| content.operations.iter().filter(|op| op.operator == operator).count();
+        let (left, top, _, _) = PageBuilder::new(create_test_pdf_options()).content_area_points();
+
+        // 三条路径加一个圆
+        assert_eq!(count("m"), 4);
+        assert_eq!(count("f"), 3);
+        assert_eq!(count("S"), 1);
+        // 路径一条曲线，半径放大后的半圆弧两段，圆四段
+        assert_eq!(count("c"), 7);
+        assert_eq!(count("l"), 4);
+
+        // 圆弧终点落在 (20, 20)
+        let arc_start = content.operations.iter().position(|op| op.operator == "m" && op.operands[0].as_float().unwrap() as f64 - left < 0.01).unwrap();
+        let arc_end = &content.operations[arc_start + 2];
+        assert_eq!(arc_end.operator, "c");
+        let (x, y) = (arc_end.operands[4].as_float().unwrap() as f64, arc_end.operands[5].as_float().unwrap() as f64);
+        assert!((x - (left + 20.0)).abs() < 0.01 && (y - (top - 20.0)).abs() < 0.01, "{} {}", x, y);
+    }
+
+    #[test]
+    fn test_svg_to_pdf_curves_transforms_and_group_paint() {
+        use crate::preview::formats::pdf::{PageBuilder, SvgToPdfConverter};
+
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 200 100">
+            <g fill="#ff0000" stroke="#0000ff" stroke-width="2">
+                <path d="M0 0Q10 10 20 0T40 0S50 10 60 0"/>
+                <rect x="0" y="20" width="10" height="10" transform="rotate(45 5 25)"/>
+                <rect x="20" y="20" width="10" height="10" transform="skewX(30) skewY(10)"/>
+                <rect x="40" y="20" width="10" height="10" transform="perspective(2)"/>
+            </g>
+        </svg>"##;
+
+        let mut converter = SvgToPdfConverter::new(PageBuilder::new(create_test_pdf_options()));
+        let pdf = converter.convert_svg_to_pdf(svg).unwrap();
+        let document = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+        let page_id = *document.get_pages().values().next().unwrap();
+        let content = document.get_and_decode_page_content(page_id).unwrap();
+        let count = |operator: &str| content.operations.iter().filter(|op| op.operator == operator).count();
+
+        // 二次曲线和平滑曲线转换为三段三次曲线，未知变换忽略后照常绘制
+        assert_eq!(count("c"), 3);
+        // 子元素继承分组的填充和描边
+        assert_eq!(count("B"), 4);
+        let red = content.operations.iter().filter(|op| op.operator == "rg").all(|op| {
+            let channels: Vec<f32> = op.operands.iter().map(|v| v.as_float().unwrap()).collect();
+            channels == [1.0, 0.0, 0.0]
+        });
+        assert!(red);
+
+        // 旋转后的矩形：第一个顶点 (0,20) 绕 (5,25) 转 45° 后横坐标变为 5
+        let (left, _, _, _) = PageBuilder::new(create_test_pdf_options()).content_area_points();
+        let rotated = content.operations.iter().filter(|op| op.operator == "m").nth(1).unwrap();
+        let x = rotated.operands[0].as_float().unwrap() as f64;
+        assert!((x - (left + 5.0)).abs() < 0.01, "{}", x);
+    }
+
+    #[test]
+    fn test_svg_to_pdf_images() {
+        use crate::core::image_source::ImageData;
+        use crate::preview::formats::pdf::{PageBuilder, SvgToPdfConverter};
+
+        let logo = ImageData::from_image(&image::DynamicImage::ImageRgba8(image::RgbaImage::new(4, 2))).unwrap();
+        let svg = format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 200 100">
+                <g transform="translate(10, 20)"><image x="0" y="0" width="40" height="20" href="{}"/></g>
+                <image x="0" y="0" width="10" height="10" href="data:image/png;base64,AAAA"/>
+            </svg>"##,
+            logo.to_data_uri()
+        );
+
+        let mut converter = SvgToPdfConverter::new(PageBuilder::new(create_test_pdf_options()));
+        let pdf = converter.convert_svg_to_pdf(&svg).unwrap();
+        let document = printpdf::lopdf::Document::load_mem(&pdf).unwrap();
+        let page_id = *document.get_pages().values().next().unwrap();
+        let content = document.get_and_decode_page_content(page_id).unwrap();
+
+        // 无法解码的图片跳过，只绘制一个图片对象，缩放到 40×20pt
+        let draws: Vec<_> = content.operations.iter().filter(|op| op.operator == "Do").collect();
+        assert_eq!(draws.len(), 1);
+        let matrix = content.operations.iter().find(|op| op.operator == "cm").unwrap();
+        assert!((matrix.operands[0].as_float().unwrap() - 40.0).abs() < 0.01);
+        assert!((matrix.operands[3].as_float().unwrap() - 20.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_svg_to_pdf_invalid_svg() {
+        use crate::preview::formats::pdf::{PageBuilder, SvgToPdfConverter};
+
+        let mut converter = SvgToPdfConverter::new(PageBuilder::new(create_test_pdf_options()));
+        assert!(converter.convert_svg_to_pdf("<svg").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pdf_render_elements() {
+        use crate::core::element::{ReportElement, ElementContent, Position, Size, TextStyle, TextAlign};
+        use crate::preview::svg_converter::SvgConverter;
+
+        let text = ReportElement::new(
+            ElementContent::Text {
+                content: "报表标题 Title".to_string(),
+                style: TextStyle {
+                    font_family: "Arial".to_string(),
+                    font_size: 14.0,
+                    font_weight: "bold".to_string(),
+                    color: "#000000".to_string(),
+                    align: TextAlign::Center,
+                    border: None,
+                    background: None,
+                },
+            },
+            Position::new(50.0, 50.0).unwrap(),
+            Size::new(200.0, 30.0).unwrap(),
+        )
+        .unwrap();
+
+        let svg = SvgConverter::elements_to_svg(&[text]).unwrap();
+        let renderer = crate::preview::formats::pdf::PdfRenderer::new();
+        let pdf = renderer.render(&svg, &create_test_render_options()).await.unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+    }
 }
\ No newline at end of file
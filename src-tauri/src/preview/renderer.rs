@@ -80,11 +80,16 @@ impl PreviewRenderer {
             cache.put_svg(svg_cache_key, optimized_svg.clone());
         }
 
-        // 选择合适的渲染器
-        let renderer = self.get_renderer(&options.format)?;
+        // 执行渲染（Excel直接使用元素模型，避免SVG丢失单元格结构和样式）
+        let render_result = match options.format {
+            OutputFormat::Excel => self.excel_renderer.render_elements(elements, options),
+            _ => {
+                let renderer = self.get_renderer(&options.format)?;
+                renderer.render(&optimized_svg, options).await
+            }
+        };
 
-        // 执行渲染
-        match renderer.render(&optimized_svg, options).await {
+        match render_result {
             Ok(data) => {
                 let render_time = start_time.elapsed().as_millis() as u64;
                 
@@ -185,6 +190,7 @@ impl PreviewRenderer {
         // 对选项进行哈希
         format!("{:?}", options.format).hash(&mut hasher);
         format!("{:?}", options.quality).hash(&mut hasher);
+        format!("{:?}", options.excel_options).hash(&mut hasher);
         
         format!("render-{:x}", hasher.finish())
     }
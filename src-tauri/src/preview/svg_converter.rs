@@ -1,5 +1,6 @@
 use crate::preview::{PreviewError, PreviewResult};
 use crate::core::element::{ReportElement, ElementContent, TextAlign, BorderStyleType, LineStyleType, LineCapType};
+use crate::core::image_source::{self, ImageData};
 
 /// SVG转换器 - 将ReportElement转换为SVG
 pub struct SvgConverter;
@@ -33,8 +34,15 @@ impl SvgConverter {
                 };
                 Self::text_to_svg(&element_id, &transform, &display_text, style, &element.size)
             }
-            ElementContent::Image { src: _, alt } => {
-                // 图片占位符实现
+            ElementContent::Image { src, alt, scale_mode, horizontal_align, vertical_align } => {
+                let size = (element.size.width, element.size.height);
+                let image = Self::load_image(src)
+                    .and_then(|image| image_source::render_svg(&image, *scale_mode, *horizontal_align, *vertical_align, size).ok());
+                if let Some(image) = image {
+                    return Ok(format!(r#"<g data-element-id="{}" transform="{}">{}</g>"#, element_id, transform, image));
+                }
+
+                // 相对路径、表达式或无法读取的图片显示占位框
                 let display_text = alt.as_deref().unwrap_or("[图片]");
                 Ok(format!(
                     r##"<g data-element-id="{}" transform="{}">
@@ -47,12 +55,126 @@ impl SvgConverter {
                     element.size.height,
                     element.size.width / 2.0,
                     element.size.height / 2.0,
-                    display_text
+                    Self::escape_xml(display_text)
                 ))
             }
+            ElementContent::Subreport(config) => {
+                // 设计时显示子报表占位框和模板路径，填充后由子报表内容替换
+                let display_text = if config.template_path.is_empty() {
+                    "[子报表]".to_string()
+                } else {
+                    format!("[子报表: {}]", config.template_path)
+                };
+                Ok(format!(
+                    r##"<g data-element-id="{}" transform="{}">
+                        <rect width="{}" height="{}" fill="#f5f8ff" stroke="#7a8fb8" stroke-width="1" stroke-dasharray="5,5"/>
+                        <text x="{}" y="{}" font-family="Arial" font-size="12" fill="#4a5a7a" text-anchor="middle" dominant-baseline="middle">{}</text>
+                    </g>"##,
+                    element_id,
+                    transform,
+                    element.size.width,
+                    element.size.height,
+                    element.size.width / 2.0,
+                    element.size.height / 2.0,
+                    Self::escape_xml(&display_text)
+                ))
+            }
+            ElementContent::Table(config) => Self::table_to_svg(element, &element_id, &transform, config),
+            ElementContent::Crosstab(config) => Self::crosstab_to_svg(element, &element_id, &transform, config),
+            ElementContent::Chart(config) => {
+                // 填充前没有数据，显示示例数据
+                let sample;
+                let data = match &config.data {
+                    Some(data) => data,
+                    None => {
+                        sample = crate::core::element::ChartData::sample(config);
+                        &sample
+                    }
+                };
+                Ok(format!(
+                    r#"<g data-element-id="{}" transform="{}">{}</g>"#,
+                    element_id,
+                    transform,
+                    crate::core::chart::render_svg(config, data, element.size.width, element.size.height)
+                ))
+            }
+            ElementContent::Barcode(config) => Ok(format!(
+                r#"<g data-element-id="{}" transform="{}">{}</g>"#,
+                element_id,
+                transform,
+                crate::core::barcode::render_svg(config, element.size.width, element.size.height)
+            )),
         }
     }
 
+    /// 填充后的图片已嵌入为数据 URI；转换器不知道模板目录，只加载绝对路径
+    fn load_image(src: &str) -> Option<ImageData> {
+        if !image_source::is_resolved(src) {
+            return None;
+        }
+        image_source::load(src, None)
+            .map_err(|e| log::warn!("Image '{}' could not be loaded: {}", src.chars().take(64).collect::<String>(), e))
+            .ok()
+    }
+
+    /// 表格设计时预览：表头、填满元素高度的示例行（单元格显示列表达式）和表尾
+    fn table_to_svg(
+        element: &ReportElement,
+        element_id: &str,
+        transform: &str,
+        config: &crate::core::element::TableConfig,
+    ) -> PreviewResult<String> {
+        let footer_height = if config.show_footer { config.row_height } else { 0.0 };
+        let body_height = element.size.height - config.header_extent() - footer_height;
+        let sample_rows = if config.row_height > 0.0 {
+            ((body_height / config.row_height).floor() as usize).max(1)
+        } else {
+            1
+        };
+
+        let samples: Vec<String> = config.columns.iter().map(|column| format!("[{}]", column.expression)).collect();
+        let samples: Vec<&str> = samples.iter().map(String::as_str).collect();
+        let footer: Vec<String> = config
+            .columns
+            .iter()
+            .map(|column| match column.footer_calculation {
+                Some(calculation) => format!("[{:?}]", calculation),
+                None => column.footer_text.clone().unwrap_or_default(),
+            })
+            .collect();
+        let footer: Vec<&str> = footer.iter().map(String::as_str).collect();
+
+        let mut cells = config.header_cells();
+        for index in 0..sample_rows {
+            cells.extend(config.body_cells(index, &samples));
+        }
+        cells.extend(config.footer_cells(sample_rows, &footer));
+        Self::cells_to_svg(element, element_id, transform, cells)
+    }
+
+    /// 交叉表设计时预览：每个分组一个占位组值，带小计/总计行列，单元格显示度量
+    fn crosstab_to_svg(
+        element: &ReportElement,
+        element_id: &str,
+        transform: &str,
+        config: &crate::core::element::CrosstabConfig,
+    ) -> PreviewResult<String> {
+        let format = |value: &serde_json::Value, _: Option<&str>| value.as_str().unwrap_or_default().to_string();
+        let grid = crate::core::crosstab::Crosstab::sample(config).grid(config, &format);
+        Self::cells_to_svg(element, element_id, transform, grid.elements)
+    }
+
+    fn cells_to_svg(element: &ReportElement, element_id: &str, transform: &str, cells: Vec<ReportElement>) -> PreviewResult<String> {
+        let mut svg = format!(r#"<g data-element-id="{}" transform="{}">"#, element_id, transform);
+        for mut cell in cells {
+            // 单元格沿用所属元素的 ID，点击任意单元格都选中整个表格
+            cell.id = element.id.clone();
+            svg.push_str(&Self::element_to_svg(&cell)?);
+        }
+        svg.push_str("</g>");
+        Ok(svg)
+    }
+
     /// 文本元素转SVG
     fn text_to_svg(
         element_id: &str,
@@ -3,6 +3,7 @@ mod tests {
     use super::*;
     use crate::core::element::{ReportElement, ElementId, ElementContent, TextStyle, TextAlign, Position, Size};
     use crate::preview::formats::{OutputFormat, RenderQuality, RenderOptions};
+    use crate::preview::PreviewRenderer;
     use std::collections::HashMap;
 
     /// 创建测试用的ReportElement
@@ -27,6 +28,8 @@ mod tests {
             visible: true,
             locked: false,
             name: None,
+            band: None,
+            origin: None,
         }
     }
 
@@ -35,6 +38,9 @@ mod tests {
         RenderOptions {
             format,
             quality: RenderQuality::High,
+            pdf_options: None,
+            image_quality: None,
+            excel_options: None,
             custom_properties: HashMap::new(),
         }
     }
@@ -48,7 +54,7 @@ mod tests {
         let svg = result.unwrap();
         
         // 验证SVG包含基本元素
-        assert!(svg.contains(&element.id));
+        assert!(svg.contains(&element.id.to_string()));
         assert!(svg.contains("10")); // x position
         assert!(svg.contains("20")); // y position
         assert!(svg.contains("100")); // width
@@ -79,6 +85,8 @@ mod tests {
                 visible: true,
                 locked: false,
                 name: Some("Test Element 2".to_string()),
+                band: None,
+                origin: None,
             }
         ];
 
@@ -198,6 +206,46 @@ mod tests {
         assert!(excel_formats.contains(&OutputFormat::Excel));
     }
 
+    #[test]
+    fn test_svg_converter_renders_images() {
+        use crate::core::element::{ImageAlign, ImageScaleMode};
+        use crate::core::image_source::ImageData;
+        use resvg::usvg::{self, TreeParsing};
+
+        let red = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(2, 1, image::Rgb([255, 0, 0])));
+        let mut element = create_test_element();
+        element.position = Position { x: 0.0, y: 0.0 };
+        element.size = Size { width: 40.0, height: 40.0 };
+        element.content = ElementContent::Image {
+            src: ImageData::from_image(&red).unwrap().to_data_uri(),
+            alt: Some("Logo".to_string()),
+            scale_mode: ImageScaleMode::RetainShape,
+            horizontal_align: ImageAlign::Start,
+            vertical_align: ImageAlign::End,
+        };
+        let svg = crate::preview::svg_converter::SvgConverter::element_to_svg(&element).unwrap();
+        assert!(svg.contains(r#"<image x="0.000" y="20.000" width="40.000" height="20.000""#));
+
+        // 栅格化后图片位于元素框下半部分
+        let document = format!(r#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="40" viewBox="0 0 40 40">{}</svg>"#, svg);
+        let tree = usvg::Tree::from_str(&document, &usvg::Options::default()).unwrap();
+        let mut pixmap = tiny_skia::Pixmap::new(40, 40).unwrap();
+        resvg::Tree::from_usvg(&tree).render(tiny_skia::Transform::identity(), &mut pixmap.as_mut());
+        assert_eq!(pixmap.pixel(20, 30).map(|p| (p.red(), p.alpha())), Some((255, 255)));
+        assert_eq!(pixmap.pixel(20, 10).map(|p| p.alpha()), Some(0));
+
+        // 相对路径在转换时无法解析，显示占位框
+        element.content = ElementContent::Image {
+            src: "images/logo.png".to_string(),
+            alt: Some("Logo".to_string()),
+            scale_mode: ImageScaleMode::default(),
+            horizontal_align: ImageAlign::default(),
+            vertical_align: ImageAlign::default(),
+        };
+        let placeholder = crate::preview::svg_converter::SvgConverter::element_to_svg(&element).unwrap();
+        assert!(placeholder.contains("stroke-dasharray") && placeholder.contains("Logo"));
+    }
+
     #[test]
     fn test_svg_optimization() {
         let messy_svg = r#"
@@ -241,6 +289,8 @@ mod tests {
             visible: true,
             locked: false,
             name: None,
+            band: None,
+            origin: None,
         };
 
         let svg_result = crate::preview::svg_converter::SvgConverter::element_to_svg(&rectangle_element);
@@ -267,6 +317,8 @@ mod tests {
             visible: true,
             locked: false,
             name: None,
+            band: None,
+            origin: None,
         };
 
         let svg_result = crate::preview::svg_converter::SvgConverter::element_to_svg(&line_element);
@@ -291,7 +343,7 @@ mod tests {
                         font_size: 16.0,
                         font_weight: "normal".to_string(),
                         color: "#000000".to_string(),
-                        align,
+                        align: align.clone(),
                         border: None,
                         background: None,
                     },
@@ -300,6 +352,8 @@ mod tests {
                 visible: true,
                 locked: false,
                 name: None,
+                band: None,
+                origin: None,
             };
 
             let svg_result = crate::preview::svg_converter::SvgConverter::element_to_svg(&text_element);
@@ -349,6 +403,8 @@ mod tests {
             visible: true,
             locked: false,
             name: None,
+            band: None,
+            origin: None,
         };
 
         let svg_result = crate::preview::svg_converter::SvgConverter::element_to_svg(&element);
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 
 /// 预览相关的所有类型定义
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     Pdf,
@@ -48,7 +48,7 @@ pub struct PdfOptions {
 }
 
 /// 页面尺寸
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PageSize {
     A4,
@@ -60,7 +60,7 @@ pub enum PageSize {
 }
 
 /// 页面方向
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Orientation {
     Portrait,
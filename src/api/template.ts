@@ -5,6 +5,13 @@ import type {
   TemplateInfo,
   TemplatePreview,
   TemplateFormat,
+  TemplateDiff,
+  TemplateMerge,
+  LintReport,
+  LibrarySettings,
+  LibraryChanges,
+  LibraryQuery,
+  LibraryEntry,
 } from '../types/template'
 
 /**
@@ -47,6 +54,13 @@ export class TemplateAPI {
     return await invoke<boolean>('validate_template', { template })
   }
   
+  /**
+   * Lint a template and return all problems (templatePath resolves relative image paths)
+   */
+  static async lintTemplate(template: JasperTemplate, templatePath?: string): Promise<LintReport> {
+    return await invoke<LintReport>('lint_template', { template, templatePath })
+  }
+  
   /**
    * Create a new empty template
    */
@@ -104,13 +118,24 @@ export class TemplateAPI {
   }
   
   /**
-   * Merge two templates
+   * Structural diff between two templates
+   */
+  static async diffTemplates(
+    baseTemplate: JasperTemplate,
+    otherTemplate: JasperTemplate
+  ): Promise<TemplateDiff> {
+    return await invoke<TemplateDiff>('diff_templates', { baseTemplate, otherTemplate })
+  }
+
+  /**
+   * Three-way merge of two edits of a common base template
    */
   static async mergeTemplates(
     baseTemplate: JasperTemplate,
-    overlayTemplate: JasperTemplate
-  ): Promise<JasperTemplate> {
-    return await invoke<JasperTemplate>('merge_templates', { baseTemplate, overlayTemplate })
+    ourTemplate: JasperTemplate,
+    theirTemplate: JasperTemplate
+  ): Promise<TemplateMerge> {
+    return await invoke<TemplateMerge>('merge_templates', { baseTemplate, ourTemplate, theirTemplate })
   }
   
   /**
@@ -122,6 +147,31 @@ export class TemplateAPI {
   ): Promise<JasperTemplate> {
     return await invoke<JasperTemplate>('extract_template_elements', { template, elementIds })
   }
+
+  // === Template Library ===
+
+  static async getLibrarySettings(): Promise<LibrarySettings> {
+    return await invoke<LibrarySettings>('get_template_library_settings')
+  }
+
+  /**
+   * Set the library directory (null disables the library) and rebuild the index
+   */
+  static async setLibraryRoot(root: string | null): Promise<LibraryChanges> {
+    return await invoke<LibraryChanges>('set_template_library_root', { root })
+  }
+
+  static async refreshLibrary(): Promise<LibraryChanges> {
+    return await invoke<LibraryChanges>('refresh_template_library')
+  }
+
+  static async searchLibrary(query: LibraryQuery = {}): Promise<LibraryEntry[]> {
+    return await invoke<LibraryEntry[]>('search_template_library', { query })
+  }
+
+  static async listLibraryTags(): Promise<string[]> {
+    return await invoke<string[]>('list_template_library_tags')
+  }
 }
 
 // === Template Manager Class ===
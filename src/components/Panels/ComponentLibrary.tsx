@@ -108,7 +108,7 @@ const ComponentLibrary: Component = () => {
       name: '图片',
       category: 'basic',
       icon: '🖼️',
-      description: '图片占位符',
+      description: '图片（本地文件、数据 URI 或数据字段）',
       default_size: { width: 100, height: 80 },
       create_content: (data) => ({
         type: 'Image',
@@ -1,5 +1,7 @@
 // Type definitions that mirror Rust types
 
+import type { Calculation, TemplateElement } from './template';
+
 // ElementId is serialized as a string from Rust
 export type ElementId = string;
 
@@ -47,9 +49,150 @@ export type LineCapType = 'None' | 'Arrow' | 'Circle' | 'Square';
 
 export type LineStyleType = 'Solid' | 'Dashed' | 'Dotted' | 'DashDot';
 
+export interface SubreportParameter {
+  readonly name: string;
+  readonly expression: string;
+}
+
+// 子报表：按父记录求值参数，数据取自 data_expression 或子模板自己的数据源
+export interface SubreportConfig {
+  readonly template_path: string;
+  readonly parameters: ReadonlyArray<SubreportParameter>;
+  readonly data_expression?: string;
+}
+
+export interface TableColumn {
+  readonly header: string;
+  readonly expression: string;
+  readonly width: number;
+  readonly align: TextAlign;
+  readonly format?: string;
+  // 表尾汇总方式，为空时表尾显示 footer_text
+  readonly footer_calculation?: Calculation;
+  readonly footer_text?: string;
+}
+
+// 表格：按列定义输出 data_source_id 数据源（或父记录数组 data_expression）的记录，跨页时重复表头
+export interface TableConfig {
+  readonly data_source_id: string;
+  readonly data_expression?: string;
+  readonly columns: ReadonlyArray<TableColumn>;
+  readonly header_height: number;
+  readonly row_height: number;
+  readonly show_header: boolean;
+  readonly show_footer: boolean;
+  readonly font_family: string;
+  readonly font_size: number;
+  readonly text_color: string;
+  readonly header_background?: string;
+  readonly zebra_color?: string;
+  readonly border?: BorderStyle;
+}
+
+export type AggregateFunction = 'Count' | 'Sum' | 'Avg' | 'Min' | 'Max' | 'First' | 'Last';
+
+// 交叉表分组（外层在前），show_total 为每组之后输出小计，最外层的小计即总计
+export interface CrosstabGroup {
+  readonly field: string;
+  readonly label?: string;
+  readonly format?: string;
+  readonly show_total: boolean;
+}
+
+export interface CrosstabMeasure {
+  readonly field: string;
+  readonly function: AggregateFunction;
+  readonly label?: string;
+  readonly format?: string;
+}
+
+// 交叉表：按行组和列组透视汇总数据源的记录，跨页时重复列表头
+export interface CrosstabConfig {
+  readonly data_source_id: string;
+  readonly data_expression?: string;
+  readonly row_groups: ReadonlyArray<CrosstabGroup>;
+  readonly column_groups: ReadonlyArray<CrosstabGroup>;
+  readonly measures: ReadonlyArray<CrosstabMeasure>;
+  readonly row_header_width: number;
+  readonly cell_width: number;
+  readonly cell_height: number;
+  readonly total_label: string;
+  readonly font_family: string;
+  readonly font_size: number;
+  readonly text_color: string;
+  readonly header_background?: string;
+  readonly total_background?: string;
+  readonly border?: BorderStyle;
+}
+
+export type ChartType = 'Bar' | 'StackedBar' | 'Line' | 'Area' | 'Pie' | 'Donut' | 'Scatter';
+
+export interface ChartSeries {
+  readonly name: string;
+  readonly values: ReadonlyArray<number | null>;
+  readonly points?: ReadonlyArray<readonly [number, number]>;
+}
+
+export interface ChartData {
+  readonly categories: ReadonlyArray<string>;
+  readonly series: ReadonlyArray<ChartSeries>;
+  readonly locale?: string;
+}
+
+export interface ChartConfig {
+  readonly chart_type: ChartType;
+  readonly data_source_id: string;
+  readonly data_expression?: string;
+  readonly category_expression: string;
+  readonly series_expression?: string;
+  readonly value_expression: string;
+  readonly aggregate: AggregateFunction;
+  readonly title?: string;
+  readonly show_legend: boolean;
+  readonly show_labels: boolean;
+  readonly show_axes: boolean;
+  readonly value_format?: string;
+  readonly palette: ReadonlyArray<string>;
+  readonly font_family: string;
+  readonly font_size: number;
+  readonly text_color: string;
+  // 填充后的图表数据
+  readonly data?: ChartData;
+}
+
+// 图片缩放：按原尺寸裁剪、拉伸填满、等比缩放
+export type ImageScaleMode = 'Clip' | 'FillFrame' | 'RetainShape';
+
+// 水平方向为左/中/右，垂直方向为上/中/下
+export type ImageAlign = 'Start' | 'Center' | 'End';
+
+export type BarcodeType = 'QrCode' | 'Code128' | 'Code39' | 'Ean13' | 'Pdf417' | 'DataMatrix';
+
+export type QrErrorCorrection = 'L' | 'M' | 'Q' | 'H';
+
+export interface BarcodeConfig {
+  readonly barcode_type: BarcodeType;
+  readonly value: string;
+  // 设置后填充时求值，覆盖 value
+  readonly expression?: string;
+  readonly error_correction: QrErrorCorrection;
+  readonly show_text: boolean;
+  readonly color: string;
+  readonly background?: string;
+  readonly font_family: string;
+  readonly font_size: number;
+}
+
 export type ElementContent = 
   | { type: 'Text'; content: string; style: TextStyle }
-  | { type: 'Image'; src: string; alt?: string }
+  | {
+      type: 'Image';
+      src: string;
+      alt?: string;
+      scale_mode?: ImageScaleMode;
+      horizontal_align?: ImageAlign;
+      vertical_align?: ImageAlign;
+    }
   | { 
       type: 'Rectangle'; 
       fill_color?: string; 
@@ -66,7 +209,12 @@ export type ElementContent =
       end_cap?: LineCapType;
       opacity?: number;
     }
-  | { type: 'DataField'; expression: string; format?: string; style: TextStyle; data_source_id?: string };
+  | { type: 'DataField'; expression: string; format?: string; style: TextStyle; data_source_id?: string }
+  | ({ type: 'Subreport' } & SubreportConfig)
+  | ({ type: 'Table' } & TableConfig)
+  | ({ type: 'Crosstab' } & CrosstabConfig)
+  | ({ type: 'Chart' } & ChartConfig)
+  | ({ type: 'Barcode' } & BarcodeConfig);
 
 export interface ReportElement {
   readonly id: ElementId;
@@ -77,6 +225,8 @@ export interface ReportElement {
   readonly visible: boolean;
   readonly locked: boolean;
   readonly name?: string;
+  // 从模板载入时的原始元素，保存时据此保留模板独有的属性
+  readonly origin?: TemplateElement;
 }
 
 export interface CanvasConfig {
@@ -104,6 +254,15 @@ export interface AppState {
   readonly template_name?: string;
 }
 
+// Unsaved session left by an unclean shutdown (get_recovery_info)
+export interface RecoveryInfo {
+  readonly templateName: string | null;
+  readonly snapshotAt: string;
+  readonly lastChangeAt: string;
+  readonly pendingChanges: number;
+  readonly elementCount: number;
+}
+
 // UI Component types
 export interface DragOperation {
   readonly type: 'move' | 'resize' | 'create';
@@ -1,6 +1,8 @@
 // === Jasper Template TypeScript Types ===
 // This file matches the Rust structures for type safety
 
+import type { BarcodeConfig, ChartConfig, CrosstabConfig, ElementContent as ReportElementContent, ImageScaleMode, SubreportConfig, TableConfig } from './index'
+
 // === Template Metadata ===
 export interface TemplateMetadata {
   readonly version: string
@@ -136,9 +138,17 @@ export interface TemplateElement {
   readonly content: ElementContent
   readonly style: ElementStyle
   readonly data_binding?: DataBinding
+  readonly designer?: DesignerProperties
+}
+
+// 模板字段无法表达、仅供设计器使用的元素属性
+export interface DesignerProperties {
+  readonly locked: boolean
+  readonly name?: string
+  readonly content?: ReportElementContent
 }
 
-export type ElementType = 'Text' | 'DataField' | 'Rectangle' | 'Line' | 'Image' | 'Barcode' | 'Chart'
+export type ElementType = 'Text' | 'DataField' | 'Rectangle' | 'Line' | 'Image' | 'Barcode' | 'Chart' | 'Subreport' | 'Table' | 'Crosstab'
 
 export interface Position {
   readonly x: number
@@ -157,6 +167,18 @@ export interface ElementContent {
   readonly alignment?: AlignmentConfig
   readonly color?: string
   readonly format?: string
+  // 仅 Subreport 元素使用
+  readonly subreport?: SubreportConfig
+  // 仅 Table 元素使用
+  readonly table?: TableConfig
+  // 仅 Crosstab 元素使用
+  readonly crosstab?: CrosstabConfig
+  // 仅 Chart 元素使用
+  readonly chart?: ChartConfig
+  // 仅 Barcode 元素使用
+  readonly barcode?: BarcodeConfig
+  // 仅 Image 元素使用，缺省为 RetainShape
+  readonly scale_mode?: ImageScaleMode
 }
 
 export interface FontConfig {
@@ -231,8 +253,27 @@ export interface Variable {
   readonly expression: string
   readonly initial_value?: any
   readonly description?: string
+  readonly calculation?: Calculation
+  readonly reset_type?: ResetType
+  readonly reset_group?: string
+  readonly increment_type?: IncrementType
+  readonly increment_group?: string
 }
 
+export type Calculation =
+  | 'nothing'
+  | 'count'
+  | 'distinct_count'
+  | 'sum'
+  | 'average'
+  | 'min'
+  | 'max'
+  | 'first'
+
+export type ResetType = 'report' | 'page' | 'column' | 'group' | 'none'
+
+export type IncrementType = 'none' | 'report' | 'page' | 'column' | 'group'
+
 // === Groups ===
 export interface Group {
   readonly name: string
@@ -249,6 +290,12 @@ export interface BandConfig {
   readonly elements: ReadonlyArray<string> // Element IDs
 }
 
+// === Formatting ===
+export interface FormatSettings {
+  readonly locale: string // zh-CN, en-US, de-DE ...
+  readonly null_text: string
+}
+
 // === Main Template Structure ===
 export interface JasperTemplate {
   readonly metadata: TemplateMetadata
@@ -258,6 +305,7 @@ export interface JasperTemplate {
   readonly parameters: ReadonlyArray<Parameter>
   readonly variables: ReadonlyArray<Variable>
   readonly groups: ReadonlyArray<Group>
+  readonly formatting?: FormatSettings
 }
 
 // === Template Information and Utilities ===
@@ -305,7 +353,180 @@ export interface DataSourceSummary {
 }
 
 // === Template Format Types ===
-export type TemplateFormat = 'json' | 'binary' | 'jrxml' | 'unknown'
+export type TemplateFormat = 'json' | 'binary' | 'jrxml' | 'package' | 'unknown'
+
+// JRXML import result (import_jrxml_template)
+export interface UnsupportedConstruct {
+  readonly construct: string // tag or attribute name, e.g. "subreport"
+  readonly path: string // e.g. "detail/band[2]/frame"
+  readonly line: number
+  readonly message: string
+}
+
+export interface JrxmlImport {
+  readonly template: JasperTemplate
+  readonly unsupported: ReadonlyArray<UnsupportedConstruct>
+}
+
+// Template load result with format migrations (load_template_with_migrations)
+export interface AppliedMigration {
+  readonly from: string
+  readonly to: string
+  readonly description: string
+}
+
+export interface LoadedTemplate {
+  readonly template: JasperTemplate
+  readonly migrations: ReadonlyArray<AppliedMigration>
+}
+
+// Template package (.jpkg) manifest and contents (save_template_package / open_template_package)
+export type AssetKind = 'template' | 'image' | 'subreport' | 'font' | 'sample_data'
+
+export interface ManifestEntry {
+  readonly path: string // path inside the package, e.g. "images/3f2a9c41d0e7-logo.png"
+  readonly kind: AssetKind
+  readonly size: number
+  readonly sha256: string
+  readonly source?: string // original image path, font family or data source id
+}
+
+export interface PackageManifest {
+  readonly package_version: number
+  readonly format_version: string
+  readonly created_at: string
+  readonly created_by: string
+  readonly entries: ReadonlyArray<ManifestEntry>
+}
+
+export interface TemplatePackageContents {
+  readonly template: JasperTemplate
+  readonly manifest: PackageManifest
+  readonly sampleData: Readonly<Record<string, unknown>>
+  readonly migrations: ReadonlyArray<AppliedMigration>
+}
+
+// Detached signatures for binary templates (save_signed_template / verify_template_signature)
+export interface SigningKeyPair {
+  readonly publicKey: string
+  readonly secretKey: string
+}
+
+export interface BinarySignature {
+  readonly algorithm: 'ed25519'
+  readonly public_key: string
+  readonly sha256: string
+  readonly signature: string
+  readonly signed_at: string
+}
+
+// Structural diff and three-way merge (diff_templates / merge_templates)
+export type ChangeKind = 'added' | 'removed' | 'modified'
+
+export interface PropertyChange {
+  readonly path: string
+  readonly before: unknown | null
+  readonly after: unknown | null
+}
+
+export interface ItemChange {
+  readonly key: string
+  readonly kind: ChangeKind
+  readonly changes: ReadonlyArray<PropertyChange>
+}
+
+export interface TemplateDiff {
+  readonly elements: ReadonlyArray<ItemChange>
+  readonly dataSources: ReadonlyArray<ItemChange>
+  readonly parameters: ReadonlyArray<ItemChange>
+  readonly variables: ReadonlyArray<ItemChange>
+  readonly groups: ReadonlyArray<ItemChange>
+  readonly properties: ReadonlyArray<PropertyChange>
+}
+
+export interface MergeConflict {
+  readonly path: string
+  readonly base: unknown | null
+  readonly ours: unknown | null
+  readonly theirs: unknown | null
+}
+
+export interface TemplateMerge {
+  readonly merged: JasperTemplate
+  readonly conflicts: ReadonlyArray<MergeConflict>
+}
+
+// Template lint diagnostics (lint_template)
+export type LintSeverity = 'error' | 'warning'
+
+export type LintCode =
+  | 'outside_printable_area'
+  | 'exceeds_band'
+  | 'overlapping_elements'
+  | 'invalid_expression'
+  | 'invalid_group_expression'
+  | 'unknown_field'
+  | 'unknown_parameter'
+  | 'unknown_variable'
+  | 'unknown_data_source'
+  | 'missing_image'
+  | 'missing_subreport'
+  | 'unavailable_font'
+
+export interface LintDiagnostic {
+  readonly code: LintCode
+  readonly severity: LintSeverity
+  readonly message: string
+  readonly elementId: string | null
+  readonly path: string | null
+}
+
+export interface LintReport {
+  readonly diagnostics: ReadonlyArray<LintDiagnostic>
+  readonly errorCount: number
+  readonly warningCount: number
+}
+
+// Template library index and recent files (search_template_library / get_recent_templates)
+export interface LibrarySettings {
+  readonly root: string | null
+  readonly maxRecent: number
+}
+
+export interface LibraryEntry {
+  readonly path: string
+  readonly name: string
+  readonly format: 'json' | 'binary' | 'jrxml' | 'package'
+  readonly description: string | null
+  readonly tags: ReadonlyArray<string>
+  readonly createdBy: string
+  readonly createdAt: string
+  readonly lastModified: string
+  readonly elementCount: number
+  readonly fileSize: number
+  readonly fileModified: string
+  readonly thumbnail: string | null
+}
+
+export interface LibraryQuery {
+  readonly text?: string
+  readonly tags?: ReadonlyArray<string>
+  readonly createdBy?: string
+  readonly limit?: number
+}
+
+export interface LibraryChanges {
+  readonly added: ReadonlyArray<string>
+  readonly updated: ReadonlyArray<string>
+  readonly removed: ReadonlyArray<string>
+}
+
+export interface RecentTemplate {
+  readonly path: string
+  readonly name: string
+  readonly openedAt: string
+  readonly exists: boolean
+}
 
 // === Validation Results ===
 export interface TemplateValidationResult {
@@ -318,7 +539,7 @@ export interface TemplateValidationResult {
 export const createEmptyTemplate = (): Partial<JasperTemplate> => ({
   metadata: {
     version: '2.0.0',
-    format_version: '1.0.0',
+    format_version: '1.9.0',
     created_at: new Date().toISOString(),
     last_modified: new Date().toISOString(),
     created_by: 'jasper-designer-v2',
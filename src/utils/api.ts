@@ -4,8 +4,10 @@ import type {
   UpdateElementRequest, 
   UpdateCanvasConfigRequest,
   AppState,
-  CanvasConfig 
+  CanvasConfig,
+  RecoveryInfo
 } from '../types';
+import type { JasperTemplate, RecentTemplate } from '../types/template';
 
 // Wrapper functions for Tauri commands with proper error handling
 
@@ -99,6 +101,33 @@ export async function redo(): Promise<void> {
   }
 }
 
+export async function getRecoveryInfo(): Promise<RecoveryInfo | null> {
+  try {
+    return await invoke<RecoveryInfo | null>('get_recovery_info');
+  } catch (error) {
+    console.error('API: Failed to get recovery info', error);
+    return null;
+  }
+}
+
+export async function restoreSession(): Promise<AppState> {
+  try {
+    return await invoke<AppState>('restore_session');
+  } catch (error) {
+    console.error('API: Failed to restore session', error);
+    throw new Error(`Failed to restore session: ${error}`);
+  }
+}
+
+export async function discardRecovery(): Promise<void> {
+  try {
+    await invoke('discard_recovery');
+  } catch (error) {
+    console.error('API: Failed to discard recovery', error);
+    throw new Error(`Failed to discard recovery: ${error}`);
+  }
+}
+
 export async function getCanvasConfig(): Promise<CanvasConfig> {
   try {
     return await invoke<CanvasConfig>('get_canvas_config');
@@ -183,6 +212,24 @@ export async function newTemplate(): Promise<void> {
   }
 }
 
+export async function openTemplateForEditing(template: JasperTemplate): Promise<AppState> {
+  try {
+    return await invoke<AppState>('open_template_for_editing', { template });
+  } catch (error) {
+    console.error('API: Failed to open template for editing', error);
+    throw new Error(`Failed to open template for editing: ${error}`);
+  }
+}
+
+export async function getEditedTemplate(): Promise<JasperTemplate> {
+  try {
+    return await invoke<JasperTemplate>('get_edited_template');
+  } catch (error) {
+    console.error('API: Failed to get edited template', error);
+    throw new Error(`Failed to get edited template: ${error}`);
+  }
+}
+
 export async function exportJson(): Promise<string> {
   try {
     return await invoke<string>('export_json');
@@ -192,15 +239,24 @@ export async function exportJson(): Promise<string> {
   }
 }
 
-export async function getRecentTemplates(): Promise<string[]> {
+export async function getRecentTemplates(): Promise<RecentTemplate[]> {
   try {
-    return await invoke<string[]>('get_recent_templates');
+    return await invoke<RecentTemplate[]>('get_recent_templates');
   } catch (error) {
     console.error('API: Failed to get recent templates', error);
     return [];
   }
 }
 
+export async function clearRecentTemplates(): Promise<void> {
+  try {
+    await invoke<void>('clear_recent_templates');
+  } catch (error) {
+    console.error('API: Failed to clear recent templates', error);
+    throw new Error(`Failed to clear recent templates: ${error}`);
+  }
+}
+
 // Utility functions
 export function handleApiError(error: unknown): string {
   if (typeof error === 'string') {
@@ -356,6 +356,7 @@ export class AppStateConverter {
           type: 'Image',
           src: element.content.expression || '',
           ...(element.content.text && { alt: element.content.text }),
+          ...(element.content.scale_mode && { scale_mode: element.content.scale_mode }),
         }
         
       default: